@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use rust_bert::gpt_j::{
+    GptJConfigResources, GptJGenerator, GptJMergesResources, GptJVocabResources,
+};
+use rust_bert::pipelines::common::ModelResource;
+use rust_bert::pipelines::generation_utils::{GenerateConfig, LanguageGenerator};
+use rust_bert::resources::{LocalResource, RemoteResource};
+use rust_bert::tensor_parallel::TcpProcessGroup;
+use tch::Device;
+
+/// Tensor-parallel generation with GPT-J 6B split across several processes (possibly on different machines).
+/// Start one process per worker, for example for 2 workers on the same machine:
+///
+/// ```
+/// cargo run --release --example generation_gptj_sharded -- 0 2 127.0.0.1:29500
+/// cargo run --release --example generation_gptj_sharded -- 1 2 127.0.0.1:29500
+/// ```
+///
+/// The arguments are the rank of the worker, the number of workers and the address of the worker of rank 0.
+/// Each worker holds half of the attention heads and feed-forward weights of every layer.
+///
+/// To run this example you need to download `pytorch_model.bin` from [EleutherAI GPT-J 6B
+/// (float16)][gpt-j-6B-float16] and then convert its weights with:
+///
+/// ```
+/// python utils/convert_model.py resources/gpt-j-6B-float16/pytorch_model.bin
+/// ```
+///
+/// [gpt-j-6B-float16]: https://huggingface.co/EleutherAI/gpt-j-6B/tree/float16
+fn main() -> anyhow::Result<()> {
+    let args = std::env::args().collect::<Vec<String>>();
+    let rank: usize = args.get(1).map_or(Ok(0), |rank| rank.parse())?;
+    let world_size: usize = args.get(2).map_or(Ok(2), |world_size| world_size.parse())?;
+    let address = args
+        .get(3)
+        .map_or("127.0.0.1:29500", |address| address.as_str());
+
+    // Resources paths
+
+    let config_resource = Box::new(RemoteResource::from_pretrained(
+        GptJConfigResources::GPT_J_6B_FLOAT16,
+    ));
+
+    let vocab_resource = Box::new(RemoteResource::from_pretrained(
+        GptJVocabResources::GPT_J_6B_FLOAT16,
+    ));
+
+    let merges_resource = Box::new(RemoteResource::from_pretrained(
+        GptJMergesResources::GPT_J_6B_FLOAT16,
+    ));
+
+    let model_resource = Box::new(LocalResource::from(PathBuf::from(
+        "resources/gpt-j-6B-float16/rust_model.ot",
+    )));
+
+    // Set-up model
+
+    let generate_config = GenerateConfig {
+        model_resource: ModelResource::Torch(model_resource),
+        config_resource,
+        vocab_resource,
+        merges_resource: Some(merges_resource),
+        min_length: 10,
+        max_length: Some(32),
+        do_sample: false,
+        early_stopping: true,
+        num_beams: 1,
+        num_return_sequences: 1,
+        device: Device::Cpu,
+        ..Default::default()
+    };
+
+    let process_group = Arc::new(TcpProcessGroup::new(rank, world_size, address)?);
+    let mut model = GptJGenerator::new_sharded(generate_config, process_group)?;
+    // The float16 weights are converted to single precision for CPU inference
+    model.float()?;
+
+    // Generate text (all workers run the same generation)
+
+    let prompts = [
+        "It was a very nice and sunny",
+        "It was a gloom winter night, and",
+    ];
+    let output = model.generate(Some(&prompts), None);
+
+    if rank == 0 {
+        for sentence in output {
+            println!("{}", sentence.text);
+        }
+    }
+
+    Ok(())
+}
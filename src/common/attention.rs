@@ -0,0 +1,203 @@
+// Copyright 2019 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::dropout::Dropout;
+use tch::nn::ModuleT;
+use tch::{Kind, Tensor};
+
+/// Computes the attention context `softmax(scale * query.keyᵀ + mask).value` for tensors of shape
+/// (*batch size*, *num_heads*, *sequence_length*, *head_dim*). Other leading dimensions (e.g. a flattened
+/// *batch size* x *num_heads* dimension) are treated as batch dimensions.
+///
+/// Dispatches to the fused libtorch `scaled_dot_product_attention` kernel, avoiding the materialization of the
+/// intermediate attention scores, unless the attention weights are requested (`output_attentions`). The explicit
+/// computation is then used, returning the attention probabilities after dropout.
+///
+/// The fused kernel of libtorch 2.0 always scales the scores by `1/sqrt(head_dim)`: smaller custom scales are applied
+/// to the query beforehand. Larger scales (e.g. the unscaled attention of T5) would amplify the query, risking
+/// overflows in half precision, and always use the explicit computation.
+///
+/// # Arguments
+///
+/// * `query` - Query tensor of shape (*batch size*, *num_heads*, *target_length*, *head_dim*)
+/// * `key` - Key tensor of shape (*batch size*, *num_heads*, *source_length*, *head_dim*)
+/// * `value` - Value tensor of shape (*batch size*, *num_heads*, *source_length*, *value_dim*)
+/// * `attention_mask` - Optional mask broadcastable to (*batch size*, *num_heads*, *target_length*, *source_length*).
+///   Floating point masks are added to the attention scores, boolean masks indicate the positions that may be attended to.
+/// * `scale` - Optional scaling of the attention scores, defaults to `1/sqrt(head_dim)`
+/// * `dropout` - Dropout applied to the attention probabilities
+/// * `output_attentions` - Flag indicating if the attention probabilities should be returned
+/// * `train` - boolean flag to turn on/off the dropout layers in the model. Should be set to false for inference.
+///
+/// # Returns
+///
+/// * `Tensor` context of shape (*batch size*, *num_heads*, *target_length*, *value_dim*)
+/// * `Option<Tensor>` attention probabilities of shape (*batch size*, *num_heads*, *target_length*, *source_length*) if `output_attentions` is set
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn scaled_dot_product_attention(
+    query: &Tensor,
+    key: &Tensor,
+    value: &Tensor,
+    attention_mask: Option<&Tensor>,
+    scale: Option<f64>,
+    dropout: &Dropout,
+    output_attentions: bool,
+    train: bool,
+) -> (Tensor, Option<Tensor>) {
+    let default_scale = 1.0 / (*query.size().last().unwrap() as f64).sqrt();
+    let scale = scale.unwrap_or(default_scale);
+    if output_attentions || scale > default_scale {
+        let (context, weights) =
+            explicit_attention(query, key, value, attention_mask, scale, dropout, train);
+        (
+            context,
+            if output_attentions {
+                Some(weights)
+            } else {
+                None
+            },
+        )
+    } else {
+        (
+            fused_attention(
+                query,
+                key,
+                value,
+                attention_mask,
+                scale / default_scale,
+                dropout,
+                train,
+            ),
+            None,
+        )
+    }
+}
+
+fn explicit_attention(
+    query: &Tensor,
+    key: &Tensor,
+    value: &Tensor,
+    attention_mask: Option<&Tensor>,
+    scale: f64,
+    dropout: &Dropout,
+    train: bool,
+) -> (Tensor, Tensor) {
+    let mut scores = (query * scale).matmul(&key.transpose(-1, -2));
+    if let Some(mask) = attention_mask {
+        scores = if mask.kind() == Kind::Bool {
+            scores.masked_fill(&mask.logical_not(), f64::NEG_INFINITY)
+        } else {
+            scores + mask
+        };
+    }
+    let weights = scores
+        .softmax(-1, Kind::Float)
+        .to_kind(scores.kind())
+        .apply_t(dropout, train);
+    (weights.matmul(value), weights)
+}
+
+/// `query_scale` is the ratio between the requested scale and the `1/sqrt(head_dim)` applied by the kernel
+fn fused_attention(
+    query: &Tensor,
+    key: &Tensor,
+    value: &Tensor,
+    attention_mask: Option<&Tensor>,
+    query_scale: f64,
+    dropout: &Dropout,
+    train: bool,
+) -> Tensor {
+    let query = if query_scale != 1.0 {
+        query * query_scale
+    } else {
+        query.shallow_clone()
+    };
+    let attention_mask = attention_mask.map(|mask| {
+        if mask.kind() == Kind::Bool {
+            mask.shallow_clone()
+        } else {
+            mask.to_kind(query.kind())
+        }
+    });
+    let dropout_prob = if train { dropout.probability() } else { 0.0 };
+    Tensor::scaled_dot_product_attention(&query, key, value, attention_mask, dropout_prob, false)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tch::Device;
+
+    fn max_difference(fused: &Tensor, explicit: &Tensor) -> f64 {
+        (fused - explicit).abs().max().double_value(&[])
+    }
+
+    #[test]
+    fn fused_and_explicit_attention_agree() {
+        tch::manual_seed(0);
+        let options = (Kind::Float, Device::Cpu);
+        let query = Tensor::randn([2, 4, 5, 16], options);
+        let key = Tensor::randn([2, 4, 7, 16], options);
+        let value = Tensor::randn([2, 4, 7, 8], options);
+        let dropout = Dropout::new(0.1);
+
+        let padding = Tensor::from_slice2(&[
+            [true, true, true, true, true, true, true],
+            [true, true, true, true, false, false, false],
+        ])
+        .view([2, 1, 1, 7]);
+        let float_mask = (padding.logical_not().to_kind(Kind::Float) * -1e4)
+            + Tensor::randn([1, 4, 5, 7], options);
+
+        for mask in [None, Some(&padding), Some(&float_mask)] {
+            for scale in [None, Some(0.1)] {
+                let (fused, fused_weights) = scaled_dot_product_attention(
+                    &query, &key, &value, mask, scale, &dropout, false, false,
+                );
+                let (explicit, explicit_weights) = scaled_dot_product_attention(
+                    &query, &key, &value, mask, scale, &dropout, true, false,
+                );
+                assert!(fused_weights.is_none());
+                assert_eq!(explicit_weights.unwrap().size(), vec![2, 4, 5, 7]);
+                assert_eq!(fused.size(), vec![2, 4, 5, 8]);
+                assert!(max_difference(&fused, &explicit) < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn explicit_attention_custom_scale() {
+        tch::manual_seed(0);
+        let options = (Kind::Float, Device::Cpu);
+        let query = Tensor::randn([1, 2, 3, 16], options);
+        let key = Tensor::randn([1, 2, 3, 16], options);
+        let value = Tensor::randn([1, 2, 3, 16], options);
+        let dropout = Dropout::new(0.1);
+
+        //    Unscaled attention is computed explicitly to avoid amplifying the query
+        let (context, weights) = scaled_dot_product_attention(
+            &query,
+            &key,
+            &value,
+            None,
+            Some(1.0),
+            &dropout,
+            false,
+            false,
+        );
+        let expected = query
+            .matmul(&key.transpose(-1, -2))
+            .softmax(-1, Kind::Float)
+            .matmul(&value);
+        assert!(weights.is_none());
+        assert!(max_difference(&context, &expected) < 1e-5);
+    }
+}
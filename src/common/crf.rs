@@ -0,0 +1,251 @@
+// Copyright 2019 Kemal Kurniawan (pytorch-crf)
+// Copyright 2023 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Linear-chain Conditional Random Field
+//! Output layer scoring label transitions on top of per-token label scores (emissions), as used by BERT-CRF token classification models.
+//! The parameters follow the [pytorch-crf](https://github.com/kmkurn/pytorch-crf) naming (`transitions`, `start_transitions`, `end_transitions`),
+//! allowing to load the weights of checkpoints trained with this library after conversion.
+
+use crate::RustBertError;
+use std::borrow::Borrow;
+use tch::{nn, Device, Kind, Tensor};
+
+#[derive(Debug)]
+/// # Linear-chain CRF layer
+/// Holds the transition scores between labels and the scores for starting and ending a sequence with each label.
+/// The most likely label sequence is obtained by Viterbi decoding.
+pub struct LinearChainCRF {
+    /// Transition scores of shape (*num_labels*, *num_labels*), indexed as `[from_label, to_label]`
+    pub transitions: Tensor,
+    /// Scores of shape (*num_labels*) for starting a sequence with each label
+    pub start_transitions: Tensor,
+    /// Scores of shape (*num_labels*) for ending a sequence with each label
+    pub end_transitions: Tensor,
+}
+
+impl LinearChainCRF {
+    /// Build a new `LinearChainCRF`
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Variable store path for the root of the CRF layer
+    /// * `num_labels` - Number of labels (tags) of the token classification model
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::crf::LinearChainCRF;
+    /// use tch::{nn, Device};
+    ///
+    /// let mut vs = nn::VarStore::new(Device::Cpu);
+    /// let crf = LinearChainCRF::new(vs.root() / "crf", 9);
+    /// vs.load("path/to/rust_model.ot")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new<'p, P>(p: P, num_labels: i64) -> LinearChainCRF
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+        let transitions = p.zeros("transitions", &[num_labels, num_labels]);
+        let start_transitions = p.zeros("start_transitions", &[num_labels]);
+        let end_transitions = p.zeros("end_transitions", &[num_labels]);
+        LinearChainCRF {
+            transitions,
+            start_transitions,
+            end_transitions,
+        }
+    }
+
+    /// Returns the number of labels scored by the CRF layer
+    pub fn num_labels(&self) -> i64 {
+        self.start_transitions.size()[0]
+    }
+
+    /// Decodes the most likely label sequences using the Viterbi algorithm
+    ///
+    /// # Arguments
+    ///
+    /// * `emissions` - Per-token label scores (e.g. classifier logits) of shape (*batch size*, *sequence_length*, *num_labels*)
+    /// * `mask` - Optional mask of shape (*batch size*, *sequence_length*). Only positions with a non-zero mask are decoded. If None, all positions are decoded.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Vec<i64>>` containing the label indices of the unmasked positions for each sequence of the batch
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_bert::crf::LinearChainCRF;
+    /// # use tch::{nn, Device, Kind, Tensor};
+    /// # let vs = nn::VarStore::new(Device::Cpu);
+    /// # let crf = LinearChainCRF::new(vs.root() / "crf", 9);
+    /// let emissions = Tensor::rand(&[2, 16, 9], (Kind::Float, Device::Cpu));
+    /// let mask = Tensor::ones(&[2, 16], (Kind::Int64, Device::Cpu));
+    /// let labels = crf.decode(&emissions, Some(&mask));
+    /// ```
+    pub fn decode(&self, emissions: &Tensor, mask: Option<&Tensor>) -> Vec<Vec<i64>> {
+        let (start, end, transitions) = self
+            .get_transition_scores()
+            .expect("CRF transition scores should be convertible to f64");
+        let emissions = emissions.to(Device::Cpu).to_kind(Kind::Double);
+        let mask = mask.map(|mask| mask.to(Device::Cpu));
+        let (batch_size, sequence_length) = (emissions.size()[0], emissions.size()[1]);
+
+        (0..batch_size)
+            .map(|sequence_index| {
+                let sequence_emissions = emissions.get(sequence_index);
+                let sequence_scores = (0..sequence_length)
+                    .filter(|position| match &mask {
+                        Some(mask) => mask.int64_value(&[sequence_index, *position]) != 0,
+                        None => true,
+                    })
+                    .map(|position| {
+                        sequence_emissions
+                            .get(position)
+                            .iter::<f64>()
+                            .unwrap()
+                            .collect::<Vec<f64>>()
+                    })
+                    .collect::<Vec<Vec<f64>>>();
+                viterbi_decode(&sequence_scores, &start, &end, &transitions).unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Returns the start, end and transition scores as vectors of `f64`
+    pub(crate) fn get_transition_scores(
+        &self,
+    ) -> Result<(Vec<f64>, Vec<f64>, Vec<Vec<f64>>), RustBertError> {
+        let start = self.start_transitions.iter::<f64>()?.collect::<Vec<f64>>();
+        let end = self.end_transitions.iter::<f64>()?.collect::<Vec<f64>>();
+        let transitions = (0..self.num_labels())
+            .map(|from_label| {
+                Ok(self
+                    .transitions
+                    .get(from_label)
+                    .iter::<f64>()?
+                    .collect::<Vec<f64>>())
+            })
+            .collect::<Result<Vec<Vec<f64>>, RustBertError>>()?;
+        Ok((start, end, transitions))
+    }
+}
+
+/// Viterbi search for the highest-scoring label sequence given per-position label scores of shape (*sequence_length*, *num_labels*),
+/// start and end scores of shape (*num_labels*) and transition scores of shape (*num_labels*, *num_labels*) indexed as `[from_label][to_label]`.
+/// Returns `None` if all label sequences have a score of negative infinity (e.g. forbidden by hard transition constraints).
+pub(crate) fn viterbi_decode(
+    emissions: &[Vec<f64>],
+    start: &[f64],
+    end: &[f64],
+    transitions: &[Vec<f64>],
+) -> Option<Vec<i64>> {
+    let (first_emissions, emissions_tail) = match emissions.split_first() {
+        Some(split) => split,
+        None => return Some(vec![]),
+    };
+
+    let mut scores: Vec<f64> = start
+        .iter()
+        .zip(first_emissions)
+        .map(|(start, emission)| start + emission)
+        .collect();
+    let mut backpointers: Vec<Vec<usize>> = Vec::with_capacity(emissions_tail.len());
+    for position_emissions in emissions_tail {
+        let (new_scores, pointers): (Vec<f64>, Vec<usize>) = position_emissions
+            .iter()
+            .enumerate()
+            .map(|(to, emission)| {
+                let (from, score) = argmax(
+                    &scores
+                        .iter()
+                        .zip(transitions)
+                        .map(|(score, transitions)| score + transitions[to])
+                        .collect::<Vec<f64>>(),
+                );
+                (score + emission, from)
+            })
+            .unzip();
+        scores = new_scores;
+        backpointers.push(pointers);
+    }
+
+    let final_scores = scores
+        .iter()
+        .zip(end)
+        .map(|(score, end)| score + end)
+        .collect::<Vec<f64>>();
+    let (mut label, best_score) = argmax(&final_scores);
+    if best_score == f64::NEG_INFINITY {
+        return None;
+    }
+    let mut labels = Vec::with_capacity(emissions.len());
+    labels.push(label as i64);
+    for pointers in backpointers.iter().rev() {
+        label = pointers[label];
+        labels.push(label as i64);
+    }
+    labels.reverse();
+    Some(labels)
+}
+
+/// Returns the index and value of the maximum of a slice (first occurrence)
+pub(crate) fn argmax(values: &[f64]) -> (usize, f64) {
+    values.iter().enumerate().fold(
+        (0, f64::NEG_INFINITY),
+        |(best_index, best_value), (index, value)| {
+            if *value > best_value {
+                (index, *value)
+            } else {
+                (best_index, best_value)
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_viterbi_decode() {
+        // Given
+        let emissions = vec![vec![2.0, 0.0], vec![0.0, 0.6], vec![0.0, 0.6]];
+        let start = vec![0.0, 0.0];
+        let end = vec![0.0, 0.0];
+        // Switching label is penalized: the greedy sequence [0, 1, 1] is not optimal
+        let transitions = vec![vec![0.0, -1.5], vec![-1.5, 0.0]];
+
+        // When
+        let labels = viterbi_decode(&emissions, &start, &end, &transitions);
+
+        // Then
+        assert_eq!(labels, Some(vec![0, 0, 0]));
+    }
+
+    #[test]
+    fn test_viterbi_decode_infeasible() {
+        let emissions = vec![vec![1.0, 0.0]];
+        let start = vec![f64::NEG_INFINITY, 0.0];
+        let end = vec![0.0, f64::NEG_INFINITY];
+        let transitions = vec![vec![0.0, 0.0], vec![0.0, 0.0]];
+
+        assert_eq!(viterbi_decode(&emissions, &start, &end, &transitions), None);
+        assert_eq!(
+            viterbi_decode(&[], &start, &end, &transitions),
+            Some(vec![])
+        );
+    }
+}
@@ -21,6 +21,10 @@ impl Dropout {
     pub fn new(p: f64) -> Dropout {
         Dropout { dropout_prob: p }
     }
+
+    pub fn probability(&self) -> f64 {
+        self.dropout_prob
+    }
 }
 
 impl ModuleT for Dropout {
@@ -0,0 +1,306 @@
+// Copyright 2019 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::mem;
+use tch::Tensor;
+
+/// Minimum number of positions allocated for a cache
+const MIN_CAPACITY: i64 = 64;
+
+#[derive(Debug)]
+/// # Preallocated key/value cache for decoder self-attention layers
+/// Keys and values are stored in buffers of shape (*batch size*, *num_heads*, *capacity*, *head_dim*) and new
+/// positions are written in place. When the final sequence length is known (e.g. the maximum length of a generation),
+/// the buffers are preallocated once with `reserve`. Otherwise, the capacity is doubled when exceeded (up to the
+/// maximum length supported by the model), so that buffers are reallocated a logarithmic number of times over a
+/// generation instead of at every step.
+/// Beam search reordering copies the buffers into a second set of buffers allocated once and swapped at each reordering.
+pub struct KVCache {
+    keys: Tensor,
+    values: Tensor,
+    length: i64,
+    max_length: i64,
+    reorder_buffers: Option<(Tensor, Tensor)>,
+}
+
+impl KVCache {
+    /// Creates a new cache initialized with the keys and values of a first forward pass
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - Keys of shape (*batch size*, *num_heads*, *sequence_length*, *head_dim*)
+    /// * `values` - Values of shape (*batch size*, *num_heads*, *sequence_length*, *head_dim*)
+    /// * `max_length` - Maximum sequence length supported by the model, limiting the growth of the buffers
+    ///
+    /// # Returns
+    ///
+    /// * `KVCache` with a capacity of at least *sequence_length*
+    pub fn new(keys: &Tensor, values: &Tensor, max_length: i64) -> KVCache {
+        let length = keys.size()[2];
+        let capacity = Self::capacity_for(length, max_length);
+        let mut cache = KVCache {
+            keys: Self::allocate(keys, capacity),
+            values: Self::allocate(values, capacity),
+            length: 0,
+            max_length,
+            reorder_buffers: None,
+        };
+        cache.write(keys, values);
+        cache
+    }
+
+    fn capacity_for(length: i64, max_length: i64) -> i64 {
+        let capacity = (length.max(MIN_CAPACITY) as u64).next_power_of_two() as i64;
+        capacity.min(max_length).max(length)
+    }
+
+    fn allocate(reference: &Tensor, capacity: i64) -> Tensor {
+        let mut shape = reference.size();
+        shape[2] = capacity;
+        Tensor::empty(shape.as_slice(), (reference.kind(), reference.device()))
+    }
+
+    fn write(&mut self, keys: &Tensor, values: &Tensor) {
+        let new_positions = keys.size()[2];
+        self.keys.narrow(2, self.length, new_positions).copy_(keys);
+        self.values
+            .narrow(2, self.length, new_positions)
+            .copy_(values);
+        self.length += new_positions;
+    }
+
+    fn grow(&mut self, capacity: i64) {
+        let mut keys = Self::allocate(&self.keys, capacity);
+        let mut values = Self::allocate(&self.values, capacity);
+        keys.narrow(2, 0, self.length).copy_(&self.keys());
+        values.narrow(2, 0, self.length).copy_(&self.values());
+        self.keys = keys;
+        self.values = values;
+        self.reorder_buffers = None;
+    }
+
+    /// Appends new keys and values to the cache
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - New keys of shape (*batch size*, *num_heads*, *new_sequence_length*, *head_dim*)
+    /// * `values` - New values of shape (*batch size*, *num_heads*, *new_sequence_length*, *head_dim*)
+    ///
+    /// # Returns
+    ///
+    /// * `(Tensor, Tensor)` views over all cached keys and values, including the new positions
+    pub fn update(&mut self, keys: &Tensor, values: &Tensor) -> (Tensor, Tensor) {
+        let required_length = self.length + keys.size()[2];
+        if required_length > self.capacity() {
+            self.grow(Self::capacity_for(required_length, self.max_length));
+        }
+        self.write(keys, values);
+        (self.keys(), self.values())
+    }
+
+    /// Preallocates the buffers for a total of `length` positions (limited to the maximum length supported by the
+    /// model), avoiding further reallocations while the cache is filled up to this length
+    ///
+    /// # Arguments
+    ///
+    /// * `length` - Expected total number of cached positions (e.g. the maximum length of a generation)
+    pub fn reserve(&mut self, length: i64) {
+        let capacity = length.min(self.max_length);
+        if capacity > self.capacity() {
+            self.grow(capacity);
+        }
+    }
+
+    /// View over the cached keys, of shape (*batch size*, *num_heads*, *sequence_length*, *head_dim*).
+    /// The view shares the storage of the cache and should not be kept after the cache is reordered.
+    pub fn keys(&self) -> Tensor {
+        self.keys.narrow(2, 0, self.length)
+    }
+
+    /// View over the cached values, of shape (*batch size*, *num_heads*, *sequence_length*, *head_dim*).
+    /// The view shares the storage of the cache and should not be kept after the cache is reordered.
+    pub fn values(&self) -> Tensor {
+        self.values.narrow(2, 0, self.length)
+    }
+
+    /// Number of cached positions
+    pub fn len(&self) -> i64 {
+        self.length
+    }
+
+    /// Returns true if no position is cached
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Number of positions that can be cached before the buffers are reallocated
+    pub fn capacity(&self) -> i64 {
+        self.keys.size()[2]
+    }
+
+    pub(crate) fn reorder_cache(&mut self, new_indices: &Tensor) {
+        if new_indices.size()[0] != self.keys.size()[0] {
+            self.keys = self.keys.index_select(0, new_indices);
+            self.values = self.values.index_select(0, new_indices);
+            self.reorder_buffers = None;
+            return;
+        }
+        let (keys, values) = self
+            .reorder_buffers
+            .take()
+            .unwrap_or_else(|| (self.keys.empty_like(), self.values.empty_like()));
+        let _ = self.keys.index_select_out(&keys, 0, new_indices);
+        let _ = self.values.index_select_out(&values, 0, new_indices);
+        self.reorder_buffers = Some((
+            mem::replace(&mut self.keys, keys),
+            mem::replace(&mut self.values, values),
+        ));
+    }
+}
+
+impl Clone for KVCache {
+    fn clone(&self) -> Self {
+        KVCache::new(&self.keys(), &self.values(), self.max_length)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tch::{Device, Kind};
+
+    fn random_states(batch_size: i64, sequence_length: i64) -> (Tensor, Tensor) {
+        let options = (Kind::Float, Device::Cpu);
+        (
+            Tensor::rand([batch_size, 2, sequence_length, 4], options),
+            Tensor::rand([batch_size, 2, sequence_length, 4], options),
+        )
+    }
+
+    #[test]
+    fn test_growth() {
+        let (keys, values) = random_states(1, 60);
+        let mut cache = KVCache::new(&keys, &values, 1024);
+        assert_eq!(cache.len(), 60);
+        assert_eq!(cache.capacity(), MIN_CAPACITY);
+
+        let mut all_keys = vec![keys];
+        let mut all_values = vec![values];
+        for step in 0..8 {
+            let (keys, values) = random_states(1, 1);
+            let (cached_keys, cached_values) = cache.update(&keys, &values);
+            all_keys.push(keys);
+            all_values.push(values);
+            //    Capacity is doubled once the 64 initial positions are filled
+            let expected_capacity = if step < 4 { 64 } else { 128 };
+            assert_eq!(cache.capacity(), expected_capacity);
+            assert_eq!(cached_keys, Tensor::cat(&all_keys, 2));
+            assert_eq!(cached_values, Tensor::cat(&all_values, 2));
+        }
+        assert_eq!(cache.len(), 68);
+    }
+
+    #[test]
+    fn test_max_length() {
+        let (keys, values) = random_states(1, 10);
+        let cache = KVCache::new(&keys, &values, 32);
+        assert_eq!(cache.capacity(), 32);
+
+        let mut cache = KVCache::new(&keys, &values, 100);
+        assert_eq!(cache.capacity(), 64);
+        let (keys, values) = random_states(1, 60);
+        cache.update(&keys, &values);
+        assert_eq!(cache.capacity(), 100);
+        assert_eq!(cache.len(), 70);
+
+        //    Initial sequences longer than the maximum length are kept entirely
+        let (keys, values) = random_states(1, 40);
+        let cache = KVCache::new(&keys, &values, 32);
+        assert_eq!(cache.capacity(), 40);
+        assert_eq!(cache.len(), 40);
+    }
+
+    #[test]
+    fn test_reserve() {
+        let (keys, values) = random_states(1, 10);
+        let mut cache = KVCache::new(&keys, &values, 150);
+        cache.reserve(200);
+        assert_eq!(cache.capacity(), 150);
+        assert_eq!(cache.keys(), keys);
+        assert_eq!(cache.values(), values);
+
+        //    Buffers are not reallocated while filling the reserved positions
+        let buffer_ptr = cache.keys.data_ptr();
+        for _ in 0..140 {
+            let (keys, values) = random_states(1, 1);
+            cache.update(&keys, &values);
+        }
+        assert_eq!(cache.len(), 150);
+        assert_eq!(cache.capacity(), 150);
+        assert_eq!(cache.keys.data_ptr(), buffer_ptr);
+
+        //    Reserving less than the current capacity has no effect
+        cache.reserve(20);
+        assert_eq!(cache.capacity(), 150);
+    }
+
+    #[test]
+    fn test_views() {
+        let (keys, values) = random_states(2, 5);
+        let mut cache = KVCache::new(&keys, &values, 1024);
+        assert_eq!(cache.keys().size(), vec![2, 2, 5, 4]);
+        assert_eq!(cache.values().size(), vec![2, 2, 5, 4]);
+        assert!(cache.capacity() > cache.len());
+
+        let (new_keys, new_values) = random_states(2, 3);
+        let (cached_keys, cached_values) = cache.update(&new_keys, &new_values);
+        assert_eq!(cached_keys.size(), vec![2, 2, 8, 4]);
+        assert_eq!(cached_values.size(), vec![2, 2, 8, 4]);
+        assert_eq!(cached_keys.narrow(2, 5, 3), new_keys);
+        assert_eq!(cached_values.narrow(2, 5, 3), new_values);
+    }
+
+    #[test]
+    fn test_reorder() {
+        let (keys, values) = random_states(3, 5);
+        let mut cache = KVCache::new(&keys, &values, 1024);
+
+        //    Reordering with the same number of beams swaps the buffers
+        let beam_indices = Tensor::from_slice(&[2i64, 0, 0]);
+        cache.reorder_cache(&beam_indices);
+        let expected_keys = keys.index_select(0, &beam_indices);
+        let expected_values = values.index_select(0, &beam_indices);
+        assert_eq!(cache.keys(), expected_keys);
+        assert_eq!(cache.values(), expected_values);
+
+        let beam_indices = Tensor::from_slice(&[1i64, 2, 0]);
+        cache.reorder_cache(&beam_indices);
+        let expected_keys = expected_keys.index_select(0, &beam_indices);
+        let expected_values = expected_values.index_select(0, &beam_indices);
+        assert_eq!(cache.keys(), expected_keys);
+        assert_eq!(cache.values(), expected_values);
+
+        //    Positions appended after a reordering follow the new order
+        let (new_keys, new_values) = random_states(3, 1);
+        let (cached_keys, cached_values) = cache.update(&new_keys, &new_values);
+        assert_eq!(cached_keys, Tensor::cat(&[expected_keys, new_keys], 2));
+        assert_eq!(
+            cached_values,
+            Tensor::cat(&[expected_values, new_values], 2)
+        );
+
+        //    Reordering to a different batch size (e.g. finished sequences removed)
+        let batch_indices = Tensor::from_slice(&[1i64]);
+        cache.reorder_cache(&batch_indices);
+        assert_eq!(cache.keys().size(), vec![1, 2, 6, 4]);
+        assert_eq!(cache.keys(), cached_keys.index_select(0, &batch_indices));
+    }
+}
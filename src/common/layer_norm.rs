@@ -0,0 +1,68 @@
+// Copyright 2019 Laurent Mazare.
+// Copyright 2019 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::borrow::Borrow;
+use tch::nn::{LayerNormConfig, Module, Path};
+use tch::{nn, Kind, Tensor};
+
+/// # Layer normalization
+/// Wraps `tch::nn::LayerNorm` (same variables), computing the normalization of half and bfloat16 precision
+/// inputs in single precision before casting the output back to the input type.
+#[derive(Debug)]
+pub struct LayerNorm {
+    layer_norm: nn::LayerNorm,
+    eps: f64,
+    cudnn_enabled: bool,
+}
+
+impl LayerNorm {
+    pub fn new<'a, T: Borrow<Path<'a>>>(
+        vs: T,
+        normalized_shape: Vec<i64>,
+        config: LayerNormConfig,
+    ) -> LayerNorm {
+        LayerNorm {
+            layer_norm: nn::layer_norm(vs, normalized_shape, config),
+            eps: config.eps,
+            cudnn_enabled: config.cudnn_enabled,
+        }
+    }
+}
+
+impl Module for LayerNorm {
+    fn forward(&self, xs: &Tensor) -> Tensor {
+        match xs.kind() {
+            Kind::Half | Kind::BFloat16 => {
+                let ws = self
+                    .layer_norm
+                    .ws
+                    .as_ref()
+                    .map(|ws| ws.to_kind(Kind::Float));
+                let bs = self
+                    .layer_norm
+                    .bs
+                    .as_ref()
+                    .map(|bs| bs.to_kind(Kind::Float));
+                xs.to_kind(Kind::Float)
+                    .layer_norm(
+                        self.layer_norm.normalized_shape.as_slice(),
+                        ws.as_ref(),
+                        bs.as_ref(),
+                        self.eps,
+                        self.cudnn_enabled,
+                    )
+                    .to_kind(xs.kind())
+            }
+            _ => self.layer_norm.forward(xs),
+        }
+    }
+}
@@ -1,12 +1,18 @@
 pub(crate) mod activations;
+pub(crate) mod attention;
 pub mod config;
+pub mod crf;
 pub(crate) mod dropout;
 pub(crate) mod embeddings;
 pub mod error;
 pub(crate) mod kind;
+pub(crate) mod kv_cache;
+pub(crate) mod layer_norm;
 pub(crate) mod linear;
+pub mod quantization;
 pub mod resources;
 pub(crate) mod summary;
+pub mod tensor_parallel;
 
 pub use activations::Activation;
 pub use config::Config;
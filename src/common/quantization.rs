@@ -0,0 +1,934 @@
+// Copyright 2019 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Weight-only quantization
+//!
+//! The linear layers of the GPT2, GPT-Neo and GPT-J decoders can hold their weights as 8-bit integers (symmetric,
+//! one scale per output channel) or as 4-bit integers (asymmetric, with a scale and zero point per group of input
+//! channels, packed two per byte). The weights are dequantized to the data type of the activations before each
+//! matrix multiplication, so that only the weights of the layer being computed are held in full precision.
+//! Embeddings, layer norms and the language model head are not quantized.
+//!
+//! Quantization is enabled by the `quantization` field of the model configuration. The model then expects
+//! quantized weights, stored in the following variables for each quantized layer (instead of `weight`):
+//! - `qweight`: quantized weights, `Int8` of shape (*out*, *in*) for 8-bit quantization, `Uint8` of shape
+//!   (*out*, *in* / 2) for 4-bit quantization (the even input channel in the low 4 bits)
+//! - `scales`: scales of shape (*out*, 1) for 8-bit quantization, (*out*, *in* / *group_size*) for 4-bit quantization
+//! - `zeros`: `Uint8` zero points of shape (*out*, *in* / *group_size*), for 4-bit quantization only
+//! - `bias`: full precision bias, if the layer has one
+//!
+//! The GPT2 `GPTConv1D` layers store their weights transposed, of shape (*in*, *out*): their quantized variables
+//! are transposed accordingly, with the input dimension first.
+//!
+//! This layout is specific to this crate. Checkpoints quantized with GPTQ or AWQ pack 8 values per `int32` in their
+//! `qweight` and `qzeros` tensors: they are converted when loaded with `load_packed_int4_weights` into a model
+//! configured with the same group size. GPTQ checkpoints with activation reordering (`desc_act`) are not supported.
+//!
+//! Pre-quantized weights are loaded with the other model weights. They can be generated from full precision weights
+//! with `load_and_quantize_weights` and saved with `VarStore::save`:
+//!
+//! ```no_run
+//! # fn main() -> anyhow::Result<()> {
+//! use rust_bert::gpt_j::{GptJConfig, GptJLMHeadModel};
+//! use rust_bert::quantization::{load_and_quantize_weights, WeightQuantization};
+//! use rust_bert::resources::LocalResource;
+//! use rust_bert::Config;
+//! use std::path::PathBuf;
+//! use tch::{nn, Device};
+//!
+//! let mut config = GptJConfig::from_file("path/to/config.json");
+//! config.quantization = Some(WeightQuantization::Int4 { group_size: 128 });
+//! let mut vs = nn::VarStore::new(Device::Cpu);
+//! let _model = GptJLMHeadModel::new(vs.root(), &config);
+//! let weights = LocalResource::from(PathBuf::from("path/to/rust_model.ot"));
+//! load_and_quantize_weights(&weights, &mut vs)?;
+//! vs.save("path/to/rust_model_int4.ot")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::common::error::RustBertError;
+use crate::resources::{with_named_weights, ResourceProvider};
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use tch::nn::{Module, VarStore};
+use tch::{nn, Kind, Tensor};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+/// # Weight-only quantization scheme
+pub enum WeightQuantization {
+    /// Symmetric 8-bit quantization with one scale per output channel
+    Int8,
+    /// Asymmetric 4-bit quantization with a scale and zero point per group of `group_size` input channels
+    Int4 { group_size: i64 },
+}
+
+/// Creates an integer variable. Variables are created as floating point tensors by `nn::Path` and converted in place.
+fn integer_variable(p: &nn::Path, name: &str, dims: &[i64], kind: Kind) -> Tensor {
+    let mut variable = p.zeros_no_train(name, dims);
+    variable.set_data(&variable.to_kind(kind));
+    variable
+}
+
+#[derive(Debug)]
+/// # Linear layer with quantized weights
+/// Weights are dequantized to the data type of the input before each matrix multiplication.
+pub struct QuantizedLinear {
+    qweight: Tensor,
+    scales: Tensor,
+    zeros: Option<Tensor>,
+    bias: Option<Tensor>,
+    quantization: WeightQuantization,
+    transposed: bool,
+}
+
+impl QuantizedLinear {
+    /// Build a new `QuantizedLinear`
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Variable store path for the root of the layer
+    /// * `in_dim` - Input dimension
+    /// * `out_dim` - Output dimension
+    /// * `bias` - Flag indicating if the layer has a bias
+    /// * `transposed` - Flag indicating if the weights are stored with the input dimension first (`GPTConv1D` layout)
+    /// * `quantization` - `WeightQuantization` scheme
+    pub fn new<'p, P>(
+        p: P,
+        in_dim: i64,
+        out_dim: i64,
+        bias: bool,
+        transposed: bool,
+        quantization: WeightQuantization,
+    ) -> QuantizedLinear
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+        let oriented = |out_size: i64, in_size: i64| {
+            if transposed {
+                [in_size, out_size]
+            } else {
+                [out_size, in_size]
+            }
+        };
+
+        let (qweight, scales, zeros) = match quantization {
+            WeightQuantization::Int8 => (
+                integer_variable(p, "qweight", &oriented(out_dim, in_dim), Kind::Int8),
+                p.ones_no_train("scales", &oriented(out_dim, 1)),
+                None,
+            ),
+            WeightQuantization::Int4 { group_size } => {
+                assert!(
+                    group_size % 2 == 0 && in_dim % group_size == 0,
+                    "Input dimension {} not a multiple of the (even) quantization group size {}",
+                    in_dim,
+                    group_size
+                );
+                let groups_shape = oriented(out_dim, in_dim / group_size);
+                (
+                    integer_variable(p, "qweight", &oriented(out_dim, in_dim / 2), Kind::Uint8),
+                    p.ones_no_train("scales", &groups_shape),
+                    Some(integer_variable(p, "zeros", &groups_shape, Kind::Uint8)),
+                )
+            }
+        };
+        let bias = if bias {
+            Some(p.zeros("bias", &[out_dim]))
+        } else {
+            None
+        };
+
+        QuantizedLinear {
+            qweight,
+            scales,
+            zeros,
+            bias,
+            quantization,
+            transposed,
+        }
+    }
+
+    /// Dequantizes the weights of the layer
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Data type of the dequantized weights
+    ///
+    /// # Returns
+    ///
+    /// * `Tensor` of shape (*out*, *in*), or (*in*, *out*) for transposed layers
+    pub fn dequantize(&self, kind: Kind) -> Tensor {
+        match self.quantization {
+            WeightQuantization::Int8 => self.qweight.to_kind(kind) * self.scales.to_kind(kind),
+            WeightQuantization::Int4 { group_size } => {
+                let packed_shape = self.qweight.size();
+                // Input channels are unpacked and grouped along the same dimension
+                let (group_dim, full_shape, grouped_shape) = if self.transposed {
+                    let (in_dim, out_dim) = (packed_shape[0] * 2, packed_shape[1]);
+                    (
+                        1,
+                        [in_dim, out_dim],
+                        [in_dim / group_size, group_size, out_dim],
+                    )
+                } else {
+                    let (out_dim, in_dim) = (packed_shape[0], packed_shape[1] * 2);
+                    (
+                        2,
+                        [out_dim, in_dim],
+                        [out_dim, in_dim / group_size, group_size],
+                    )
+                };
+                let unpacked = Tensor::stack(
+                    &[
+                        self.qweight.bitwise_and(15),
+                        self.qweight.bitwise_right_shift_tensor_scalar(4),
+                    ],
+                    group_dim,
+                )
+                .to_kind(kind)
+                .view(grouped_shape);
+                let zeros = self
+                    .zeros
+                    .as_ref()
+                    .unwrap()
+                    .to_kind(kind)
+                    .unsqueeze(group_dim);
+                let scales = self.scales.to_kind(kind).unsqueeze(group_dim);
+                ((unpacked - zeros) * scales).view(full_shape)
+            }
+        }
+    }
+
+    /// Forward pass without the bias of the layer
+    pub fn forward_no_bias(&self, xs: &Tensor) -> Tensor {
+        let weight = self.dequantize(xs.kind());
+        if self.transposed {
+            xs.matmul(&weight)
+        } else {
+            xs.matmul(&weight.tr())
+        }
+    }
+}
+
+impl Module for QuantizedLinear {
+    fn forward(&self, xs: &Tensor) -> Tensor {
+        let output = self.forward_no_bias(xs);
+        match &self.bias {
+            Some(bias) => output + bias,
+            None => output,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// # Linear layer with full precision or quantized weights
+pub enum LinearLayer {
+    Linear(nn::Linear),
+    Quantized(QuantizedLinear),
+}
+
+impl LinearLayer {
+    /// Forward pass without the bias of the layer
+    pub fn forward_no_bias(&self, xs: &Tensor) -> Tensor {
+        match self {
+            LinearLayer::Linear(linear) => xs.matmul(&linear.ws.tr()),
+            LinearLayer::Quantized(linear) => linear.forward_no_bias(xs),
+        }
+    }
+}
+
+impl Module for LinearLayer {
+    fn forward(&self, xs: &Tensor) -> Tensor {
+        match self {
+            LinearLayer::Linear(linear) => linear.forward(xs),
+            LinearLayer::Quantized(linear) => linear.forward(xs),
+        }
+    }
+}
+
+/// Creates a linear layer (weights of shape (*out*, *in*)), quantized if a `WeightQuantization` is provided
+pub(crate) fn linear<'p, P>(
+    p: P,
+    in_dim: i64,
+    out_dim: i64,
+    bias: bool,
+    quantization: Option<WeightQuantization>,
+) -> LinearLayer
+where
+    P: Borrow<nn::Path<'p>>,
+{
+    match quantization {
+        Some(quantization) => LinearLayer::Quantized(QuantizedLinear::new(
+            p,
+            in_dim,
+            out_dim,
+            bias,
+            false,
+            quantization,
+        )),
+        None => LinearLayer::Linear(nn::linear(
+            p,
+            in_dim,
+            out_dim,
+            nn::LinearConfig {
+                bias,
+                ..Default::default()
+            },
+        )),
+    }
+}
+
+/// Quantizes full precision weights
+///
+/// # Arguments
+///
+/// * `weight` - Full precision weights of shape (*out*, *in*), or (*in*, *out*) if `transposed`
+/// * `quantization` - `WeightQuantization` scheme
+/// * `transposed` - Flag indicating if the weights are stored with the input dimension first (`GPTConv1D` layout)
+///
+/// # Returns
+///
+/// * `(Tensor, Tensor, Option<Tensor>)` quantized weights, scales and zero points (4-bit quantization only), in the
+///   layout of the `QuantizedLinear` variables
+pub fn quantize(
+    weight: &Tensor,
+    quantization: WeightQuantization,
+    transposed: bool,
+) -> Result<(Tensor, Tensor, Option<Tensor>), RustBertError> {
+    let weight = weight.to_kind(Kind::Float);
+    let shape = weight.size();
+    if shape.len() != 2 {
+        return Err(RustBertError::ValueError(format!(
+            "Only 2-dimensional weights can be quantized, got shape {:?}",
+            shape
+        )));
+    }
+    let (in_dim_index, out_dim) = if transposed {
+        (0, shape[1])
+    } else {
+        (1, shape[0])
+    };
+    let in_dim = shape[in_dim_index];
+    match quantization {
+        WeightQuantization::Int8 => {
+            let scales = weight
+                .abs()
+                .amax([in_dim_index as i64], true)
+                .clamp_min(1e-8)
+                / 127.0;
+            let qweight = (&weight / &scales)
+                .round()
+                .clamp(-127, 127)
+                .to_kind(Kind::Int8);
+            Ok((qweight, scales, None))
+        }
+        WeightQuantization::Int4 { group_size } => {
+            if group_size % 2 != 0 || in_dim % group_size != 0 {
+                return Err(RustBertError::ValueError(format!(
+                    "Input dimension {} not a multiple of the (even) quantization group size {}",
+                    in_dim, group_size
+                )));
+            }
+            let n_groups = in_dim / group_size;
+            let (grouped_shape, group_dim) = if transposed {
+                ([n_groups, group_size, out_dim], 1)
+            } else {
+                ([out_dim, n_groups, group_size], 2)
+            };
+            let grouped = weight.view(grouped_shape);
+            let min = grouped.amin([group_dim], true).clamp_max(0.0);
+            let max = grouped.amax([group_dim], true).clamp_min(0.0);
+            let scales = ((max - &min) / 15.0).clamp_min(1e-8);
+            let zeros = (-min / &scales).round().clamp(0, 15);
+            let qweight = (grouped / &scales + &zeros).round().clamp(0, 15);
+
+            // Pairs of consecutive input channels are packed in a byte, the even channel in the low 4 bits
+            let (paired_shape, pair_dim) = if transposed {
+                ([in_dim / 2, 2, out_dim], 1)
+            } else {
+                ([out_dim, in_dim / 2, 2], 2)
+            };
+            let qweight = qweight.view(paired_shape);
+            let qweight = (qweight.select(pair_dim, 0) + qweight.select(pair_dim, 1) * 16)
+                .to_kind(Kind::Uint8);
+            Ok((
+                qweight,
+                scales.squeeze_dim(group_dim),
+                Some(zeros.squeeze_dim(group_dim).to_kind(Kind::Uint8)),
+            ))
+        }
+    }
+}
+
+/// Loads full precision weights into a model created with a `WeightQuantization` configuration.
+/// The weights of the quantized layers are quantized when loaded, the other variables are loaded as-is.
+/// The resulting `VarStore` can be saved to generate pre-quantized weights for the model.
+///
+/// # Arguments
+///
+/// * `rp` - `ResourceProvider` for the full precision weights
+/// * `vs` - `VarStore` of the quantized model
+pub fn load_and_quantize_weights(
+    rp: &(impl ResourceProvider + ?Sized),
+    vs: &mut VarStore,
+) -> Result<(), RustBertError> {
+    with_named_weights(rp, |get_weights| {
+        let mut variables = vs.variables();
+        let mut names = variables.keys().cloned().collect::<Vec<String>>();
+        names.sort();
+        tch::no_grad(|| -> Result<(), RustBertError> {
+            for name in names.iter() {
+                if let Some(prefix) = name.strip_suffix(".qweight") {
+                    let weight = get_weights(&format!("{}.weight", prefix))?;
+                    let (quantization, transposed) = quantization_of(&variables, prefix, &weight)?;
+                    let (qweight, scales, zeros) = quantize(&weight, quantization, transposed)?;
+                    let mut quantized_variables = vec![("qweight", qweight), ("scales", scales)];
+                    if let Some(zeros) = zeros {
+                        quantized_variables.push(("zeros", zeros));
+                    }
+                    for (suffix, value) in quantized_variables {
+                        let name = format!("{}.{}", prefix, suffix);
+                        copy_variable(variables.get_mut(&name).unwrap(), &value, &name)?;
+                    }
+                } else if !is_quantization_parameter(&variables, name) {
+                    copy_variable(variables.get_mut(name).unwrap(), &get_weights(name)?, name)?;
+                }
+            }
+            Ok(())
+        })
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// # Layout of 4-bit checkpoints packing 8 quantized values per `int32`
+pub enum PackedInt4Format {
+    /// GPTQ layout: `qweight` of shape (*in* / 8, *out*) packing consecutive input channels, `qzeros` of shape
+    /// (*in* / *group_size*, *out* / 8) packing consecutive output channels and storing the zero points minus one
+    Gptq,
+    /// AWQ (GEMM) layout: `qweight` of shape (*in*, *out* / 8) and `qzeros` of shape (*in* / *group_size*, *out* / 8),
+    /// packing the output channels in the order 0, 2, 4, 6, 1, 3, 5, 7
+    Awq,
+}
+
+/// Position of the 4-bit value of each channel in the `int32` packing it
+const GPTQ_NIBBLES: [i64; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+const AWQ_NIBBLES: [i64; 8] = [0, 4, 1, 5, 2, 6, 3, 7];
+
+/// Loads 4-bit weights quantized with GPTQ or AWQ into a model created with a `WeightQuantization::Int4`
+/// configuration with the same group size. The packed weights and zero points of the quantized layers are
+/// converted to the layout of this crate, the other variables are loaded as-is. GPTQ checkpoints using activation
+/// reordering (`desc_act`, with a non-sequential `g_idx`) are not supported.
+/// The resulting `VarStore` can be saved to generate pre-quantized weights for the model.
+///
+/// # Arguments
+///
+/// * `rp` - `ResourceProvider` for the GPTQ or AWQ weights
+/// * `vs` - `VarStore` of the quantized model
+/// * `format` - `PackedInt4Format` of the weights
+pub fn load_packed_int4_weights(
+    rp: &(impl ResourceProvider + ?Sized),
+    vs: &mut VarStore,
+    format: PackedInt4Format,
+) -> Result<(), RustBertError> {
+    with_named_weights(rp, |get_weights| {
+        let mut variables = vs.variables();
+        let mut names = variables.keys().cloned().collect::<Vec<String>>();
+        names.sort();
+        tch::no_grad(|| -> Result<(), RustBertError> {
+            for name in names.iter() {
+                if let Some(prefix) = name.strip_suffix(".qweight") {
+                    let (qweight, scales, zeros) =
+                        unpack_int4_layer(&mut *get_weights, prefix, format)?;
+                    let quantized_variables =
+                        to_quantized_layout(&variables, prefix, &qweight, &scales, &zeros)?;
+                    for (suffix, value) in quantized_variables {
+                        let name = format!("{}.{}", prefix, suffix);
+                        copy_variable(variables.get_mut(&name).unwrap(), &value, &name)?;
+                    }
+                } else if !is_quantization_parameter(&variables, name) {
+                    copy_variable(variables.get_mut(name).unwrap(), &get_weights(name)?, name)?;
+                }
+            }
+            Ok(())
+        })
+    })
+}
+
+/// Unpacks the 8 4-bit values of each `int32` along `dim`, `nibbles[i]` holding the i-th value
+fn unpack_int32(packed: &Tensor, dim: usize, nibbles: [i64; 8]) -> Tensor {
+    let mut shifts_shape = vec![1i64; packed.dim() + 1];
+    shifts_shape[dim + 1] = 8;
+    let mut unpacked_shape = packed.size();
+    unpacked_shape[dim] *= 8;
+    let shifts = (Tensor::from_slice(&nibbles) * 4)
+        .to_device(packed.device())
+        .view(shifts_shape.as_slice());
+    packed
+        .to_kind(Kind::Int64)
+        .unsqueeze(dim as i64 + 1)
+        .bitwise_right_shift(&shifts)
+        .bitwise_and(15)
+        .reshape(unpacked_shape.as_slice())
+}
+
+/// Reads and unpacks the weights of a GPTQ or AWQ quantized layer
+///
+/// # Returns
+///
+/// * `(Tensor, Tensor, Tensor)` quantized weights of shape (*in*, *out*), scales and zero points of shape
+///   (*in* / *group_size*, *out*)
+fn unpack_int4_layer(
+    get_weights: &mut dyn FnMut(&str) -> Result<Tensor, RustBertError>,
+    prefix: &str,
+    format: PackedInt4Format,
+) -> Result<(Tensor, Tensor, Tensor), RustBertError> {
+    let qweight = get_weights(&format!("{}.qweight", prefix))?;
+    let qzeros = get_weights(&format!("{}.qzeros", prefix))?;
+    let scales = get_weights(&format!("{}.scales", prefix))?;
+    if qweight.kind() != Kind::Int || qzeros.kind() != Kind::Int {
+        return Err(RustBertError::ValueError(format!(
+            "Expected int32 packed weights and zero points for {}, found {:?} and {:?}",
+            prefix,
+            qweight.kind(),
+            qzeros.kind()
+        )));
+    }
+    let (qweight, zeros) = match format {
+        PackedInt4Format::Gptq => (
+            unpack_int32(&qweight, 0, GPTQ_NIBBLES),
+            unpack_int32(&qzeros, 1, GPTQ_NIBBLES) + 1,
+        ),
+        PackedInt4Format::Awq => (
+            unpack_int32(&qweight, 1, AWQ_NIBBLES),
+            unpack_int32(&qzeros, 1, AWQ_NIBBLES),
+        ),
+    };
+    let (weight_shape, scales_shape) = (qweight.size(), scales.size());
+    if scales_shape.len() != 2
+        || zeros.size() != scales_shape
+        || weight_shape[1] != scales_shape[1]
+        || scales_shape[0] == 0
+        || weight_shape[0] % scales_shape[0] != 0
+    {
+        return Err(RustBertError::ValueError(format!(
+            "Inconsistent shapes for {}: unpacked weights {:?}, zero points {:?} and scales {:?}",
+            prefix,
+            weight_shape,
+            zeros.size(),
+            scales_shape
+        )));
+    }
+    if format == PackedInt4Format::Gptq {
+        // `g_idx` maps input channels to their group, it is absent from checkpoints with sequential groups
+        if let Ok(g_idx) = get_weights(&format!("{}.g_idx", prefix)) {
+            let group_size = weight_shape[0] / scales_shape[0];
+            let g_idx = Vec::<i64>::try_from(g_idx.to_kind(Kind::Int64))?;
+            if g_idx.len() as i64 != weight_shape[0]
+                || g_idx
+                    .iter()
+                    .enumerate()
+                    .any(|(channel, group)| *group != channel as i64 / group_size)
+            {
+                return Err(RustBertError::ValueError(format!(
+                    "Activation reordering (non-sequential g_idx) of {} is not supported",
+                    prefix
+                )));
+            }
+        }
+    }
+    Ok((qweight, scales, zeros))
+}
+
+/// Converts unpacked 4-bit weights of shape (*in*, *out*), scales and zero points of shape
+/// (*in* / *group_size*, *out*) to the layout of the `QuantizedLinear` variables of `prefix`
+fn to_quantized_layout(
+    variables: &HashMap<String, Tensor>,
+    prefix: &str,
+    qweight: &Tensor,
+    scales: &Tensor,
+    zeros: &Tensor,
+) -> Result<[(&'static str, Tensor); 3], RustBertError> {
+    let qweight_variable = &variables[&format!("{}.qweight", prefix)];
+    let scales_variable = variables
+        .get(&format!("{}.scales", prefix))
+        .ok_or_else(|| {
+            RustBertError::ValueError(format!("Missing quantization scales for {}", prefix))
+        })?;
+    if qweight_variable.kind() != Kind::Uint8 {
+        return Err(RustBertError::InvalidConfigurationError(format!(
+            "GPTQ and AWQ weights can only be loaded in layers with a 4-bit quantization, found {:?} weights for {}",
+            qweight_variable.kind(),
+            prefix
+        )));
+    }
+    let (in_dim, out_dim) = (qweight.size()[0], scales.size()[1]);
+    let n_groups = scales.size()[0];
+    let transposed = qweight_variable.size() != [out_dim, in_dim / 2]
+        || scales_variable.size() != [out_dim, n_groups];
+
+    // Pairs of consecutive input channels are packed in a byte, the even channel in the low 4 bits
+    let (qweight, scales, zeros, paired_shape, pair_dim) = if transposed {
+        (
+            qweight.shallow_clone(),
+            scales.shallow_clone(),
+            zeros.shallow_clone(),
+            [in_dim / 2, 2, out_dim],
+            1,
+        )
+    } else {
+        (
+            qweight.tr(),
+            scales.tr(),
+            zeros.tr(),
+            [out_dim, in_dim / 2, 2],
+            2,
+        )
+    };
+    let qweight = qweight.reshape(paired_shape);
+    let qweight =
+        (qweight.select(pair_dim, 0) + qweight.select(pair_dim, 1) * 16).to_kind(Kind::Uint8);
+    Ok([
+        ("qweight", qweight),
+        ("scales", scales),
+        ("zeros", zeros.to_kind(Kind::Uint8)),
+    ])
+}
+
+fn copy_variable(variable: &mut Tensor, value: &Tensor, name: &str) -> Result<(), RustBertError> {
+    if variable.size() != value.size() {
+        return Err(RustBertError::ValueError(format!(
+            "Shape mismatch for {}: expected {:?}, found {:?}",
+            name,
+            variable.size(),
+            value.size()
+        )));
+    }
+    Ok(variable.f_copy_(value)?)
+}
+
+/// Scales and zero points of quantized layers are computed with their quantized weights
+fn is_quantization_parameter(variables: &HashMap<String, Tensor>, name: &str) -> bool {
+    [".scales", ".zeros"]
+        .iter()
+        .filter_map(|suffix| name.strip_suffix(suffix))
+        .any(|prefix| variables.contains_key(&format!("{}.qweight", prefix)))
+}
+
+/// Infers the quantization scheme and layout of a quantized layer from the shapes of its variables
+fn quantization_of(
+    variables: &HashMap<String, Tensor>,
+    prefix: &str,
+    weight: &Tensor,
+) -> Result<(WeightQuantization, bool), RustBertError> {
+    let qweight = &variables[&format!("{}.qweight", prefix)];
+    let scales = variables
+        .get(&format!("{}.scales", prefix))
+        .ok_or_else(|| {
+            RustBertError::ValueError(format!("Missing quantization scales for {}", prefix))
+        })?;
+    let (weight_shape, scales_shape) = (weight.size(), scales.size());
+    match qweight.kind() {
+        Kind::Int8 => Ok((WeightQuantization::Int8, scales_shape[0] == 1)),
+        Kind::Uint8 => {
+            let transposed = qweight.size()[0] * 2 == weight_shape[0];
+            let (in_dim, n_groups) = if transposed {
+                (weight_shape[0], scales_shape[0])
+            } else {
+                (weight_shape[1], scales_shape[1])
+            };
+            Ok((
+                WeightQuantization::Int4 {
+                    group_size: in_dim / n_groups,
+                },
+                transposed,
+            ))
+        }
+        kind => Err(RustBertError::ValueError(format!(
+            "Unsupported data type {:?} for the quantized weights of {}",
+            kind, prefix
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::resources::BufferResource;
+    use tch::Device;
+
+    fn round_trip(weight: &Tensor, quantization: WeightQuantization, transposed: bool) -> Tensor {
+        let (qweight, scales, zeros) = quantize(weight, quantization, transposed).unwrap();
+        let layer = QuantizedLinear {
+            qweight,
+            scales,
+            zeros,
+            bias: None,
+            quantization,
+            transposed,
+        };
+        layer.dequantize(Kind::Float)
+    }
+
+    #[test]
+    fn test_quantized_layout() {
+        let weight = Tensor::randn([8, 32], (Kind::Float, Device::Cpu));
+
+        let (qweight, scales, zeros) = quantize(&weight, WeightQuantization::Int8, false).unwrap();
+        assert_eq!((qweight.size(), qweight.kind()), (vec![8, 32], Kind::Int8));
+        assert_eq!(scales.size(), vec![8, 1]);
+        assert!(zeros.is_none());
+
+        let (qweight, scales, zeros) =
+            quantize(&weight.tr(), WeightQuantization::Int8, true).unwrap();
+        assert_eq!((qweight.size(), qweight.kind()), (vec![32, 8], Kind::Int8));
+        assert_eq!(scales.size(), vec![1, 8]);
+        assert!(zeros.is_none());
+
+        let int4 = WeightQuantization::Int4 { group_size: 8 };
+        let (qweight, scales, zeros) = quantize(&weight, int4, false).unwrap();
+        assert_eq!((qweight.size(), qweight.kind()), (vec![8, 16], Kind::Uint8));
+        assert_eq!(scales.size(), vec![8, 4]);
+        let zeros = zeros.unwrap();
+        assert_eq!((zeros.size(), zeros.kind()), (vec![8, 4], Kind::Uint8));
+
+        let (qweight, scales, zeros) = quantize(&weight.tr(), int4, true).unwrap();
+        assert_eq!((qweight.size(), qweight.kind()), (vec![16, 8], Kind::Uint8));
+        assert_eq!(scales.size(), vec![4, 8]);
+        assert_eq!(zeros.unwrap().size(), vec![4, 8]);
+
+        assert!(quantize(&weight, WeightQuantization::Int4 { group_size: 6 }, false).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        tch::manual_seed(0);
+        let weight = Tensor::randn([8, 32], (Kind::Float, Device::Cpu));
+        for quantization in [
+            WeightQuantization::Int8,
+            WeightQuantization::Int4 { group_size: 8 },
+        ] {
+            let (_, scales, _) = quantize(&weight, quantization, false).unwrap();
+            //    Rounding to the nearest quantization level
+            let max_error = scales.max().double_value(&[]) / 2.0 + 1e-6;
+
+            let dequantized = round_trip(&weight, quantization, false);
+            assert_eq!(dequantized.size(), weight.size());
+            assert!((&dequantized - &weight).abs().max().double_value(&[]) <= max_error);
+
+            //    Transposed layers hold the same quantized weights
+            let dequantized_transposed = round_trip(&weight.tr(), quantization, true);
+            assert_eq!(dequantized_transposed.size(), vec![32, 8]);
+            assert_eq!(dequantized_transposed.tr(), dequantized);
+        }
+    }
+
+    #[test]
+    fn test_int4_packing() {
+        //    Weights on the quantization levels (scale 1, zero point 8) are restored exactly
+        let levels = Tensor::arange(16, (Kind::Float, Device::Cpu)) - 8.0;
+        let weight = Tensor::cat(&[levels.flip([0]), levels.shallow_clone()], 0).view([2, 16]);
+        let quantization = WeightQuantization::Int4 { group_size: 16 };
+        let (qweight, _, zeros) = quantize(&weight, quantization, false).unwrap();
+        assert_eq!(zeros.unwrap(), Tensor::from_slice(&[8u8, 8]).view([2, 1]));
+        //    Even input channels in the low 4 bits
+        assert_eq!(qweight.int64_value(&[1, 0]), 1 << 4);
+        assert_eq!(round_trip(&weight, quantization, false), weight);
+        assert_eq!(round_trip(&weight.tr(), quantization, true), weight.tr());
+    }
+
+    /// Packs 4-bit values in `int32`, `nibbles[i]` holding the i-th of 8 consecutive values along `dim`
+    fn pack_int32(values: &[Vec<i64>], dim: usize, nibbles: [i64; 8]) -> Tensor {
+        let (rows, cols) = (values.len(), values[0].len());
+        let (packed_rows, packed_cols) = if dim == 0 {
+            (rows / 8, cols)
+        } else {
+            (rows, cols / 8)
+        };
+        let mut packed = vec![0u32; packed_rows * packed_cols];
+        for (row, row_values) in values.iter().enumerate() {
+            for (col, value) in row_values.iter().enumerate() {
+                let (packed_index, index) = if dim == 0 {
+                    ((row / 8) * packed_cols + col, row % 8)
+                } else {
+                    (row * packed_cols + col / 8, col % 8)
+                };
+                packed[packed_index] |= (*value as u32) << (4 * nibbles[index]);
+            }
+        }
+        let packed = packed
+            .into_iter()
+            .map(|value| value as i32)
+            .collect::<Vec<i32>>();
+        Tensor::from_slice(&packed).view([packed_rows as i64, packed_cols as i64])
+    }
+
+    fn to_tensor(values: &[Vec<i64>]) -> Tensor {
+        Tensor::from_slice(&values.concat()).view([values.len() as i64, values[0].len() as i64])
+    }
+
+    fn save_to_buffer(weights: &[(&str, Tensor)]) -> BufferResource {
+        let mut buffer = Vec::new();
+        Tensor::save_multi_to_stream(weights, &mut buffer).unwrap();
+        BufferResource::from(buffer)
+    }
+
+    #[test]
+    fn test_load_packed_int4_weights() {
+        tch::manual_seed(0);
+        let (in_dim, out_dim, group_size) = (32, 16, 8);
+        let n_groups = in_dim / group_size;
+        let values = (0..in_dim)
+            .map(|k| (0..out_dim).map(|n| (3 * k + 5 * n) % 16).collect())
+            .collect::<Vec<Vec<i64>>>();
+        let zeros = (0..n_groups)
+            .map(|g| (0..out_dim).map(|n| (3 * g + n) % 15 + 1).collect())
+            .collect::<Vec<Vec<i64>>>();
+        let gptq_zeros = zeros
+            .iter()
+            .map(|row| row.iter().map(|zero| zero - 1).collect())
+            .collect::<Vec<Vec<i64>>>();
+        //    Half precision scales, as stored by GPTQ and AWQ
+        let scales = (Tensor::rand([n_groups, out_dim], (Kind::Float, Device::Cpu)) + 0.1)
+            .to_kind(Kind::Half)
+            .to_kind(Kind::Float);
+        let bias = Tensor::randn([out_dim], (Kind::Float, Device::Cpu));
+        let g_idx = (0..in_dim as i32)
+            .map(|k| k / group_size as i32)
+            .collect::<Vec<i32>>();
+        //    Dequantized weights of shape (in, out)
+        let expected = (to_tensor(&values)
+            - to_tensor(&zeros).repeat_interleave_self_int(group_size, 0, None))
+            * scales.repeat_interleave_self_int(group_size, 0, None);
+
+        for format in [PackedInt4Format::Gptq, PackedInt4Format::Awq] {
+            let mut weights = match format {
+                PackedInt4Format::Gptq => vec![
+                    ("layer.qweight", pack_int32(&values, 0, GPTQ_NIBBLES)),
+                    ("layer.qzeros", pack_int32(&gptq_zeros, 1, GPTQ_NIBBLES)),
+                    ("layer.g_idx", Tensor::from_slice(&g_idx)),
+                ],
+                PackedInt4Format::Awq => vec![
+                    ("layer.qweight", pack_int32(&values, 1, AWQ_NIBBLES)),
+                    ("layer.qzeros", pack_int32(&zeros, 1, AWQ_NIBBLES)),
+                ],
+            };
+            weights.push(("layer.scales", scales.to_kind(Kind::Half)));
+            weights.push(("layer.bias", bias.shallow_clone()));
+            let weights = save_to_buffer(&weights);
+
+            for transposed in [false, true] {
+                let mut vs = VarStore::new(Device::Cpu);
+                let layer = QuantizedLinear::new(
+                    vs.root() / "layer",
+                    in_dim,
+                    out_dim,
+                    true,
+                    transposed,
+                    WeightQuantization::Int4 { group_size },
+                );
+                load_packed_int4_weights(&weights, &mut vs, format).unwrap();
+
+                let dequantized = layer.dequantize(Kind::Float);
+                if transposed {
+                    assert_eq!(dequantized, expected);
+                } else {
+                    assert_eq!(dequantized, expected.tr());
+                }
+                assert_eq!(layer.bias.as_ref().unwrap(), &bias);
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_packed_int4_weights_errors() {
+        let (in_dim, out_dim, group_size) = (16, 8, 8);
+        let values = vec![vec![1i64; out_dim as usize]; in_dim as usize];
+        let zeros = vec![vec![7i64; out_dim as usize]; 2];
+        let weights = |g_idx: &[i32]| {
+            save_to_buffer(&[
+                ("layer.qweight", pack_int32(&values, 0, GPTQ_NIBBLES)),
+                ("layer.qzeros", pack_int32(&zeros, 1, GPTQ_NIBBLES)),
+                (
+                    "layer.scales",
+                    Tensor::ones([2, out_dim], (Kind::Float, Device::Cpu)),
+                ),
+                ("layer.g_idx", Tensor::from_slice(g_idx)),
+            ])
+        };
+        let sequential = (0..in_dim as i32)
+            .map(|k| k / group_size as i32)
+            .collect::<Vec<i32>>();
+        let mut reordered = sequential.clone();
+        reordered.swap(0, in_dim as usize - 1);
+
+        let int4_model = || {
+            let vs = VarStore::new(Device::Cpu);
+            let layer = QuantizedLinear::new(
+                vs.root() / "layer",
+                in_dim,
+                out_dim,
+                false,
+                false,
+                WeightQuantization::Int4 { group_size },
+            );
+            (vs, layer)
+        };
+        let (mut vs, layer) = int4_model();
+        load_packed_int4_weights(&weights(&sequential), &mut vs, PackedInt4Format::Gptq).unwrap();
+        //    All the weights are 1, with a zero point of 8 (stored as 7)
+        assert_eq!(
+            layer.dequantize(Kind::Float),
+            Tensor::full([out_dim, in_dim], -7.0, (Kind::Float, Device::Cpu))
+        );
+
+        //    Activation reordering
+        let (mut vs, _) = int4_model();
+        assert!(
+            load_packed_int4_weights(&weights(&reordered), &mut vs, PackedInt4Format::Gptq)
+                .is_err()
+        );
+
+        //    Group size mismatch
+        let mut vs = VarStore::new(Device::Cpu);
+        let _ = QuantizedLinear::new(
+            vs.root() / "layer",
+            in_dim,
+            out_dim,
+            false,
+            false,
+            WeightQuantization::Int4 { group_size: 4 },
+        );
+        assert!(
+            load_packed_int4_weights(&weights(&sequential), &mut vs, PackedInt4Format::Gptq)
+                .is_err()
+        );
+
+        //    8-bit quantized model
+        let mut vs = VarStore::new(Device::Cpu);
+        let _ = QuantizedLinear::new(
+            vs.root() / "layer",
+            in_dim,
+            out_dim,
+            false,
+            false,
+            WeightQuantization::Int8,
+        );
+        assert!(
+            load_packed_int4_weights(&weights(&sequential), &mut vs, PackedInt4Format::Gptq)
+                .is_err()
+        );
+    }
+}
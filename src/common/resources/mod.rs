@@ -18,18 +18,29 @@
 //! `get_local_path`, allowing to reference the resource file location regardless if it is a remote
 //! or local resource. Default implementations for a number of `RemoteResources` are available as
 //! pre-trained models in each model module.
+//!
+//! Model weights are read from `.ot` files (saved by `tch::nn::VarStore`) or from files in the
+//! [safetensors](https://github.com/huggingface/safetensors) format. Safetensors files are memory-mapped:
+//! the model variables use the mapped file data directly when their data type matches the stored weights and
+//! the model is loaded on the CPU. The weights are then paged in on demand and processes serving the same model share
+//! these pages, instead of each holding a copy of the weights in memory. Safetensors weights can be generated by
+//! passing `--safetensors` to `utils/convert_model.py`.
 
 mod buffer;
 mod local;
+pub(crate) mod safetensors;
 
 use crate::common::error::RustBertError;
+use crate::resources::safetensors::{is_safetensors, is_safetensors_file, SafeTensors};
 pub use buffer::BufferResource;
 pub use local::LocalResource;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::ops::DerefMut;
 use std::path::PathBuf;
 use std::sync::RwLockWriteGuard;
 use tch::nn::VarStore;
+use tch::Tensor;
 
 pub enum Resource<'a> {
     PathBuf(PathBuf),
@@ -80,17 +91,72 @@ impl<T: ResourceProvider + ?Sized> ResourceProvider for Box<T> {
     }
 }
 
-/// Load the provided `VarStore` with model weights from the provided `ResourceProvider`
+/// Load the provided `VarStore` with model weights from the provided `ResourceProvider`.
+/// Weights in the safetensors format are memory-mapped when read from a file, and copied tensor by tensor
+/// when read from a buffer.
 pub fn load_weights(
     rp: &(impl ResourceProvider + ?Sized),
     vs: &mut VarStore,
 ) -> Result<(), RustBertError> {
     match rp.get_resource()? {
         Resource::Buffer(mut data) => {
-            vs.load_from_stream(std::io::Cursor::new(data.deref_mut()))?;
-            Ok(())
+            if is_safetensors(&data) {
+                SafeTensors::from_buffer(&data)?.load(vs)
+            } else {
+                vs.load_from_stream(std::io::Cursor::new(data.deref_mut()))?;
+                Ok(())
+            }
+        }
+        Resource::PathBuf(path) => {
+            if is_safetensors_file(&path)? {
+                SafeTensors::mmap(&path)?.load(vs)
+            } else {
+                Ok(vs.load(path)?)
+            }
         }
-        Resource::PathBuf(path) => Ok(vs.load(path)?),
+    }
+}
+
+/// Provides access by name to the weights of a `ResourceProvider`, for loading schemes not supported by
+/// `VarStore::load` (e.g. quantization or sharding of the weights). Safetensors weights are memory-mapped and
+/// returned as views over the file, other weights are read in full before calling `f`.
+pub(crate) fn with_named_weights<T, F>(
+    rp: &(impl ResourceProvider + ?Sized),
+    f: F,
+) -> Result<T, RustBertError>
+where
+    F: FnOnce(&mut dyn FnMut(&str) -> Result<Tensor, RustBertError>) -> Result<T, RustBertError>,
+{
+    match rp.get_resource()? {
+        Resource::Buffer(mut data) => {
+            if is_safetensors(&data) {
+                let weights = SafeTensors::from_buffer(&data)?;
+                f(&mut |name| weights.tensor(name))
+            } else {
+                let weights =
+                    Tensor::load_multi_from_stream(std::io::Cursor::new(data.deref_mut()))?;
+                f(&mut named_tensors_getter(weights))
+            }
+        }
+        Resource::PathBuf(path) => {
+            if is_safetensors_file(&path)? {
+                let weights = SafeTensors::mmap(&path)?;
+                f(&mut |name| weights.tensor(name))
+            } else {
+                f(&mut named_tensors_getter(Tensor::load_multi(path)?))
+            }
+        }
+    }
+}
+
+fn named_tensors_getter(
+    weights: Vec<(String, Tensor)>,
+) -> impl FnMut(&str) -> Result<Tensor, RustBertError> {
+    let mut weights = weights.into_iter().collect::<HashMap<String, Tensor>>();
+    move |name| {
+        weights.remove(name).ok_or_else(|| {
+            RustBertError::TchError(format!("Cannot find {} in the model weights", name))
+        })
     }
 }
 
@@ -0,0 +1,208 @@
+use crate::common::error::RustBertError;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use tch::nn::VarStore;
+use tch::{Device, Kind, Tensor};
+
+/// Metadata entry of a tensor in the header of a safetensors file
+#[derive(Debug, Deserialize)]
+struct TensorInfo {
+    dtype: String,
+    shape: Vec<i64>,
+    data_offsets: (usize, usize),
+}
+
+enum Data<'a> {
+    Mapped(Tensor),
+    Buffer(&'a [u8]),
+}
+
+/// # Weights stored in the safetensors format
+/// The file starts with the size of a JSON header (8 bytes, little endian) followed by the header, mapping each
+/// tensor name to its data type, shape and byte range in the data section following the header.
+///
+/// Files are memory-mapped: the tensors are views over the mapped file and pages are only read when accessed.
+/// The mapping is private (copy-on-write), so that processes loading the same file share its pages.
+pub(crate) struct SafeTensors<'a> {
+    tensors: HashMap<String, TensorInfo>,
+    data_offset: usize,
+    data: Data<'a>,
+}
+
+/// Checks if the start of a weights file matches the safetensors format (as opposed to the zip archives
+/// produced by `VarStore::save`)
+pub(crate) fn is_safetensors(prefix: &[u8]) -> bool {
+    prefix.len() > 8 && !prefix.starts_with(b"PK") && prefix[8] == b'{'
+}
+
+/// Checks if the file at `path` is a safetensors file
+pub(crate) fn is_safetensors_file(path: &Path) -> Result<bool, RustBertError> {
+    let mut prefix = Vec::with_capacity(9);
+    File::open(path)?.take(9).read_to_end(&mut prefix)?;
+    Ok(is_safetensors(&prefix))
+}
+
+fn parse_header(header: &[u8]) -> Result<HashMap<String, TensorInfo>, RustBertError> {
+    let invalid_header = |error: serde_json::Error| {
+        RustBertError::IOError(format!("Invalid safetensors header: {}", error))
+    };
+    let mut entries: HashMap<String, serde_json::Value> =
+        serde_json::from_slice(header).map_err(invalid_header)?;
+    entries.remove("__metadata__");
+    entries
+        .into_iter()
+        .map(|(name, value)| Ok((name, serde_json::from_value(value).map_err(invalid_header)?)))
+        .collect()
+}
+
+fn read_header_size(bytes: &[u8], total_size: usize) -> Result<usize, RustBertError> {
+    let header_size = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize;
+    if header_size > total_size.saturating_sub(8) {
+        return Err(RustBertError::IOError(format!(
+            "Invalid safetensors header size {} for a file of {} bytes",
+            header_size, total_size
+        )));
+    }
+    Ok(header_size)
+}
+
+fn kind_from_dtype(dtype: &str) -> Result<Kind, RustBertError> {
+    Ok(match dtype {
+        "F64" => Kind::Double,
+        "F32" => Kind::Float,
+        "F16" => Kind::Half,
+        "BF16" => Kind::BFloat16,
+        "I64" => Kind::Int64,
+        "I32" => Kind::Int,
+        "I16" => Kind::Int16,
+        "I8" => Kind::Int8,
+        "U8" => Kind::Uint8,
+        "BOOL" => Kind::Bool,
+        _ => {
+            return Err(RustBertError::ValueError(format!(
+                "Unsupported safetensors data type {}",
+                dtype
+            )))
+        }
+    })
+}
+
+impl SafeTensors<'static> {
+    /// Memory-maps a safetensors file
+    pub(crate) fn mmap(path: &Path) -> Result<SafeTensors<'static>, RustBertError> {
+        let file_size = std::fs::metadata(path)?.len() as usize;
+        let mut file = File::open(path)?;
+        let mut header_size = [0u8; 8];
+        file.read_exact(&mut header_size)?;
+        let header_size = read_header_size(&header_size, file_size)?;
+        let mut header = vec![0u8; header_size];
+        file.read_exact(&mut header)?;
+        let tensors = parse_header(&header)?;
+
+        let path = path.to_str().ok_or_else(|| {
+            RustBertError::IOError(format!("Invalid weights path {}", path.display()))
+        })?;
+        let mapped =
+            Tensor::f_from_file(path, false, file_size as i64, (Kind::Uint8, Device::Cpu))?;
+        Ok(SafeTensors {
+            tensors,
+            data_offset: 8 + header_size,
+            data: Data::Mapped(mapped),
+        })
+    }
+}
+
+impl<'a> SafeTensors<'a> {
+    /// Reads the header of safetensors data held in memory. Tensors are copied from the buffer when accessed.
+    pub(crate) fn from_buffer(buffer: &'a [u8]) -> Result<SafeTensors<'a>, RustBertError> {
+        if buffer.len() < 8 {
+            return Err(RustBertError::IOError(
+                "Invalid safetensors data: missing header".to_string(),
+            ));
+        }
+        let header_size = read_header_size(buffer, buffer.len())?;
+        let tensors = parse_header(&buffer[8..8 + header_size])?;
+        Ok(SafeTensors {
+            tensors,
+            data_offset: 8 + header_size,
+            data: Data::Buffer(buffer),
+        })
+    }
+
+    /// Returns a tensor by name, as a view over the mapped file or as a copy of the buffer data
+    pub(crate) fn tensor(&self, name: &str) -> Result<Tensor, RustBertError> {
+        let info = self.tensors.get(name).ok_or_else(|| {
+            RustBertError::TchError(format!("Cannot find {} in the model weights", name))
+        })?;
+        let kind = kind_from_dtype(&info.dtype)?;
+        let (start, end) = info.data_offsets;
+        let expected_size = info.shape.iter().product::<i64>() as usize * kind.elt_size_in_bytes();
+        if start > end || end - start != expected_size {
+            return Err(RustBertError::IOError(format!(
+                "Invalid data offsets for {}: {:?} for shape {:?} and data type {}",
+                name, info.data_offsets, info.shape, info.dtype
+            )));
+        }
+        let (start, end) = (self.data_offset + start, self.data_offset + end);
+        match &self.data {
+            Data::Mapped(mapped) => {
+                if end as i64 > mapped.size()[0] {
+                    return Err(RustBertError::IOError(format!(
+                        "Data of {} extends past the end of the weights file",
+                        name
+                    )));
+                }
+                let bytes = mapped.f_narrow(0, start as i64, (end - start) as i64)?;
+                // Reinterpreting the bytes requires the offset of the tensor to be aligned on its element size,
+                // misaligned tensors are copied to a new buffer
+                let tensor = match bytes.f_view_dtype(kind) {
+                    Ok(tensor) => tensor,
+                    Err(_) => bytes.copy().f_view_dtype(kind)?,
+                };
+                Ok(tensor.f_view(info.shape.as_slice())?)
+            }
+            Data::Buffer(buffer) => {
+                if end > buffer.len() {
+                    return Err(RustBertError::IOError(format!(
+                        "Data of {} extends past the end of the weights buffer",
+                        name
+                    )));
+                }
+                Ok(Tensor::f_from_data_size(
+                    &buffer[start..end],
+                    info.shape.as_slice(),
+                    kind,
+                )?)
+            }
+        }
+    }
+
+    /// Loads the variables of a `VarStore`. Variables with the data type of the stored tensors and located on the CPU
+    /// directly use the tensor data (sharing the memory mapping), other variables receive a converted copy.
+    pub(crate) fn load(&self, vs: &mut VarStore) -> Result<(), RustBertError> {
+        let mut variables = vs.variables();
+        tch::no_grad(|| -> Result<(), RustBertError> {
+            for (name, variable) in variables.iter_mut() {
+                let tensor = self.tensor(name)?;
+                if tensor.size() != variable.size() {
+                    return Err(RustBertError::TchError(format!(
+                        "Shape mismatch for {}: expected {:?}, found {:?} in the model weights",
+                        name,
+                        variable.size(),
+                        tensor.size()
+                    )));
+                }
+                if tensor.kind() == variable.kind() && variable.device() == Device::Cpu {
+                    variable.f_set_data(&tensor)?;
+                } else {
+                    variable.f_copy_(&tensor)?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
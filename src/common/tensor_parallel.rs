@@ -0,0 +1,465 @@
+// Copyright 2019 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Tensor parallelism
+//!
+//! Utilities to split a model across several workers (threads or processes, possibly on different machines).
+//! Each worker holds a shard of the attention heads and feed-forward columns of every layer, and the partial
+//! layer outputs are summed across workers with an all-reduce at layer boundaries. All workers must run the same
+//! computation in lockstep (same inputs and generation options, and the same random seed when sampling).
+//!
+//! Two process groups are available:
+//! - `LocalProcessGroup`: workers are threads of the same process, exchanging tensors through shared memory
+//! - `TcpProcessGroup`: workers are processes exchanging tensors over TCP sockets. The worker of rank 0 listens on
+//!   the given address and sums the partial outputs of the other workers.
+//!
+//! Sharded weights should be provided as safetensors files: they are memory-mapped and each worker only copies its
+//! shard, so that a model larger than the memory of a single node (e.g. GPT-J 6B) can be split across modest nodes.
+//! Weights in the `.ot` format are read in full by every worker before being sharded.
+//!
+//! ```no_run
+//! # fn main() -> anyhow::Result<()> {
+//! use rust_bert::gpt_j::GptJGenerator;
+//! use rust_bert::pipelines::generation_utils::{GenerateConfig, LanguageGenerator};
+//! use rust_bert::tensor_parallel::TcpProcessGroup;
+//! use std::sync::Arc;
+//!
+//! let rank: usize = std::env::args().nth(1).unwrap().parse()?;
+//! let process_group = Arc::new(TcpProcessGroup::new(rank, 2, "127.0.0.1:29500")?);
+//! let generator = GptJGenerator::new_sharded(GenerateConfig::default(), process_group)?;
+//! let output = generator.generate(Some(&["It was a very nice and sunny"]), None);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::common::error::RustBertError;
+use crate::resources::{with_named_weights, ResourceProvider};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+use tch::nn::VarStore;
+use tch::{Device, Tensor};
+
+/// Maximum time a worker waits for the worker of rank 0 to accept connections
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// # Communication group of the workers sharing a model
+pub trait ProcessGroup: Send + Sync {
+    /// Index of the current worker, between 0 and `world_size - 1`
+    fn rank(&self) -> usize;
+
+    /// Number of workers in the group
+    fn world_size(&self) -> usize;
+
+    /// Sums a tensor element-wise across all workers of the group. Every worker must call this method
+    /// with a tensor of the same shape and type, and the tensor is replaced in place by the sum.
+    fn all_reduce(&self, tensor: &mut Tensor) -> Result<(), RustBertError>;
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>, RustBertError> {
+    mutex.lock().map_err(|_| {
+        RustBertError::ValueError("A worker of the process group panicked".to_string())
+    })
+}
+
+#[derive(Default)]
+struct LocalReduction {
+    sum: Option<Tensor>,
+    reads: usize,
+}
+
+struct LocalGroupState {
+    barrier: Barrier,
+    // Consecutive reductions alternate between two buffers, so that a worker may start the next
+    // reduction before the slowest worker has read the result of the current one.
+    reductions: [Mutex<LocalReduction>; 2],
+}
+
+/// # Process group for workers running in threads of the same process
+/// Partial results are summed in a buffer shared by all threads.
+pub struct LocalProcessGroup {
+    rank: usize,
+    world_size: usize,
+    round: AtomicUsize,
+    state: Arc<LocalGroupState>,
+}
+
+impl LocalProcessGroup {
+    /// Creates the handles of a group of `world_size` workers. Each handle should be moved to a different thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `world_size` - Number of workers in the group
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<LocalProcessGroup>` handles of the workers, ordered by rank
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::tensor_parallel::{LocalProcessGroup, ProcessGroup};
+    /// use std::thread;
+    /// use tch::Tensor;
+    ///
+    /// let workers = LocalProcessGroup::new_group(2)
+    ///     .into_iter()
+    ///     .map(|process_group| {
+    ///         thread::spawn(move || {
+    ///             let mut tensor = Tensor::from(process_group.rank() as f64);
+    ///             process_group.all_reduce(&mut tensor).unwrap();
+    ///             tensor
+    ///         })
+    ///     })
+    ///     .collect::<Vec<_>>();
+    /// ```
+    pub fn new_group(world_size: usize) -> Vec<LocalProcessGroup> {
+        let state = Arc::new(LocalGroupState {
+            barrier: Barrier::new(world_size),
+            reductions: Default::default(),
+        });
+        (0..world_size)
+            .map(|rank| LocalProcessGroup {
+                rank,
+                world_size,
+                round: AtomicUsize::new(0),
+                state: state.clone(),
+            })
+            .collect()
+    }
+}
+
+impl ProcessGroup for LocalProcessGroup {
+    fn rank(&self) -> usize {
+        self.rank
+    }
+
+    fn world_size(&self) -> usize {
+        self.world_size
+    }
+
+    fn all_reduce(&self, tensor: &mut Tensor) -> Result<(), RustBertError> {
+        if self.world_size == 1 {
+            return Ok(());
+        }
+        let round = self.round.fetch_add(1, Ordering::Relaxed);
+        let reduction = &self.state.reductions[round % 2];
+        {
+            let mut reduction = lock(reduction)?;
+            match reduction.sum.as_mut() {
+                Some(sum) => {
+                    let _ = sum.f_add_(&tensor.to_device(sum.device()))?;
+                }
+                None => reduction.sum = Some(tensor.copy()),
+            }
+        }
+        self.state.barrier.wait();
+
+        let mut reduction = lock(reduction)?;
+        tensor.f_copy_(reduction.sum.as_ref().unwrap())?;
+        reduction.reads += 1;
+        if reduction.reads == self.world_size {
+            *reduction = LocalReduction::default();
+        }
+        Ok(())
+    }
+}
+
+/// # Process group for workers running in separate processes
+/// Workers communicate over TCP and can run on the same machine or on different machines. The worker of rank 0
+/// accepts a connection from every other worker, sums their partial results and sends back the total.
+pub struct TcpProcessGroup {
+    rank: usize,
+    world_size: usize,
+    // Rank 0 holds the connections to the workers of rank 1 to `world_size - 1` (in order),
+    // other workers hold their connection to rank 0.
+    streams: Mutex<Vec<TcpStream>>,
+}
+
+impl TcpProcessGroup {
+    /// Creates the process group, blocking until all workers are connected. The worker of rank 0 listens on
+    /// `address`, other workers connect to it (retrying until it is available).
+    ///
+    /// # Arguments
+    ///
+    /// * `rank` - Index of the current worker, between 0 and `world_size - 1`
+    /// * `world_size` - Number of workers in the group
+    /// * `address` - Address of the worker of rank 0 (e.g. `"127.0.0.1:29500"`)
+    ///
+    /// # Returns
+    ///
+    /// * `TcpProcessGroup` connected process group
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::tensor_parallel::TcpProcessGroup;
+    ///
+    /// let process_group = TcpProcessGroup::new(0, 2, "127.0.0.1:29500")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new<A: ToSocketAddrs>(
+        rank: usize,
+        world_size: usize,
+        address: A,
+    ) -> Result<TcpProcessGroup, RustBertError> {
+        if rank >= world_size {
+            return Err(RustBertError::InvalidConfigurationError(format!(
+                "Worker rank ({}) must be lower than the world size ({})",
+                rank, world_size
+            )));
+        }
+        if rank == 0 {
+            return Self::from_listener(TcpListener::bind(address)?, world_size);
+        }
+        let addresses = address.to_socket_addrs()?.collect::<Vec<_>>();
+        let start = Instant::now();
+        let mut stream = loop {
+            match TcpStream::connect(addresses.as_slice()) {
+                Ok(stream) => break stream,
+                Err(error) => {
+                    if start.elapsed() > CONNECTION_TIMEOUT {
+                        return Err(error.into());
+                    }
+                    thread::sleep(Duration::from_millis(100));
+                }
+            }
+        };
+        stream.set_nodelay(true)?;
+        stream.write_all(&(rank as u64).to_le_bytes())?;
+        stream.write_all(&(world_size as u64).to_le_bytes())?;
+        Ok(TcpProcessGroup {
+            rank,
+            world_size,
+            streams: Mutex::new(vec![stream]),
+        })
+    }
+
+    /// Creates the process group of the worker of rank 0 from a bound listener, blocking until all other workers
+    /// are connected. This allows binding an address chosen by the system (port 0) and communicating it to the
+    /// other workers before they connect.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - Listener accepting the connections of the other workers
+    /// * `world_size` - Number of workers in the group
+    ///
+    /// # Returns
+    ///
+    /// * `TcpProcessGroup` connected process group of the worker of rank 0
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::tensor_parallel::TcpProcessGroup;
+    /// use std::net::TcpListener;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:0")?;
+    /// println!("Listening on {}", listener.local_addr()?);
+    /// let process_group = TcpProcessGroup::from_listener(listener, 2)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_listener(
+        listener: TcpListener,
+        world_size: usize,
+    ) -> Result<TcpProcessGroup, RustBertError> {
+        let mut streams: Vec<Option<TcpStream>> = (1..world_size).map(|_| None).collect();
+        for _ in 1..world_size {
+            let (mut stream, _) = listener.accept()?;
+            stream.set_nodelay(true)?;
+            let worker_rank = read_u64(&mut stream)? as usize;
+            let worker_world_size = read_u64(&mut stream)? as usize;
+            if worker_world_size != world_size
+                || worker_rank == 0
+                || worker_rank >= world_size
+                || streams[worker_rank - 1].is_some()
+            {
+                return Err(RustBertError::InvalidConfigurationError(format!(
+                    "Invalid worker of rank {} for a world size of {} (expected world size {})",
+                    worker_rank, worker_world_size, world_size
+                )));
+            }
+            streams[worker_rank - 1] = Some(stream);
+        }
+        Ok(TcpProcessGroup {
+            rank: 0,
+            world_size,
+            streams: Mutex::new(streams.into_iter().flatten().collect()),
+        })
+    }
+}
+
+fn read_u64(stream: &mut TcpStream) -> Result<u64, RustBertError> {
+    let mut bytes = [0u8; 8];
+    stream.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn write_message(stream: &mut TcpStream, buffer: &[u8]) -> Result<(), RustBertError> {
+    stream.write_all(&(buffer.len() as u64).to_le_bytes())?;
+    stream.write_all(buffer)?;
+    Ok(())
+}
+
+fn read_message(stream: &mut TcpStream, buffer: &mut [u8]) -> Result<(), RustBertError> {
+    let length = read_u64(stream)? as usize;
+    if length != buffer.len() {
+        return Err(RustBertError::ValueError(format!(
+            "Received a tensor of {} bytes, expected {} bytes",
+            length,
+            buffer.len()
+        )));
+    }
+    stream.read_exact(buffer)?;
+    Ok(())
+}
+
+impl ProcessGroup for TcpProcessGroup {
+    fn rank(&self) -> usize {
+        self.rank
+    }
+
+    fn world_size(&self) -> usize {
+        self.world_size
+    }
+
+    fn all_reduce(&self, tensor: &mut Tensor) -> Result<(), RustBertError> {
+        if self.world_size == 1 {
+            return Ok(());
+        }
+        let mut streams = lock(&self.streams)?;
+        let (size, kind) = (tensor.size(), tensor.kind());
+        let numel = tensor.numel();
+        let mut buffer = vec![0u8; numel * kind.elt_size_in_bytes()];
+        let local = tensor.f_to(Device::Cpu)?.f_contiguous()?;
+
+        let sum = if self.rank == 0 {
+            let mut sum = local;
+            for stream in streams.iter_mut() {
+                read_message(stream, &mut buffer)?;
+                let _ = sum.f_add_(&Tensor::f_from_data_size(&buffer, &size, kind)?)?;
+            }
+            sum.f_copy_data_u8(&mut buffer, numel)?;
+            for stream in streams.iter_mut() {
+                write_message(stream, &buffer)?;
+            }
+            sum
+        } else {
+            local.f_copy_data_u8(&mut buffer, numel)?;
+            write_message(&mut streams[0], &buffer)?;
+            read_message(&mut streams[0], &mut buffer)?;
+            Tensor::f_from_data_size(&buffer, &size, kind)?
+        };
+        tensor.f_copy_(&sum)?;
+        Ok(())
+    }
+}
+
+/// Returns the shard of `weights` for a variable of shape `shape`. Weights are split into `world_size`
+/// contiguous chunks along the single dimension that differs between the full and the sharded shapes.
+fn shard_weights(
+    weights: &Tensor,
+    shape: &[i64],
+    rank: usize,
+    world_size: usize,
+) -> Option<Tensor> {
+    let full_shape = weights.size();
+    if full_shape == shape {
+        return Some(weights.shallow_clone());
+    }
+    if full_shape.len() != shape.len() {
+        return None;
+    }
+    let sharded_dims = full_shape
+        .iter()
+        .zip(shape)
+        .enumerate()
+        .filter(|(_, (full_dim, dim))| full_dim != dim)
+        .map(|(index, _)| index)
+        .collect::<Vec<usize>>();
+    match sharded_dims.as_slice() {
+        &[dim] if shape[dim] * world_size as i64 == full_shape[dim] => {
+            Some(weights.narrow(dim as i64, rank as i64 * shape[dim], shape[dim]))
+        }
+        _ => None,
+    }
+}
+
+/// Loads the shard of the model weights held by a worker into a `VarStore` built for a sharded model.
+/// Variables with the same shape as in the weights file are loaded as-is (replicated across workers), variables
+/// differing from the weights file along a single dimension receive the chunk of index `rank` along this dimension.
+///
+/// Safetensors files are memory-mapped and only the shard of the current worker is copied. For `.ot` files, the
+/// full weights file is read in memory by every worker before the shards are copied (the weights of other workers
+/// are then released): the peak memory use of each worker is the size of the full model. Models that do not fit in
+/// the memory of a single worker should therefore be converted to safetensors (e.g. with `utils/convert_model.py --safetensors`).
+///
+/// # Arguments
+///
+/// * `rp` - `ResourceProvider` for the weights of the full (non-sharded) model
+/// * `vs` - `VarStore` of the sharded model
+/// * `rank` - Index of the current worker
+/// * `world_size` - Number of workers the model is split across
+pub fn load_sharded_weights(
+    rp: &(impl ResourceProvider + ?Sized),
+    vs: &mut VarStore,
+    rank: usize,
+    world_size: usize,
+) -> Result<(), RustBertError> {
+    with_named_weights(rp, |get_weights| {
+        let mut variables = vs.variables();
+        tch::no_grad(|| -> Result<(), RustBertError> {
+            for (name, variable) in variables.iter_mut() {
+                let full_weights = get_weights(name)?;
+                let weights_shard = shard_weights(&full_weights, &variable.size(), rank, world_size)
+                    .ok_or_else(|| {
+                        RustBertError::ValueError(format!(
+                            "Shape mismatch for {}: cannot shard weights of shape {:?} into {} variables of shape {:?}",
+                            name,
+                            full_weights.size(),
+                            world_size,
+                            variable.size()
+                        ))
+                    })?;
+                variable.f_copy_(&weights_shard)?;
+            }
+            Ok(())
+        })
+    })
+}
+
+/// Validates that a dimension can be split evenly across the workers of a process group
+pub(crate) fn shard_size(
+    dimension: i64,
+    process_group: Option<&dyn ProcessGroup>,
+) -> Result<i64, RustBertError> {
+    match process_group {
+        Some(process_group) => {
+            let world_size = process_group.world_size() as i64;
+            if dimension % world_size != 0 {
+                Err(RustBertError::InvalidConfigurationError(format!(
+                    "Dimension {} cannot be split evenly across {} workers",
+                    dimension, world_size
+                )))
+            } else {
+                Ok(dimension / world_size)
+            }
+        }
+        None => Ok(dimension),
+    }
+}
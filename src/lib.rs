@@ -55,10 +55,11 @@
 //!DistilBERT|✅|✅|✅| | | |✅| ✅|
 //!MobileBERT|✅|✅|✅| | | |✅| |
 //!DeBERTa|✅|✅|✅| | | |✅| |
-//!DeBERTa (v2)|✅|✅|✅| | | |✅| |
+//!DeBERTa (v2)|✅|✅|✅| | | |✅| ✅|
 //!FNet|✅|✅|✅| | | |✅| |
 //!BERT|✅|✅|✅| | | |✅| ✅|
 //!RoBERTa|✅|✅|✅| | | |✅| ✅|
+//!MPNet| | | | | | | | ✅|
 //!GPT| | | |✅ | | | |  |
 //!GPT2| | | |✅ | | | |  |
 //!GPT-Neo| | | |✅ | | | | |
@@ -731,11 +732,14 @@ mod common;
 pub mod models;
 pub mod pipelines;
 
+pub use common::crf;
 pub use common::error::RustBertError;
+pub use common::quantization;
 pub use common::resources;
+pub use common::tensor_parallel;
 pub use common::{Activation, Config};
 pub use models::{
     albert, bart, bert, deberta, deberta_v2, distilbert, electra, fnet, gpt2, gpt_j, gpt_neo,
-    longformer, longt5, m2m_100, marian, mbart, mobilebert, nllb, openai_gpt, pegasus, prophetnet,
-    reformer, roberta, t5, xlnet,
+    longformer, longt5, m2m_100, marian, mbart, mobilebert, mpnet, nllb, openai_gpt, pegasus,
+    prophetnet, reformer, roberta, t5, xlnet,
 };
@@ -15,6 +15,7 @@ use crate::albert::encoder::AlbertTransformer;
 use crate::common::activations::Activation;
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::get_shape_and_device_from_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
 use crate::{albert::embeddings::AlbertEmbeddings, common::activations::TensorFunction};
 use crate::{Config, RustBertError};
 use serde::{Deserialize, Serialize};
@@ -295,7 +296,7 @@ impl AlbertModel {
 }
 
 pub struct AlbertMLMHead {
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dense: nn::Linear,
     decoder: nn::Linear,
     activation: TensorFunction,
@@ -313,7 +314,7 @@ impl AlbertMLMHead {
             eps: layer_norm_eps,
             ..Default::default()
         };
-        let layer_norm = nn::layer_norm(
+        let layer_norm = LayerNorm::new(
             p / "LayerNorm",
             vec![config.embedding_size],
             layer_norm_config,
@@ -12,7 +12,9 @@
 // limitations under the License.
 
 use crate::albert::AlbertConfig;
+use crate::common::attention::scaled_dot_product_attention;
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use std::borrow::Borrow;
 use tch::{nn, Tensor};
 
@@ -27,7 +29,7 @@ pub struct AlbertSelfAttention {
     key: nn::Linear,
     value: nn::Linear,
     dense: nn::Linear,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
 }
 
 impl AlbertSelfAttention {
@@ -75,7 +77,7 @@ impl AlbertSelfAttention {
             ..Default::default()
         };
         let layer_norm =
-            nn::layer_norm(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
 
         AlbertSelfAttention {
             num_attention_heads: config.num_attention_heads,
@@ -110,19 +112,18 @@ impl AlbertSelfAttention {
         let query_layer =
             self.split_heads(input_ids.apply(&self.query), bs, self.attention_head_size);
 
-        let query_layer: Tensor = query_layer / (self.attention_head_size as f64).sqrt();
-
-        let scores = if let Some(mask) = mask {
-            query_layer.matmul(&key_layer.transpose(-1, -2)) + mask
-        } else {
-            query_layer.matmul(&key_layer.transpose(-1, -2))
-        };
-
-        let weights = scores
-            .softmax(-1, scores.kind())
-            .apply_t(&self.dropout, train);
+        let (context, weights) = scaled_dot_product_attention(
+            &query_layer,
+            &key_layer,
+            &value_layer,
+            mask,
+            None,
+            &self.dropout,
+            self.output_attentions,
+            train,
+        );
 
-        let context = weights.matmul(&value_layer).transpose(1, 2).contiguous();
+        let context = context.transpose(1, 2).contiguous();
 
         let w = self.dense.ws.transpose(0, 1).view((
             self.num_attention_heads,
@@ -134,10 +135,6 @@ impl AlbertSelfAttention {
             + self.dense.bs.as_ref().unwrap();
         let context = (input_ids + context.apply_t(&self.dropout, train)).apply(&self.layer_norm);
 
-        if !self.output_attentions {
-            (context, None)
-        } else {
-            (context, Some(weights))
-        }
+        (context, weights)
     }
 }
@@ -14,6 +14,7 @@
 use crate::albert::AlbertConfig;
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
 use crate::RustBertError;
 use std::borrow::Borrow;
 use tch::nn::{embedding, EmbeddingConfig};
@@ -25,7 +26,7 @@ pub struct AlbertEmbeddings {
     word_embeddings: nn::Embedding,
     position_embeddings: nn::Embedding,
     token_type_embeddings: nn::Embedding,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: Dropout,
 }
 
@@ -67,7 +68,7 @@ impl AlbertEmbeddings {
             eps: layer_norm_eps,
             ..Default::default()
         };
-        let layer_norm: nn::LayerNorm = nn::layer_norm(
+        let layer_norm: LayerNorm = LayerNorm::new(
             p / "LayerNorm",
             vec![config.embedding_size],
             layer_norm_config,
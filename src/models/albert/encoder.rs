@@ -12,13 +12,14 @@
 // limitations under the License.
 
 use crate::albert::AlbertConfig;
+use crate::common::layer_norm::LayerNorm;
 use crate::{albert::attention::AlbertSelfAttention, common::activations::TensorFunction};
 use std::borrow::{Borrow, BorrowMut};
 use tch::{nn, Tensor};
 
 pub struct AlbertLayer {
     attention: AlbertSelfAttention,
-    full_layer_layer_norm: nn::LayerNorm,
+    full_layer_layer_norm: LayerNorm,
     ffn: nn::Linear,
     ffn_output: nn::Linear,
     activation: TensorFunction,
@@ -38,7 +39,7 @@ impl AlbertLayer {
             eps: layer_norm_eps,
             ..Default::default()
         };
-        let full_layer_layer_norm = nn::layer_norm(
+        let full_layer_layer_norm = LayerNorm::new(
             p / "full_layer_layer_norm",
             vec![config.hidden_size],
             layer_norm_config,
@@ -11,6 +11,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::common::attention::scaled_dot_product_attention;
 use crate::common::dropout::Dropout;
 use std::borrow::Borrow;
 use tch::{nn, Tensor};
@@ -46,7 +47,6 @@ pub struct BartAttention {
     num_heads: i64,
     head_dim: i64,
     dropout: Dropout,
-    scaling: f64,
     encoder_decoder_attention: bool,
     output_attentions: bool,
     k_proj: nn::Linear,
@@ -77,14 +77,12 @@ impl BartAttention {
         let out_proj = nn::linear(p / "out_proj", embed_dim, embed_dim, Default::default());
 
         let head_dim = embed_dim / num_heads;
-        let scaling = (head_dim as f64).powf(-0.5);
         let dropout = Dropout::new(dropout);
 
         BartAttention {
             num_heads,
             head_dim,
             dropout,
-            scaling,
             encoder_decoder_attention,
             output_attentions,
             k_proj,
@@ -111,7 +109,7 @@ impl BartAttention {
     ) -> (Tensor, Option<Tensor>, Option<LayerState>) {
         let (bs, target_length, embed_dim) = hidden_states.size3().unwrap();
 
-        let query_states = hidden_states.apply(&self.q_proj) * self.scaling;
+        let query_states = hidden_states.apply(&self.q_proj);
 
         let (key_states, value_states) = if self.encoder_decoder_attention {
             if let Some(layer_state_value) = layer_state {
@@ -145,40 +143,23 @@ impl BartAttention {
             None
         };
 
-        let proj_shape = [bs * self.num_heads, -1, self.head_dim];
-        let query_states = self
-            ._shape(query_states, target_length, bs)
-            .view(proj_shape);
-        let key_states = key_states.view(proj_shape);
-        let value_states = value_states.view(proj_shape);
-
-        let source_length = key_states.size()[1];
-        let mut attention_weights = query_states.bmm(&key_states.transpose(1, 2));
-
-        if let Some(attention_mask_value) = attention_mask {
-            attention_weights =
-                attention_weights.view([bs, self.num_heads, target_length, source_length])
-                    + attention_mask_value;
-            attention_weights =
-                attention_weights.view([bs * self.num_heads, target_length, source_length]);
-        };
-
-        attention_weights = attention_weights.softmax(-1, attention_weights.kind());
-
-        let saved_attention_weights = if self.output_attentions {
-            Some(attention_weights.view((bs, self.num_heads, target_length, source_length)))
-        } else {
-            None
-        };
-
-        let attention_probas = attention_weights.apply_t(&self.dropout, train);
-        let attention_output = attention_probas
-            .bmm(&value_states)
-            .view([bs, self.num_heads, target_length, self.head_dim])
+        let query_states = self._shape(query_states, target_length, bs);
+
+        let (attention_output, attention_weights) = scaled_dot_product_attention(
+            &query_states,
+            &key_states,
+            &value_states,
+            attention_mask,
+            None,
+            &self.dropout,
+            self.output_attentions,
+            train,
+        );
+        let attention_output = attention_output
             .transpose(1, 2)
             .reshape([bs, target_length, embed_dim])
             .apply(&self.out_proj);
 
-        (attention_output, saved_attention_weights, new_layer_state)
+        (attention_output, attention_weights, new_layer_state)
     }
 }
@@ -18,6 +18,7 @@ use crate::bart::embeddings::{
 use crate::bart::BartConfig;
 use crate::common::activations::Activation;
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::{
     bart::attention::{BartAttention, LayerState},
     common::activations::TensorFunction,
@@ -28,14 +29,14 @@ use tch::{nn, Tensor};
 pub struct DecoderLayer {
     self_attention: BartAttention,
     encoder_attention: BartAttention,
-    self_attention_layer_norm: nn::LayerNorm,
-    encoder_attention_layer_norm: nn::LayerNorm,
+    self_attention_layer_norm: LayerNorm,
+    encoder_attention_layer_norm: LayerNorm,
     dropout: Dropout,
     activation_dropout: Dropout,
     activation: TensorFunction,
     fc1: nn::Linear,
     fc2: nn::Linear,
-    final_layer_norm: nn::LayerNorm,
+    final_layer_norm: LayerNorm,
 }
 
 impl DecoderLayer {
@@ -68,12 +69,12 @@ impl DecoderLayer {
             true,
             output_attention,
         );
-        let self_attention_layer_norm = nn::layer_norm(
+        let self_attention_layer_norm = LayerNorm::new(
             p / "self_attn_layer_norm",
             vec![config.d_model],
             layer_norm_config,
         );
-        let encoder_attention_layer_norm = nn::layer_norm(
+        let encoder_attention_layer_norm = LayerNorm::new(
             p / "encoder_attn_layer_norm",
             vec![config.d_model],
             layer_norm_config,
@@ -96,7 +97,7 @@ impl DecoderLayer {
             Default::default(),
         );
 
-        let final_layer_norm = nn::layer_norm(
+        let final_layer_norm = LayerNorm::new(
             p / "final_layer_norm",
             vec![config.d_model],
             layer_norm_config,
@@ -160,7 +161,7 @@ impl DecoderLayer {
 
 pub struct BartDecoder {
     dropout: Dropout,
-    layer_norm_embedding: Option<nn::LayerNorm>,
+    layer_norm_embedding: Option<LayerNorm>,
     layers: Vec<DecoderLayer>,
     embed_positions: EmbeddingOption,
     output_attentions: bool,
@@ -198,7 +199,7 @@ impl BartDecoder {
                 eps: 1e-5,
                 ..Default::default()
             };
-            Some(nn::layer_norm(
+            Some(LayerNorm::new(
                 p / "layernorm_embedding",
                 vec![config.d_model],
                 layer_norm_config,
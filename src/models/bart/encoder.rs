@@ -19,18 +19,19 @@ use crate::bart::embeddings::{
 use crate::bart::BartConfig;
 use crate::common::activations::{Activation, TensorFunction};
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use std::borrow::{Borrow, BorrowMut};
 use tch::{nn, Tensor};
 
 pub struct EncoderLayer {
     self_attention: BartAttention,
-    self_attention_layer_norm: nn::LayerNorm,
+    self_attention_layer_norm: LayerNorm,
     dropout: Dropout,
     activation_dropout: Dropout,
     activation: TensorFunction,
     fc1: nn::Linear,
     fc2: nn::Linear,
-    final_layer_norm: nn::LayerNorm,
+    final_layer_norm: LayerNorm,
 }
 
 impl EncoderLayer {
@@ -54,7 +55,7 @@ impl EncoderLayer {
             false,
             output_attention,
         );
-        let self_attention_layer_norm = nn::layer_norm(
+        let self_attention_layer_norm = LayerNorm::new(
             p / "self_attn_layer_norm",
             vec![config.d_model],
             layer_norm_config,
@@ -79,7 +80,7 @@ impl EncoderLayer {
             Default::default(),
         );
 
-        let final_layer_norm = nn::layer_norm(
+        let final_layer_norm = LayerNorm::new(
             p / "final_layer_norm",
             vec![config.d_model],
             layer_norm_config,
@@ -122,7 +123,7 @@ impl EncoderLayer {
 
 pub struct BartEncoder {
     dropout: Dropout,
-    layer_norm_embedding: Option<nn::LayerNorm>,
+    layer_norm_embedding: Option<LayerNorm>,
     layers: Vec<EncoderLayer>,
     embed_positions: EmbeddingOption,
     output_attentions: bool,
@@ -158,7 +159,7 @@ impl BartEncoder {
                 eps: 1e-5,
                 ..Default::default()
             };
-            Some(nn::layer_norm(
+            Some(LayerNorm::new(
                 p / "layernorm_embedding",
                 vec![config.d_model],
                 layer_norm_config,
@@ -13,7 +13,9 @@
 
 use crate::bert::bert_model::BertConfig;
 use crate::common::activations::TensorFunction;
+use crate::common::attention::scaled_dot_product_attention;
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use std::borrow::Borrow;
 use tch::{nn, Tensor};
 
@@ -115,31 +117,26 @@ impl BertSelfAttention {
         );
         let key_layer = self.split_heads(key_layer, bs, self.attention_head_size);
         let value_layer = self.split_heads(value_layer, bs, self.attention_head_size);
-        let query_layer: Tensor = query_layer / (self.attention_head_size as f64).sqrt();
 
-        let scores = if let Some(mask) = mask {
-            query_layer.matmul(&key_layer.transpose(-1, -2)) + mask
-        } else {
-            query_layer.matmul(&key_layer.transpose(-1, -2))
-        };
-
-        let weights = scores
-            .softmax(-1, scores.kind())
-            .apply_t(&self.dropout, train);
-        let context = self.flatten(weights.matmul(&value_layer), bs, self.attention_head_size);
+        let (context, weights) = scaled_dot_product_attention(
+            &query_layer,
+            &key_layer,
+            &value_layer,
+            mask,
+            None,
+            &self.dropout,
+            self.output_attentions,
+            train,
+        );
 
-        if !self.output_attentions {
-            (context, None)
-        } else {
-            (context, Some(weights))
-        }
+        (self.flatten(context, bs, self.attention_head_size), weights)
     }
 }
 
 #[derive(Debug)]
 pub struct BertSelfOutput {
     linear: nn::Linear,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: Dropout,
 }
 
@@ -161,7 +158,7 @@ impl BertSelfOutput {
             ..Default::default()
         };
         let layer_norm =
-            nn::layer_norm(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
         let dropout = Dropout::new(config.hidden_dropout_prob);
 
         BertSelfOutput {
@@ -248,7 +245,7 @@ impl BertIntermediate {
 
 pub struct BertOutput {
     lin: nn::Linear,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: Dropout,
 }
 
@@ -270,7 +267,7 @@ impl BertOutput {
             ..Default::default()
         };
         let layer_norm =
-            nn::layer_norm(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
         let dropout = Dropout::new(config.hidden_dropout_prob);
 
         BertOutput {
@@ -15,6 +15,7 @@ use crate::bert::encoder::{BertEncoder, BertPooler};
 use crate::common::activations::Activation;
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::get_shape_and_device_from_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
 use crate::common::linear::{linear_no_bias, LinearNoBias};
 use crate::{
     bert::embeddings::{BertEmbedding, BertEmbeddings},
@@ -452,7 +453,7 @@ impl<T: BertEmbedding> BertModel<T> {
 pub struct BertPredictionHeadTransform {
     dense: nn::Linear,
     activation: TensorFunction,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
 }
 
 impl BertPredictionHeadTransform {
@@ -474,7 +475,7 @@ impl BertPredictionHeadTransform {
             ..Default::default()
         };
         let layer_norm =
-            nn::layer_norm(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
 
         BertPredictionHeadTransform {
             dense,
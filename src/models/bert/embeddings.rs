@@ -14,6 +14,7 @@
 use crate::bert::bert_model::BertConfig;
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
 use crate::RustBertError;
 use std::borrow::Borrow;
 use tch::nn::{embedding, EmbeddingConfig};
@@ -43,7 +44,7 @@ pub struct BertEmbeddings {
     word_embeddings: nn::Embedding,
     position_embeddings: nn::Embedding,
     token_type_embeddings: nn::Embedding,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: Dropout,
 }
 
@@ -105,8 +106,8 @@ impl BertEmbedding for BertEmbeddings {
             eps: 1e-12,
             ..Default::default()
         };
-        let layer_norm: nn::LayerNorm =
-            nn::layer_norm(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+        let layer_norm: LayerNorm =
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
         let dropout: Dropout = Dropout::new(config.hidden_dropout_prob);
         BertEmbeddings {
             word_embeddings,
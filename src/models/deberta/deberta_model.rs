@@ -17,6 +17,7 @@ use crate::common::activations::TensorFunction;
 use crate::common::dropout::{Dropout, XDropout};
 use crate::common::embeddings::get_shape_and_device_from_ids_embeddings_pair;
 use crate::common::kind::get_min;
+use crate::common::layer_norm::LayerNorm;
 use crate::deberta::embeddings::DebertaEmbeddings;
 use crate::deberta::encoder::{DebertaEncoder, DebertaEncoderOutput};
 use crate::{Activation, Config, RustBertError};
@@ -265,7 +266,8 @@ pub fn x_softmax(input: &Tensor, mask: &Tensor, dim: i64) -> Tensor {
     let inverse_mask = ((1 - mask) as Tensor).to_kind(Kind::Bool);
     input
         .masked_fill(&inverse_mask, get_min(input.kind()).unwrap())
-        .softmax(dim, input.kind())
+        .softmax(dim, Kind::Float)
+        .to_kind(input.kind())
         .masked_fill(&inverse_mask, 0.0)
 }
 
@@ -455,7 +457,7 @@ impl DebertaModel {
 struct DebertaPredictionHeadTransform {
     dense: nn::Linear,
     activation: TensorFunction,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
 }
 
 impl DebertaPredictionHeadTransform {
@@ -484,7 +486,7 @@ impl DebertaPredictionHeadTransform {
             ..Default::default()
         };
         let layer_norm =
-            nn::layer_norm(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
 
         DebertaPredictionHeadTransform {
             dense,
@@ -1003,6 +1003,10 @@ impl DebertaV2ForQuestionAnswering {
     }
 }
 
+/// # DeBERTa V2 for sentence embeddings
+/// Transformer usable in [`SentenceEmbeddingsModel`](crate::pipelines::sentence_embeddings::SentenceEmbeddingsModel).
+pub type DebertaV2ForSentenceEmbeddings = DebertaV2Model;
+
 /// Container for the DeBERTa V2 model output.
 pub type DebertaV2ModelOutput = DebertaModelOutput;
 
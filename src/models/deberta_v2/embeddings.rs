@@ -1,4 +1,4 @@
+use crate::common::layer_norm::LayerNorm;
 use crate::deberta::BaseDebertaEmbeddings;
-use tch::nn::LayerNorm;
 
 pub type DebertaV2Embeddings = BaseDebertaEmbeddings<LayerNorm>;
@@ -12,20 +12,21 @@
 
 use crate::common::activations::TensorFunction;
 use crate::common::dropout::XDropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::deberta::{BaseDebertaLayer, BaseDebertaLayerNorm, DebertaEncoderOutput};
 use crate::deberta_v2::attention::{build_relative_position, DebertaV2DisentangledSelfAttention};
 use crate::deberta_v2::deberta_v2_model::NormRelEmbedType;
 use crate::deberta_v2::DebertaV2Config;
 use crate::{Activation, RustBertError};
 use std::borrow::{Borrow, BorrowMut};
-use tch::nn::{ConvConfig, LayerNorm, LayerNormConfig, Path};
+use tch::nn::{ConvConfig, LayerNormConfig, Path};
 use tch::{nn, Kind, Tensor};
 
 pub type DebertaV2Layer = BaseDebertaLayer<DebertaV2DisentangledSelfAttention, LayerNorm>;
 
 pub struct ConvLayer {
     conv: nn::Conv1D,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: XDropout,
     conv_act: TensorFunction,
 }
@@ -58,7 +59,7 @@ impl ConvLayer {
             ..Default::default()
         };
         let layer_norm =
-            nn::layer_norm(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
 
         let dropout = XDropout::new(config.hidden_dropout_prob);
 
@@ -119,7 +120,7 @@ impl BaseDebertaLayerNorm for LayerNorm {
             ..Default::default()
         };
 
-        nn::layer_norm(p, vec![size], layer_norm_config)
+        LayerNorm::new(p, vec![size], layer_norm_config)
     }
 }
 
@@ -130,7 +131,7 @@ pub struct DebertaV2Encoder {
     max_relative_positions: Option<i64>,
     position_buckets: Option<i64>,
     rel_embeddings: Option<nn::Embedding>,
-    layer_norm: Option<nn::LayerNorm>,
+    layer_norm: Option<LayerNorm>,
     conv: Option<ConvLayer>,
 }
 
@@ -183,7 +184,7 @@ impl DebertaV2Encoder {
             .unwrap_or_default()
             .has_type(NormRelEmbedType::layer_norm)
         {
-            Some(nn::layer_norm(
+            Some(LayerNorm::new(
                 p / "LayerNorm",
                 vec![config.hidden_size],
                 LayerNormConfig {
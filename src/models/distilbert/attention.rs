@@ -10,6 +10,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::common::attention::scaled_dot_product_attention;
 use crate::common::dropout::Dropout;
 use crate::distilbert::distilbert_model::DistilBertConfig;
 use std::borrow::Borrow;
@@ -77,30 +78,22 @@ impl MultiHeadSelfAttention {
         let q = self.split_heads(query.apply(&self.q_lin), bs, self.dim_per_head);
         let k = self.split_heads(key.apply(&self.k_lin), bs, self.dim_per_head);
         let v = self.split_heads(value.apply(&self.v_lin), bs, self.dim_per_head);
-        let q: Tensor = q / (self.dim_per_head as f64).sqrt();
+        let mask = mask.map(|mask| mask.gt(0.1).view((bs, 1i64, 1i64, k_length)));
 
-        let scores = if let Some(mask) = mask {
-            let unmasked_scores = q.matmul(&k.transpose(2, 3));
-            let mask = mask
-                .le_tensor(&(mask.zeros_like() + 0.1))
-                .view((bs, 1i64, 1i64, k_length))
-                .expand_as(&unmasked_scores);
-            unmasked_scores.masked_fill(&mask, f64::NEG_INFINITY)
-        } else {
-            q.matmul(&k.transpose(2, 3))
-        };
-
-        let weights = scores
-            .softmax(-1, scores.kind())
-            .apply_t(&self.dropout, train);
+        let (context, weights) = scaled_dot_product_attention(
+            &q,
+            &k,
+            &v,
+            mask.as_ref(),
+            None,
+            &self.dropout,
+            self.output_attentions,
+            train,
+        );
         let context = self
-            .flatten(weights.matmul(&v), bs, self.dim_per_head)
+            .flatten(context, bs, self.dim_per_head)
             .apply(&self.out_lin);
 
-        if !self.output_attentions {
-            (context, None)
-        } else {
-            (context, Some(weights))
-        }
+        (context, weights)
     }
 }
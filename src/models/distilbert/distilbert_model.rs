@@ -15,6 +15,7 @@ extern crate tch;
 use self::tch::{nn, Tensor};
 use crate::common::activations::Activation;
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::distilbert::embeddings::DistilBertEmbedding;
 use crate::distilbert::transformer::{DistilBertTransformerOutput, Transformer};
 use crate::{Config, RustBertError};
@@ -406,7 +407,7 @@ impl DistilBertModelClassifier {
 pub struct DistilBertModelMaskedLM {
     distil_bert_model: DistilBertModel,
     vocab_transform: nn::Linear,
-    vocab_layer_norm: nn::LayerNorm,
+    vocab_layer_norm: LayerNorm,
     vocab_projector: nn::Linear,
 }
 
@@ -450,7 +451,7 @@ impl DistilBertModelMaskedLM {
             ..Default::default()
         };
         let vocab_layer_norm =
-            nn::layer_norm(p / "vocab_layer_norm", vec![config.dim], layer_norm_config);
+            LayerNorm::new(p / "vocab_layer_norm", vec![config.dim], layer_norm_config);
         let vocab_projector = nn::linear(
             p / "vocab_projector",
             config.dim,
@@ -12,6 +12,7 @@
 
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
 use crate::distilbert::distilbert_model::DistilBertConfig;
 use crate::RustBertError;
 use std::borrow::Borrow;
@@ -78,7 +79,7 @@ where
 pub struct DistilBertEmbedding {
     word_embeddings: nn::Embedding,
     position_embeddings: nn::Embedding,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: Dropout,
 }
 
@@ -113,8 +114,8 @@ impl DistilBertEmbedding {
             eps: 1e-12,
             ..Default::default()
         };
-        let layer_norm: nn::LayerNorm =
-            nn::layer_norm(p / "LayerNorm", vec![config.dim], layer_norm_config);
+        let layer_norm: LayerNorm =
+            LayerNorm::new(p / "LayerNorm", vec![config.dim], layer_norm_config);
         let dropout: Dropout = Dropout::new(config.dropout);
         DistilBertEmbedding {
             word_embeddings,
@@ -12,10 +12,10 @@
 
 use crate::common::activations::TensorFunction;
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::distilbert::attention::MultiHeadSelfAttention;
 use crate::distilbert::distilbert_model::DistilBertConfig;
 use std::borrow::{Borrow, BorrowMut};
-use tch::nn::LayerNorm;
 use tch::{nn, Tensor};
 
 pub struct FeedForwardNetwork {
@@ -80,10 +80,10 @@ impl TransformerBlock {
             ..Default::default()
         };
         let sa_layer_norm =
-            nn::layer_norm(p / "sa_layer_norm", vec![config.dim], layer_norm_config);
+            LayerNorm::new(p / "sa_layer_norm", vec![config.dim], layer_norm_config);
         let ffn = FeedForwardNetwork::new(p / "ffn", config);
         let output_layer_norm =
-            nn::layer_norm(p / "output_layer_norm", vec![config.dim], layer_norm_config);
+            LayerNorm::new(p / "output_layer_norm", vec![config.dim], layer_norm_config);
 
         TransformerBlock {
             attention,
@@ -16,6 +16,7 @@ use crate::bert::BertConfig;
 use crate::common::activations::Activation;
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::get_shape_and_device_from_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
 use crate::electra::embeddings::ElectraEmbeddings;
 use crate::{bert::encoder::BertEncoder, common::activations::TensorFunction};
 use crate::{Config, RustBertError};
@@ -418,7 +419,7 @@ impl ElectraDiscriminatorHead {
 /// - `activation`: GeLU activation
 pub struct ElectraGeneratorHead {
     dense: nn::Linear,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     activation: TensorFunction,
 }
 
@@ -451,7 +452,7 @@ impl ElectraGeneratorHead {
     {
         let p = p.borrow();
 
-        let layer_norm = nn::layer_norm(
+        let layer_norm = LayerNorm::new(
             p / "LayerNorm",
             vec![config.embedding_size],
             Default::default(),
@@ -14,6 +14,7 @@
 
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
 use crate::electra::electra_model::ElectraConfig;
 use crate::RustBertError;
 use std::borrow::Borrow;
@@ -26,7 +27,7 @@ pub struct ElectraEmbeddings {
     word_embeddings: nn::Embedding,
     position_embeddings: nn::Embedding,
     token_type_embeddings: nn::Embedding,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: Dropout,
 }
 
@@ -68,7 +69,7 @@ impl ElectraEmbeddings {
             eps: layer_norm_eps,
             ..Default::default()
         };
-        let layer_norm: nn::LayerNorm = nn::layer_norm(
+        let layer_norm: LayerNorm = LayerNorm::new(
             p / "LayerNorm",
             vec![config.embedding_size],
             layer_norm_config,
@@ -13,13 +13,14 @@
 
 use crate::common::activations::TensorFunction;
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::fnet::FNetConfig;
 use std::borrow::Borrow;
 use tch::nn::LayerNormConfig;
 use tch::{nn, Tensor};
 
 pub struct FNetFourierTransform {
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
 }
 
 impl FNetFourierTransform {
@@ -33,7 +34,7 @@ impl FNetFourierTransform {
             eps: config.layer_norm_eps.unwrap_or(1e-12),
             ..Default::default()
         };
-        let layer_norm = nn::layer_norm(
+        let layer_norm = LayerNorm::new(
             p.sub("output").sub("LayerNorm"),
             vec![config.hidden_size],
             layer_norm_config,
@@ -83,7 +84,7 @@ impl FNetIntermediate {
 
 pub struct FNetOutput {
     dense: nn::Linear,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: Dropout,
 }
 
@@ -106,7 +107,7 @@ impl FNetOutput {
             ..Default::default()
         };
         let layer_norm =
-            nn::layer_norm(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
 
         let dropout = Dropout::new(config.hidden_dropout_prob);
 
@@ -13,6 +13,7 @@
 
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
 use crate::fnet::FNetConfig;
 use crate::RustBertError;
 use std::borrow::Borrow;
@@ -24,7 +25,7 @@ pub struct FNetEmbeddings {
     position_embeddings: nn::Embedding,
     token_type_embeddings: nn::Embedding,
     projection: nn::Linear,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: Dropout,
 }
 
@@ -63,7 +64,7 @@ impl FNetEmbeddings {
             ..Default::default()
         };
         let layer_norm =
-            nn::layer_norm(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
 
         let projection = nn::linear(
             p / "projection",
@@ -11,9 +11,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::common::activations::{TensorFunction, _tanh};
+use crate::common::activations::{_tanh, TensorFunction};
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::get_shape_and_device_from_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
 use crate::fnet::embeddings::FNetEmbeddings;
 use crate::fnet::encoder::FNetEncoder;
 use crate::{Activation, Config, RustBertError};
@@ -151,7 +152,7 @@ impl FNetPooler {
 struct FNetPredictionHeadTransform {
     dense: nn::Linear,
     activation: TensorFunction,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
 }
 
 impl FNetPredictionHeadTransform {
@@ -173,7 +174,7 @@ impl FNetPredictionHeadTransform {
             ..Default::default()
         };
         let layer_norm =
-            nn::layer_norm(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
 
         FNetPredictionHeadTransform {
             dense,
@@ -12,42 +12,74 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::common::attention::scaled_dot_product_attention;
 use crate::common::dropout::Dropout;
+use crate::common::kv_cache::KVCache;
+use crate::common::quantization::{QuantizedLinear, WeightQuantization};
 use crate::gpt2::gpt2_model::Gpt2Config;
 use std::borrow::Borrow;
 use tch::kind::Kind::Float;
 use tch::nn::{Init, Module};
 use tch::{nn, Tensor};
 
+#[derive(Debug)]
+enum Conv1DWeights {
+    Full { weight: Tensor, bias: Tensor },
+    Quantized(QuantizedLinear),
+}
+
 #[derive(Debug)]
 pub struct GPTConv1D {
-    weight: Tensor,
-    bias: Tensor,
+    weights: Conv1DWeights,
 }
 
 impl GPTConv1D {
     pub fn new<'p, P>(p: P, nf: i64, nx: i64) -> GPTConv1D
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        GPTConv1D::new_with_quantization(p, nf, nx, None)
+    }
+
+    /// Creates a new `GPTConv1D` layer, with quantized weights if a `WeightQuantization` is provided
+    pub fn new_with_quantization<'p, P>(
+        p: P,
+        nf: i64,
+        nx: i64,
+        quantization: Option<WeightQuantization>,
+    ) -> GPTConv1D
     where
         P: Borrow<nn::Path<'p>>,
     {
         let p = p.borrow();
 
-        let weight = p.var(
-            "weight",
-            &[nx, nf],
-            Init::Randn {
-                mean: 0.,
-                stdev: 0.02,
-            },
-        );
-        let bias = p.var("bias", &[nf], Init::Const(0.));
-        GPTConv1D { weight, bias }
+        let weights = match quantization {
+            Some(quantization) => {
+                Conv1DWeights::Quantized(QuantizedLinear::new(p, nx, nf, true, true, quantization))
+            }
+            None => {
+                let weight = p.var(
+                    "weight",
+                    &[nx, nf],
+                    Init::Randn {
+                        mean: 0.,
+                        stdev: 0.02,
+                    },
+                );
+                let bias = p.var("bias", &[nf], Init::Const(0.));
+                Conv1DWeights::Full { weight, bias }
+            }
+        };
+        GPTConv1D { weights }
     }
 }
 
 impl Module for GPTConv1D {
     fn forward(&self, xs: &Tensor) -> Tensor {
-        xs.matmul(&self.weight) + &self.bias
+        match &self.weights {
+            Conv1DWeights::Full { weight, bias } => xs.matmul(weight) + bias,
+            Conv1DWeights::Quantized(linear) => linear.forward(xs),
+        }
     }
 }
 
@@ -61,6 +93,7 @@ pub struct Attention {
     n_state: i64,
     dim_per_head: i64,
     n_head: i64,
+    max_positions: i64,
     scale: bool,
 }
 
@@ -77,8 +110,18 @@ impl Attention {
 
         let bias = p.var_copy("bias", &bias);
 
-        let c_attn = GPTConv1D::new(p / "c_attn", config.n_embd * 3, config.n_embd);
-        let c_proj = GPTConv1D::new(p / "c_proj", config.n_embd, config.n_embd);
+        let c_attn = GPTConv1D::new_with_quantization(
+            p / "c_attn",
+            config.n_embd * 3,
+            config.n_embd,
+            config.quantization,
+        );
+        let c_proj = GPTConv1D::new_with_quantization(
+            p / "c_proj",
+            config.n_embd,
+            config.n_embd,
+            config.quantization,
+        );
 
         let attn_pdrop = config.attn_pdrop.unwrap_or(0.1);
         let resid_pdrop = config.resid_pdrop.unwrap_or(0.1);
@@ -104,17 +147,14 @@ impl Attention {
             n_state: config.n_embd,
             dim_per_head,
             n_head: config.n_head,
+            max_positions: config.n_ctx,
             scale,
         }
     }
 
-    fn split_heads(&self, x: &Tensor, k: bool) -> Tensor {
-        let x = x.view((x.size()[0], -1, self.n_head, self.dim_per_head));
-        if k {
-            x.permute([0, 2, 3, 1])
-        } else {
-            x.permute([0, 2, 1, 3])
-        }
+    fn split_heads(&self, x: &Tensor) -> Tensor {
+        x.view((x.size()[0], -1, self.n_head, self.dim_per_head))
+            .permute([0, 2, 1, 3])
     }
 
     fn flatten(&self, x: Tensor) -> Tensor {
@@ -131,51 +171,50 @@ impl Attention {
         attention_mask: Option<&Tensor>,
         train: bool,
     ) -> (Tensor, Option<Tensor>) {
-        let mut w = query.matmul(key);
-        if self.scale {
-            w = w / (*value.size().last().unwrap() as f64).sqrt();
-        }
-
-        let (nd, ns) = (w.size()[2], w.size()[3]);
+        let (nd, ns) = (query.size()[2], key.size()[2]);
         let b = self.bias.narrow(2, ns - nd, nd).narrow(3, 0, ns);
-        let mut w: Tensor = w * &b + 1e4 * (&b - 1);
-        if let Some(mask) = attention_mask {
-            w = w + mask;
+        let mut mask: Tensor = 1e4 * (b - 1);
+        if let Some(attention_mask) = attention_mask {
+            mask = mask + attention_mask;
         }
-        w = w.softmax(-1, w.kind()).apply_t(&self.attn_dropout, train);
-
-        let output = w.matmul(value);
+        let scale = if self.scale { None } else { Some(1.0) };
 
-        if self.output_attentions {
-            (output, Some(w))
-        } else {
-            (output, None)
-        }
+        scaled_dot_product_attention(
+            query,
+            key,
+            value,
+            Some(&mask),
+            scale,
+            &self.attn_dropout,
+            self.output_attentions,
+            train,
+        )
     }
 
     pub fn forward_t(
         &self,
         x: &Tensor,
-        layer_past: Option<&Tensor>,
+        layer_past: Option<KVCache>,
         attention_mask: Option<&Tensor>,
         train: bool,
-    ) -> (Tensor, Tensor, Option<Tensor>) {
+    ) -> (Tensor, KVCache, Option<Tensor>) {
         let x = x.apply(&self.c_attn).split(self.n_state, 2);
 
         let (query, key, value) = (
-            self.split_heads(&x[0], false),
-            self.split_heads(&x[1], true),
-            self.split_heads(&x[2], false),
+            self.split_heads(&x[0]),
+            self.split_heads(&x[1]),
+            self.split_heads(&x[2]),
         );
-        let (key, value) = match layer_past {
-            Some(past) => {
-                let key = Tensor::cat(&[past.get(0).transpose(-2, -1), key], -1);
-                let value = Tensor::cat(&[past.get(1), value], -2);
-                (key, value)
+        let (key, value, present) = match layer_past {
+            Some(mut past) => {
+                let (key, value) = past.update(&key, &value);
+                (key, value, past)
+            }
+            None => {
+                let present = KVCache::new(&key, &value, self.max_positions);
+                (key, value, present)
             }
-            None => (key, value),
         };
-        let present = Tensor::stack(&[key.transpose(-2, -1), value.copy()], 0);
         let (a, attentions) = self.attention(&query, &key, &value, attention_mask, train);
 
         let a = self
@@ -15,12 +15,16 @@
 use crate::common::activations::Activation;
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
+use crate::common::quantization::WeightQuantization;
 use crate::gpt2::transformer::Block;
 use crate::pipelines::common::{ModelType, TokenizerOption};
 use crate::pipelines::generation_utils::private_generation_utils::{
     PreparedInput, PrivateLanguageGenerator,
 };
-use crate::pipelines::generation_utils::{Cache, GenerateConfig, LMModelOutput, LanguageGenerator};
+use crate::pipelines::generation_utils::{
+    Cache, GenerateConfig, KVCache, LMModelOutput, LanguageGenerator,
+};
 use crate::{Config, RustBertError};
 use serde::{Deserialize, Serialize};
 use std::borrow::{Borrow, BorrowMut};
@@ -197,6 +201,7 @@ pub struct Gpt2Config {
     pub decoder_start_token_id: Option<i64>,
     pub forced_bos_token_id: Option<i64>,
     pub forced_eos_token_id: Option<i64>,
+    pub quantization: Option<WeightQuantization>,
 }
 
 impl Config for Gpt2Config {}
@@ -224,6 +229,7 @@ impl Default for Gpt2Config {
             decoder_start_token_id: None,
             forced_bos_token_id: None,
             forced_eos_token_id: None,
+            quantization: None,
         }
     }
 }
@@ -241,7 +247,7 @@ pub struct Gpt2Model {
     wte: nn::Embedding,
     wpe: nn::Embedding,
     drop: Dropout,
-    ln_f: nn::LayerNorm,
+    ln_f: LayerNorm,
     h: Vec<Block>,
     output_past: bool,
     output_hidden_states: bool,
@@ -295,7 +301,7 @@ impl Gpt2Model {
             eps: config.layer_norm_epsilon,
             ..Default::default()
         };
-        let ln_f = nn::layer_norm(&p / "ln_f", vec![config.n_embd], layer_norm_config);
+        let ln_f = LayerNorm::new(&p / "ln_f", vec![config.n_embd], layer_norm_config);
         let mut h: Vec<Block> = vec![];
         let h_path = &p / "h";
         for layer_index in 0..config.n_layer {
@@ -322,7 +328,7 @@ impl Gpt2Model {
     /// # Arguments
     ///
     /// * `input_ids` - Optional input tensor of shape (*batch size*, *sequence_length*). If None, pre-computed embeddings must be provided (see `input_embeds`)
-    /// * `layer_past` - Optional vector of length *n_layer* containing the past keys and values of each layer of shape (*2*, *batch size*, *number of heads*, *past_sequence_length*, *hidden size per head*). When provided, these are concatenated with the current input keys and values.
+    /// * `layer_past` - Optional vector of length *n_layer* containing the `KVCache` of past keys and values of each layer. When provided, the current input keys and values are appended to the caches in place.
     /// * `attention_mask` - Optional mask of shape (*batch size*, *sequence_length*). Masked position have value 0, non-masked value 1. If None set to 1
     /// * `input_embeds` - Optional pre-computed input embeddings of shape (*batch size*, *sequence_length*, *hidden_size*). If None, input ids must be provided (see `input_ids`)
     /// * `token_type_ids` - Optional token type ids used to indicate the portion of the input the token belongs to. If not None, token type embeddings will be added to the token and position embeddings.
@@ -333,7 +339,7 @@ impl Gpt2Model {
     ///
     /// * `Gpt2ModelOutput` containing:
     ///   - `output` - `Tensor` of shape (*batch size*, *sequence_length*, *vocab_size*) representing the activations of the last hidden state
-    ///   - `cache` - `Option<Vec<Tensor>>` of length *n_layer* containing the past keys and values of each layer of shape (*2*, *batch size*, *number of heads*, *past_sequence_length*, *hidden size per head*)
+    ///   - `cache` - `Option<Vec<KVCache>>` of length *n_layer* containing the caches of past keys and values of each layer
     ///   - `all_hidden_states` - `Option<Vec<Tensor>>` of length *num_hidden_layers* with shape (*batch size*, *sequence_length*, *hidden_size*)
     ///   - `all_attentions` - `Option<Vec<Tensor>>` of length *num_hidden_layers* with shape (*batch size*, *sequence_length*, *hidden_size*)
     ///
@@ -345,6 +351,7 @@ impl Gpt2Model {
     /// # use std::path::Path;
     /// # use tch::kind::Kind::{Int64, Double};
     /// use rust_bert::gpt2::{Gpt2Config, Gpt2Model};
+    /// use rust_bert::pipelines::generation_utils::KVCache;
     /// # let config_path = Path::new("path/to/config.json");
     /// # let vocab_path = Path::new("path/to/vocab.txt");
     /// # let device = Device::Cpu;
@@ -353,17 +360,18 @@ impl Gpt2Model {
     /// # let gpt2_model: Gpt2Model = Gpt2Model::new(&vs.root(), &config);
     /// let (batch_size, sequence_length, past_sequence_length) = (64, 128, 56);
     /// let input_tensor = Tensor::rand(&[batch_size, sequence_length], (Int64, device));
-    /// let mut past: Vec<Tensor> = Vec::with_capacity(config.n_layer as usize);
+    /// let past_shape = [
+    ///     batch_size,
+    ///     config.n_head,
+    ///     past_sequence_length,
+    ///     config.n_embd / config.n_head,
+    /// ];
+    /// let mut past: Vec<KVCache> = Vec::with_capacity(config.n_layer as usize);
     /// for _ in 0..config.n_layer as usize {
-    ///     past.push(Tensor::rand(
-    ///         &[
-    ///             2,
-    ///             batch_size,
-    ///             config.n_head,
-    ///             past_sequence_length,
-    ///             config.n_embd / config.n_head,
-    ///         ],
-    ///         (Double, device),
+    ///     past.push(KVCache::new(
+    ///         &Tensor::rand(&past_shape, (Double, device)),
+    ///         &Tensor::rand(&past_shape, (Double, device)),
+    ///         config.n_ctx,
     ///     ))
     /// }
     /// let attention_mask = Tensor::zeros(&[batch_size, sequence_length], (Int64, device));
@@ -375,7 +383,7 @@ impl Gpt2Model {
     ///     gpt2_model
     ///         .forward_t(
     ///             Some(&input_tensor),
-    ///             Some(&past),
+    ///             Some(past),
     ///             Some(&attention_mask),
     ///             Some(&token_type_ids),
     ///             Some(&position_ids),
@@ -388,7 +396,7 @@ impl Gpt2Model {
     pub fn forward_t(
         &self,
         input_ids: Option<&Tensor>,
-        layer_past: Option<&Vec<Tensor>>,
+        layer_past: Option<Vec<KVCache>>,
         attention_mask: Option<&Tensor>,
         token_type_ids: Option<&Tensor>,
         position_ids: Option<&Tensor>,
@@ -409,17 +417,18 @@ impl Gpt2Model {
                     self.h.len(),
                     "Past activations vector must be of length equal to the number of layers"
                 );
+                let layer_past_length = value[0].len();
                 (
                     value
-                        .iter()
-                        .map(|v| Some(v.copy()))
-                        .collect::<Vec<Option<Tensor>>>(),
-                    value[0].size()[3],
+                        .into_iter()
+                        .map(Some)
+                        .collect::<Vec<Option<KVCache>>>(),
+                    layer_past_length,
                 )
             }
             None => {
                 let mut out = Vec::with_capacity(self.h.len());
-                out.resize_with(self.h.len(), || None::<Tensor>);
+                out.resize_with(self.h.len(), || None::<KVCache>);
                 (out, 0)
             }
         };
@@ -452,7 +461,7 @@ impl Gpt2Model {
         };
         let mut hidden_state: Tensor =
             (input_embeddings + position_embeds + token_type_embeds).apply_t(&self.drop, train);
-        let mut all_presents: Option<Vec<Tensor>> =
+        let mut all_presents: Option<Vec<KVCache>> =
             if self.output_past { Some(vec![]) } else { None };
         let mut all_hidden_states: Option<Vec<Tensor>> = if self.output_hidden_states {
             Some(vec![])
@@ -468,8 +477,7 @@ impl Gpt2Model {
         let layer_iter = self.h.iter().zip(layer_past);
         for layer_values in layer_iter {
             let (layer, past) = layer_values;
-            let temp =
-                layer.forward_t(&hidden_state, past.as_ref(), attention_mask.as_ref(), train);
+            let temp = layer.forward_t(&hidden_state, past, attention_mask.as_ref(), train);
             hidden_state = temp.0;
             if let Some(presents) = all_presents.borrow_mut() {
                 presents.push(temp.1);
@@ -535,7 +543,7 @@ impl GPT2LMHeadModel {
     pub fn forward_t(
         &self,
         input_ids: Option<&Tensor>,
-        layer_past: Option<&Vec<Tensor>>,
+        layer_past: Option<Vec<KVCache>>,
         attention_mask: Option<&Tensor>,
         token_type_ids: Option<&Tensor>,
         position_ids: Option<&Tensor>,
@@ -568,7 +576,7 @@ pub struct Gpt2ModelOutput {
     /// module after the decoder (e.g. vocabulary logits for language modeling tasks)
     pub output: Tensor,
     /// Cached attention layers keys and values if the model is used for generation
-    pub cache: Option<Vec<Tensor>>,
+    pub cache: Option<Vec<KVCache>>,
     /// Hidden states for all intermediate layers
     pub all_hidden_states: Option<Vec<Tensor>>,
     /// Attention weights for all intermediate layers
@@ -731,7 +739,7 @@ impl PrivateLanguageGenerator for GPT2Generator {
         match layer_past {
             Cache::GPT2Cache(layer_past) => self.model.forward_t(
                 input_ids,
-                layer_past.as_ref(),
+                layer_past,
                 attention_mask,
                 token_type_ids,
                 position_ids,
@@ -807,7 +815,7 @@ impl PrivateLanguageGenerator for GPT2Generator {
             Cache::GPT2Cache(cached_decoder_state) => match cached_decoder_state {
                 Some(value) => {
                     for layer_past in value.iter_mut() {
-                        *layer_past = layer_past.index_select(1, beam_indices);
+                        layer_past.reorder_cache(beam_indices);
                     }
                     None
                 }
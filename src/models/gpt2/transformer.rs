@@ -14,6 +14,7 @@
 
 use crate::common::activations::{Activation, TensorFunction};
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::gpt2::attention::{Attention, GPTConv1D};
 use crate::gpt2::gpt2_model::Gpt2Config;
 use std::borrow::Borrow;
@@ -33,8 +34,18 @@ impl MLP {
     {
         let p = p.borrow();
 
-        let c_fc = GPTConv1D::new(p / "c_fc", config.n_embd * 4, config.n_embd);
-        let c_proj = GPTConv1D::new(p / "c_proj", config.n_embd, config.n_embd * 4);
+        let c_fc = GPTConv1D::new_with_quantization(
+            p / "c_fc",
+            config.n_embd * 4,
+            config.n_embd,
+            config.quantization,
+        );
+        let c_proj = GPTConv1D::new_with_quantization(
+            p / "c_proj",
+            config.n_embd,
+            config.n_embd * 4,
+            config.quantization,
+        );
         let activation = match &config.afn {
             Some(activation_enum) => match activation_enum {
                 Activation::gelu => &Activation::gelu_new,
@@ -60,9 +71,9 @@ impl MLP {
 }
 
 pub struct Block {
-    ln_1: nn::LayerNorm,
+    ln_1: LayerNorm,
     attn: Attention,
-    ln_2: nn::LayerNorm,
+    ln_2: LayerNorm,
     mlp: MLP,
 }
 
@@ -77,8 +88,8 @@ impl Block {
             eps: config.layer_norm_epsilon,
             ..Default::default()
         };
-        let ln_1 = nn::layer_norm(p / "ln_1", vec![config.n_embd], layer_norm_config);
-        let ln_2 = nn::layer_norm(p / "ln_2", vec![config.n_embd], layer_norm_config);
+        let ln_1 = LayerNorm::new(p / "ln_1", vec![config.n_embd], layer_norm_config);
+        let ln_2 = LayerNorm::new(p / "ln_2", vec![config.n_embd], layer_norm_config);
         let attn = Attention::new(p / "attn", config, scale);
         let mlp = MLP::new(p / "mlp", config);
 
@@ -93,10 +104,10 @@ impl Block {
     pub fn forward_t(
         &self,
         x: &Tensor,
-        layer_past: Option<&Tensor>,
+        layer_past: Option<KVCache>,
         attention_mask: Option<&Tensor>,
         train: bool,
-    ) -> (Tensor, Tensor, Option<Tensor>) {
+    ) -> (Tensor, KVCache, Option<Tensor>) {
         let (output, present, attentions) =
             self.attn
                 .forward_t(&x.apply(&self.ln_1), layer_past, attention_mask, train);
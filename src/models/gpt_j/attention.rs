@@ -10,58 +10,51 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::common::attention::scaled_dot_product_attention;
 use crate::common::dropout::Dropout;
 use crate::common::kind::get_min;
+use crate::common::kv_cache::KVCache;
+use crate::common::quantization::{linear, LinearLayer};
+use crate::common::tensor_parallel::ProcessGroup;
 use crate::gpt_j::gpt_j_model::GptJConfig;
 use std::borrow::Borrow;
-use tch::nn::Linear;
 use tch::{nn, IndexOp, Kind, NewAxis, Tensor};
 
-#[derive(Debug)]
 /// # Cache for GPT-J attention layers
-/// Stores the cached value of key and value
-pub struct LayerState {
-    /// Cached keys
-    pub prev_key: Tensor,
-    /// Cached values
-    pub prev_value: Tensor,
-}
-
-impl Clone for LayerState {
-    fn clone(&self) -> Self {
-        LayerState {
-            prev_key: self.prev_key.copy(),
-            prev_value: self.prev_value.copy(),
-        }
-    }
-}
-
-impl LayerState {
-    pub(crate) fn reorder_cache(&mut self, new_indices: &Tensor) {
-        self.prev_key = self.prev_key.index_select(0, new_indices);
-        self.prev_value = self.prev_value.index_select(0, new_indices);
-    }
-}
+/// Stores the cached value of key and value in preallocated buffers
+pub type LayerState = KVCache;
 
 pub struct GptJAttention {
     bias: Tensor,
     attn_dropout: Dropout,
     resid_dropout: Dropout,
-    scale_attn: f32,
-    k_proj: Linear,
-    v_proj: Linear,
-    q_proj: Linear,
-    out_proj: Linear,
+    k_proj: LinearLayer,
+    v_proj: LinearLayer,
+    q_proj: LinearLayer,
+    out_proj: LinearLayer,
     output_attentions: bool,
     dim_per_head: i64,
     n_head: i64,
     rotary_dim: Option<i64>,
     scale: bool,
     use_cache: bool,
+    max_positions: i64,
 }
 
 impl GptJAttention {
     pub fn new<'p, P>(p: P, config: &GptJConfig) -> GptJAttention
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        Self::new_with_process_group(p, config, None)
+    }
+
+    /// Build a new `GptJAttention` holding the shard of the attention heads of the current worker of `process_group`
+    pub fn new_with_process_group<'p, P>(
+        p: P,
+        config: &GptJConfig,
+        process_group: Option<&dyn ProcessGroup>,
+    ) -> GptJAttention
     where
         P: Borrow<nn::Path<'p>>,
     {
@@ -87,26 +80,47 @@ impl GptJAttention {
             "Attention hidden states not a multiple of the number of heads"
         );
         let dim_per_head = config.n_embd / config.n_head;
-
-        let scale_attn = (dim_per_head as f32).sqrt();
-
-        let linear_config = nn::LinearConfig {
-            bias: false,
-            ..Default::default()
-        };
-        let k_proj = nn::linear(p / "k_proj", config.n_embd, config.n_embd, linear_config);
+        // With tensor parallelism, each worker holds a contiguous shard of the attention heads
+        let n_head = config.n_head / process_group.map_or(1, |group| group.world_size() as i64);
+        let shard_dim = n_head * dim_per_head;
+
+        let k_proj = linear(
+            p / "k_proj",
+            config.n_embd,
+            shard_dim,
+            false,
+            config.quantization,
+        );
         if config.use_float16 {
             (p / "k_proj").half();
         }
-        let v_proj = nn::linear(p / "v_proj", config.n_embd, config.n_embd, linear_config);
+        let v_proj = linear(
+            p / "v_proj",
+            config.n_embd,
+            shard_dim,
+            false,
+            config.quantization,
+        );
         if config.use_float16 {
             (p / "v_proj").half();
         }
-        let q_proj = nn::linear(p / "q_proj", config.n_embd, config.n_embd, linear_config);
+        let q_proj = linear(
+            p / "q_proj",
+            config.n_embd,
+            shard_dim,
+            false,
+            config.quantization,
+        );
         if config.use_float16 {
             (p / "q_proj").half();
         }
-        let out_proj = nn::linear(p / "out_proj", config.n_embd, config.n_embd, linear_config);
+        let out_proj = linear(
+            p / "out_proj",
+            shard_dim,
+            config.n_embd,
+            false,
+            config.quantization,
+        );
         if config.use_float16 {
             (p / "out_proj").half();
         }
@@ -116,16 +130,16 @@ impl GptJAttention {
             attn_dropout,
             resid_dropout,
             output_attentions,
-            scale_attn,
             k_proj,
             v_proj,
             q_proj,
             out_proj,
             dim_per_head,
-            n_head: config.n_head,
+            n_head,
             rotary_dim: config.rotary_dim,
             scale: config.scale_attn_weights.unwrap_or(true),
             use_cache: config.use_cache.unwrap_or(true),
+            max_positions,
         }
     }
 
@@ -177,53 +191,52 @@ impl GptJAttention {
         value: &Tensor,
         attention_mask: Option<&Tensor>,
         train: bool,
-    ) -> (Tensor, Tensor) {
+    ) -> (Tensor, Option<Tensor>) {
+        //    The attention is computed in full precision
         let query = query.to_kind(Kind::Float);
         let key = key.to_kind(Kind::Float);
-
-        let attention_weights = query.matmul(&key.transpose(-1, -2));
+        let float_value = value.to_kind(Kind::Float);
 
         let query_dims = query.size();
         let key_dims = key.size();
         let query_length = query_dims[query_dims.len() - 2];
         let key_length = key_dims[key_dims.len() - 2];
 
-        let causal_mask = &self
+        let causal_mask = self
             .bias
             .slice(2, key_length - query_length, key_length, 1)
             .slice(3, 0, key_length, 1)
             .to_kind(Kind::Bool)
-            .to_device(attention_weights.device());
-
-        let mask_value = get_min(attention_weights.kind()).unwrap();
-        let mask_value = Tensor::full(
-            attention_weights.size(),
-            mask_value,
-            (attention_weights.kind(), attention_weights.device()),
-        );
-
-        let mut attention_weights = attention_weights.where_self(causal_mask, &mask_value);
-        if self.scale {
-            attention_weights /= self.scale_attn;
-        }
+            .to_device(query.device());
+        let mut mask = Tensor::zeros(causal_mask.size(), (Kind::Float, query.device()))
+            .masked_fill(&causal_mask.logical_not(), get_min(Kind::Float).unwrap());
         if let Some(attention_mask_value) = attention_mask {
-            attention_weights += attention_mask_value;
+            mask = mask + attention_mask_value;
         };
-        let attention_weights = attention_weights.softmax(-1, attention_weights.kind());
-        let attention_weights = attention_weights
-            .to_kind(value.kind())
-            .apply_t(&self.attn_dropout, train);
-
-        let attention_output = attention_weights.matmul(value);
+        let scale = if self.scale { None } else { Some(1.0) };
+
+        let (attention_output, attention_weights) = scaled_dot_product_attention(
+            &query,
+            &key,
+            &float_value,
+            Some(&mask),
+            scale,
+            &self.attn_dropout,
+            self.output_attentions,
+            train,
+        );
 
-        (attention_output, attention_weights)
+        (
+            attention_output.to_kind(value.kind()),
+            attention_weights.map(|weights| weights.to_kind(value.kind())),
+        )
     }
 
     pub fn forward_t(
         &self,
         hidden_states: &Tensor,
         attention_mask: Option<&Tensor>,
-        layer_past: Option<&LayerState>,
+        layer_past: Option<LayerState>,
         train: bool,
     ) -> (Tensor, Option<LayerState>, Option<Tensor>) {
         let query = hidden_states.apply(&self.q_proj);
@@ -237,8 +250,8 @@ impl GptJAttention {
         let mut seq_len = key.size()[1];
         let mut offset = 0;
 
-        if let Some(layer_past) = layer_past {
-            offset = layer_past.prev_key.size()[layer_past.prev_key.size().len() - 2];
+        if let Some(layer_past) = &layer_past {
+            offset = layer_past.len();
             seq_len += offset
         };
 
@@ -264,15 +277,17 @@ impl GptJAttention {
         key = key.permute([0, 2, 1, 3]);
         query = query.permute([0, 2, 1, 3]);
 
-        if let Some(layer_past) = layer_past {
-            key = Tensor::cat(&[&layer_past.prev_key, &key], -2);
-            value = Tensor::cat(&[&layer_past.prev_value, &value], -2);
-        }
-
-        let present = self.use_cache.then(|| LayerState {
-            prev_key: key.copy(),
-            prev_value: value.copy(),
-        });
+        let present = match layer_past {
+            Some(mut layer_past) => {
+                let (cached_key, cached_value) = layer_past.update(&key, &value);
+                key = cached_key;
+                value = cached_value;
+                self.use_cache.then_some(layer_past)
+            }
+            None => self
+                .use_cache
+                .then(|| LayerState::new(&key, &value, self.max_positions)),
+        };
 
         let (attn_output, attn_weights) =
             self.attention(&query, &key, &value, attention_mask, train);
@@ -281,8 +296,6 @@ impl GptJAttention {
             .apply(&self.out_proj)
             .apply_t(&self.resid_dropout, train);
 
-        let attn_weights = self.output_attentions.then_some(attn_weights);
-
         (attn_output, present, attn_weights)
     }
 }
@@ -14,6 +14,9 @@ use crate::common::activations::Activation;
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::process_ids_embeddings_pair;
 use crate::common::kind::get_min;
+use crate::common::layer_norm::LayerNorm;
+use crate::common::quantization::WeightQuantization;
+use crate::common::tensor_parallel::{load_sharded_weights, shard_size, ProcessGroup};
 use crate::gpt_j::attention::LayerState;
 use crate::gpt_j::transformer::GptJBlock;
 use crate::pipelines::common::{ModelType, TokenizerOption};
@@ -24,6 +27,7 @@ use crate::pipelines::generation_utils::{Cache, GenerateConfig, LMModelOutput, L
 use crate::{Config, RustBertError};
 use serde::{Deserialize, Serialize};
 use std::borrow::{Borrow, BorrowMut};
+use std::sync::Arc;
 use tch::nn::{embedding, Linear};
 use tch::{nn, Device, Tensor};
 
@@ -138,6 +142,7 @@ pub struct GptJConfig {
     pub decoder_start_token_id: Option<i64>,
     pub forced_bos_token_id: Option<i64>,
     pub forced_eos_token_id: Option<i64>,
+    pub quantization: Option<WeightQuantization>,
 }
 
 impl Config for GptJConfig {}
@@ -169,6 +174,7 @@ impl Default for GptJConfig {
             decoder_start_token_id: None,
             forced_bos_token_id: None,
             forced_eos_token_id: None,
+            quantization: None,
         }
     }
 }
@@ -192,7 +198,7 @@ fn default_preload_on_cpu() -> bool {
 pub struct GptJModel {
     wte: nn::Embedding,
     drop: Dropout,
-    ln_f: nn::LayerNorm,
+    ln_f: LayerNorm,
     h: Vec<GptJBlock>,
     use_cache: bool,
     output_hidden_states: bool,
@@ -222,6 +228,75 @@ impl GptJModel {
     /// let gpt_j: GptJModel = GptJModel::new(&p.root() / "gpt_j", &config);
     /// ```
     pub fn new<'p, P>(p: P, config: &GptJConfig) -> GptJModel
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        Self::new_with_process_group(p, config, None)
+    }
+
+    /// Build a new `GptJModel` holding a shard of the attention heads and feed-forward layers, for tensor-parallel
+    /// inference across the workers of a process group. The outputs of the layers are summed across workers, and
+    /// all workers must run the same forward passes. Weights should be loaded with `load_sharded_weights`.
+    /// The number of attention heads and the intermediate size must be multiples of the number of workers. The cached
+    /// keys and values and the attention weights returned by each worker only cover its shard of the attention heads.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Variable store path for the root of the GPT-J model
+    /// * `config` - `GptJConfig` object defining the model architecture
+    /// * `process_group` - `ProcessGroup` of the workers sharing the model
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::gpt_j::{GptJConfig, GptJModel};
+    /// use rust_bert::tensor_parallel::TcpProcessGroup;
+    /// use rust_bert::Config;
+    /// use std::path::Path;
+    /// use std::sync::Arc;
+    /// use tch::{nn, Device};
+    ///
+    /// let config_path = Path::new("path/to/config.json");
+    /// let device = Device::Cpu;
+    /// let p = nn::VarStore::new(device);
+    /// let config = GptJConfig::from_file(config_path);
+    /// let process_group = Arc::new(TcpProcessGroup::new(0, 2, "127.0.0.1:29500")?);
+    /// let gpt_j: GptJModel = GptJModel::new_sharded(&p.root() / "gpt_j", &config, process_group)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_sharded<'p, P>(
+        p: P,
+        config: &GptJConfig,
+        process_group: Arc<dyn ProcessGroup>,
+    ) -> Result<GptJModel, RustBertError>
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let attention_shard_size = shard_size(config.n_head, Some(process_group.as_ref()))?
+            * (config.n_embd / config.n_head);
+        let intermediate_shard_size = shard_size(
+            config.n_inner.unwrap_or(4 * config.n_embd),
+            Some(process_group.as_ref()),
+        )?;
+        // Quantization groups of the layers sharded along their input dimension must not be split across workers
+        if let Some(WeightQuantization::Int4 { group_size }) = config.quantization {
+            if attention_shard_size % group_size != 0 || intermediate_shard_size % group_size != 0 {
+                return Err(RustBertError::InvalidConfigurationError(format!(
+                    "Sharded input dimensions ({} for the attention output, {} for the feed-forward output) must be multiples of the quantization group size {}",
+                    attention_shard_size, intermediate_shard_size, group_size
+                )));
+            }
+        }
+        Ok(Self::new_with_process_group(p, config, Some(process_group)))
+    }
+
+    fn new_with_process_group<'p, P>(
+        p: P,
+        config: &GptJConfig,
+        process_group: Option<Arc<dyn ProcessGroup>>,
+    ) -> GptJModel
     where
         P: Borrow<nn::Path<'p>>,
     {
@@ -244,7 +319,7 @@ impl GptJModel {
             eps: config.layer_norm_epsilon,
             ..Default::default()
         };
-        let ln_f = nn::layer_norm(&p / "ln_f", vec![config.n_embd], layer_norm_config);
+        let ln_f = LayerNorm::new(&p / "ln_f", vec![config.n_embd], layer_norm_config);
         if config.use_float16 {
             (&p / "ln_f").half()
         };
@@ -252,7 +327,11 @@ impl GptJModel {
         let mut h: Vec<GptJBlock> = vec![];
         let h_path = &p / "h";
         for layer_index in 0..config.n_layer {
-            h.push(GptJBlock::new(&h_path / layer_index, config));
+            h.push(GptJBlock::new_with_process_group(
+                &h_path / layer_index,
+                config,
+                process_group.clone(),
+            ));
         }
 
         let use_cache = config.use_cache.unwrap_or(true);
@@ -275,7 +354,7 @@ impl GptJModel {
     /// # Arguments
     ///
     /// * `input_ids` - Optional input tensor of shape (*batch size*, *sequence_length*). If None, pre-computed embeddings must be provided (see `input_embeds`)
-    /// * `layer_past` - Optional vector of length *n_layer* containing the past keys and values of each layer of shape (*2*, *batch size*, *number of heads*, *past_sequence_length*, *hidden size per head*). When provided, these are concatenated with the current input keys and values.
+    /// * `layer_past` - Optional vector of length *n_layer* containing the `LayerState` caches of past keys and values of each layer. When provided, the current input keys and values are appended to the caches in place.
     /// * `attention_mask` - Optional mask of shape (*batch size*, *sequence_length*). Masked position have value 0, non-masked value 1. If None set to 1
     /// * `input_embeds` - Optional pre-computed input embeddings of shape (*batch size*, *sequence_length*, *hidden_size*). If None, input ids must be provided (see `input_ids`)
     /// * `token_type_ids` - Optional token type ids used to indicate the portion of the input the token belongs to. If not None, token type embeddings will be added to the token and position embeddings.
@@ -286,7 +365,7 @@ impl GptJModel {
     ///
     /// * `GptJModelOutput` containing:
     ///   - `output` - `Tensor` of shape (*batch size*, *sequence_length*, *vocab_size*) representing the activations of the last hidden state
-    ///   - `cache` - `Option<Vec<Tensor>>` of length *n_layer* containing the past keys and values of each layer of shape (*2*, *batch size*, *number of heads*, *past_sequence_length*, *hidden size per head*)
+    ///   - `cache` - `Option<Vec<Option<LayerState>>>` of length *n_layer* containing the caches of past keys and values of each layer
     ///   - `all_hidden_states` - `Option<Vec<Tensor>>` of length *num_hidden_layers* with shape (*batch size*, *sequence_length*, *hidden_size*)
     ///   - `all_attentions` - `Option<Vec<Tensor>>` of length *num_hidden_layers* with shape (*batch size*, *sequence_length*, *hidden_size*)
     ///
@@ -308,26 +387,17 @@ impl GptJModel {
     /// let input_tensor = Tensor::rand(&[batch_size, sequence_length], (Int64, device));
     /// let mut past: Vec<Option<LayerState>> = Vec::with_capacity(config.n_layer as usize);
     /// for _ in 0..config.n_layer as usize {
-    ///     past.push(Some(LayerState {
-    ///         prev_key: Tensor::rand(
-    ///             &[
-    ///                 batch_size,
-    ///                 config.n_head,
-    ///                 past_sequence_length,
-    ///                 config.n_embd / config.n_head,
-    ///             ],
-    ///             (Double, device),
-    ///         ),
-    ///         prev_value: Tensor::rand(
-    ///             &[
-    ///                 batch_size,
-    ///                 config.n_head,
-    ///                 past_sequence_length,
-    ///                 config.n_embd / config.n_head,
-    ///             ],
-    ///             (Double, device),
-    ///         ),
-    ///     }))
+    ///     let past_shape = [
+    ///         batch_size,
+    ///         config.n_head,
+    ///         past_sequence_length,
+    ///         config.n_embd / config.n_head,
+    ///     ];
+    ///     past.push(Some(LayerState::new(
+    ///         &Tensor::rand(&past_shape, (Double, device)),
+    ///         &Tensor::rand(&past_shape, (Double, device)),
+    ///         config.n_positions,
+    ///     )))
     /// }
     /// let attention_mask = Tensor::zeros(&[batch_size, sequence_length], (Int64, device));
     /// let token_type_ids = Tensor::ones(&[batch_size, sequence_length], (Int64, device));
@@ -406,8 +476,7 @@ impl GptJModel {
         let mut all_attentions: Option<Vec<Tensor>> = self.output_attentions.then(Vec::new);
 
         for (layer, past) in self.h.iter().zip(layer_past) {
-            let temp =
-                layer.forward_t(&hidden_state, past.as_ref(), attention_mask.as_ref(), train);
+            let temp = layer.forward_t(&hidden_state, past, attention_mask.as_ref(), train)?;
             hidden_state = temp.0;
             if let Some(presents) = all_presents.borrow_mut() {
                 presents.push(temp.1);
@@ -469,6 +538,65 @@ impl GptJLMHeadModel {
         let p = p.borrow();
 
         let transformer = GptJModel::new(p, config);
+        Self::new_with_transformer(p, config, transformer)
+    }
+
+    /// Build a new `GptJLMHeadModel` holding a shard of the transformer layers, for tensor-parallel inference across
+    /// the workers of a process group (see `GptJModel::new_sharded`). The language modeling head is replicated on all workers.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Variable store path for the root of the GPT-J model
+    /// * `config` - `GptJConfig` object defining the model architecture
+    /// * `process_group` - `ProcessGroup` of the workers sharing the model
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::gpt_j::{GptJConfig, GptJLMHeadModel};
+    /// use rust_bert::tensor_parallel::{load_sharded_weights, ProcessGroup, TcpProcessGroup};
+    /// use rust_bert::resources::LocalResource;
+    /// use rust_bert::Config;
+    /// use std::path::{Path, PathBuf};
+    /// use std::sync::Arc;
+    /// use tch::{nn, Device};
+    ///
+    /// let config_path = Path::new("path/to/config.json");
+    /// let weights_resource = LocalResource::from(PathBuf::from("path/to/rust_model.ot"));
+    /// let device = Device::Cpu;
+    /// let mut p = nn::VarStore::new(device);
+    /// let config = GptJConfig::from_file(config_path);
+    /// let process_group = Arc::new(TcpProcessGroup::new(0, 2, "127.0.0.1:29500")?);
+    /// let gpt_j = GptJLMHeadModel::new_sharded(p.root(), &config, process_group.clone())?;
+    /// load_sharded_weights(
+    ///     &weights_resource,
+    ///     &mut p,
+    ///     process_group.rank(),
+    ///     process_group.world_size(),
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_sharded<'p, P>(
+        p: P,
+        config: &GptJConfig,
+        process_group: Arc<dyn ProcessGroup>,
+    ) -> Result<GptJLMHeadModel, RustBertError>
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+
+        let transformer = GptJModel::new_sharded(p, config, process_group)?;
+        Ok(Self::new_with_transformer(p, config, transformer))
+    }
+
+    fn new_with_transformer(
+        p: &nn::Path,
+        config: &GptJConfig,
+        transformer: GptJModel,
+    ) -> GptJLMHeadModel {
         let lm_head = nn::linear(
             p / "lm_head",
             config.n_embd,
@@ -587,6 +715,11 @@ impl GptJGenerator {
     /// # }
     /// ```
     pub fn new(generate_config: GenerateConfig) -> Result<GptJGenerator, RustBertError> {
+        let tokenizer = Self::build_tokenizer(&generate_config)?;
+        Self::new_with_tokenizer(generate_config, tokenizer)
+    }
+
+    fn build_tokenizer(generate_config: &GenerateConfig) -> Result<TokenizerOption, RustBertError> {
         let vocab_path = generate_config.vocab_resource.get_local_path()?;
         let merges_path = generate_config
             .merges_resource
@@ -598,21 +731,73 @@ impl GptJGenerator {
             })?
             .get_local_path()?;
 
-        let tokenizer = TokenizerOption::from_file(
+        TokenizerOption::from_file(
             ModelType::GPTJ,
             vocab_path.to_str().unwrap(),
             Some(merges_path.to_str().unwrap()),
             false,
             None,
             None,
-        )?;
-
-        Self::new_with_tokenizer(generate_config, tokenizer)
+        )
     }
 
     pub fn new_with_tokenizer(
         generate_config: GenerateConfig,
         tokenizer: TokenizerOption,
+    ) -> Result<GptJGenerator, RustBertError> {
+        Self::new_with_process_group(generate_config, tokenizer, None)
+    }
+
+    /// Build a new `GptJGenerator` holding a shard of the model, for tensor-parallel generation across the workers
+    /// of a process group. Each worker loads its shard of the (non-sharded) model weights. All workers must call the
+    /// generation methods with the same inputs and options (and the same random seed when sampling).
+    ///
+    /// # Arguments
+    ///
+    /// * `generate_config` - `GenerateConfig` object containing the resource references (model, vocabulary, configuration), generation options and device placement (CPU/GPU)
+    /// * `process_group` - `ProcessGroup` of the workers sharing the model
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::gpt_j::GptJGenerator;
+    /// use rust_bert::pipelines::generation_utils::GenerateConfig;
+    /// use rust_bert::tensor_parallel::TcpProcessGroup;
+    /// use std::sync::Arc;
+    ///
+    /// let process_group = Arc::new(TcpProcessGroup::new(0, 2, "127.0.0.1:29500")?);
+    /// let gpt_j_generator = GptJGenerator::new_sharded(GenerateConfig::default(), process_group)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_sharded(
+        generate_config: GenerateConfig,
+        process_group: Arc<dyn ProcessGroup>,
+    ) -> Result<GptJGenerator, RustBertError> {
+        let tokenizer = Self::build_tokenizer(&generate_config)?;
+        Self::new_with_process_group(generate_config, tokenizer, Some(process_group))
+    }
+
+    /// Build a new sharded `GptJGenerator` with a given tokenizer (see `new_sharded`)
+    ///
+    /// # Arguments
+    ///
+    /// * `generate_config` - `GenerateConfig` object containing the resource references (model, vocabulary, configuration), generation options and device placement (CPU/GPU)
+    /// * `tokenizer` - `TokenizerOption` tokenizer to use for text generation
+    /// * `process_group` - `ProcessGroup` of the workers sharing the model
+    pub fn new_with_tokenizer_sharded(
+        generate_config: GenerateConfig,
+        tokenizer: TokenizerOption,
+        process_group: Arc<dyn ProcessGroup>,
+    ) -> Result<GptJGenerator, RustBertError> {
+        Self::new_with_process_group(generate_config, tokenizer, Some(process_group))
+    }
+
+    fn new_with_process_group(
+        generate_config: GenerateConfig,
+        tokenizer: TokenizerOption,
+        process_group: Option<Arc<dyn ProcessGroup>>,
     ) -> Result<GptJGenerator, RustBertError> {
         let config_path = generate_config.config_resource.get_local_path()?;
         let device = generate_config.device;
@@ -621,11 +806,26 @@ impl GptJGenerator {
         let mut var_store = nn::VarStore::new(device);
 
         let config = GptJConfig::from_file(config_path);
-        let model = GptJLMHeadModel::new(var_store.root(), &config);
+        let model = match &process_group {
+            Some(process_group) => {
+                GptJLMHeadModel::new_sharded(var_store.root(), &config, process_group.clone())?
+            }
+            None => GptJLMHeadModel::new(var_store.root(), &config),
+        };
         if config.preload_on_cpu && device != Device::Cpu {
             var_store.set_device(Device::Cpu);
         }
-        crate::resources::load_weights(&generate_config.model_resource, &mut var_store)?;
+        match &process_group {
+            Some(process_group) => load_sharded_weights(
+                &generate_config.model_resource,
+                &mut var_store,
+                process_group.rank(),
+                process_group.world_size(),
+            )?,
+            None => {
+                crate::resources::load_weights(&generate_config.model_resource, &mut var_store)?
+            }
+        }
         if device != Device::Cpu {
             var_store.set_device(device);
         }
@@ -12,21 +12,38 @@
 
 use crate::common::activations::{Activation, TensorFunction};
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
+use crate::common::quantization::{linear, LinearLayer};
+use crate::common::tensor_parallel::ProcessGroup;
 use crate::gpt_j::attention::{GptJAttention, LayerState};
 use crate::gpt_j::gpt_j_model::GptJConfig;
+use crate::RustBertError;
 use std::borrow::Borrow;
-use tch::nn::Linear;
+use std::sync::Arc;
 use tch::{nn, Tensor};
 
 pub struct GptJMLP {
-    fc_in: Linear,
-    fc_out: Linear,
+    fc_in: LinearLayer,
+    fc_out: LinearLayer,
     activation: TensorFunction,
     dropout: Dropout,
+    add_output_bias: bool,
 }
 
 impl GptJMLP {
     pub fn new<'p, P>(p: P, config: &GptJConfig) -> GptJMLP
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        Self::new_with_process_group(p, config, None)
+    }
+
+    /// Build a new `GptJMLP` holding the shard of the intermediate activations of the current worker of `process_group`
+    pub fn new_with_process_group<'p, P>(
+        p: P,
+        config: &GptJConfig,
+        process_group: Option<&dyn ProcessGroup>,
+    ) -> GptJMLP
     where
         P: Borrow<nn::Path<'p>>,
     {
@@ -37,20 +54,25 @@ impl GptJMLP {
         } else {
             4 * config.n_embd
         };
-        let fc_in = nn::linear(
+        // With tensor parallelism, each worker holds a contiguous shard of the intermediate activations
+        let intermediate_size =
+            intermediate_size / process_group.map_or(1, |group| group.world_size() as i64);
+        let fc_in = linear(
             p / "fc_in",
             config.n_embd,
             intermediate_size,
-            Default::default(),
+            true,
+            config.quantization,
         );
         if config.use_float16 {
             (p / "fc_in").half()
         };
-        let fc_out = nn::linear(
+        let fc_out = linear(
             p / "fc_out",
             intermediate_size,
             config.n_embd,
-            Default::default(),
+            true,
+            config.quantization,
         );
         if config.use_float16 {
             (p / "fc_out").half()
@@ -68,28 +90,50 @@ impl GptJMLP {
         let resid_pdrop = config.resid_pdrop.unwrap_or(0.1);
         let dropout = Dropout::new(resid_pdrop);
 
+        // The partial outputs of the workers are summed: the output bias is added by the first worker only
+        let add_output_bias = process_group.map_or(true, |group| group.rank() == 0);
+
         GptJMLP {
             fc_in,
             fc_out,
             activation,
             dropout,
+            add_output_bias,
         }
     }
 
     pub fn forward_t(&self, hidden_states: &Tensor, train: bool) -> Tensor {
         let h = (self.activation.get_fn())(&hidden_states.apply(&self.fc_in));
-        h.apply(&self.fc_out).apply_t(&self.dropout, train)
+        let h = if self.add_output_bias {
+            h.apply(&self.fc_out)
+        } else {
+            self.fc_out.forward_no_bias(&h)
+        };
+        h.apply_t(&self.dropout, train)
     }
 }
 
 pub struct GptJBlock {
-    ln_1: nn::LayerNorm,
+    ln_1: LayerNorm,
     attn: GptJAttention,
     mlp: GptJMLP,
+    process_group: Option<Arc<dyn ProcessGroup>>,
 }
 
 impl GptJBlock {
     pub fn new<'p, P>(p: P, config: &GptJConfig) -> GptJBlock
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        Self::new_with_process_group(p, config, None)
+    }
+
+    /// Build a new `GptJBlock` holding the shard of the current worker of `process_group`, summing the outputs of the workers
+    pub fn new_with_process_group<'p, P>(
+        p: P,
+        config: &GptJConfig,
+        process_group: Option<Arc<dyn ProcessGroup>>,
+    ) -> GptJBlock
     where
         P: Borrow<nn::Path<'p>>,
     {
@@ -99,23 +143,29 @@ impl GptJBlock {
             eps: config.layer_norm_epsilon,
             ..Default::default()
         };
-        let ln_1 = nn::layer_norm(p / "ln_1", vec![config.n_embd], layer_norm_config);
+        let ln_1 = LayerNorm::new(p / "ln_1", vec![config.n_embd], layer_norm_config);
         if config.use_float16 {
             (p / "ln_1").half()
         };
-        let attn = GptJAttention::new(p / "attn", config);
-        let mlp = GptJMLP::new(p / "mlp", config);
-
-        GptJBlock { ln_1, attn, mlp }
+        let attn =
+            GptJAttention::new_with_process_group(p / "attn", config, process_group.as_deref());
+        let mlp = GptJMLP::new_with_process_group(p / "mlp", config, process_group.as_deref());
+
+        GptJBlock {
+            ln_1,
+            attn,
+            mlp,
+            process_group,
+        }
     }
 
     pub fn forward_t(
         &self,
         hidden_states: &Tensor,
-        layer_past: Option<&LayerState>,
+        layer_past: Option<LayerState>,
         attention_mask: Option<&Tensor>,
         train: bool,
-    ) -> (Tensor, Option<LayerState>, Option<Tensor>) {
+    ) -> Result<(Tensor, Option<LayerState>, Option<Tensor>), RustBertError> {
         let residual = hidden_states;
         let hidden_states = hidden_states.apply(&self.ln_1);
 
@@ -124,8 +174,12 @@ impl GptJBlock {
                 .forward_t(&hidden_states, attention_mask, layer_past, train);
 
         let feed_forward_hidden_states = self.mlp.forward_t(&hidden_states, train);
-        let hidden_states = attn_output + feed_forward_hidden_states + residual;
+        let mut hidden_states = attn_output + feed_forward_hidden_states;
+        if let Some(process_group) = &self.process_group {
+            process_group.all_reduce(&mut hidden_states)?;
+        }
+        let hidden_states = hidden_states + residual;
 
-        (hidden_states, present, attn_weights)
+        Ok((hidden_states, present, attn_weights))
     }
 }
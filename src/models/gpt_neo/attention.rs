@@ -10,51 +10,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::common::attention::scaled_dot_product_attention;
 use crate::common::dropout::Dropout;
+use crate::common::kv_cache::KVCache;
+use crate::common::quantization::{linear, LinearLayer};
 use crate::gpt_neo::gpt_neo_model::AttentionLayerType;
 use crate::gpt_neo::GptNeoConfig;
 use std::borrow::Borrow;
 use tch::{nn, Kind, Tensor};
 
-#[derive(Debug)]
 /// # Cache for GPT-Neo attention layers
-/// Stores the cached value of key and value
-pub struct LayerState {
-    /// Cached keys
-    pub prev_key: Tensor,
-    /// Cached values
-    pub prev_value: Option<Tensor>,
-}
-
-impl Clone for LayerState {
-    fn clone(&self) -> Self {
-        LayerState {
-            prev_key: self.prev_key.copy(),
-            prev_value: self.prev_value.as_ref().map(|value| value.copy()),
-        }
-    }
-}
-
-impl LayerState {
-    pub(crate) fn reorder_cache(&mut self, new_indices: &Tensor) {
-        self.prev_key = self.prev_key.index_select(0, new_indices);
-        self.prev_value = self
-            .prev_value
-            .as_ref()
-            .map(|value| value.index_select(0, new_indices));
-    }
-}
+/// Stores the cached value of key and value in preallocated buffers
+pub type LayerState = KVCache;
 
 pub struct GptNeoSelfAttention {
-    k_proj: nn::Linear,
-    v_proj: nn::Linear,
-    q_proj: nn::Linear,
-    out_proj: nn::Linear,
+    k_proj: LinearLayer,
+    v_proj: LinearLayer,
+    q_proj: LinearLayer,
+    out_proj: LinearLayer,
     attention_dropout: Dropout,
     resid_dropout: Dropout,
     bias: Tensor,
     num_heads: i64,
     head_dim: i64,
+    max_positions: i64,
     output_attentions: bool,
 }
 
@@ -85,33 +64,33 @@ impl GptNeoSelfAttention {
         let num_heads = config.num_heads;
         let head_dim = config.hidden_size / config.num_heads;
 
-        let linear_config = nn::LinearConfig {
-            bias: false,
-            ..Default::default()
-        };
-        let k_proj = nn::linear(
+        let k_proj = linear(
             p / "k_proj",
             config.hidden_size,
             config.hidden_size,
-            linear_config,
+            false,
+            config.quantization,
         );
-        let v_proj = nn::linear(
+        let v_proj = linear(
             p / "v_proj",
             config.hidden_size,
             config.hidden_size,
-            linear_config,
+            false,
+            config.quantization,
         );
-        let q_proj = nn::linear(
+        let q_proj = linear(
             p / "q_proj",
             config.hidden_size,
             config.hidden_size,
-            linear_config,
+            false,
+            config.quantization,
         );
-        let out_proj = nn::linear(
+        let out_proj = linear(
             p / "out_proj",
             config.hidden_size,
             config.hidden_size,
-            Default::default(),
+            true,
+            config.quantization,
         );
 
         let output_attentions = config.output_attentions.unwrap_or(false);
@@ -126,6 +105,7 @@ impl GptNeoSelfAttention {
             bias,
             num_heads,
             head_dim,
+            max_positions,
             output_attentions,
         }
     }
@@ -153,44 +133,49 @@ impl GptNeoSelfAttention {
         value: &Tensor,
         attention_mask: Option<&Tensor>,
         train: bool,
-    ) -> (Tensor, Tensor) {
+    ) -> (Tensor, Option<Tensor>) {
         let query = query.to_kind(Kind::Float);
         let key = key.to_kind(Kind::Float);
-
-        let attention_weights = query.matmul(&key.transpose(-1, -2));
+        let float_value = value.to_kind(Kind::Float);
 
         let query_dims = query.size();
         let key_dims = key.size();
         let query_length = query_dims[query_dims.len() - 2];
         let key_length = key_dims[key_dims.len() - 2];
 
-        let causal_mask = &self
+        let causal_mask = self
             .bias
             .slice(2, key_length - query_length, key_length, 1)
             .slice(3, 0, key_length, 1)
             .to_kind(Kind::Bool)
-            .to_device(attention_weights.device());
-
-        let mut attention_weights = attention_weights.where_self(
-            causal_mask,
-            &Tensor::from_slice(&[-1e9f32]).to_device(attention_weights.device()),
-        );
+            .to_device(query.device());
+        let mut mask = Tensor::zeros(causal_mask.size(), (Kind::Float, query.device()))
+            .masked_fill(&causal_mask.logical_not(), -1e9);
         if let Some(attention_mask_value) = attention_mask {
-            attention_weights = attention_weights + attention_mask_value;
+            mask = mask + attention_mask_value;
         };
 
-        let attention_weights = attention_weights.softmax(-1, attention_weights.kind());
-        let attention_weights = attention_weights
-            .to_kind(value.kind())
-            .apply_t(&self.attention_dropout, train);
-        let attention_output = attention_weights.matmul(value);
-        (attention_output, attention_weights)
+        // GPT-Neo does not scale the attention scores: the attention is always computed explicitly
+        let (attention_output, attention_weights) = scaled_dot_product_attention(
+            &query,
+            &key,
+            &float_value,
+            Some(&mask),
+            Some(1.0),
+            &self.attention_dropout,
+            self.output_attentions,
+            train,
+        );
+        (
+            attention_output.to_kind(value.kind()),
+            attention_weights.map(|weights| weights.to_kind(value.kind())),
+        )
     }
 
     pub fn forward_t(
         &self,
         hidden_states: &Tensor,
-        layer_state: Option<&LayerState>,
+        layer_state: Option<LayerState>,
         attention_mask: Option<&Tensor>,
         train: bool,
     ) -> (Tensor, Option<Tensor>, Option<LayerState>) {
@@ -199,22 +184,20 @@ impl GptNeoSelfAttention {
         let value = hidden_states.apply(&self.v_proj);
 
         let query = Self::split_heads(&query, self.num_heads, self.head_dim);
-        let mut key = Self::split_heads(&key, self.num_heads, self.head_dim);
-        let mut value = Self::split_heads(&value, self.num_heads, self.head_dim);
-
-        if let Some(layer_state_value) = &layer_state {
-            key = Tensor::cat(&[&layer_state_value.prev_key, &key], -2);
-            value = Tensor::cat(
-                &[layer_state_value.prev_value.as_ref().unwrap(), &value],
-                -2,
-            );
+        let key = Self::split_heads(&key, self.num_heads, self.head_dim);
+        let value = Self::split_heads(&value, self.num_heads, self.head_dim);
+
+        let (key, value, layer_state) = match layer_state {
+            Some(mut layer_state) => {
+                let (key, value) = layer_state.update(&key, &value);
+                (key, value, layer_state)
+            }
+            None => {
+                let layer_state = LayerState::new(&key, &value, self.max_positions);
+                (key, value, layer_state)
+            }
         };
 
-        let layer_state = Some(LayerState {
-            prev_key: key.copy(),
-            prev_value: Some(value.copy()),
-        });
-
         let (attention_output, attention_weights) =
             self.attend(&query, &key, &value, attention_mask, train);
 
@@ -222,12 +205,6 @@ impl GptNeoSelfAttention {
             .apply(&self.out_proj)
             .apply_t(&self.resid_dropout, train);
 
-        let attention_weights = if self.output_attentions {
-            Some(attention_weights)
-        } else {
-            None
-        };
-
-        (attention_output, attention_weights, layer_state)
+        (attention_output, attention_weights, Some(layer_state))
     }
 }
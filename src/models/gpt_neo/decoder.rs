@@ -12,6 +12,8 @@
 
 use crate::common::activations::TensorFunction;
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
+use crate::common::quantization::{linear, LinearLayer};
 use crate::gpt_neo::attention::{GptNeoSelfAttention, LayerState};
 use crate::gpt_neo::GptNeoConfig;
 use crate::RustBertError;
@@ -21,8 +23,8 @@ use tch::{nn, Tensor};
 
 #[derive(Debug)]
 pub struct GptNeoMLP {
-    c_fc: nn::Linear,
-    c_proj: nn::Linear,
+    c_fc: LinearLayer,
+    c_proj: LinearLayer,
     activation_function: TensorFunction,
     dropout: Dropout,
 }
@@ -34,17 +36,19 @@ impl GptNeoMLP {
     {
         let p = p.borrow();
 
-        let c_fc = nn::linear(
+        let c_fc = linear(
             p / "c_fc",
             config.hidden_size,
             intermediate_size,
-            Default::default(),
+            true,
+            config.quantization,
         );
-        let c_proj = nn::linear(
+        let c_proj = linear(
             p / "c_proj",
             intermediate_size,
             config.hidden_size,
-            Default::default(),
+            true,
+            config.quantization,
         );
 
         let activation_function = config.activation_function.get_function();
@@ -70,8 +74,8 @@ impl ModuleT for GptNeoMLP {
 }
 
 pub struct GptNeoBlock {
-    ln_1: nn::LayerNorm,
-    ln_2: nn::LayerNorm,
+    ln_1: LayerNorm,
+    ln_2: LayerNorm,
     attention: GptNeoSelfAttention,
     mlp: GptNeoMLP,
 }
@@ -88,8 +92,8 @@ impl GptNeoBlock {
             ..Default::default()
         };
 
-        let ln_1 = nn::layer_norm(p / "ln_1", vec![config.hidden_size], layer_norm_config);
-        let ln_2 = nn::layer_norm(p / "ln_2", vec![config.hidden_size], layer_norm_config);
+        let ln_1 = LayerNorm::new(p / "ln_1", vec![config.hidden_size], layer_norm_config);
+        let ln_2 = LayerNorm::new(p / "ln_2", vec![config.hidden_size], layer_norm_config);
         let attention_type = &config.attention_layers[layer_id];
         let attention =
             GptNeoSelfAttention::new(p.sub("attn").sub("attention"), config, attention_type);
@@ -109,7 +113,7 @@ impl GptNeoBlock {
     pub fn forward_t(
         &self,
         hidden_states: &Tensor,
-        layer_state: Option<&LayerState>,
+        layer_state: Option<LayerState>,
         attention_mask: Option<&Tensor>,
         train: bool,
     ) -> Result<(Tensor, Option<Tensor>, Option<LayerState>), RustBertError> {
@@ -12,6 +12,8 @@
 
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
+use crate::common::quantization::WeightQuantization;
 use crate::gpt_neo::decoder::GptNeoBlock;
 use crate::gpt_neo::LayerState;
 use crate::pipelines::common::{ModelType, TokenizerOption};
@@ -143,6 +145,7 @@ pub struct GptNeoConfig {
     pub output_hidden_states: Option<bool>,
     pub resid_dropout: f64,
     pub decoder_start_token_id: Option<i64>,
+    pub quantization: Option<WeightQuantization>,
 }
 
 impl Config for GptNeoConfig {}
@@ -181,6 +184,7 @@ impl Default for GptNeoConfig {
             output_hidden_states: None,
             resid_dropout: 0.0,
             decoder_start_token_id: None,
+            quantization: None,
         }
     }
 }
@@ -196,7 +200,7 @@ pub struct GptNeoModel {
     position_embeddings: nn::Embedding,
     layers: Vec<GptNeoBlock>,
     dropout: Dropout,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     output_attentions: bool,
     output_hidden_states: bool,
 }
@@ -250,7 +254,7 @@ impl GptNeoModel {
             ..Default::default()
         };
 
-        let layer_norm = nn::layer_norm(p / "ln_f", vec![config.hidden_size], layer_norm_config);
+        let layer_norm = LayerNorm::new(p / "ln_f", vec![config.hidden_size], layer_norm_config);
 
         let mut layers: Vec<GptNeoBlock> = Vec::with_capacity(config.num_layers as usize);
         let p_layers = p / "h";
@@ -284,7 +288,7 @@ impl GptNeoModel {
     /// * `input_embeds` - Optional input tensor of shape (*batch size*, *sequence_length*, *embeddings dimension*). This or `input_ids` must be provided.
     /// * `token_type_ids` - Optional token type ids used to indicate the portion of the input the token belongs to. If not None, token type embeddings will be added to the token and position embeddings.
     /// * `position_ids` - Optional position ids of shape (*batch size*, *sequence_length*). If None, will be incremented starting from the length of the past input.
-    /// * `layer_states` - Optional Vector `Option<Vec<Option<&LayerState>>>` of length *n_layer* containing tuples with the past keys and values for both the self attention of each layer.
+    /// * `layer_states` - Optional Vector `Option<Vec<Option<LayerState>>>` of length *n_layer* containing the caches of past keys and values of the self attention of each layer, updated in place.
     /// * `attention_mask` - Optional attention mask of shape (*batch size*, *sequence_length*) for the encoder positions. Positions with a mask with value 0 will be masked.
     /// * `train` - boolean flag to turn on/off the dropout layers in the model. Should be set to false for inference.
     ///
@@ -343,9 +347,7 @@ impl GptNeoModel {
 
         let past_length = if let Some(past_state_value) = &layer_states {
             if let Some(first_layer_state) = &past_state_value[0] {
-                let mut size_iter = first_layer_state.prev_key.size().into_iter().rev();
-                size_iter.next();
-                size_iter.next().unwrap()
+                first_layer_state.len()
             } else {
                 0
             }
@@ -409,19 +411,9 @@ impl GptNeoModel {
             self.layers.iter().enumerate().zip(old_cache.into_iter())
         {
             let temp = if let Some(x_value) = &x {
-                layer.forward_t(
-                    x_value,
-                    layer_state.as_ref(),
-                    attention_mask.as_ref(),
-                    train,
-                )?
+                layer.forward_t(x_value, layer_state, attention_mask.as_ref(), train)?
             } else {
-                layer.forward_t(
-                    &hidden_state,
-                    layer_state.as_ref(),
-                    attention_mask.as_ref(),
-                    train,
-                )?
+                layer.forward_t(&hidden_state, layer_state, attention_mask.as_ref(), train)?
             };
             x = Some(temp.0);
             attention_weights = temp.1;
@@ -497,7 +489,7 @@ impl GptNeoForCausalLM {
     /// * `input_embeds` - Optional input tensor of shape (*batch size*, *sequence_length*, *embeddings dimension*). This or `input_ids` must be provided.
     /// * `token_type_ids` - Optional token type ids used to indicate the portion of the input the token belongs to. If not None, token type embeddings will be added to the token and position embeddings.
     /// * `position_ids` - Optional position ids of shape (*batch size*, *sequence_length*). If None, will be incremented starting from the length of the past input.
-    /// * `layer_states` - Optional Vector `Option<Vec<Option<&LayerState>>>` of length *n_layer* containing tuples with the past keys and values for both the self attention of each layer.
+    /// * `layer_states` - Optional Vector `Option<Vec<Option<LayerState>>>` of length *n_layer* containing the caches of past keys and values of the self attention of each layer, updated in place.
     /// * `attention_mask` - Optional attention mask of shape (*batch size*, *sequence_length*) for the encoder positions. Positions with a mask with value 0 will be masked.
     /// * `train` - boolean flag to turn on/off the dropout layers in the model. Should be set to false for inference.
     ///
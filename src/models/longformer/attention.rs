@@ -591,7 +591,8 @@ impl LongformerSelfAttention {
             ]);
 
         let global_attention_probas = global_attention_scores
-            .softmax(-1, global_attention_scores.kind())
+            .softmax(-1, Kind::Float)
+            .to_kind(global_attention_scores.kind())
             .apply_t(&self.dropout, train);
 
         let global_attention_output = global_attention_probas.bmm(&global_value_vectors);
@@ -693,7 +694,8 @@ impl LongformerSelfAttention {
         };
 
         let mut attention_probas = attention_scores
-            .softmax(-1, attention_scores.kind())
+            .softmax(-1, Kind::Float)
+            .to_kind(attention_scores.kind())
             .masked_fill(&is_index_masked.unsqueeze(-1).unsqueeze(-1), 0.0)
             .apply_t(&self.dropout, train);
 
@@ -12,6 +12,7 @@
 
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
 use crate::longformer::LongformerConfig;
 use crate::RustBertError;
 use std::borrow::Borrow;
@@ -22,7 +23,7 @@ pub struct LongformerEmbeddings {
     word_embeddings: nn::Embedding,
     position_embeddings: nn::Embedding,
     token_type_embeddings: nn::Embedding,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: Dropout,
     pad_token_id: i64,
 }
@@ -65,7 +66,7 @@ impl LongformerEmbeddings {
             ..Default::default()
         };
         let layer_norm =
-            nn::layer_norm(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
         let dropout = Dropout::new(config.hidden_dropout_prob);
 
         LongformerEmbeddings {
@@ -12,6 +12,7 @@
 
 use crate::common::activations::TensorFunction;
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::longformer::attention::LongformerSelfAttention;
 use crate::longformer::LongformerConfig;
 use std::borrow::{Borrow, BorrowMut};
@@ -21,7 +22,7 @@ use tch::{nn, Tensor};
 
 pub struct LongformerSelfOutput {
     dense: nn::Linear,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: Dropout,
 }
 
@@ -44,7 +45,7 @@ impl LongformerSelfOutput {
         };
 
         let layer_norm =
-            nn::layer_norm(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
 
         let dropout = Dropout::new(config.hidden_dropout_prob);
         LongformerSelfOutput {
@@ -147,7 +148,7 @@ impl Module for LongformerIntermediate {
 
 pub struct LongformerOutput {
     dense: nn::Linear,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: Dropout,
 }
 
@@ -170,7 +171,7 @@ impl LongformerOutput {
         };
 
         let layer_norm =
-            nn::layer_norm(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
 
         let dropout = Dropout::new(config.hidden_dropout_prob);
 
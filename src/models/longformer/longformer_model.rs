@@ -10,9 +10,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::common::activations::{TensorFunction, _tanh};
+use crate::common::activations::{_tanh, TensorFunction};
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::get_shape_and_device_from_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
 use crate::longformer::embeddings::LongformerEmbeddings;
 use crate::longformer::encoder::LongformerEncoder;
 use crate::{Activation, Config, RustBertError};
@@ -217,7 +218,7 @@ impl Module for LongformerPooler {
 #[derive(Debug)]
 pub struct LongformerLMHead {
     dense: nn::Linear,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     decoder: nn::Linear,
     bias: Tensor,
 }
@@ -241,7 +242,7 @@ impl LongformerLMHead {
             ..Default::default()
         };
 
-        let layer_norm = nn::layer_norm(
+        let layer_norm = LayerNorm::new(
             p / "layer_norm",
             vec![config.hidden_size],
             layer_norm_config,
@@ -353,8 +353,7 @@ impl LongT5LocalAttention {
         let position_bias = position_bias.unwrap_or_else(|| calc_position_bias.as_ref().unwrap());
         scores += position_bias;
         let attention_weights = scores
-            .to_kind(Kind::Float)
-            .softmax(-1, scores.kind())
+            .softmax(-1, Kind::Float)
             .apply_t(&self.dropout, train)
             .to_kind(value_states.kind());
         let attention_output = unshape(&Tensor::einsum(
@@ -611,9 +610,9 @@ impl LongT5TransientGlobalAttention {
 
         scores += position_bias;
         let attention_weights = scores
-            .to_kind(Kind::Float)
-            .softmax(-1, scores.kind())
-            .apply_t(&self.dropout, train);
+            .softmax(-1, Kind::Float)
+            .apply_t(&self.dropout, train)
+            .to_kind(value_states.kind());
 
         let attention_output = unshape(&Tensor::einsum(
             "...hqk,...khd->...qhd",
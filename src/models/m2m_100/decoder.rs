@@ -10,8 +10,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::bart::{BartDecoderOutput, _expand_mask, _make_causal_mask};
+use crate::bart::{_expand_mask, _make_causal_mask, BartDecoderOutput};
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::m2m_100::embeddings::SinusoidalPositionalEmbedding;
 use crate::m2m_100::{LayerState, M2M100Config};
 use crate::mbart::MBartDecoderLayer;
@@ -22,7 +23,7 @@ pub type M2M100DecoderLayer = MBartDecoderLayer;
 
 pub struct M2M100Decoder {
     dropout: Dropout,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     layers: Vec<M2M100DecoderLayer>,
     embed_positions: SinusoidalPositionalEmbedding,
     output_attentions: bool,
@@ -53,7 +54,7 @@ impl M2M100Decoder {
 
         let dropout = Dropout::new(config.dropout);
 
-        let layer_norm = nn::layer_norm(p / "layer_norm", vec![config.d_model], Default::default());
+        let layer_norm = LayerNorm::new(p / "layer_norm", vec![config.d_model], Default::default());
 
         let embed_positions = SinusoidalPositionalEmbedding::new(
             p / "embed_positions",
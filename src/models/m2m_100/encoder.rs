@@ -10,8 +10,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::bart::{BartEncoderOutput, _expand_mask};
+use crate::bart::{_expand_mask, BartEncoderOutput};
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::m2m_100::embeddings::SinusoidalPositionalEmbedding;
 use crate::m2m_100::M2M100Config;
 use crate::mbart::MBartEncoderLayer;
@@ -22,7 +23,7 @@ pub type M2M100EncoderLayer = MBartEncoderLayer;
 
 pub struct M2M100Encoder {
     dropout: Dropout,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     layers: Vec<M2M100EncoderLayer>,
     embed_positions: SinusoidalPositionalEmbedding,
     output_attentions: bool,
@@ -51,7 +52,7 @@ impl M2M100Encoder {
 
         let dropout = Dropout::new(config.dropout);
 
-        let layer_norm = nn::layer_norm(p / "layer_norm", vec![config.d_model], Default::default());
+        let layer_norm = LayerNorm::new(p / "layer_norm", vec![config.d_model], Default::default());
 
         let embed_positions = SinusoidalPositionalEmbedding::new(
             p / "embed_positions",
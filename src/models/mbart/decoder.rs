@@ -10,9 +10,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::bart::{BartDecoderOutput, _expand_mask, _prepare_decoder_attention_mask};
+use crate::bart::{_expand_mask, _prepare_decoder_attention_mask, BartDecoderOutput};
 use crate::common::activations::TensorFunction;
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::mbart::attention::MBartAttention;
 use crate::mbart::embeddings::MBartLearnedPositionalEmbedding;
 use crate::mbart::{LayerState, MBartConfig};
@@ -23,14 +24,14 @@ use tch::{nn, Tensor};
 pub struct MBartDecoderLayer {
     self_attention: MBartAttention,
     encoder_attention: MBartAttention,
-    self_attention_layer_norm: nn::LayerNorm,
-    encoder_attention_layer_norm: nn::LayerNorm,
+    self_attention_layer_norm: LayerNorm,
+    encoder_attention_layer_norm: LayerNorm,
     dropout: Dropout,
     activation_dropout: Dropout,
     activation: TensorFunction,
     fc1: nn::Linear,
     fc2: nn::Linear,
-    final_layer_norm: nn::LayerNorm,
+    final_layer_norm: LayerNorm,
 }
 
 impl MBartDecoderLayer {
@@ -63,12 +64,12 @@ impl MBartDecoderLayer {
             true,
             output_attention,
         );
-        let self_attention_layer_norm = nn::layer_norm(
+        let self_attention_layer_norm = LayerNorm::new(
             p / "self_attn_layer_norm",
             vec![config.d_model],
             layer_norm_config,
         );
-        let encoder_attention_layer_norm = nn::layer_norm(
+        let encoder_attention_layer_norm = LayerNorm::new(
             p / "encoder_attn_layer_norm",
             vec![config.d_model],
             layer_norm_config,
@@ -91,7 +92,7 @@ impl MBartDecoderLayer {
             Default::default(),
         );
 
-        let final_layer_norm = nn::layer_norm(
+        let final_layer_norm = LayerNorm::new(
             p / "final_layer_norm",
             vec![config.d_model],
             layer_norm_config,
@@ -162,8 +163,8 @@ impl MBartDecoderLayer {
 
 pub struct MBartDecoder {
     dropout: Dropout,
-    layer_norm_embedding: nn::LayerNorm,
-    layer_norm: nn::LayerNorm,
+    layer_norm_embedding: LayerNorm,
+    layer_norm: LayerNorm,
     layers: Vec<MBartDecoderLayer>,
     embed_positions: MBartLearnedPositionalEmbedding,
     output_attentions: bool,
@@ -194,12 +195,12 @@ impl MBartDecoder {
 
         let dropout = Dropout::new(config.dropout);
 
-        let layer_norm_embedding = nn::layer_norm(
+        let layer_norm_embedding = LayerNorm::new(
             p / "layernorm_embedding",
             vec![config.d_model],
             Default::default(),
         );
-        let layer_norm = nn::layer_norm(p / "layer_norm", vec![config.d_model], Default::default());
+        let layer_norm = LayerNorm::new(p / "layer_norm", vec![config.d_model], Default::default());
 
         let embed_positions = MBartLearnedPositionalEmbedding::new(
             p / "embed_positions",
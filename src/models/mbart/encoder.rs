@@ -10,9 +10,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::bart::{BartEncoderOutput, _expand_mask};
+use crate::bart::{_expand_mask, BartEncoderOutput};
 use crate::common::activations::TensorFunction;
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::mbart::attention::MBartAttention;
 use crate::mbart::embeddings::MBartLearnedPositionalEmbedding;
 use crate::mbart::MBartConfig;
@@ -22,13 +23,13 @@ use tch::{nn, Tensor};
 
 pub struct MBartEncoderLayer {
     self_attention: MBartAttention,
-    self_attention_layer_norm: nn::LayerNorm,
+    self_attention_layer_norm: LayerNorm,
     dropout: Dropout,
     activation_dropout: Dropout,
     activation: TensorFunction,
     fc1: nn::Linear,
     fc2: nn::Linear,
-    final_layer_norm: nn::LayerNorm,
+    final_layer_norm: LayerNorm,
 }
 
 impl MBartEncoderLayer {
@@ -52,7 +53,7 @@ impl MBartEncoderLayer {
             false,
             output_attention,
         );
-        let self_attention_layer_norm = nn::layer_norm(
+        let self_attention_layer_norm = LayerNorm::new(
             p / "self_attn_layer_norm",
             vec![config.d_model],
             layer_norm_config,
@@ -77,7 +78,7 @@ impl MBartEncoderLayer {
             Default::default(),
         );
 
-        let final_layer_norm = nn::layer_norm(
+        let final_layer_norm = LayerNorm::new(
             p / "final_layer_norm",
             vec![config.d_model],
             layer_norm_config,
@@ -121,8 +122,8 @@ impl MBartEncoderLayer {
 
 pub struct MBartEncoder {
     dropout: Dropout,
-    layer_norm_embedding: nn::LayerNorm,
-    layer_norm: nn::LayerNorm,
+    layer_norm_embedding: LayerNorm,
+    layer_norm: LayerNorm,
     layers: Vec<MBartEncoderLayer>,
     embed_positions: MBartLearnedPositionalEmbedding,
     output_attentions: bool,
@@ -151,13 +152,13 @@ impl MBartEncoder {
 
         let dropout = Dropout::new(config.dropout);
 
-        let layer_norm_embedding = nn::layer_norm(
+        let layer_norm_embedding = LayerNorm::new(
             p / "layernorm_embedding",
             vec![config.d_model],
             Default::default(),
         );
 
-        let layer_norm = nn::layer_norm(p / "layer_norm", vec![config.d_model], Default::default());
+        let layer_norm = LayerNorm::new(p / "layer_norm", vec![config.d_model], Default::default());
 
         let embed_positions = MBartLearnedPositionalEmbedding::new(
             p / "embed_positions",
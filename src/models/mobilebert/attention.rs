@@ -10,6 +10,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::common::attention::scaled_dot_product_attention;
 use crate::common::dropout::Dropout;
 use crate::mobilebert::mobilebert_model::{NormalizationLayer, NormalizationType};
 use crate::mobilebert::MobileBertConfig;
@@ -99,21 +100,17 @@ impl MobileBertSelfAttention {
         let key = self.split_heads(key, bs, self.attention_head_size);
         let value = self.split_heads(value, bs, self.attention_head_size);
 
-        let mut attention_scores =
-            query.matmul(&key.transpose(-1, -2)) / (self.attention_head_size as f64).sqrt();
-        if let Some(attention_mask_value) = attention_mask {
-            attention_scores = attention_scores + attention_mask_value;
-        }
-        let attention_probs = attention_scores
-            .softmax(-1, attention_scores.kind())
-            .apply_t(&self.dropout, train);
-        let context = attention_probs.matmul(&value);
+        let (context, attention_probs) = scaled_dot_product_attention(
+            &query,
+            &key,
+            &value,
+            attention_mask,
+            None,
+            &self.dropout,
+            self.output_attentions,
+            train,
+        );
         let context = self.flatten(context, bs, self.attention_head_size);
-        let attention_probs = if self.output_attentions {
-            attention_probs.into()
-        } else {
-            None
-        };
         (context, attention_probs)
     }
 }
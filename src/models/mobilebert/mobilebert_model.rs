@@ -13,6 +13,7 @@
 use crate::common::activations::{Activation, TensorFunction};
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::get_shape_and_device_from_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
 use crate::mobilebert::embeddings::MobileBertEmbeddings;
 use crate::mobilebert::encoder::{MobileBertEncoder, MobileBertPooler};
 use crate::{Config, RustBertError};
@@ -124,7 +125,7 @@ impl Module for NoNorm {
 }
 
 pub enum NormalizationLayer {
-    LayerNorm(nn::LayerNorm),
+    LayerNorm(LayerNorm),
     NoNorm(NoNorm),
 }
 
@@ -145,7 +146,7 @@ impl NormalizationLayer {
                     eps: eps.unwrap_or(1e-12),
                     ..Default::default()
                 };
-                let layer_norm = nn::layer_norm(p, vec![hidden_size], layer_norm_config);
+                let layer_norm = LayerNorm::new(p, vec![hidden_size], layer_norm_config);
                 NormalizationLayer::LayerNorm(layer_norm)
             }
             NormalizationType::no_norm => {
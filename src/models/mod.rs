@@ -17,6 +17,7 @@ pub mod m2m_100;
 pub mod marian;
 pub mod mbart;
 pub mod mobilebert;
+pub mod mpnet;
 pub mod nllb;
 pub mod openai_gpt;
 pub mod pegasus;
@@ -0,0 +1,251 @@
+// Copyright 2020 The HuggingFace Inc. team, Microsoft Corporation.
+// Copyright 2019 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::activations::TensorFunction;
+use crate::common::attention::scaled_dot_product_attention;
+use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
+use crate::mpnet::mpnet_model::MPNetConfig;
+use std::borrow::Borrow;
+use tch::{nn, Tensor};
+
+#[derive(Debug)]
+pub struct MPNetSelfAttention {
+    num_attention_heads: i64,
+    attention_head_size: i64,
+    dropout: Dropout,
+    output_attentions: bool,
+    q: nn::Linear,
+    k: nn::Linear,
+    v: nn::Linear,
+    o: nn::Linear,
+}
+
+impl MPNetSelfAttention {
+    pub fn new<'p, P>(p: P, config: &MPNetConfig) -> MPNetSelfAttention
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        assert_eq!(
+            config.hidden_size % config.num_attention_heads,
+            0,
+            "Hidden size not a multiple of the number of attention heads"
+        );
+        let p = p.borrow();
+
+        let q = nn::linear(
+            p / "q",
+            config.hidden_size,
+            config.hidden_size,
+            Default::default(),
+        );
+        let k = nn::linear(
+            p / "k",
+            config.hidden_size,
+            config.hidden_size,
+            Default::default(),
+        );
+        let v = nn::linear(
+            p / "v",
+            config.hidden_size,
+            config.hidden_size,
+            Default::default(),
+        );
+        let o = nn::linear(
+            p / "o",
+            config.hidden_size,
+            config.hidden_size,
+            Default::default(),
+        );
+
+        let dropout = Dropout::new(config.attention_probs_dropout_prob);
+        let attention_head_size = config.hidden_size / config.num_attention_heads;
+        let output_attentions = config.output_attentions.unwrap_or(false);
+
+        MPNetSelfAttention {
+            num_attention_heads: config.num_attention_heads,
+            attention_head_size,
+            dropout,
+            output_attentions,
+            q,
+            k,
+            v,
+            o,
+        }
+    }
+
+    fn split_heads(&self, x: Tensor, bs: i64, dim_per_head: i64) -> Tensor {
+        x.view((bs, -1, self.num_attention_heads, dim_per_head))
+            .transpose(1, 2)
+    }
+
+    fn flatten(&self, x: Tensor, bs: i64, dim_per_head: i64) -> Tensor {
+        x.transpose(1, 2)
+            .contiguous()
+            .view((bs, -1, self.num_attention_heads * dim_per_head))
+    }
+
+    pub fn forward_t(
+        &self,
+        hidden_states: &Tensor,
+        mask: Option<&Tensor>,
+        position_bias: Option<&Tensor>,
+        train: bool,
+    ) -> (Tensor, Option<Tensor>) {
+        let bs = hidden_states.size()[0];
+
+        let query_layer =
+            self.split_heads(hidden_states.apply(&self.q), bs, self.attention_head_size);
+        let key_layer =
+            self.split_heads(hidden_states.apply(&self.k), bs, self.attention_head_size);
+        let value_layer =
+            self.split_heads(hidden_states.apply(&self.v), bs, self.attention_head_size);
+
+        //    The relative position bias is added to the attention scores as part of the mask
+        let mask = match (position_bias, mask) {
+            (Some(position_bias), Some(mask)) => Some(position_bias + mask),
+            (Some(position_bias), None) => Some(position_bias.shallow_clone()),
+            (None, Some(mask)) => Some(mask.shallow_clone()),
+            (None, None) => None,
+        };
+
+        let (context, weights) = scaled_dot_product_attention(
+            &query_layer,
+            &key_layer,
+            &value_layer,
+            mask.as_ref(),
+            None,
+            &self.dropout,
+            self.output_attentions,
+            train,
+        );
+        let context = self
+            .flatten(context, bs, self.attention_head_size)
+            .apply(&self.o);
+
+        (context, weights)
+    }
+}
+
+#[derive(Debug)]
+pub struct MPNetAttention {
+    attn: MPNetSelfAttention,
+    layer_norm: LayerNorm,
+    dropout: Dropout,
+}
+
+impl MPNetAttention {
+    pub fn new<'p, P>(p: P, config: &MPNetConfig) -> MPNetAttention
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+
+        let attn = MPNetSelfAttention::new(p / "attn", config);
+        let layer_norm_config = nn::LayerNormConfig {
+            eps: config.layer_norm_eps.unwrap_or(1e-12),
+            ..Default::default()
+        };
+        let layer_norm =
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+        let dropout = Dropout::new(config.hidden_dropout_prob);
+
+        MPNetAttention {
+            attn,
+            layer_norm,
+            dropout,
+        }
+    }
+
+    pub fn forward_t(
+        &self,
+        hidden_states: &Tensor,
+        mask: Option<&Tensor>,
+        position_bias: Option<&Tensor>,
+        train: bool,
+    ) -> (Tensor, Option<Tensor>) {
+        let (self_output, attention_weights) =
+            self.attn
+                .forward_t(hidden_states, mask, position_bias, train);
+
+        let self_output: Tensor = self_output.apply_t(&self.dropout, train) + hidden_states;
+        (self_output.apply(&self.layer_norm), attention_weights)
+    }
+}
+
+pub struct MPNetIntermediate {
+    lin: nn::Linear,
+    activation: TensorFunction,
+}
+
+impl MPNetIntermediate {
+    pub fn new<'p, P>(p: P, config: &MPNetConfig) -> MPNetIntermediate
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+
+        let lin = nn::linear(
+            p / "dense",
+            config.hidden_size,
+            config.intermediate_size,
+            Default::default(),
+        );
+        let activation = config.hidden_act.get_function();
+        MPNetIntermediate { lin, activation }
+    }
+
+    pub fn forward(&self, hidden_states: &Tensor) -> Tensor {
+        (self.activation.get_fn())(&hidden_states.apply(&self.lin))
+    }
+}
+
+pub struct MPNetOutput {
+    lin: nn::Linear,
+    layer_norm: LayerNorm,
+    dropout: Dropout,
+}
+
+impl MPNetOutput {
+    pub fn new<'p, P>(p: P, config: &MPNetConfig) -> MPNetOutput
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+
+        let lin = nn::linear(
+            p / "dense",
+            config.intermediate_size,
+            config.hidden_size,
+            Default::default(),
+        );
+        let layer_norm_config = nn::LayerNormConfig {
+            eps: config.layer_norm_eps.unwrap_or(1e-12),
+            ..Default::default()
+        };
+        let layer_norm =
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+        let dropout = Dropout::new(config.hidden_dropout_prob);
+
+        MPNetOutput {
+            lin,
+            layer_norm,
+            dropout,
+        }
+    }
+
+    pub fn forward_t(&self, hidden_states: &Tensor, input_tensor: &Tensor, train: bool) -> Tensor {
+        let hidden_states: Tensor =
+            input_tensor + hidden_states.apply(&self.lin).apply_t(&self.dropout, train);
+        hidden_states.apply(&self.layer_norm)
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright 2020 The HuggingFace Inc. team, Microsoft Corporation.
+// Copyright 2019 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::dropout::Dropout;
+use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
+use crate::mpnet::MPNetConfig;
+use crate::RustBertError;
+use std::borrow::Borrow;
+use tch::nn::{embedding, EmbeddingConfig};
+use tch::{nn, Kind, Tensor};
+
+#[derive(Debug)]
+/// # Embeddings implementation for MPNet model
+/// MPNet embeddings are made of word and absolute position embeddings (no segment embeddings).
+/// Position ids are offset by the padding index, following the RoBERTa convention.
+pub struct MPNetEmbeddings {
+    word_embeddings: nn::Embedding,
+    position_embeddings: nn::Embedding,
+    layer_norm: LayerNorm,
+    dropout: Dropout,
+    padding_index: i64,
+}
+
+impl MPNetEmbeddings {
+    /// Build a new `MPNetEmbeddings`
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Variable store path for the root of the embeddings
+    /// * `config` - `MPNetConfig` object defining the model architecture and vocab/hidden size
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::mpnet::{MPNetConfig, MPNetEmbeddings};
+    /// use rust_bert::Config;
+    /// use std::path::Path;
+    /// use tch::{nn, Device};
+    ///
+    /// let config_path = Path::new("path/to/config.json");
+    /// let device = Device::Cpu;
+    /// let p = nn::VarStore::new(device);
+    /// let config = MPNetConfig::from_file(config_path);
+    /// let mpnet_embeddings = MPNetEmbeddings::new(&p.root() / "embeddings", &config);
+    /// ```
+    pub fn new<'p, P>(p: P, config: &MPNetConfig) -> MPNetEmbeddings
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+        let padding_index = config.pad_token_id.unwrap_or(1);
+
+        let embedding_config = EmbeddingConfig {
+            padding_idx: padding_index,
+            ..Default::default()
+        };
+
+        let word_embeddings: nn::Embedding = embedding(
+            p / "word_embeddings",
+            config.vocab_size,
+            config.hidden_size,
+            embedding_config,
+        );
+
+        let position_embeddings: nn::Embedding = embedding(
+            p / "position_embeddings",
+            config.max_position_embeddings,
+            config.hidden_size,
+            embedding_config,
+        );
+
+        let layer_norm_config = nn::LayerNormConfig {
+            eps: config.layer_norm_eps.unwrap_or(1e-12),
+            ..Default::default()
+        };
+        let layer_norm: LayerNorm =
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+        let dropout: Dropout = Dropout::new(config.hidden_dropout_prob);
+        MPNetEmbeddings {
+            word_embeddings,
+            position_embeddings,
+            layer_norm,
+            dropout,
+            padding_index,
+        }
+    }
+
+    fn create_position_ids_from_input_ids(&self, x: &Tensor) -> Tensor {
+        let mask: Tensor = x.ne(self.padding_index).to_kind(Kind::Int64);
+        mask.cumsum(1, Kind::Int64) * mask + self.padding_index
+    }
+
+    fn create_position_ids_from_embeddings(&self, x: &Tensor) -> Tensor {
+        let input_shape = x.size();
+        let input_shape = vec![input_shape[0], input_shape[1]];
+        let position_ids: Tensor = Tensor::arange_start(
+            self.padding_index + 1,
+            input_shape[1] + self.padding_index + 1,
+            (Kind::Int64, x.device()),
+        );
+        position_ids.unsqueeze(0).expand(&input_shape, true)
+    }
+
+    /// Forward pass through the embedding layer.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_ids` - Optional input tensor of shape (*batch size*, *sequence_length*). If None, pre-computed embeddings must be provided (see *input_embeds*)
+    /// * `position_ids` - Optional position ids of shape (*batch size*, *sequence_length*). If None, will be incremented from the padding index, skipping padding positions.
+    /// * `input_embeds` - Optional pre-computed input embeddings of shape (*batch size*, *sequence_length*, *hidden_size*). If None, input ids must be provided (see *input_ids*)
+    /// * `train` - boolean flag to turn on/off the dropout layers in the model. Should be set to false for inference.
+    ///
+    /// # Returns
+    ///
+    /// * `embedded_output` - `Tensor` of shape (*batch size*, *sequence_length*, *hidden_size*)
+    pub fn forward_t(
+        &self,
+        input_ids: Option<&Tensor>,
+        position_ids: Option<&Tensor>,
+        input_embeds: Option<&Tensor>,
+        train: bool,
+    ) -> Result<Tensor, RustBertError> {
+        let (calc_input_embeddings, _, _) =
+            process_ids_embeddings_pair(input_ids, input_embeds, &self.word_embeddings)?;
+
+        let input_embeddings =
+            input_embeds.unwrap_or_else(|| calc_input_embeddings.as_ref().unwrap());
+
+        let calc_position_ids = if position_ids.is_none() {
+            Some(match input_ids {
+                Some(value) => self.create_position_ids_from_input_ids(value),
+                None => self.create_position_ids_from_embeddings(input_embeds.unwrap()),
+            })
+        } else {
+            None
+        };
+        let position_ids = position_ids.unwrap_or_else(|| calc_position_ids.as_ref().unwrap());
+
+        let position_embeddings = position_ids.apply(&self.position_embeddings);
+
+        let input_embeddings: Tensor = input_embeddings + position_embeddings;
+        Ok(input_embeddings
+            .apply(&self.layer_norm)
+            .apply_t(&self.dropout, train))
+    }
+}
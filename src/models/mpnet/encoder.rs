@@ -0,0 +1,337 @@
+// Copyright 2020 The HuggingFace Inc. team, Microsoft Corporation.
+// Copyright 2019 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::mpnet::attention::{MPNetAttention, MPNetIntermediate, MPNetOutput};
+use crate::mpnet::mpnet_model::MPNetConfig;
+use std::borrow::{Borrow, BorrowMut};
+use tch::nn::embedding;
+use tch::{nn, Kind, Tensor};
+
+/// # MPNet Layer
+/// Layer used in MPNet encoders.
+/// It is made of the following blocks:
+/// - `attention`: self-attention `MPNetAttention` layer, using the relative position bias computed by the encoder
+/// - `intermediate`: `MPNetIntermediate` intermediate layer
+/// - `output`: `MPNetOutput` output layer
+pub struct MPNetLayer {
+    attention: MPNetAttention,
+    intermediate: MPNetIntermediate,
+    output: MPNetOutput,
+}
+
+impl MPNetLayer {
+    /// Build a new `MPNetLayer`
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Variable store path for the root of the layer
+    /// * `config` - `MPNetConfig` object defining the model architecture
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::mpnet::{MPNetConfig, MPNetLayer};
+    /// use rust_bert::Config;
+    /// use std::path::Path;
+    /// use tch::{nn, Device};
+    ///
+    /// let config_path = Path::new("path/to/config.json");
+    /// let device = Device::Cpu;
+    /// let p = nn::VarStore::new(device);
+    /// let config = MPNetConfig::from_file(config_path);
+    /// let layer: MPNetLayer = MPNetLayer::new(&p.root(), &config);
+    /// ```
+    pub fn new<'p, P>(p: P, config: &MPNetConfig) -> MPNetLayer
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+
+        let attention = MPNetAttention::new(p / "attention", config);
+        let intermediate = MPNetIntermediate::new(p / "intermediate", config);
+        let output = MPNetOutput::new(p / "output", config);
+
+        MPNetLayer {
+            attention,
+            intermediate,
+            output,
+        }
+    }
+
+    /// Forward pass through the layer
+    ///
+    /// # Arguments
+    ///
+    /// * `hidden_states` - input tensor of shape (*batch size*, *sequence_length*, *hidden_size*).
+    /// * `mask` - Optional extended attention mask, broadcastable to (*batch size*, *num_heads*, *sequence_length*, *sequence_length*), with masked positions set to a large negative value.
+    /// * `position_bias` - Optional relative position bias of shape (*batch size*, *num_heads*, *sequence_length*, *sequence_length*).
+    /// * `train` - boolean flag to turn on/off the dropout layers in the model. Should be set to false for inference.
+    ///
+    /// # Returns
+    ///
+    /// * `MPNetLayerOutput` containing:
+    ///   - `hidden_state` - `Tensor` of shape (*batch size*, *sequence_length*, *hidden_size*)
+    ///   - `attention_weights` - `Option<Tensor>` of shape (*batch size*, *num_heads*, *sequence_length*, *sequence_length*)
+    pub fn forward_t(
+        &self,
+        hidden_states: &Tensor,
+        mask: Option<&Tensor>,
+        position_bias: Option<&Tensor>,
+        train: bool,
+    ) -> MPNetLayerOutput {
+        let (attention_output, attention_weights) =
+            self.attention
+                .forward_t(hidden_states, mask, position_bias, train);
+
+        let output = self.intermediate.forward(&attention_output);
+        let output = self.output.forward_t(&output, &attention_output, train);
+
+        MPNetLayerOutput {
+            hidden_state: output,
+            attention_weights,
+        }
+    }
+}
+
+/// # MPNet Encoder
+/// Encoder used in MPNet models.
+/// It is made of a Vector of `MPNetLayer` through which hidden states will be passed, and of
+/// a relative attention bias embedding shared by all layers.
+pub struct MPNetEncoder {
+    output_attentions: bool,
+    output_hidden_states: bool,
+    layers: Vec<MPNetLayer>,
+    relative_attention_bias: nn::Embedding,
+    relative_attention_num_buckets: i64,
+}
+
+impl MPNetEncoder {
+    /// Build a new `MPNetEncoder`
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Variable store path for the root of the encoder
+    /// * `config` - `MPNetConfig` object defining the model architecture
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::mpnet::{MPNetConfig, MPNetEncoder};
+    /// use rust_bert::Config;
+    /// use std::path::Path;
+    /// use tch::{nn, Device};
+    ///
+    /// let config_path = Path::new("path/to/config.json");
+    /// let device = Device::Cpu;
+    /// let p = nn::VarStore::new(device);
+    /// let config = MPNetConfig::from_file(config_path);
+    /// let encoder: MPNetEncoder = MPNetEncoder::new(&p.root(), &config);
+    /// ```
+    pub fn new<'p, P>(p: P, config: &MPNetConfig) -> MPNetEncoder
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+        let output_attentions = config.output_attentions.unwrap_or(false);
+        let output_hidden_states = config.output_hidden_states.unwrap_or(false);
+        let relative_attention_num_buckets = config.relative_attention_num_buckets.unwrap_or(32);
+
+        let p_layers = p / "layer";
+        let mut layers: Vec<MPNetLayer> = vec![];
+        for layer_index in 0..config.num_hidden_layers {
+            layers.push(MPNetLayer::new(&p_layers / layer_index, config));
+        }
+
+        let relative_attention_bias = embedding(
+            p / "relative_attention_bias",
+            relative_attention_num_buckets,
+            config.num_attention_heads,
+            Default::default(),
+        );
+
+        MPNetEncoder {
+            output_attentions,
+            output_hidden_states,
+            layers,
+            relative_attention_bias,
+            relative_attention_num_buckets,
+        }
+    }
+
+    fn get_relative_position_bucket(
+        relative_position: &Tensor,
+        num_buckets: i64,
+        max_distance: i64,
+    ) -> Tensor {
+        let n = relative_position.neg();
+        let num_buckets = num_buckets / 2;
+        let ret = n.lt(0).to_kind(Kind::Int64) * num_buckets;
+        let n = n.abs();
+
+        let max_exact = num_buckets / 2;
+        let is_small = n.lt(max_exact);
+
+        let value_if_large: Tensor = ((n.to_kind(Kind::Float) / max_exact as f64).log()
+            / (max_distance as f64 / max_exact as f64).ln()
+            * (num_buckets - max_exact) as f64)
+            .to_kind(Kind::Int64)
+            + max_exact;
+        let value_if_large = value_if_large.clamp_max(num_buckets - 1);
+
+        ret + n.where_self(&is_small, &value_if_large)
+    }
+
+    /// Computes the relative position bias shared by all layers, of shape
+    /// (*batch size*, *num_heads*, *sequence_length*, *sequence_length*).
+    fn compute_position_bias(&self, hidden_states: &Tensor) -> Tensor {
+        let input_shape = hidden_states.size();
+        let (batch_size, sequence_length) = (input_shape[0], input_shape[1]);
+        let device = hidden_states.device();
+
+        let context_position = Tensor::arange(sequence_length, (Kind::Int64, device)).unsqueeze(1);
+        let memory_position = Tensor::arange(sequence_length, (Kind::Int64, device)).unsqueeze(0);
+        let relative_position = memory_position - context_position;
+
+        let rp_bucket = Self::get_relative_position_bucket(
+            &relative_position,
+            self.relative_attention_num_buckets,
+            128,
+        );
+        rp_bucket
+            .apply(&self.relative_attention_bias)
+            .permute([2, 0, 1])
+            .unsqueeze(0)
+            .expand([batch_size, -1, sequence_length, sequence_length], true)
+            .contiguous()
+            .to_kind(hidden_states.kind())
+    }
+
+    /// Forward pass through the encoder
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - input tensor of shape (*batch size*, *sequence_length*, *hidden_size*).
+    /// * `mask` - Optional extended attention mask, broadcastable to (*batch size*, *num_heads*, *sequence_length*, *sequence_length*), with masked positions set to a large negative value.
+    /// * `train` - boolean flag to turn on/off the dropout layers in the model. Should be set to false for inference.
+    ///
+    /// # Returns
+    ///
+    /// * `MPNetEncoderOutput` containing:
+    ///   - `hidden_state` - `Tensor` of shape (*batch size*, *sequence_length*, *hidden_size*)
+    ///   - `all_hidden_states` - `Option<Vec<Tensor>>` of length *num_hidden_layers* with shape (*batch size*, *sequence_length*, *hidden_size*)
+    ///   - `all_attentions` - `Option<Vec<Tensor>>` of length *num_hidden_layers* with shape (*batch size*, *num_heads*, *sequence_length*, *sequence_length*)
+    pub fn forward_t(
+        &self,
+        input: &Tensor,
+        mask: Option<&Tensor>,
+        train: bool,
+    ) -> MPNetEncoderOutput {
+        let mut all_hidden_states: Option<Vec<Tensor>> = if self.output_hidden_states {
+            Some(vec![])
+        } else {
+            None
+        };
+        let mut all_attentions: Option<Vec<Tensor>> = if self.output_attentions {
+            Some(vec![])
+        } else {
+            None
+        };
+
+        let position_bias = self.compute_position_bias(input);
+        let mut hidden_state = None::<Tensor>;
+        let mut attention_weights: Option<Tensor>;
+
+        for layer in &self.layers {
+            let layer_output = if let Some(hidden_state) = &hidden_state {
+                layer.forward_t(hidden_state, mask, Some(&position_bias), train)
+            } else {
+                layer.forward_t(input, mask, Some(&position_bias), train)
+            };
+
+            hidden_state = Some(layer_output.hidden_state);
+            attention_weights = layer_output.attention_weights;
+            if let Some(attentions) = all_attentions.borrow_mut() {
+                attentions.push(std::mem::take(&mut attention_weights.unwrap()));
+            };
+            if let Some(hidden_states) = all_hidden_states.borrow_mut() {
+                hidden_states.push(hidden_state.as_ref().unwrap().copy());
+            };
+        }
+
+        MPNetEncoderOutput {
+            hidden_state: hidden_state.unwrap_or_else(|| input.copy()),
+            all_hidden_states,
+            all_attentions,
+        }
+    }
+}
+
+/// # MPNet Pooler
+/// Pooler used in MPNet models.
+/// It is made of a fully connected layer which is applied to the first sequence element.
+pub struct MPNetPooler {
+    lin: nn::Linear,
+}
+
+impl MPNetPooler {
+    /// Build a new `MPNetPooler`
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Variable store path for the root of the pooler
+    /// * `config` - `MPNetConfig` object defining the model architecture
+    pub fn new<'p, P>(p: P, config: &MPNetConfig) -> MPNetPooler
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+
+        let lin = nn::linear(
+            p / "dense",
+            config.hidden_size,
+            config.hidden_size,
+            Default::default(),
+        );
+        MPNetPooler { lin }
+    }
+
+    /// Forward pass through the pooler
+    ///
+    /// # Arguments
+    ///
+    /// * `hidden_states` - input tensor of shape (*batch size*, *sequence_length*, *hidden_size*).
+    ///
+    /// # Returns
+    ///
+    /// * `Tensor` of shape (*batch size*, *hidden_size*)
+    pub fn forward(&self, hidden_states: &Tensor) -> Tensor {
+        hidden_states.select(1, 0).apply(&self.lin).tanh()
+    }
+}
+
+/// Container for the MPNet layer output.
+pub struct MPNetLayerOutput {
+    /// Hidden states
+    pub hidden_state: Tensor,
+    /// Self attention scores
+    pub attention_weights: Option<Tensor>,
+}
+
+/// Container for the MPNet encoder output.
+pub struct MPNetEncoderOutput {
+    /// Last hidden states from the model
+    pub hidden_state: Tensor,
+    /// Hidden states for all intermediate layers
+    pub all_hidden_states: Option<Vec<Tensor>>,
+    /// Attention weights for all intermediate layers
+    pub all_attentions: Option<Vec<Tensor>>,
+}
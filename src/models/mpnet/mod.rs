@@ -0,0 +1,54 @@
+//! # MPNet: Masked and Permuted Pre-training for Language Understanding (Song et al.)
+//!
+//! Implementation of the MPNet language model ([https://arxiv.org/abs/2004.09297](https://arxiv.org/abs/2004.09297) Song, Tan, Qin, Lu, Liu, 2020).
+//! The base model is implemented in the `mpnet_model::MPNetModel` struct. MPNet shares the BERT encoder layout but
+//! replaces segment embeddings with a learned relative position bias (T5-style buckets) shared across all layers.
+//! The model is mainly used as a backbone for sentence embeddings (e.g. `all-mpnet-base-v2`).
+//!
+//! # Model set-up and pre-trained weights loading
+//!
+//! All models expect the following resources:
+//! - Configuration file expected to have a structure following the [Transformers library](https://github.com/huggingface/transformers)
+//! - Model weights are expected to have a structure and parameter names following the [Transformers library](https://github.com/huggingface/transformers). A conversion using the Python utility scripts is required to convert the `.bin` weights to the `.ot` format.
+//! - `BertTokenizer` using a `vocab.txt` vocabulary remapped to the MPNet special tokens (see `TokenizerOption::from_file` with `ModelType::MPNet`)
+//!
+//! Pretrained models are available and can be downloaded using RemoteResources.
+//!
+//! ```no_run
+//! # fn main() -> anyhow::Result<()> {
+//! #
+//! use tch::{nn, Device};
+//! # use std::path::PathBuf;
+//! use rust_bert::mpnet::{MPNetConfig, MPNetModel};
+//! use rust_bert::resources::{LocalResource, ResourceProvider};
+//! use rust_bert::Config;
+//!
+//! let config_resource = LocalResource {
+//!     local_path: PathBuf::from("path/to/config.json"),
+//! };
+//! let weights_resource = LocalResource {
+//!     local_path: PathBuf::from("path/to/model.ot"),
+//! };
+//! let config_path = config_resource.get_local_path()?;
+//! let weights_path = weights_resource.get_local_path()?;
+//! let device = Device::cuda_if_available();
+//! let mut vs = nn::VarStore::new(device);
+//! let config = MPNetConfig::from_file(config_path);
+//! let mpnet_model = MPNetModel::new(&vs.root(), &config);
+//! vs.load(weights_path)?;
+//!
+//! # Ok(())
+//! # }
+//! ```
+
+mod attention;
+mod embeddings;
+mod encoder;
+mod mpnet_model;
+
+pub use embeddings::MPNetEmbeddings;
+pub use encoder::{MPNetEncoder, MPNetEncoderOutput, MPNetLayer, MPNetLayerOutput, MPNetPooler};
+pub use mpnet_model::{
+    MPNetConfig, MPNetConfigResources, MPNetForSentenceEmbeddings, MPNetModel, MPNetModelOutput,
+    MPNetModelResources, MPNetVocabResources,
+};
@@ -0,0 +1,301 @@
+// Copyright 2020 The HuggingFace Inc. team, Microsoft Corporation.
+// Copyright 2019 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::activations::Activation;
+use crate::common::embeddings::get_shape_and_device_from_ids_embeddings_pair;
+use crate::mpnet::embeddings::MPNetEmbeddings;
+use crate::mpnet::encoder::{MPNetEncoder, MPNetPooler};
+use crate::{Config, RustBertError};
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use tch::{nn, Kind, Tensor};
+
+/// # MPNet Pretrained model weight files
+pub struct MPNetModelResources;
+
+/// # MPNet Pretrained model config files
+pub struct MPNetConfigResources;
+
+/// # MPNet Pretrained model vocab files
+pub struct MPNetVocabResources;
+
+impl MPNetModelResources {
+    /// Shared under Apache 2.0 license at <https://huggingface.co/sentence-transformers/all-mpnet-base-v2>. Modified with conversion to C-array format.
+    pub const ALL_MPNET_BASE_V2: (&'static str, &'static str) = (
+        "all-mpnet-base-v2/model",
+        "https://huggingface.co/sentence-transformers/all-mpnet-base-v2/resolve/main/rust_model.ot",
+    );
+}
+
+impl MPNetConfigResources {
+    /// Shared under Apache 2.0 license at <https://huggingface.co/sentence-transformers/all-mpnet-base-v2>. Modified with conversion to C-array format.
+    pub const ALL_MPNET_BASE_V2: (&'static str, &'static str) = (
+        "all-mpnet-base-v2/config",
+        "https://huggingface.co/sentence-transformers/all-mpnet-base-v2/resolve/main/config.json",
+    );
+}
+
+impl MPNetVocabResources {
+    /// Shared under Apache 2.0 license at <https://huggingface.co/sentence-transformers/all-mpnet-base-v2>. Modified with conversion to C-array format.
+    pub const ALL_MPNET_BASE_V2: (&'static str, &'static str) = (
+        "all-mpnet-base-v2/vocab",
+        "https://huggingface.co/sentence-transformers/all-mpnet-base-v2/resolve/main/vocab.txt",
+    );
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+/// # MPNet model configuration
+/// Defines the MPNet model architecture (e.g. number of layers, hidden layer size, label mapping...)
+pub struct MPNetConfig {
+    pub hidden_act: Activation,
+    pub attention_probs_dropout_prob: f64,
+    pub hidden_dropout_prob: f64,
+    pub hidden_size: i64,
+    pub initializer_range: f32,
+    pub intermediate_size: i64,
+    pub max_position_embeddings: i64,
+    pub num_attention_heads: i64,
+    pub num_hidden_layers: i64,
+    pub vocab_size: i64,
+    pub relative_attention_num_buckets: Option<i64>,
+    pub layer_norm_eps: Option<f64>,
+    pub pad_token_id: Option<i64>,
+    pub bos_token_id: Option<i64>,
+    pub eos_token_id: Option<i64>,
+    pub output_attentions: Option<bool>,
+    pub output_hidden_states: Option<bool>,
+    pub id2label: Option<HashMap<i64, String>>,
+    pub label2id: Option<HashMap<String, i64>>,
+}
+
+impl Config for MPNetConfig {}
+
+impl Default for MPNetConfig {
+    fn default() -> Self {
+        MPNetConfig {
+            hidden_act: Activation::gelu,
+            attention_probs_dropout_prob: 0.1,
+            hidden_dropout_prob: 0.1,
+            hidden_size: 768,
+            initializer_range: 0.02,
+            intermediate_size: 3072,
+            max_position_embeddings: 514,
+            num_attention_heads: 12,
+            num_hidden_layers: 12,
+            vocab_size: 30527,
+            relative_attention_num_buckets: Some(32),
+            layer_norm_eps: Some(1e-5),
+            pad_token_id: Some(1),
+            bos_token_id: Some(0),
+            eos_token_id: Some(2),
+            output_attentions: None,
+            output_hidden_states: None,
+            id2label: None,
+            label2id: None,
+        }
+    }
+}
+
+/// # MPNet Base model
+/// Base architecture for MPNet models. Task-specific models will be built from this common base model
+/// It is made of the following blocks:
+/// - `embeddings`: `token` and `position` embeddings
+/// - `encoder`: Encoder (transformer) made of a vector of layers and a relative attention bias shared by all layers
+/// - `pooler`: optional linear layer applied to the first element of the sequence
+pub struct MPNetModel {
+    embeddings: MPNetEmbeddings,
+    encoder: MPNetEncoder,
+    pooler: Option<MPNetPooler>,
+}
+
+impl MPNetModel {
+    /// Build a new `MPNetModel`
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Variable store path for the root of the MPNet model
+    /// * `config` - `MPNetConfig` object defining the model architecture
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::mpnet::{MPNetConfig, MPNetModel};
+    /// use rust_bert::Config;
+    /// use std::path::Path;
+    /// use tch::{nn, Device};
+    ///
+    /// let config_path = Path::new("path/to/config.json");
+    /// let device = Device::Cpu;
+    /// let p = nn::VarStore::new(device);
+    /// let config = MPNetConfig::from_file(config_path);
+    /// let mpnet: MPNetModel = MPNetModel::new(&p.root() / "mpnet", &config);
+    /// ```
+    pub fn new<'p, P>(p: P, config: &MPNetConfig) -> MPNetModel
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        Self::new_with_optional_pooler(p, config, true)
+    }
+
+    /// Build a new `MPNetModel` with an optional Pooling layer
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Variable store path for the root of the MPNet model
+    /// * `config` - `MPNetConfig` object defining the model architecture
+    /// * `add_pooling_layer` - Enable/Disable an optional pooling layer at the end of the model
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::mpnet::{MPNetConfig, MPNetModel};
+    /// use rust_bert::Config;
+    /// use std::path::Path;
+    /// use tch::{nn, Device};
+    ///
+    /// let config_path = Path::new("path/to/config.json");
+    /// let device = Device::Cpu;
+    /// let p = nn::VarStore::new(device);
+    /// let config = MPNetConfig::from_file(config_path);
+    /// let mpnet: MPNetModel = MPNetModel::new_with_optional_pooler(&p.root(), &config, false);
+    /// ```
+    pub fn new_with_optional_pooler<'p, P>(
+        p: P,
+        config: &MPNetConfig,
+        add_pooling_layer: bool,
+    ) -> MPNetModel
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+
+        let embeddings = MPNetEmbeddings::new(p / "embeddings", config);
+        let encoder = MPNetEncoder::new(p / "encoder", config);
+        let pooler = if add_pooling_layer {
+            Some(MPNetPooler::new(p / "pooler", config))
+        } else {
+            None
+        };
+
+        MPNetModel {
+            embeddings,
+            encoder,
+            pooler,
+        }
+    }
+
+    /// Forward pass through the model
+    ///
+    /// # Arguments
+    ///
+    /// * `input_ids` - Optional input tensor of shape (*batch size*, *sequence_length*). If None, pre-computed embeddings must be provided (see `input_embeds`)
+    /// * `mask` - Optional mask of shape (*batch size*, *sequence_length*). Masked position have value 0, non-masked value 1. If None set to 1
+    /// * `position_ids` - Optional position ids of shape (*batch size*, *sequence_length*). If None, will be incremented from the padding index.
+    /// * `input_embeds` - Optional pre-computed input embeddings of shape (*batch size*, *sequence_length*, *hidden_size*). If None, input ids must be provided (see `input_ids`)
+    /// * `train` - boolean flag to turn on/off the dropout layers in the model. Should be set to false for inference.
+    ///
+    /// # Returns
+    ///
+    /// * `MPNetModelOutput` containing:
+    ///   - `hidden_state` - `Tensor` of shape (*batch size*, *sequence_length*, *hidden_size*)
+    ///   - `pooled_output` - `Option<Tensor>` of shape (*batch size*, *hidden_size*)
+    ///   - `all_hidden_states` - `Option<Vec<Tensor>>` of length *num_hidden_layers* with shape (*batch size*, *sequence_length*, *hidden_size*)
+    ///   - `all_attentions` - `Option<Vec<Tensor>>` of length *num_hidden_layers* with shape (*batch size*, *num_heads*, *sequence_length*, *sequence_length*)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_bert::mpnet::{MPNetConfig, MPNetModel};
+    /// # use tch::{nn, Device, Tensor, no_grad, Kind};
+    /// # use rust_bert::Config;
+    /// # use std::path::Path;
+    /// # let config_path = Path::new("path/to/config.json");
+    /// # let device = Device::Cpu;
+    /// # let vs = nn::VarStore::new(device);
+    /// # let config = MPNetConfig::from_file(config_path);
+    /// # let mpnet_model: MPNetModel = MPNetModel::new(&vs.root(), &config);
+    /// let (batch_size, sequence_length) = (64, 128);
+    /// let input_tensor = Tensor::rand(&[batch_size, sequence_length], (Kind::Int64, device));
+    /// let mask = Tensor::ones(&[batch_size, sequence_length], (Kind::Int64, device));
+    ///
+    /// let model_output = no_grad(|| {
+    ///     mpnet_model
+    ///         .forward_t(Some(&input_tensor), Some(&mask), None, None, false)
+    ///         .unwrap()
+    /// });
+    /// ```
+    pub fn forward_t(
+        &self,
+        input_ids: Option<&Tensor>,
+        mask: Option<&Tensor>,
+        position_ids: Option<&Tensor>,
+        input_embeds: Option<&Tensor>,
+        train: bool,
+    ) -> Result<MPNetModelOutput, RustBertError> {
+        let (input_shape, device) =
+            get_shape_and_device_from_ids_embeddings_pair(input_ids, input_embeds)?;
+
+        let calc_mask = Tensor::ones(&input_shape, (Kind::Int8, device));
+        let mask = mask.unwrap_or(&calc_mask);
+
+        let extended_attention_mask = match mask.dim() {
+            3 => mask.unsqueeze(1),
+            2 => mask.unsqueeze(1).unsqueeze(1),
+            _ => {
+                return Err(RustBertError::ValueError(
+                    "Invalid attention mask dimension, must be 2 or 3".into(),
+                ));
+            }
+        };
+
+        let embedding_output =
+            self.embeddings
+                .forward_t(input_ids, position_ids, input_embeds, train)?;
+
+        let extended_attention_mask: Tensor =
+            ((extended_attention_mask.ones_like() - extended_attention_mask) * -10000.0)
+                .to_kind(embedding_output.kind());
+
+        let encoder_output =
+            self.encoder
+                .forward_t(&embedding_output, Some(&extended_attention_mask), train);
+
+        let pooled_output = self
+            .pooler
+            .as_ref()
+            .map(|pooler| pooler.forward(&encoder_output.hidden_state));
+
+        Ok(MPNetModelOutput {
+            hidden_state: encoder_output.hidden_state,
+            pooled_output,
+            all_hidden_states: encoder_output.all_hidden_states,
+            all_attentions: encoder_output.all_attentions,
+        })
+    }
+}
+
+/// # MPNet for sentence embeddings
+/// Transformer usable in [`SentenceEmbeddingsModel`](crate::pipelines::sentence_embeddings::SentenceEmbeddingsModel).
+pub type MPNetForSentenceEmbeddings = MPNetModel;
+
+/// Container for the MPNet model output.
+pub struct MPNetModelOutput {
+    /// Last hidden states from the model
+    pub hidden_state: Tensor,
+    /// Pooled output (hidden state for the first token)
+    pub pooled_output: Option<Tensor>,
+    /// Hidden states for all intermediate layers
+    pub all_hidden_states: Option<Vec<Tensor>>,
+    /// Attention weights for all intermediate layers
+    pub all_attentions: Option<Vec<Tensor>>,
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::common::layer_norm::LayerNorm;
 use crate::gpt2::attention::Attention;
 use crate::gpt2::transformer::MLP;
 use crate::gpt2::Gpt2Config;
@@ -19,9 +20,9 @@ use std::borrow::Borrow;
 use tch::{nn, Tensor};
 
 pub struct Block {
-    ln_1: nn::LayerNorm,
+    ln_1: LayerNorm,
     attn: Attention,
-    ln_2: nn::LayerNorm,
+    ln_2: LayerNorm,
     mlp: MLP,
 }
 
@@ -36,8 +37,8 @@ impl Block {
             eps: config.layer_norm_epsilon,
             ..Default::default()
         };
-        let ln_1 = nn::layer_norm(p / "ln_1", vec![config.n_embd], layer_norm_config);
-        let ln_2 = nn::layer_norm(p / "ln_2", vec![config.n_embd], layer_norm_config);
+        let ln_1 = LayerNorm::new(p / "ln_1", vec![config.n_embd], layer_norm_config);
+        let ln_2 = LayerNorm::new(p / "ln_2", vec![config.n_embd], layer_norm_config);
         let attn = Attention::new(p / "attn", config, scale);
         let mlp = MLP::new(p / "mlp", config);
 
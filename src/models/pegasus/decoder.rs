@@ -10,8 +10,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::bart::{BartDecoderOutput, _expand_mask, _prepare_decoder_attention_mask};
+use crate::bart::{_expand_mask, _prepare_decoder_attention_mask, BartDecoderOutput};
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::mbart::MBartDecoderLayer;
 use crate::pegasus::attention::LayerState;
 use crate::pegasus::embeddings::SinusoidalPositionalEmbedding;
@@ -23,7 +24,7 @@ pub type PegasusDecoderLayer = MBartDecoderLayer;
 
 pub struct PegasusDecoder {
     dropout: Dropout,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     layers: Vec<PegasusDecoderLayer>,
     embed_positions: SinusoidalPositionalEmbedding,
     output_attentions: bool,
@@ -59,7 +60,7 @@ impl PegasusDecoder {
             eps: 1e-5,
             ..Default::default()
         };
-        let layer_norm = nn::layer_norm(p / "layer_norm", vec![config.d_model], layer_norm_config);
+        let layer_norm = LayerNorm::new(p / "layer_norm", vec![config.d_model], layer_norm_config);
 
         let embed_positions = SinusoidalPositionalEmbedding::new(
             p / "embed_positions",
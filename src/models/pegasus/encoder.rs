@@ -10,8 +10,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::bart::{BartEncoderOutput, _expand_mask};
+use crate::bart::{_expand_mask, BartEncoderOutput};
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::mbart::MBartEncoderLayer;
 use crate::pegasus::embeddings::SinusoidalPositionalEmbedding;
 use crate::pegasus::PegasusConfig;
@@ -22,7 +23,7 @@ pub type PegasusEncoderLayer = MBartEncoderLayer;
 
 pub struct PegasusEncoder {
     dropout: Dropout,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     layers: Vec<PegasusEncoderLayer>,
     embed_positions: SinusoidalPositionalEmbedding,
     output_attentions: bool,
@@ -48,7 +49,7 @@ impl PegasusEncoder {
             eps: 1e-5,
             ..Default::default()
         };
-        let layer_norm = nn::layer_norm(p / "layer_norm", vec![config.d_model], layer_norm_config);
+        let layer_norm = LayerNorm::new(p / "layer_norm", vec![config.d_model], layer_norm_config);
 
         let embed_positions = SinusoidalPositionalEmbedding::new(
             p / "embed_positions",
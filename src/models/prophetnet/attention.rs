@@ -11,6 +11,7 @@
 // limitations under the License.
 
 use crate::common::activations::TensorFunction;
+use crate::common::attention::scaled_dot_product_attention;
 use crate::common::dropout::Dropout;
 use crate::prophetnet::ProphetNetConfig;
 use crate::RustBertError;
@@ -142,7 +143,7 @@ impl ProphetNetAttention {
             hidden_states_size[2],
         );
         let is_cross_attention = key_value_states.is_some();
-        let query_states = hidden_states.apply(&self.query_proj) / (self.head_dim as f64).sqrt();
+        let query_states = hidden_states.apply(&self.query_proj);
         let query_states = self.flatten(query_states, sequence_length, batch_size);
 
         let (key_states, value_states) = if !is_cross_attention {
@@ -202,41 +203,32 @@ impl ProphetNetAttention {
         };
 
         let key_sequence_key = key_states.size()[1];
-        let mut attention_weights = query_states.bmm(&key_states.transpose(1, 2));
-
-        if let Some(attention_mask) = attention_mask {
-            attention_weights = attention_weights + attention_mask;
-        };
-
-        let attention_weights_reshaped = attention_weights.view([
-            batch_size,
-            self.num_attention_heads,
-            sequence_length,
-            key_sequence_key,
-        ]);
-
-        let attention_probs = attention_weights_reshaped
-            .view([
-                batch_size * self.num_attention_heads,
-                sequence_length,
-                key_sequence_key,
-            ])
-            .softmax(-1, attention_weights_reshaped.kind())
-            .apply_t(&self.attention_dropout, train);
+        let (attention_output, attention_weights) = scaled_dot_product_attention(
+            &query_states,
+            &key_states,
+            &value_states,
+            attention_mask,
+            None,
+            &self.attention_dropout,
+            self.output_attentions,
+            train,
+        );
 
-        let attention_output = attention_probs
-            .bmm(&value_states)
+        let attention_output = attention_output
             .transpose(0, 1)
             .contiguous()
             .view([sequence_length, batch_size, hidden_size])
             .apply(&self.out_proj)
             .apply_t(&self.dropout, train);
 
-        let attention_weights = if self.output_attentions {
-            Some(attention_weights_reshaped)
-        } else {
-            None
-        };
+        let attention_weights = attention_weights.map(|weights| {
+            weights.view([
+                batch_size,
+                self.num_attention_heads,
+                sequence_length,
+                key_sequence_key,
+            ])
+        });
         (attention_output, attention_weights, layer_state)
     }
 }
@@ -482,7 +474,8 @@ impl ProphetNetNgramAttention {
         };
 
         let main_attention_probas = main_attention_weights
-            .softmax(-1, main_attention_weights.kind())
+            .softmax(-1, Kind::Float)
+            .to_kind(main_attention_weights.kind())
             .apply_t(&self.attention_dropout, train);
 
         let main_attention_output = main_attention_probas
@@ -549,7 +542,8 @@ impl ProphetNetNgramAttention {
         };
 
         let predict_attention_probas = predict_attention_weights
-            .softmax(-1, predict_attention_weights.kind())
+            .softmax(-1, Kind::Float)
+            .to_kind(predict_attention_weights.kind())
             .apply_t(&self.attention_dropout, train);
 
         let predict_attention_output = Tensor::einsum(
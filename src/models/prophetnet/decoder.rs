@@ -13,6 +13,7 @@
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::process_ids_embeddings_pair;
 use crate::common::kind::get_min;
+use crate::common::layer_norm::LayerNorm;
 use crate::prophetnet::attention::{
     compute_all_stream_relative_buckets, LayerState, ProphetNetAttention, ProphetNetFeedForward,
     ProphetNetNgramAttention,
@@ -38,11 +39,11 @@ fn ngram_attention_bias(sequence_length: i64, ngram: i64, device: Device, kind:
 
 pub struct ProphetNetDecoderLayer {
     self_attention: ProphetNetNgramAttention,
-    self_attention_layer_norm: nn::LayerNorm,
+    self_attention_layer_norm: LayerNorm,
     cross_attention: Option<ProphetNetAttention>,
-    cross_attention_layer_norm: Option<nn::LayerNorm>,
+    cross_attention_layer_norm: Option<LayerNorm>,
     feed_forward: ProphetNetFeedForward,
-    feed_forward_layer_norm: nn::LayerNorm,
+    feed_forward_layer_norm: LayerNorm,
 }
 
 impl ProphetNetDecoderLayer {
@@ -56,7 +57,7 @@ impl ProphetNetDecoderLayer {
         let p = p.borrow();
 
         let self_attention = ProphetNetNgramAttention::new(p / "self_attn", config);
-        let self_attention_layer_norm = nn::layer_norm(
+        let self_attention_layer_norm = LayerNorm::new(
             p / "self_attn_layer_norm",
             vec![config.hidden_size],
             Default::default(),
@@ -69,7 +70,7 @@ impl ProphetNetDecoderLayer {
                     config,
                     config.num_decoder_attention_heads,
                 )?;
-                let cross_attention_layer_norm = nn::layer_norm(
+                let cross_attention_layer_norm = LayerNorm::new(
                     p / "cross_attn_layer_norm",
                     vec![config.hidden_size],
                     Default::default(),
@@ -81,7 +82,7 @@ impl ProphetNetDecoderLayer {
 
         let feed_forward =
             ProphetNetFeedForward::new(p / "feed_forward", config, config.decoder_ffn_dim);
-        let feed_forward_layer_norm = nn::layer_norm(
+        let feed_forward_layer_norm = LayerNorm::new(
             p / "feed_forward_layer_norm",
             vec![config.hidden_size],
             Default::default(),
@@ -169,7 +170,7 @@ pub struct ProphetNetDecoder {
     relative_max_distance: i64,
     max_target_positions: i64,
     position_embeddings: ProphetNetPositionalEmbeddings,
-    embeddings_layer_norm: nn::LayerNorm,
+    embeddings_layer_norm: LayerNorm,
     ngram_embeddings: Tensor,
     layers: Vec<ProphetNetDecoderLayer>,
     dropout: Dropout,
@@ -188,7 +189,7 @@ impl ProphetNetDecoder {
 
         let position_embeddings =
             ProphetNetPositionalEmbeddings::new(p / "position_embeddings", config);
-        let embeddings_layer_norm = nn::layer_norm(
+        let embeddings_layer_norm = LayerNorm::new(
             p / "embeddings_layer_norm",
             vec![config.hidden_size],
             Default::default(),
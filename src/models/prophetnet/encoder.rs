@@ -12,6 +12,7 @@
 
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
 use crate::prophetnet::attention::{ProphetNetAttention, ProphetNetFeedForward};
 use crate::prophetnet::embeddings::ProphetNetPositionalEmbeddings;
 use crate::prophetnet::ProphetNetConfig;
@@ -21,9 +22,9 @@ use tch::{nn, Tensor};
 
 pub struct ProphetNetEncoderLayer {
     self_attention: ProphetNetAttention,
-    self_attention_layer_norm: nn::LayerNorm,
+    self_attention_layer_norm: LayerNorm,
     feed_forward: ProphetNetFeedForward,
-    feed_forward_layer_norm: nn::LayerNorm,
+    feed_forward_layer_norm: LayerNorm,
 }
 
 impl ProphetNetEncoderLayer {
@@ -38,7 +39,7 @@ impl ProphetNetEncoderLayer {
 
         let self_attention =
             ProphetNetAttention::new(p / "self_attn", config, config.num_encoder_attention_heads)?;
-        let self_attention_layer_norm = nn::layer_norm(
+        let self_attention_layer_norm = LayerNorm::new(
             p / "self_attn_layer_norm",
             vec![config.hidden_size],
             Default::default(),
@@ -46,7 +47,7 @@ impl ProphetNetEncoderLayer {
 
         let feed_forward =
             ProphetNetFeedForward::new(p / "feed_forward", config, config.encoder_ffn_dim);
-        let feed_forward_layer_norm = nn::layer_norm(
+        let feed_forward_layer_norm = LayerNorm::new(
             p / "feed_forward_layer_norm",
             vec![config.hidden_size],
             Default::default(),
@@ -82,7 +83,7 @@ impl ProphetNetEncoderLayer {
 
 pub struct ProphetNetEncoder {
     position_embeddings: ProphetNetPositionalEmbeddings,
-    embeddings_layer_norm: nn::LayerNorm,
+    embeddings_layer_norm: LayerNorm,
     layers: Vec<ProphetNetEncoderLayer>,
     dropout: Dropout,
     output_attentions: bool,
@@ -99,7 +100,7 @@ impl ProphetNetEncoder {
 
         let position_embeddings =
             ProphetNetPositionalEmbeddings::new(p / "position_embeddings", config);
-        let embeddings_layer_norm = nn::layer_norm(
+        let embeddings_layer_norm = LayerNorm::new(
             p / "embeddings_layer_norm",
             vec![config.hidden_size],
             Default::default(),
@@ -12,6 +12,7 @@
 // limitations under the License.
 
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::reformer::attention_utils::{
     look_adjacent, merge_hidden_size_dim, retrieve_relevant_hidden_states, reverse_sort,
     split_hidden_size_dim, split_seq_length_dim_to, stable_argsort,
@@ -1299,7 +1300,7 @@ pub struct ReformerAttentionOutput {
 /// # Reformer attention layer
 pub struct ReformerAttention {
     self_attention: AttentionModule,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     self_output: ReformerSelfOutput,
     use_past: bool,
 }
@@ -1321,7 +1322,7 @@ impl ReformerAttention {
             eps: config.layer_norm_eps.unwrap_or(1e-12),
             ..Default::default()
         };
-        let layer_norm = nn::layer_norm(
+        let layer_norm = LayerNorm::new(
             p / "layer_norm",
             vec![config.hidden_size],
             layer_norm_config,
@@ -13,6 +13,7 @@
 
 use crate::common::activations::TensorFunction;
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::reformer::attention::{AttentionType, LayerState, ReformerAttention};
 use crate::reformer::ReformerConfig;
 use crate::RustBertError;
@@ -89,7 +90,7 @@ impl ReformerFeedForwardOutput {
 pub struct ChunkReformerFeedForward {
     dense: ReformerFeedForwardDense,
     output: ReformerFeedForwardOutput,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     chunk_size_feed_forward: i64,
 }
 
@@ -106,7 +107,7 @@ impl ChunkReformerFeedForward {
             eps: config.layer_norm_eps.unwrap_or(1e-12),
             ..Default::default()
         };
-        let layer_norm = nn::layer_norm(
+        let layer_norm = LayerNorm::new(
             p / "layer_norm",
             vec![config.hidden_size],
             layer_norm_config,
@@ -230,7 +231,7 @@ pub struct ReformerModelOutput {
 
 pub struct ReformerEncoder {
     layers: Vec<ReformerLayer>,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: Dropout,
     output_attentions: bool,
     output_hidden_states: bool,
@@ -252,7 +253,7 @@ impl ReformerEncoder {
             eps: config.layer_norm_eps.unwrap_or(1e-12),
             ..Default::default()
         };
-        let layer_norm = nn::layer_norm(
+        let layer_norm = LayerNorm::new(
             p / "layer_norm",
             vec![2 * config.hidden_size],
             layer_norm_config,
@@ -14,6 +14,7 @@
 use crate::bert::{BertConfig, BertEmbedding};
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::common::layer_norm::LayerNorm;
 use crate::RustBertError;
 use std::borrow::Borrow;
 use tch::nn::{embedding, EmbeddingConfig};
@@ -26,7 +27,7 @@ pub struct RobertaEmbeddings {
     word_embeddings: nn::Embedding,
     position_embeddings: nn::Embedding,
     token_type_embeddings: nn::Embedding,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: Dropout,
     padding_index: i64,
 }
@@ -108,8 +109,8 @@ impl BertEmbedding for RobertaEmbeddings {
             eps: 1e-12,
             ..Default::default()
         };
-        let layer_norm: nn::LayerNorm =
-            nn::layer_norm(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
+        let layer_norm: LayerNorm =
+            LayerNorm::new(p / "LayerNorm", vec![config.hidden_size], layer_norm_config);
         let dropout: Dropout = Dropout::new(config.hidden_dropout_prob);
         RobertaEmbeddings {
             word_embeddings,
@@ -14,6 +14,7 @@
 use crate::bert::{BertConfig, BertModel};
 use crate::common::activations::_gelu;
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::common::linear::{linear_no_bias, LinearNoBias};
 use crate::roberta::embeddings::RobertaEmbeddings;
 use crate::RustBertError;
@@ -84,6 +85,11 @@ impl RobertaModelResources {
         "codebert-mlm/model",
         "https://huggingface.co/microsoft/codebert-base-mlm/resolve/main/rust_model.ot",
     );
+    /// Shared under Apache 2.0 license at <https://huggingface.co/sentence-transformers/paraphrase-multilingual-mpnet-base-v2>. Modified with conversion to C-array format.
+    pub const PARAPHRASE_MULTILINGUAL_MPNET_BASE_V2: (&'static str, &'static str) = (
+        "paraphrase-multilingual-mpnet-base-v2/model",
+        "https://huggingface.co/sentence-transformers/paraphrase-multilingual-mpnet-base-v2/resolve/main/rust_model.ot",
+    );
 }
 
 impl RobertaConfigResources {
@@ -137,6 +143,11 @@ impl RobertaConfigResources {
         "codebert-mlm/config",
         "https://huggingface.co/microsoft/codebert-base-mlm/resolve/main/config.json",
     );
+    /// Shared under Apache 2.0 license at <https://huggingface.co/sentence-transformers/paraphrase-multilingual-mpnet-base-v2>. Modified with conversion to C-array format.
+    pub const PARAPHRASE_MULTILINGUAL_MPNET_BASE_V2: (&'static str, &'static str) = (
+        "paraphrase-multilingual-mpnet-base-v2/config",
+        "https://huggingface.co/sentence-transformers/paraphrase-multilingual-mpnet-base-v2/resolve/main/config.json",
+    );
 }
 
 impl RobertaVocabResources {
@@ -190,6 +201,11 @@ impl RobertaVocabResources {
         "codebert-mlm/vocab",
         "https://huggingface.co/microsoft/codebert-base-mlm/resolve/main/vocab.json",
     );
+    /// Shared under Apache 2.0 license at <https://huggingface.co/sentence-transformers/paraphrase-multilingual-mpnet-base-v2>. Modified with conversion to C-array format.
+    pub const PARAPHRASE_MULTILINGUAL_MPNET_BASE_V2: (&'static str, &'static str) = (
+        "paraphrase-multilingual-mpnet-base-v2/spiece",
+        "https://huggingface.co/sentence-transformers/paraphrase-multilingual-mpnet-base-v2/resolve/main/sentencepiece.bpe.model",
+    );
 }
 
 impl RobertaMergesResources {
@@ -228,7 +244,7 @@ impl RobertaMergesResources {
 pub struct RobertaLMHead {
     dense: nn::Linear,
     decoder: LinearNoBias,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     bias: Tensor,
 }
 
@@ -248,7 +264,7 @@ impl RobertaLMHead {
             eps: 1e-12,
             ..Default::default()
         };
-        let layer_norm = nn::layer_norm(
+        let layer_norm = LayerNorm::new(
             p / "layer_norm",
             vec![config.hidden_size],
             layer_norm_config,
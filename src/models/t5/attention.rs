@@ -10,6 +10,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::common::attention::scaled_dot_product_attention;
 use crate::common::dropout::Dropout;
 use crate::t5::layer_norm::T5LayerNorm;
 use crate::t5::T5Config;
@@ -223,15 +224,13 @@ impl T5Attention {
             None
         };
 
-        let mut scores = Tensor::einsum("bnqd,bnkd->bnqk", &[q, k], None::<i64>);
-
         let calculated_position_bias = if position_bias.is_none() {
             let mut temp_value = if self.has_relative_attention_bias {
                 self.compute_bias(real_seq_length, key_length, hidden_states.device())
             } else {
                 Tensor::zeros(
                     [1, self.n_heads, real_seq_length, key_length],
-                    (scores.kind(), scores.device()),
+                    (q.kind(), q.device()),
                 )
             };
             if layer_state.is_some() {
@@ -252,20 +251,18 @@ impl T5Attention {
             calculated_position_bias.as_ref().unwrap()
         };
 
-        scores += position_bias;
-
-        let attention_weights = scores
-            .softmax(-1, scores.kind())
-            .apply_t(&self.dropout, train);
-        let context = self
-            .unshape(attention_weights.matmul(&v), bs)
-            .apply(&self.output);
-
-        let attention_weights = if self.output_attentions {
-            Some(attention_weights)
-        } else {
-            None
-        };
+        // T5 does not scale the attention scores: the attention is always computed explicitly
+        let (context, attention_weights) = scaled_dot_product_attention(
+            &q,
+            &k,
+            &v,
+            Some(position_bias),
+            Some(1.0),
+            &self.dropout,
+            self.output_attentions,
+            train,
+        );
+        let context = self.unshape(context, bs).apply(&self.output);
 
         let position_bias = if self.has_relative_attention_bias {
             calculated_position_bias
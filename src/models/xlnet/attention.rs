@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::xlnet::XLNetConfig;
 use std::borrow::Borrow;
 use tch::nn::init::DEFAULT_KAIMING_UNIFORM;
@@ -53,7 +54,7 @@ pub struct XLNetRelativeAttention {
     r_s_bias: Tensor,
     r_w_bias: Tensor,
     seg_embed: Tensor,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     scale: f64,
 }
 
@@ -127,7 +128,7 @@ impl XLNetRelativeAttention {
             eps: layer_norm_eps,
             ..Default::default()
         };
-        let layer_norm = nn::layer_norm(p / "layer_norm", vec![config.d_model], layer_norm_config);
+        let layer_norm = LayerNorm::new(p / "layer_norm", vec![config.d_model], layer_norm_config);
 
         let scale = 1f64 / ((config.d_head as f64).powf(0.5f64));
 
@@ -199,7 +200,8 @@ impl XLNetRelativeAttention {
         };
 
         let attention_probas = attention_score
-            .softmax(3, attention_score.kind())
+            .softmax(3, Kind::Float)
+            .to_kind(attention_score.kind())
             .apply_t(&self.dropout, train);
 
         let attention_vector = Tensor::einsum(
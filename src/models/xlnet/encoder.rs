@@ -14,6 +14,7 @@
 
 use crate::common::activations::TensorFunction;
 use crate::common::dropout::Dropout;
+use crate::common::layer_norm::LayerNorm;
 use crate::xlnet::attention::{LayerState, XLNetRelativeAttention};
 use crate::xlnet::XLNetConfig;
 use std::borrow::Borrow;
@@ -22,7 +23,7 @@ use tch::{nn, Tensor};
 pub struct XLNetFeedForward {
     layer_1: nn::Linear,
     layer_2: nn::Linear,
-    layer_norm: nn::LayerNorm,
+    layer_norm: LayerNorm,
     dropout: Dropout,
     activation: TensorFunction,
 }
@@ -53,7 +54,7 @@ impl XLNetFeedForward {
             eps: layer_norm_eps,
             ..Default::default()
         };
-        let layer_norm = nn::layer_norm(p / "layer_norm", vec![config.d_model], layer_norm_config);
+        let layer_norm = LayerNorm::new(p / "layer_norm", vec![config.d_model], layer_norm_config);
         let activation = config.ff_activation.get_function();
 
         XLNetFeedForward {
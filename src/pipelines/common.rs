@@ -34,6 +34,7 @@ use crate::m2m_100::M2M100Config;
 use crate::marian::MarianConfig;
 use crate::mbart::MBartConfig;
 use crate::mobilebert::MobileBertConfig;
+use crate::mpnet::MPNetConfig;
 use crate::openai_gpt::OpenAiGptConfig;
 use crate::pegasus::PegasusConfig;
 use crate::pipelines::translation::Language;
@@ -51,13 +52,14 @@ use rust_tokenizers::tokenizer::{
     RobertaTokenizer, T5Tokenizer, Tokenizer, TruncationStrategy, XLMRobertaTokenizer,
     XLNetTokenizer,
 };
-use rust_tokenizers::vocab::Vocab;
+use rust_tokenizers::vocab::{BertVocab, SpecialTokenMap, Vocab};
 use rust_tokenizers::{TokenIdsWithOffsets, TokenizedInput, TokensWithOffsets};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
+use tch::nn::VarStore;
 use tch::{Device, Kind, Tensor};
 
 #[cfg(feature = "onnx")]
@@ -140,6 +142,14 @@ impl ModelResource {
     }
 }
 
+fn pad_sequence<T: Clone>(values: &mut Vec<T>, length: usize, pad_value: T, left_padding: bool) {
+    if left_padding {
+        values.splice(0..0, vec![pad_value; length - values.len()]);
+    } else {
+        values.resize(length, pad_value);
+    }
+}
+
 pub(crate) fn get_device(_model_resource: ModelResource, device: Device) -> Device {
     #[cfg(feature = "onnx")]
     let device = if let ModelResource::ONNX(_) = _model_resource {
@@ -153,6 +163,43 @@ pub(crate) fn get_device(_model_resource: ModelResource, device: Device) -> Devi
     device
 }
 
+/// Casts the floating point variables of a pipeline model to `kind`. Integer variables (e.g. quantized weights)
+/// are left unchanged. The var store is `None` for ONNX models, which do not support casting.
+pub(crate) fn cast_var_store(
+    var_store: Option<&mut VarStore>,
+    kind: Kind,
+) -> Result<(), RustBertError> {
+    let var_store = var_store.ok_or_else(|| {
+        RustBertError::InvalidConfigurationError(
+            "Type casting not supported for ONNX models.".to_string(),
+        )
+    })?;
+    tch::no_grad(|| {
+        for (_, mut variable) in var_store.variables() {
+            if variable.is_floating_point() {
+                variable.set_data(&variable.to_kind(kind));
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Moves the variables of a pipeline model to `device`. The var store is `None` for ONNX models, which do not
+/// support device assignment.
+pub(crate) fn set_var_store_device(
+    var_store: Option<&mut VarStore>,
+    device: Device,
+) -> Result<(), RustBertError> {
+    var_store
+        .ok_or_else(|| {
+            RustBertError::InvalidConfigurationError(
+                "Device assignment not supported for ONNX models.".to_string(),
+            )
+        })?
+        .set_device(device);
+    Ok(())
+}
+
 #[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
 /// # Identifies the type of model
 pub enum ModelType {
@@ -162,9 +209,11 @@ pub enum ModelType {
     #[serde(alias = "distilbert")]
     DistilBert,
     Deberta,
+    #[serde(alias = "deberta-v2")]
     DebertaV2,
     #[serde(alias = "roberta")]
     Roberta,
+    #[serde(alias = "xlm-roberta")]
     XLMRoberta,
     Electra,
     Marian,
@@ -189,6 +238,8 @@ pub enum ModelType {
     #[serde(alias = "m2m100")]
     NLLB,
     FNet,
+    #[serde(alias = "mpnet")]
+    MPNet,
     #[cfg(feature = "onnx")]
     ONNX,
 }
@@ -243,6 +294,8 @@ pub enum ConfigOption {
     M2M100(M2M100Config),
     /// FNet configuration
     FNet(FNetConfig),
+    /// MPNet configuration
+    MPNet(MPNetConfig),
     /// ONNX Model configuration
     #[cfg(feature = "onnx")]
     ONNX(ONNXModelConfig),
@@ -322,6 +375,7 @@ impl ConfigOption {
                 ConfigOption::M2M100(M2M100Config::from_file(path))
             }
             ModelType::FNet => ConfigOption::FNet(FNetConfig::from_file(path)),
+            ModelType::MPNet => ConfigOption::MPNet(MPNetConfig::from_file(path)),
             #[cfg(feature = "onnx")]
             ModelType::ONNX => ConfigOption::ONNX(ONNXModelConfig::from_file(path)),
         }
@@ -393,6 +447,10 @@ impl ConfigOption {
                 .id2label
                 .as_ref()
                 .expect("No label dictionary (id2label) provided in configuration file"),
+            Self::MPNet(config) => config
+                .id2label
+                .as_ref()
+                .expect("No label dictionary (id2label) provided in configuration file"),
             Self::Roberta(config) => config
                 .id2label
                 .as_ref()
@@ -437,6 +495,7 @@ impl ConfigOption {
             Self::MBart(config) => Some(config.max_position_embeddings),
             Self::M2M100(config) => Some(config.max_position_embeddings),
             Self::FNet(config) => Some(config.max_position_embeddings),
+            Self::MPNet(config) => Some(config.max_position_embeddings),
             Self::Roberta(config) => Some(config.max_position_embeddings),
             #[cfg(feature = "onnx")]
             Self::ONNX(config) => config.max_position_embeddings,
@@ -468,6 +527,7 @@ impl ConfigOption {
             Self::MBart(config) => config.vocab_size,
             Self::M2M100(config) => config.vocab_size,
             Self::FNet(config) => config.vocab_size,
+            Self::MPNet(config) => config.vocab_size,
             Self::Roberta(config) => config.vocab_size,
             #[cfg(feature = "onnx")]
             Self::ONNX(config) => config.vocab_size,
@@ -499,6 +559,7 @@ impl ConfigOption {
             Self::MBart(config) => config.decoder_start_token_id,
             Self::M2M100(config) => config.decoder_start_token_id,
             Self::FNet(config) => config.decoder_start_token_id,
+            Self::MPNet(_) => None,
             Self::Roberta(_) => None,
             #[cfg(feature = "onnx")]
             Self::ONNX(config) => config.decoder_start_token_id,
@@ -530,6 +591,7 @@ impl ConfigOption {
             Self::MBart(config) => config.forced_bos_token_id,
             Self::M2M100(config) => config.forced_bos_token_id,
             Self::FNet(_) => None,
+            Self::MPNet(_) => None,
             Self::Roberta(_) => None,
             #[cfg(feature = "onnx")]
             Self::ONNX(config) => config.forced_bos_token_id,
@@ -561,6 +623,7 @@ impl ConfigOption {
             Self::MBart(config) => config.forced_eos_token_id,
             Self::M2M100(config) => config.forced_eos_token_id,
             Self::FNet(_) => None,
+            Self::MPNet(_) => None,
             Self::Roberta(_) => None,
             #[cfg(feature = "onnx")]
             Self::ONNX(config) => config.forced_eos_token_id,
@@ -624,6 +687,44 @@ impl TryFrom<&ConfigOption> for T5Config {
     }
 }
 
+impl TryFrom<&ConfigOption> for MPNetConfig {
+    type Error = RustBertError;
+
+    fn try_from(config: &ConfigOption) -> Result<Self, Self::Error> {
+        if let ConfigOption::MPNet(config) = config {
+            Ok(config.clone())
+        } else {
+            Err(RustBertError::InvalidConfigurationError(
+                "You can only supply an MPNetConfig for MPNet!".to_string(),
+            ))
+        }
+    }
+}
+
+/// MPNet uses a WordPiece vocabulary with RoBERTa-style special tokens. The vocabulary is loaded
+/// as a `BertVocab` with the special tokens remapped, so that the `BertTokenizer` can be re-used.
+fn mpnet_vocab_from_file(vocab_path: &str) -> Result<BertVocab, RustBertError> {
+    let values = std::fs::read_to_string(vocab_path)?
+        .lines()
+        .enumerate()
+        .map(|(index, token)| (token.trim_end().to_string(), index as i64))
+        .collect::<HashMap<String, i64>>();
+    let special_token_map = SpecialTokenMap {
+        unk_token: "[UNK]".to_string(),
+        pad_token: Some("<pad>".to_string()),
+        bos_token: Some("<s>".to_string()),
+        sep_token: Some("</s>".to_string()),
+        cls_token: Some("<s>".to_string()),
+        eos_token: Some("</s>".to_string()),
+        mask_token: Some("<mask>".to_string()),
+        additional_special_tokens: None,
+    };
+    Ok(BertVocab::from_values_and_special_token_map(
+        values,
+        special_token_map,
+    )?)
+}
+
 impl TokenizerOption {
     /// Interface method to load a tokenizer from file
     pub fn from_file(
@@ -905,6 +1006,19 @@ impl TokenizerOption {
                 lower_case,
                 strip_accents.unwrap_or(false),
             )?),
+            ModelType::MPNet => {
+                if add_prefix_space.is_some() {
+                    return Err(RustBertError::InvalidConfigurationError(
+                        format!("Optional input `add_prefix_space` set to value {} but cannot be used by {:?}",
+                                add_prefix_space.unwrap(),
+                                model_type)));
+                }
+                TokenizerOption::Bert(BertTokenizer::from_existing_vocab(
+                    mpnet_vocab_from_file(vocab_path)?,
+                    lower_case,
+                    strip_accents.unwrap_or(lower_case),
+                ))
+            }
             #[cfg(feature = "onnx")]
             ModelType::ONNX => Err(RustBertError::InvalidConfigurationError(
                 "Default Tokenizer not defined for generic ONNX models.".to_string(),
@@ -2202,12 +2316,44 @@ impl TokenizerOption {
     where
         S: AsRef<[&'a str]>,
     {
-        let mut tokenized_input: Vec<TokenizedInput> = self.encode_list(
+        let tokenized_input: Vec<TokenizedInput> = self.encode_list(
             input.as_ref(),
             max_length,
             &TruncationStrategy::LongestFirst,
             0,
         );
+        self.pad_tokenized_input(tokenized_input, false, device)
+    }
+
+    /// Encodes a list of text pairs and pads them to the longest sequence, returning the input ids
+    /// and the token type ids (segment ids) identifying the first and second text of each pair.
+    pub fn tokenize_pair_and_pad<'a, S>(
+        &self,
+        input: S,
+        max_length: usize,
+        device: Device,
+    ) -> (Tensor, Tensor)
+    where
+        S: AsRef<[(&'a str, &'a str)]>,
+    {
+        let tokenized_input: Vec<TokenizedInput> = self.encode_pair_list(
+            input.as_ref(),
+            max_length,
+            &TruncationStrategy::LongestFirst,
+            0,
+        );
+        self.pad_tokenized_input(tokenized_input, false, device)
+    }
+
+    /// Pads encoded inputs to the longest sequence, returning the input ids and the token type ids.
+    /// Padding is added at the start of the sequences if `left_padding` is true (e.g. for models pooling
+    /// the last token such as XLNet), at the end otherwise.
+    pub(crate) fn pad_tokenized_input(
+        &self,
+        mut tokenized_input: Vec<TokenizedInput>,
+        left_padding: bool,
+        device: Device,
+    ) -> (Tensor, Tensor) {
         let max_len = tokenized_input
             .iter()
             .map(|input| input.token_ids.len())
@@ -2219,7 +2365,7 @@ impl TokenizerOption {
         let tokenized_input_tensors: Vec<Tensor> = tokenized_input
             .iter_mut()
             .map(|input| {
-                input.token_ids.resize(max_len, pad_id);
+                pad_sequence(&mut input.token_ids, max_len, pad_id, left_padding);
                 Tensor::from_slice(&(input.token_ids))
             })
             .collect::<Vec<_>>();
@@ -2227,9 +2373,17 @@ impl TokenizerOption {
         let token_type_ids: Vec<Tensor> = tokenized_input
             .iter_mut()
             .map(|input| {
-                input
-                    .segment_ids
-                    .resize(max_len, *input.segment_ids.last().unwrap_or(&0));
+                let pad_segment_id = if left_padding {
+                    *input.segment_ids.first().unwrap_or(&0)
+                } else {
+                    *input.segment_ids.last().unwrap_or(&0)
+                };
+                pad_sequence(
+                    &mut input.segment_ids,
+                    max_len,
+                    pad_segment_id,
+                    left_padding,
+                );
                 Tensor::from_slice(&(input.segment_ids))
             })
             .collect::<Vec<_>>();
@@ -754,6 +754,34 @@ impl ConversationOption {
         }
     }
 
+    /// Casts the model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        match self {
+            Self::GPT2(model_ref) => model_ref.half(),
+        }
+    }
+
+    /// Casts the model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        match self {
+            Self::GPT2(model_ref) => model_ref.bfloat16(),
+        }
+    }
+
+    /// Casts the model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        match self {
+            Self::GPT2(model_ref) => model_ref.float(),
+        }
+    }
+
+    /// Moves the model weights to the target device.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        match self {
+            Self::GPT2(model_ref) => model_ref.set_device(device),
+        }
+    }
+
     /// Interface method to generate_from_ids_and_past() of the particular models.
     pub fn generate_from_ids_and_past(
         &self,
@@ -856,6 +884,28 @@ impl ConversationModel {
         })
     }
 
+    /// Casts the model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        self.model.half()
+    }
+
+    /// Casts the model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        self.model.bfloat16()
+    }
+
+    /// Casts the model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        self.model.float()
+    }
+
+    /// Moves the model weights to the target device, inputs are placed on this device for subsequent conversation turns.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        self.model.set_device(device)?;
+        self.device = device;
+        Ok(())
+    }
+
     /// Perform a multi-turn conversation based on user input
     ///
     /// # Arguments
@@ -0,0 +1,507 @@
+// Copyright 2023 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Semantic deduplication pipeline
+//! Detects near-duplicate texts in a stream of documents. Texts are embedded with a `SentenceEmbeddingsModel`
+//! and grouped into duplicate clusters when their cosine similarity exceeds a threshold.
+//! To avoid comparing every pair of texts, candidate duplicates are retrieved by locality-sensitive hashing
+//! (random hyperplane projections): only texts sharing a hash bucket in at least one of the hash tables are compared.
+//! The representative of a duplicate cluster is its first text (in insertion order).
+//!
+//! ```no_run
+//! use rust_bert::pipelines::deduplication::SemanticDeduplicationModel;
+//! # fn main() -> anyhow::Result<()> {
+//! let mut deduplication_model = SemanticDeduplicationModel::new(Default::default())?;
+//!
+//! let input = [
+//!     "How do I reset my password?",
+//!     "The delivery arrived two days late.",
+//!     "How can I reset my password?",
+//! ];
+//! let duplicate_of = deduplication_model.add(&input)?;
+//! let clusters = deduplication_model.clusters();
+//! # Ok(())
+//! # }
+//! ```
+//! Output (representative of the texts duplicating an earlier text, duplicate clusters): \
+//! ```no_run
+//! # use rust_bert::pipelines::deduplication::DuplicateCluster;
+//! # let duplicate_of =
+//! [None, None, Some(0)]
+//! # ;
+//! # let clusters =
+//! [DuplicateCluster {
+//!     representative: 0,
+//!     members: vec![0, 2],
+//! }]
+//! # ;
+//! ```
+
+use crate::common::error::RustBertError;
+use crate::pipelines::common::TokenizerOption;
+use crate::pipelines::sentence_embeddings::{
+    Embedding, SentenceEmbeddingsConfig, SentenceEmbeddingsModel,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "remote")]
+use crate::pipelines::sentence_embeddings::SentenceEmbeddingsModelType;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// # Cluster of near-duplicate texts
+pub struct DuplicateCluster {
+    /// Index of the representative text of the cluster (first text of the cluster in insertion order)
+    pub representative: usize,
+    /// Indices of the texts in the cluster (including the representative), in insertion order
+    pub members: Vec<usize>,
+}
+
+/// # Configuration for the locality-sensitive hashing index of a `DuplicateIndex`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateIndexConfig {
+    /// Minimum cosine similarity for two texts to be considered duplicates (default: 0.9)
+    pub threshold: f32,
+    /// Number of hash tables. More tables increase the probability of retrieving duplicates (default: 8)
+    pub num_tables: usize,
+    /// Number of random hyperplanes (hash bits) per table. More hyperplanes lead to smaller buckets and
+    /// fewer comparisons, at the cost of a lower probability of retrieving duplicates (default: 12)
+    pub num_hyperplanes: usize,
+    /// Seed of the random hyperplanes generation (default: 42)
+    pub seed: u64,
+}
+
+impl Default for DuplicateIndexConfig {
+    fn default() -> Self {
+        DuplicateIndexConfig {
+            threshold: 0.9,
+            num_tables: 8,
+            num_hyperplanes: 12,
+            seed: 42,
+        }
+    }
+}
+
+/// # Index of embeddings grouping near-duplicates
+/// Embeddings are added incrementally and compared to the previously indexed embeddings sharing a
+/// locality-sensitive hash bucket. Can be used directly with pre-computed embeddings.
+pub struct DuplicateIndex {
+    config: DuplicateIndexConfig,
+    hyperplanes: Vec<Vec<Embedding>>,
+    buckets: Vec<HashMap<u64, Vec<usize>>>,
+    embeddings: Vec<Embedding>,
+    parents: Vec<usize>,
+}
+
+impl DuplicateIndex {
+    /// Build a new empty `DuplicateIndex`
+    pub fn new(config: DuplicateIndexConfig) -> Result<DuplicateIndex, RustBertError> {
+        if config.num_tables == 0 || config.num_hyperplanes == 0 || config.num_hyperplanes > 64 {
+            return Err(RustBertError::InvalidConfigurationError(format!(
+                "The duplicate index requires at least one table and between 1 and 64 hyperplanes per table, got {} tables and {} hyperplanes",
+                config.num_tables, config.num_hyperplanes
+            )));
+        }
+        Ok(DuplicateIndex {
+            config,
+            hyperplanes: vec![],
+            buckets: vec![HashMap::new(); config.num_tables],
+            embeddings: vec![],
+            parents: vec![],
+        })
+    }
+
+    /// Number of embeddings in the index
+    pub fn len(&self) -> usize {
+        self.embeddings.len()
+    }
+
+    /// Returns true if the index contains no embeddings
+    pub fn is_empty(&self) -> bool {
+        self.embeddings.is_empty()
+    }
+
+    /// Adds embeddings to the index. Embeddings are indexed in order, following the embeddings already in the index.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Option<usize>>` containing for each embedding the representative of its duplicate cluster
+    ///   if it duplicates a previously indexed embedding, `None` otherwise
+    pub fn add(&mut self, embeddings: Vec<Embedding>) -> Result<Vec<Option<usize>>, RustBertError> {
+        // Dimensions are checked before any embedding is indexed, leaving the index unchanged on error
+        let dimension = match self.hyperplanes.first() {
+            Some(table_hyperplanes) => table_hyperplanes[0].len(),
+            None => embeddings.first().map_or(0, Vec::len),
+        };
+        if let Some(embedding) = embeddings
+            .iter()
+            .find(|embedding| embedding.len() != dimension)
+        {
+            return Err(RustBertError::ValueError(format!(
+                "Embedding dimension ({}) does not match the index dimension ({})",
+                embedding.len(),
+                dimension
+            )));
+        }
+
+        let mut output = Vec::with_capacity(embeddings.len());
+        for embedding in embeddings {
+            let embedding = normalize(embedding);
+            if self.hyperplanes.is_empty() {
+                self.hyperplanes = self.generate_hyperplanes(dimension);
+            }
+
+            let index = self.embeddings.len();
+            self.parents.push(index);
+            let signatures = self
+                .hyperplanes
+                .iter()
+                .map(|table_hyperplanes| signature(&embedding, table_hyperplanes))
+                .collect::<Vec<u64>>();
+
+            let mut candidates = HashSet::new();
+            for (table_buckets, signature) in self.buckets.iter().zip(signatures.iter()) {
+                if let Some(bucket) = table_buckets.get(signature) {
+                    candidates.extend(bucket.iter().copied());
+                }
+            }
+            let mut candidates = candidates.into_iter().collect::<Vec<usize>>();
+            candidates.sort_unstable();
+            for candidate in candidates {
+                if self.find(candidate) == self.find(index) {
+                    continue;
+                }
+                if dot_product(&embedding, &self.embeddings[candidate]) >= self.config.threshold {
+                    self.union(candidate, index);
+                }
+            }
+            let representative = self.find(index);
+            output.push(if representative != index {
+                Some(representative)
+            } else {
+                None
+            });
+
+            for (table_buckets, signature) in self.buckets.iter_mut().zip(signatures) {
+                table_buckets.entry(signature).or_default().push(index);
+            }
+            self.embeddings.push(embedding);
+        }
+        Ok(output)
+    }
+
+    /// Returns the clusters of near-duplicates (containing at least two members), sorted by representative
+    pub fn clusters(&self) -> Vec<DuplicateCluster> {
+        let mut clusters: HashMap<usize, Vec<usize>> = HashMap::new();
+        for index in 0..self.parents.len() {
+            clusters.entry(self.root(index)).or_default().push(index);
+        }
+        let mut clusters = clusters
+            .into_iter()
+            .filter(|(_, members)| members.len() > 1)
+            .map(|(representative, members)| DuplicateCluster {
+                representative,
+                members,
+            })
+            .collect::<Vec<DuplicateCluster>>();
+        clusters.sort_by_key(|cluster| cluster.representative);
+        clusters
+    }
+
+    fn generate_hyperplanes(&self, dimension: usize) -> Vec<Vec<Embedding>> {
+        let mut random_generator = SplitMix64::new(self.config.seed);
+        (0..self.config.num_tables)
+            .map(|_| {
+                (0..self.config.num_hyperplanes)
+                    .map(|_| {
+                        (0..dimension)
+                            .map(|_| random_generator.next_gaussian())
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn root(&self, mut index: usize) -> usize {
+        while self.parents[index] != index {
+            index = self.parents[index];
+        }
+        index
+    }
+
+    fn find(&mut self, index: usize) -> usize {
+        let root = self.root(index);
+        let mut current = index;
+        while self.parents[current] != root {
+            let next = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (root_a, root_b) = (self.find(a), self.find(b));
+        // The earliest text remains the representative of the cluster
+        let (representative, other) = if root_a < root_b {
+            (root_a, root_b)
+        } else {
+            (root_b, root_a)
+        };
+        self.parents[other] = representative;
+    }
+}
+
+fn normalize(embedding: Embedding) -> Embedding {
+    let norm = embedding
+        .iter()
+        .map(|value| value * value)
+        .sum::<f32>()
+        .sqrt()
+        .max(1e-12);
+    embedding.into_iter().map(|value| value / norm).collect()
+}
+
+fn dot_product(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(a, b)| a * b).sum()
+}
+
+fn signature(embedding: &[f32], hyperplanes: &[Embedding]) -> u64 {
+    hyperplanes
+        .iter()
+        .enumerate()
+        .filter(|(_, hyperplane)| dot_product(embedding, hyperplane) >= 0.0)
+        .fold(0u64, |signature, (bit, _)| signature | (1 << bit))
+}
+
+/// Minimal deterministic random number generator for the hyperplanes generation
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_uniform(&mut self) -> f64 {
+        // Uniform in (0, 1]
+        ((self.next_u64() >> 11) as f64 + 1.0) / (1u64 << 53) as f64
+    }
+
+    fn next_gaussian(&mut self) -> f32 {
+        // Box-Muller transform
+        let (u1, u2) = (self.next_uniform(), self.next_uniform());
+        ((-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()) as f32
+    }
+}
+
+/// # Configuration for SemanticDeduplicationModel
+/// Contains the configuration of the sentence embeddings model and of the duplicate index.
+pub struct SemanticDeduplicationConfig {
+    /// `SentenceEmbeddingsConfig` defining the sentence embeddings model used to encode the texts
+    pub sentence_embeddings_config: SentenceEmbeddingsConfig,
+    /// `DuplicateIndexConfig` defining the similarity threshold and hashing parameters
+    pub index_config: DuplicateIndexConfig,
+    /// Batch size used for encoding the texts (default: 64)
+    pub batch_size: usize,
+}
+
+impl SemanticDeduplicationConfig {
+    /// Instantiate a new semantic deduplication configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `sentence_embeddings_config` - `SentenceEmbeddingsConfig` defining the sentence embeddings model
+    pub fn new(
+        sentence_embeddings_config: SentenceEmbeddingsConfig,
+    ) -> SemanticDeduplicationConfig {
+        SemanticDeduplicationConfig {
+            sentence_embeddings_config,
+            index_config: DuplicateIndexConfig::default(),
+            batch_size: 64,
+        }
+    }
+}
+
+#[cfg(feature = "remote")]
+impl Default for SemanticDeduplicationConfig {
+    /// Provides a default `all-MiniLM-L12-v2` sentence embeddings model
+    fn default() -> SemanticDeduplicationConfig {
+        SemanticDeduplicationConfig::new(SentenceEmbeddingsConfig::from(
+            SentenceEmbeddingsModelType::AllMiniLmL12V2,
+        ))
+    }
+}
+
+/// # SemanticDeduplicationModel to detect near-duplicate texts
+/// - `sentence_embeddings_model`: Sentence embeddings model encoding the texts
+/// - `index`: Index of the embeddings of the texts added so far
+pub struct SemanticDeduplicationModel {
+    pub sentence_embeddings_model: SentenceEmbeddingsModel,
+    pub index: DuplicateIndex,
+    batch_size: usize,
+}
+
+impl SemanticDeduplicationModel {
+    /// Build a new `SemanticDeduplicationModel` with an empty index
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - `SemanticDeduplicationConfig` object containing the sentence embeddings and index configurations
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::deduplication::SemanticDeduplicationModel;
+    ///
+    /// let deduplication_model = SemanticDeduplicationModel::new(Default::default())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(
+        config: SemanticDeduplicationConfig,
+    ) -> Result<SemanticDeduplicationModel, RustBertError> {
+        let sentence_embeddings_model =
+            SentenceEmbeddingsModel::new(config.sentence_embeddings_config)?;
+        let index = DuplicateIndex::new(config.index_config)?;
+        Ok(SemanticDeduplicationModel {
+            sentence_embeddings_model,
+            index,
+            batch_size: config.batch_size,
+        })
+    }
+
+    /// Get a reference to the sentence embeddings model tokenizer.
+    pub fn get_tokenizer(&self) -> &TokenizerOption {
+        self.sentence_embeddings_model.get_tokenizer()
+    }
+
+    /// Get a mutable reference to the sentence embeddings model tokenizer.
+    pub fn get_tokenizer_mut(&mut self) -> &mut TokenizerOption {
+        self.sentence_embeddings_model.get_tokenizer_mut()
+    }
+
+    /// Embeds texts and adds them to the index. Texts are indexed in order, following the texts already added:
+    /// successive calls can be used to process a stream of texts.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to add to the index.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Option<usize>>, RustBertError>` containing for each text the index of the representative of
+    ///   its duplicate cluster if it duplicates a previously added text, `None` otherwise
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::deduplication::SemanticDeduplicationModel;
+    /// let mut deduplication_model = SemanticDeduplicationModel::new(Default::default())?;
+    /// let duplicate_of = deduplication_model.add(&["My order never arrived", "I never received my order"])?;
+    /// let unique_texts = duplicate_of.iter().filter(|duplicate| duplicate.is_none()).count();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add<S>(&mut self, input: &[S]) -> Result<Vec<Option<usize>>, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let mut output = Vec::with_capacity(input.len());
+        for batch in input.chunks(self.batch_size.max(1)) {
+            let embeddings = self.sentence_embeddings_model.encode(batch)?;
+            output.extend(self.index.add(embeddings)?);
+        }
+        Ok(output)
+    }
+
+    /// Returns the clusters of near-duplicate texts added so far (containing at least two texts)
+    pub fn clusters(&self) -> Vec<DuplicateCluster> {
+        self.index.clusters()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[ignore] // no need to run, compilation is enough to verify it is Send
+    fn test() {
+        let config = SemanticDeduplicationConfig::default();
+        let _: Box<dyn Send> = Box::new(SemanticDeduplicationModel::new(config));
+    }
+
+    #[test]
+    fn test_duplicate_index() -> anyhow::Result<()> {
+        let mut index = DuplicateIndex::new(DuplicateIndexConfig {
+            threshold: 0.95,
+            ..Default::default()
+        })?;
+        let duplicate_of = index.add(vec![
+            vec![1.0, 0.0, 0.0, 0.0],
+            vec![0.0, 1.0, 0.0, 0.0],
+            vec![0.99, 0.05, 0.0, 0.0],
+            vec![0.0, 0.0, 1.0, 0.0],
+        ])?;
+        assert_eq!(duplicate_of, vec![None, None, Some(0), None]);
+
+        let duplicate_of = index.add(vec![vec![0.0, 2.0, 0.1, 0.0], vec![2.0, 0.0, 0.0, 0.0]])?;
+        assert_eq!(duplicate_of, vec![Some(1), Some(0)]);
+        assert_eq!(index.len(), 6);
+        assert_eq!(
+            index.clusters(),
+            vec![
+                DuplicateCluster {
+                    representative: 0,
+                    members: vec![0, 2, 5],
+                },
+                DuplicateCluster {
+                    representative: 1,
+                    members: vec![1, 4],
+                },
+            ]
+        );
+        assert!(index.add(vec![vec![1.0, 0.0]]).is_err());
+
+        //    Batches with an invalid embedding are rejected without indexing any of their embeddings
+        assert!(index
+            .add(vec![vec![1.0, 0.0, 0.0, 0.0], vec![1.0, 0.0]])
+            .is_err());
+        assert_eq!(index.len(), 6);
+        assert_eq!(index.clusters()[0].members, vec![0, 2, 5]);
+        let duplicate_of = index.add(vec![vec![0.0, 0.0, 0.0, 1.0]])?;
+        assert_eq!(duplicate_of, vec![None]);
+        assert_eq!(index.len(), 7);
+
+        //    Inconsistent dimensions within the first batch
+        let mut index = DuplicateIndex::new(Default::default())?;
+        assert!(index
+            .add(vec![vec![1.0, 0.0, 0.0], vec![1.0, 0.0]])
+            .is_err());
+        assert!(index.is_empty());
+        index.add(vec![vec![1.0, 0.0]])?;
+        assert_eq!(index.len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,576 @@
+// Copyright 2023 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Entity linking pipeline
+//! Links the entities recognized by a `NERModel` to the entries of a local knowledge base.
+//! Each knowledge base entry (identifier, name, aliases and description) is encoded once using a `SentenceEmbeddingsModel`.
+//! Entity mentions are encoded together with their surrounding context and matched against the knowledge base entries
+//! by cosine similarity. The identifier of the best matching entry is attached to each entity if its similarity exceeds
+//! a threshold. Candidate entries can optionally be restricted to the entries whose name or aliases match the mention.
+//!
+//! ```no_run
+//! use rust_bert::pipelines::entity_linking::{EntityLinkingModel, KnowledgeBaseEntry};
+//! # fn main() -> anyhow::Result<()> {
+//! let knowledge_base = vec![
+//!     KnowledgeBaseEntry {
+//!         id: "Q90".to_string(),
+//!         name: "Paris".to_string(),
+//!         aliases: vec!["City of Light".to_string()],
+//!         description: "Capital and most populous city of France".to_string(),
+//!     },
+//!     KnowledgeBaseEntry {
+//!         id: "Q830149".to_string(),
+//!         name: "Paris".to_string(),
+//!         aliases: vec!["Paris, Texas".to_string()],
+//!         description: "City in Lamar County, Texas, United States".to_string(),
+//!     },
+//! ];
+//! let entity_linking_model = EntityLinkingModel::new(Default::default(), knowledge_base)?;
+//!
+//! let input = ["Amy visited the Eiffel Tower in Paris, France."];
+//! let output = entity_linking_model.predict(&input)?;
+//! # Ok(())
+//! # }
+//! ```
+//! Output: \
+//! ```no_run
+//! # use rust_bert::pipelines::entity_linking::LinkedEntity;
+//! # use rust_bert::pipelines::ner::Entity;
+//! # use rust_tokenizers::Offset;
+//! # let output =
+//! [[
+//!     LinkedEntity {
+//!         entity: Entity {
+//!             word: String::from("Amy"),
+//!             score: 0.9986,
+//!             label: String::from("PER"),
+//!             offset: Offset { begin: 0, end: 3 },
+//!         },
+//!         kb_id: None,
+//!         score: 0.1247,
+//!     },
+//!     LinkedEntity {
+//!         entity: Entity {
+//!             word: String::from("Paris"),
+//!             score: 0.9985,
+//!             label: String::from("LOC"),
+//!             offset: Offset { begin: 32, end: 37 },
+//!         },
+//!         kb_id: Some(String::from("Q90")),
+//!         score: 0.7128,
+//!     },
+//! ]]
+//! # ;
+//! ```
+
+use crate::common::error::RustBertError;
+use crate::pipelines::common::TokenizerOption;
+use crate::pipelines::ner::{Entity, NERModel};
+use crate::pipelines::sentence_embeddings::{SentenceEmbeddingsConfig, SentenceEmbeddingsModel};
+use crate::pipelines::token_classification::TokenClassificationConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use tch::Tensor;
+
+#[cfg(feature = "remote")]
+use crate::pipelines::sentence_embeddings::SentenceEmbeddingsModelType;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// # Entry of a knowledge base used for entity linking
+pub struct KnowledgeBaseEntry {
+    /// Unique identifier of the entry, returned for linked entities
+    pub id: String,
+    /// Canonical name of the entry
+    pub name: String,
+    /// Alternative names of the entry
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Description of the entry, used to disambiguate entries sharing a name or alias
+    #[serde(default)]
+    pub description: String,
+}
+
+impl KnowledgeBaseEntry {
+    /// Text representation of the entry encoded by the sentence embeddings model
+    fn to_text(&self) -> String {
+        let mut text = self.name.clone();
+        if !self.aliases.is_empty() {
+            text.push_str(" (");
+            text.push_str(&self.aliases.join(", "));
+            text.push(')');
+        }
+        if !self.description.is_empty() {
+            text.push_str(": ");
+            text.push_str(&self.description);
+        }
+        text
+    }
+}
+
+/// Knowledge base entries indexed by their normalized name and aliases
+struct KnowledgeBase {
+    entries: Vec<KnowledgeBaseEntry>,
+    alias_index: HashMap<String, Vec<usize>>,
+}
+
+impl KnowledgeBase {
+    fn new(entries: Vec<KnowledgeBaseEntry>) -> Self {
+        let mut alias_index: HashMap<String, Vec<usize>> = HashMap::new();
+        for (entry_index, entry) in entries.iter().enumerate() {
+            for name in std::iter::once(&entry.name).chain(entry.aliases.iter()) {
+                let entry_indices = alias_index.entry(Self::normalize_alias(name)).or_default();
+                if !entry_indices.contains(&entry_index) {
+                    entry_indices.push(entry_index);
+                }
+            }
+        }
+        Self {
+            entries,
+            alias_index,
+        }
+    }
+
+    fn normalize_alias(alias: &str) -> String {
+        alias.trim().to_lowercase()
+    }
+
+    /// Indices of the entries a mention may be linked to
+    fn get_candidates(&self, mention: &str, alias_candidates_only: bool) -> Vec<usize> {
+        if alias_candidates_only {
+            self.alias_index
+                .get(&Self::normalize_alias(mention))
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            (0..self.entries.len()).collect()
+        }
+    }
+
+    /// Returns the identifier of the candidate entry with the highest similarity to the mention (if above `min_score`)
+    /// and its similarity. `similarities` contains the similarity of the mention with every entry of the knowledge base.
+    fn link(
+        &self,
+        mention: &str,
+        similarities: &[f32],
+        alias_candidates_only: bool,
+        min_score: f32,
+    ) -> (Option<String>, f32) {
+        let best_match = self
+            .get_candidates(mention, alias_candidates_only)
+            .into_iter()
+            .filter_map(|entry_index| {
+                similarities
+                    .get(entry_index)
+                    .map(|score| (entry_index, *score))
+            })
+            .fold(
+                None,
+                |best: Option<(usize, f32)>, (entry_index, score)| match best {
+                    Some((_, best_score)) if best_score >= score => best,
+                    _ => Some((entry_index, score)),
+                },
+            );
+        match best_match {
+            Some((entry_index, score)) if score >= min_score => {
+                (Some(self.entries[entry_index].id.clone()), score)
+            }
+            Some((_, score)) => (None, score),
+            None => (None, 0.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// # Entity linked to a knowledge base by an `EntityLinkingModel`
+pub struct LinkedEntity {
+    /// Entity recognized in the input text
+    pub entity: Entity,
+    /// Identifier of the best matching knowledge base entry, None if no entry exceeds the minimum score
+    pub kb_id: Option<String>,
+    /// Cosine similarity between the mention and the best matching knowledge base entry
+    pub score: f32,
+}
+
+/// # Configuration for EntityLinkingModel
+/// Contains the configuration of the entity recognition and sentence embeddings models, and the linking options.
+pub struct EntityLinkingConfig {
+    /// `TokenClassificationConfig` defining the named entity recognition model
+    pub ner_config: TokenClassificationConfig,
+    /// `SentenceEmbeddingsConfig` defining the sentence embeddings model used to encode mentions and knowledge base entries
+    pub sentence_embeddings_config: SentenceEmbeddingsConfig,
+    /// Number of characters of context on each side of the mention included in the mention representation (default: 100)
+    pub context_window: usize,
+    /// Minimum cosine similarity for an entity to be linked to a knowledge base entry (default: 0.5)
+    pub min_score: f32,
+    /// Restrict candidate entries to the entries whose name or aliases match the mention (case-insensitive).
+    /// If false, all entries are candidates (default: false)
+    pub alias_candidates_only: bool,
+    /// Batch size used for encoding knowledge base entries and mentions (default: 64)
+    pub batch_size: usize,
+}
+
+impl EntityLinkingConfig {
+    /// Instantiate a new entity linking configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `ner_config` - `TokenClassificationConfig` defining the named entity recognition model
+    /// * `sentence_embeddings_config` - `SentenceEmbeddingsConfig` defining the sentence embeddings model
+    pub fn new(
+        ner_config: TokenClassificationConfig,
+        sentence_embeddings_config: SentenceEmbeddingsConfig,
+    ) -> EntityLinkingConfig {
+        EntityLinkingConfig {
+            ner_config,
+            sentence_embeddings_config,
+            context_window: 100,
+            min_score: 0.5,
+            alias_candidates_only: false,
+            batch_size: 64,
+        }
+    }
+}
+
+#[cfg(feature = "remote")]
+impl Default for EntityLinkingConfig {
+    /// Provides a default CoNLL-2003 NER model (English) and an `all-MiniLM-L12-v2` sentence embeddings model
+    fn default() -> EntityLinkingConfig {
+        EntityLinkingConfig::new(
+            TokenClassificationConfig::default(),
+            SentenceEmbeddingsConfig::from(SentenceEmbeddingsModelType::AllMiniLmL12V2),
+        )
+    }
+}
+
+/// # EntityLinkingModel to link named entities to a local knowledge base
+/// - `ner_model`: Named entity recognition model
+/// - `sentence_embeddings_model`: Sentence embeddings model encoding mentions and knowledge base entries
+pub struct EntityLinkingModel {
+    pub ner_model: NERModel,
+    pub sentence_embeddings_model: SentenceEmbeddingsModel,
+    knowledge_base: KnowledgeBase,
+    knowledge_base_embeddings: Option<Tensor>,
+    context_window: usize,
+    min_score: f32,
+    alias_candidates_only: bool,
+    batch_size: usize,
+}
+
+impl EntityLinkingModel {
+    /// Build a new `EntityLinkingModel` and encode the knowledge base entries
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - `EntityLinkingConfig` object containing the entity recognition and sentence embeddings configurations
+    /// * `knowledge_base` - `Vec<KnowledgeBaseEntry>` entries to link entities to
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::entity_linking::{EntityLinkingModel, KnowledgeBaseEntry};
+    ///
+    /// let knowledge_base: Vec<KnowledgeBaseEntry> =
+    ///     serde_json::from_str(&std::fs::read_to_string("path/to/knowledge_base.json")?)?;
+    /// let entity_linking_model = EntityLinkingModel::new(Default::default(), knowledge_base)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(
+        config: EntityLinkingConfig,
+        knowledge_base: Vec<KnowledgeBaseEntry>,
+    ) -> Result<EntityLinkingModel, RustBertError> {
+        let ner_model = NERModel::new(config.ner_config)?;
+        let sentence_embeddings_model =
+            SentenceEmbeddingsModel::new(config.sentence_embeddings_config)?;
+        let mut model = EntityLinkingModel {
+            ner_model,
+            sentence_embeddings_model,
+            knowledge_base: KnowledgeBase::new(vec![]),
+            knowledge_base_embeddings: None,
+            context_window: config.context_window,
+            min_score: config.min_score,
+            alias_candidates_only: config.alias_candidates_only,
+            batch_size: config.batch_size,
+        };
+        model.set_knowledge_base(knowledge_base)?;
+        Ok(model)
+    }
+
+    /// Get a reference to the sentence embeddings model tokenizer.
+    pub fn get_tokenizer(&self) -> &TokenizerOption {
+        self.sentence_embeddings_model.get_tokenizer()
+    }
+
+    /// Get a mutable reference to the sentence embeddings model tokenizer.
+    pub fn get_tokenizer_mut(&mut self) -> &mut TokenizerOption {
+        self.sentence_embeddings_model.get_tokenizer_mut()
+    }
+
+    /// Get a reference to the knowledge base entries
+    pub fn get_knowledge_base(&self) -> &[KnowledgeBaseEntry] {
+        &self.knowledge_base.entries
+    }
+
+    /// Replace the knowledge base entries, encoding the new entries with the sentence embeddings model
+    ///
+    /// # Arguments
+    ///
+    /// * `knowledge_base` - `Vec<KnowledgeBaseEntry>` entries to link entities to
+    pub fn set_knowledge_base(
+        &mut self,
+        knowledge_base: Vec<KnowledgeBaseEntry>,
+    ) -> Result<(), RustBertError> {
+        let entry_texts = knowledge_base
+            .iter()
+            .map(KnowledgeBaseEntry::to_text)
+            .collect::<Vec<String>>();
+
+        self.knowledge_base_embeddings = self.encode_normalized(&entry_texts)?;
+        self.knowledge_base = KnowledgeBase::new(knowledge_base);
+        Ok(())
+    }
+
+    /// Recognize entities in texts and link them to the knowledge base
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to extract and link entities from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Vec<LinkedEntity>>, RustBertError>` containing the linked entities for each input text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::entity_linking::EntityLinkingModel;
+    /// # let knowledge_base = vec![];
+    /// let entity_linking_model = EntityLinkingModel::new(Default::default(), knowledge_base)?;
+    /// let input = [
+    ///     "My name is Amy. I live in Paris.",
+    ///     "Paris is a city in France.",
+    /// ];
+    /// let output = entity_linking_model.predict(&input)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict<S>(&self, input: &[S]) -> Result<Vec<Vec<LinkedEntity>>, RustBertError>
+    where
+        S: AsRef<str>,
+    {
+        let entities = self.ner_model.predict_full_entities(input);
+        self.link_entities(input, entities)
+    }
+
+    /// Link entities already identified in the input texts to the knowledge base
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts the entities were extracted from.
+    /// * `entities` - `Vec<Vec<Entity>>` Entities found in each of the input texts (e.g. by a `NERModel`), with character offsets.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Vec<LinkedEntity>>, RustBertError>` containing the linked entities for each input text
+    pub fn link_entities<S>(
+        &self,
+        input: &[S],
+        entities: Vec<Vec<Entity>>,
+    ) -> Result<Vec<Vec<LinkedEntity>>, RustBertError>
+    where
+        S: AsRef<str>,
+    {
+        let mention_texts = input
+            .iter()
+            .zip(entities.iter())
+            .flat_map(|(text, text_entities)| {
+                let chars = text.as_ref().chars().collect::<Vec<char>>();
+                text_entities
+                    .iter()
+                    .map(|entity| self.mention_text(&chars, entity))
+                    .collect::<Vec<String>>()
+            })
+            .collect::<Vec<String>>();
+
+        let similarities = match (
+            self.encode_normalized(&mention_texts)?,
+            &self.knowledge_base_embeddings,
+        ) {
+            (Some(mention_embeddings), Some(knowledge_base_embeddings)) => {
+                let similarities =
+                    mention_embeddings.matmul(&knowledge_base_embeddings.transpose(0, 1));
+                Vec::<Vec<f32>>::try_from(similarities)?
+            }
+            _ => vec![vec![]; mention_texts.len()],
+        };
+
+        let mut similarities = similarities.into_iter();
+        let mut output = Vec::with_capacity(entities.len());
+        for text_entities in entities {
+            let mut linked_entities = Vec::with_capacity(text_entities.len());
+            for entity in text_entities {
+                let entity_similarities = similarities.next().unwrap_or_default();
+                let (kb_id, score) = self.knowledge_base.link(
+                    &entity.word,
+                    &entity_similarities,
+                    self.alias_candidates_only,
+                    self.min_score,
+                );
+                linked_entities.push(LinkedEntity {
+                    entity,
+                    kb_id,
+                    score,
+                });
+            }
+            output.push(linked_entities);
+        }
+        Ok(output)
+    }
+
+    fn mention_text(&self, chars: &[char], entity: &Entity) -> String {
+        let begin = (entity.offset.begin as usize).min(chars.len());
+        let end = (entity.offset.end as usize).clamp(begin, chars.len());
+        let context_begin = begin.saturating_sub(self.context_window);
+        let context_end = (end + self.context_window).min(chars.len());
+        format!(
+            "{}: {}",
+            entity.word,
+            chars[context_begin..context_end].iter().collect::<String>()
+        )
+    }
+
+    fn encode_normalized<S>(&self, inputs: &[S]) -> Result<Option<Tensor>, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        if inputs.is_empty() {
+            return Ok(None);
+        }
+        let embeddings = inputs
+            .chunks(self.batch_size.max(1))
+            .map(|batch| {
+                Ok(self
+                    .sentence_embeddings_model
+                    .encode_as_tensor(batch)?
+                    .embeddings)
+            })
+            .collect::<Result<Vec<Tensor>, RustBertError>>()?;
+        let embeddings = Tensor::cat(&embeddings, 0);
+        let norm = embeddings
+            .norm_scalaropt_dim(2, [1], true)
+            .clamp_min(1e-12)
+            .expand_as(&embeddings);
+        Ok(Some(embeddings / norm))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[ignore] // no need to run, compilation is enough to verify it is Send
+    fn test() {
+        let config = EntityLinkingConfig::default();
+        let _: Box<dyn Send> = Box::new(EntityLinkingModel::new(config, vec![]));
+    }
+
+    #[test]
+    fn test_knowledge_base_entry_text() {
+        let entry = KnowledgeBaseEntry {
+            id: "Q90".to_string(),
+            name: "Paris".to_string(),
+            aliases: vec!["City of Light".to_string(), "Lutetia".to_string()],
+            description: "Capital of France".to_string(),
+        };
+        assert_eq!(
+            entry.to_text(),
+            "Paris (City of Light, Lutetia): Capital of France"
+        );
+
+        let entry = KnowledgeBaseEntry {
+            id: "Q90".to_string(),
+            name: "Paris".to_string(),
+            aliases: vec![],
+            description: String::new(),
+        };
+        assert_eq!(entry.to_text(), "Paris");
+    }
+
+    fn get_knowledge_base() -> KnowledgeBase {
+        let entry = |id: &str, name: &str, aliases: &[&str]| KnowledgeBaseEntry {
+            id: id.to_string(),
+            name: name.to_string(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            description: String::new(),
+        };
+        KnowledgeBase::new(vec![
+            entry("Q90", "Paris", &["City of Light"]),
+            entry("Q830149", "Paris", &["Paris, Texas"]),
+            entry("Q64", "Berlin", &[]),
+        ])
+    }
+
+    #[test]
+    fn test_candidates_lookup() {
+        let knowledge_base = get_knowledge_base();
+
+        assert_eq!(knowledge_base.get_candidates("Paris", true), vec![0, 1]);
+        assert_eq!(
+            knowledge_base.get_candidates(" city of LIGHT ", true),
+            vec![0]
+        );
+        assert_eq!(knowledge_base.get_candidates("Paris, Texas", true), vec![1]);
+        assert!(knowledge_base.get_candidates("London", true).is_empty());
+        assert_eq!(
+            knowledge_base.get_candidates("London", false),
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn test_linking() {
+        let knowledge_base = get_knowledge_base();
+        let similarities = [0.7, 0.6, 0.8];
+
+        // All entries are candidates: the most similar entry is linked
+        assert_eq!(
+            knowledge_base.link("Paris", &similarities, false, 0.5),
+            (Some("Q64".to_string()), 0.8)
+        );
+        // Alias matching restricts the candidates to the homonyms
+        assert_eq!(
+            knowledge_base.link("paris", &similarities, true, 0.5),
+            (Some("Q90".to_string()), 0.7)
+        );
+        assert_eq!(
+            knowledge_base.link("Paris, Texas", &similarities, true, 0.5),
+            (Some("Q830149".to_string()), 0.6)
+        );
+        // The best candidate is below the minimum score
+        assert_eq!(
+            knowledge_base.link("Paris", &similarities, true, 0.75),
+            (None, 0.7)
+        );
+        // No candidate matches the mention or the knowledge base is empty
+        assert_eq!(
+            knowledge_base.link("London", &similarities, true, 0.5),
+            (None, 0.0)
+        );
+        assert_eq!(
+            KnowledgeBase::new(vec![]).link("Paris", &[], false, 0.5),
+            (None, 0.0)
+        );
+    }
+}
@@ -67,7 +67,7 @@
 //! ```
 
 use tch::kind::Kind::Int64;
-use tch::{no_grad, Device, Tensor};
+use tch::{no_grad, Device, Kind, Tensor};
 
 use crate::bart::LayerState as BartLayerState;
 use crate::common::resources::ResourceProvider;
@@ -81,8 +81,10 @@ use crate::reformer::LayerState as ReformerLayerState;
 use crate::t5::LayerState as T5LayerState;
 use crate::xlnet::LayerState as XLNetLayerState;
 
+pub use crate::common::kv_cache::KVCache;
+
 use self::ordered_float::OrderedFloat;
-use crate::pipelines::common::{ModelResource, ModelType, TokenizerOption};
+use crate::pipelines::common::{cast_var_store, ModelResource, ModelType, TokenizerOption};
 
 extern crate ordered_float;
 #[cfg(feature = "onnx")]
@@ -219,9 +221,9 @@ impl GenerateConfig {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Cache {
-    GPT2Cache(Option<Vec<Tensor>>),
+    GPT2Cache(Option<Vec<KVCache>>),
     BARTCache(Option<Vec<(Option<BartLayerState>, Option<BartLayerState>)>>),
     T5Cache(Option<Vec<(Option<T5LayerState>, Option<T5LayerState>)>>),
     LongT5Cache(Option<Vec<(Option<T5LayerState>, Option<T5LayerState>)>>),
@@ -235,6 +237,26 @@ pub enum Cache {
     None,
 }
 
+impl Cache {
+    /// Preallocates the key/value caches of the decoder self-attention layers for `max_length` positions, so
+    /// that the buffers are not reallocated during the rest of the generation
+    pub(crate) fn reserve(&mut self, max_length: i64) {
+        match self {
+            Cache::GPT2Cache(Some(layer_caches)) => {
+                for layer_cache in layer_caches.iter_mut() {
+                    layer_cache.reserve(max_length);
+                }
+            }
+            Cache::GPTNeoCache(Some(layer_caches)) | Cache::GPTJCache(Some(layer_caches)) => {
+                for layer_cache in layer_caches.iter_mut().flatten() {
+                    layer_cache.reserve(max_length);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 pub(crate) mod private_generation_utils {
     use rust_tokenizers::TokenIdsWithOffsets;
     use std::cmp::{max, min};
@@ -247,7 +269,7 @@ pub(crate) mod private_generation_utils {
 
     use crate::pipelines::common::TokenizerOption;
     use crate::pipelines::generation_utils::{
-        BeamHypotheses, Cache, GenerateConfig, LMModelOutput, PrefixAllowedFunction,
+        BeamHypotheses, Cache, GenerateConfig, LMModelOutput, PrefixAllowedFunction, PrefixCache,
     };
 
     use super::ordered_float::OrderedFloat;
@@ -826,6 +848,7 @@ pub(crate) mod private_generation_utils {
             gen_opt: InternalGenerateOptions,
             prefix_allowed_tokens_fn: Option<PrefixAllowedFunction>,
             output_scores: bool,
+            past: Cache,
         ) -> GeneratedOutputWithScores {
             let mut unfinished_sentences =
                 Tensor::ones([batch_size], (Kind::Int64, self.get_device()));
@@ -836,7 +859,7 @@ pub(crate) mod private_generation_utils {
             let mut static_bad_words_mask: Option<Tensor> = None;
             let mut attention_mask = attention_mask.copy();
             let mut input_ids = input_ids.copy();
-            let mut past: Cache = Cache::None;
+            let mut past = past;
             let mut outputs: Tensor;
             let mut current_length = cur_len;
             let mut token_scores_output: Option<Vec<Tensor>> =
@@ -864,8 +887,12 @@ pub(crate) mod private_generation_utils {
                     .unwrap();
                 outputs = temp.lm_logits;
                 past = temp.cache;
+                if let Some(max_length) = gen_opt.max_length {
+                    past.reserve(max_length);
+                }
 
-                let mut next_token_logits = outputs.select(1, -1);
+                // Scores are processed in single precision for half and bfloat16 precision models
+                let mut next_token_logits = outputs.select(1, -1).to_kind(Kind::Float);
                 // Reduce probability for repeated inputs
                 if gen_opt.repetition_penalty > 1f64 {
                     self.enforce_repetition_penalty(
@@ -1063,6 +1090,7 @@ pub(crate) mod private_generation_utils {
             gen_opt: InternalGenerateOptions,
             prefix_allowed_tokens_fn: Option<PrefixAllowedFunction>,
             output_scores: bool,
+            past: Cache,
         ) -> GeneratedOutputWithScores {
             let num_beam_groups = gen_opt.num_beam_groups.unwrap_or(1);
             let num_sub_beams = gen_opt.num_beams / num_beam_groups;
@@ -1104,7 +1132,7 @@ pub(crate) mod private_generation_utils {
                 if output_scores { Some(vec![]) } else { None };
             let mut current_tokens = Tensor::new();
 
-            let mut past: Cache = Cache::None;
+            let mut past = past;
             let mut done = vec![false; batch_size as usize];
 
             let mut outputs: Tensor;
@@ -1139,6 +1167,9 @@ pub(crate) mod private_generation_utils {
                     .unwrap();
                 outputs = temp.lm_logits;
                 past = temp.cache;
+                if let Some(max_length) = gen_opt.max_length {
+                    past.reserve(max_length);
+                }
 
                 for beam_group_index in 0..num_beam_groups {
                     let group_start_index = beam_group_index * num_sub_beams;
@@ -1171,7 +1202,8 @@ pub(crate) mod private_generation_utils {
                         outputs
                             .select(1, -1)
                             .index_select(0, batch_group_indices.as_ref().unwrap())
-                    };
+                    }
+                    .to_kind(Kind::Float);
                     // Reduce probability for repeated inputs
                     if gen_opt.repetition_penalty > 1f64 {
                         self.enforce_repetition_penalty(
@@ -1571,6 +1603,66 @@ pub(crate) mod private_generation_utils {
             }
         }
 
+        fn prefix_cache_past(
+            &self,
+            prefix_cache: &PrefixCache,
+            input_ids: &Tensor,
+            attention_mask: &Tensor,
+        ) -> Cache {
+            let (batch_size, cur_len) = (input_ids.size()[0], input_ids.size()[1]);
+            // Inputs that do not start with the (unmasked) prefix are encoded from scratch
+            let prefix_length = prefix_cache.prefix_ids.len() as i64;
+            if prefix_length > cur_len {
+                return Cache::None;
+            }
+            let prefix_ids = Tensor::from_slice(&prefix_cache.prefix_ids)
+                .to(input_ids.device())
+                .unsqueeze(0);
+            let prefix_matches = bool::try_from(
+                input_ids
+                    .narrow(1, 0, prefix_length)
+                    .eq_tensor(&prefix_ids)
+                    .all(),
+            )
+            .unwrap()
+                & !bool::try_from(attention_mask.narrow(1, 0, prefix_length).eq(0).any()).unwrap();
+            if !prefix_matches {
+                return Cache::None;
+            }
+
+            // The prefix is cached for a single sequence, expand it to the batch size
+            let mut past = prefix_cache.cache.clone();
+            let _ = self.reorder_cache(
+                &mut past,
+                None,
+                &Tensor::zeros([batch_size], (Kind::Int64, input_ids.device())),
+            );
+
+            // The cache covers all tokens but the last one of the prefix. Encode the tokens following
+            // the prefix so that only the last token of the input remains for the first generation step.
+            let new_tokens = cur_len - prefix_length;
+            if new_tokens == 0 {
+                return past;
+            }
+            let attention_mask = attention_mask.narrow(1, 0, cur_len - 1);
+            let position_ids = (attention_mask.totype(Kind::Int64).cumsum(-1, Kind::Int64) - 1)
+                .masked_fill(&attention_mask.eq(0), 1)
+                .narrow(1, prefix_length - 1, new_tokens);
+            self.forward_t(
+                Some(&input_ids.narrow(1, prefix_length - 1, new_tokens)),
+                past,
+                Some(&attention_mask),
+                None,
+                Some(&position_ids),
+                None,
+                None,
+                None,
+                false,
+            )
+            .unwrap()
+            .cache
+        }
+
         fn reorder_cache(
             &self,
             past: &mut Cache,
@@ -1616,6 +1708,24 @@ pub struct GeneratedIndicesOutput {
     pub token_scores: Option<Vec<f64>>,
 }
 
+#[derive(Debug, Clone)]
+/// # Cached prompt prefix for causal generation
+/// Contains the token ids of a prompt prefix shared by many generations (e.g. a system prompt or few-shot examples)
+/// and the model cache resulting from its encoding. It is created once with `LanguageGenerator::prefix_cache` and
+/// passed to the generation methods with `GenerateOptions::prefix_cache`: each generation then starts from a copy of
+/// the cache instead of encoding the prefix again.
+pub struct PrefixCache {
+    prefix_ids: Vec<i64>,
+    cache: Cache,
+}
+
+impl PrefixCache {
+    /// Token ids of the cached prefix
+    pub fn prefix_ids(&self) -> &[i64] {
+        &self.prefix_ids
+    }
+}
+
 pub type PrefixAllowedFunction<'a> = &'a dyn Fn(i64, &Tensor) -> Vec<i64>;
 /// Type alias for a function defining allowed tokens based on current tokens generated.
 /// This function should take a `batch_id` and associated tensor of already generated tokens and
@@ -1669,6 +1779,10 @@ pub struct GenerateOptions<'a> {
     pub bad_word_ids: Option<&'a Vec<Vec<i64>>>,
     /// Flag indicating if text generation scores should be returned
     pub output_scores: bool,
+    /// Cached prompt prefix (causal models only). Inputs starting with the prefix token ids reuse its cache and only
+    /// encode the tokens following the prefix, other inputs are encoded from scratch. Prompts passed to `generate` are
+    /// appended to the prefix.
+    pub prefix_cache: Option<&'a PrefixCache>,
 }
 
 macro_rules! unpack_config {
@@ -1887,11 +2001,23 @@ pub trait LanguageGenerator: PrivateLanguageGenerator {
             None => eos_token_ids.as_ref().map(|eos_ids| eos_ids[0]),
         };
 
-        let input_ids = match prompt_texts {
-            Some(prompts) if !prompts.is_empty() => {
+        let prefix_cache = generate_options
+            .and_then(|generate_options| generate_options.prefix_cache)
+            .filter(|_| !self.is_encoder_decoder());
+        let input_ids = match (prompt_texts, prefix_cache) {
+            (Some(prompts), _) if !prompts.is_empty() => {
                 self.encode_prompt_text(prompts, encoding_max_len, pad_token_id)
             }
-            None => match self.get_bos_id() {
+            (None, Some(prefix_cache)) => {
+                return self.generate_from_ids_and_past(
+                    Tensor::from_slice(prefix_cache.prefix_ids())
+                        .to(self.get_device())
+                        .unsqueeze(0),
+                    None,
+                    generate_options,
+                );
+            }
+            (None, None) => match self.get_bos_id() {
                 Some(bos_id) => Tensor::ones([1, 1], (Int64, self.get_device())) * bos_id,
                 None => panic!(
                     "A model with a BOS token must be used to start generation with an empty input"
@@ -1899,7 +2025,103 @@ pub trait LanguageGenerator: PrivateLanguageGenerator {
             },
             _ => return Vec::new(),
         };
-        self.generate_from_ids_and_past(input_ids, None, generate_options)
+        match prefix_cache {
+            Some(prefix_cache) => {
+                // Prompts are appended to the prefix, padding is placed between the prefix and the prompts
+                let batch_size = input_ids.size()[0];
+                let prefix_ids = Tensor::from_slice(prefix_cache.prefix_ids())
+                    .to(self.get_device())
+                    .unsqueeze(0)
+                    .expand([batch_size, -1], true);
+                let attention_mask = match pad_token_id {
+                    Some(pad_id) => input_ids.ne(pad_id).to_kind(Int64),
+                    None => input_ids.ones_like().to_kind(Int64),
+                };
+                let input_ids = Tensor::cat(&[&prefix_ids, &input_ids], 1);
+                let attention_mask = Tensor::cat(&[&prefix_ids.ones_like(), &attention_mask], 1);
+                self.generate_from_ids_and_past(input_ids, Some(attention_mask), generate_options)
+            }
+            None => self.generate_from_ids_and_past(input_ids, None, generate_options),
+        }
+    }
+
+    /// Encodes a prompt prefix shared by many generations, to be passed to the generation methods with
+    /// `GenerateOptions::prefix_cache`. Only supported for causal models with a key/value cache (GPT2, GPT-Neo, GPT-J).
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix_ids` - `&[i64]` Token ids of the prefix, with at least 2 tokens.
+    ///
+    /// # Returns
+    /// * `PrefixCache` containing the prefix ids and the corresponding model cache.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::gpt2::GPT2Generator;
+    /// use rust_bert::pipelines::generation_utils::{GenerateOptions, LanguageGenerator};
+    ///
+    /// let gpt2_generator = GPT2Generator::new(Default::default())?;
+    /// let tokenizer = gpt2_generator.get_tokenizer();
+    /// let prefix_ids =
+    ///     tokenizer.convert_tokens_to_ids(&tokenizer.tokenize("Answer the questions politely."));
+    /// let prefix_cache = gpt2_generator.prefix_cache(&prefix_ids)?;
+    ///
+    /// let generate_options = GenerateOptions {
+    ///     max_new_tokens: Some(32),
+    ///     prefix_cache: Some(&prefix_cache),
+    ///     ..Default::default()
+    /// };
+    /// let output = gpt2_generator.generate(
+    ///     Some(&[" What is the capital of France?", " Who wrote Hamlet?"]),
+    ///     Some(generate_options),
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn prefix_cache(&self, prefix_ids: &[i64]) -> Result<PrefixCache, RustBertError> {
+        if self.is_encoder_decoder() {
+            return Err(RustBertError::InvalidConfigurationError(
+                "Prefix caching is only supported for causal generation models".to_string(),
+            ));
+        }
+        if prefix_ids.len() < 2 {
+            return Err(RustBertError::ValueError(
+                "Prefix caching requires a prefix of at least 2 tokens".to_string(),
+            ));
+        }
+        // The last token of the prefix is left for the first generation step
+        let cached_length = prefix_ids.len() as i64 - 1;
+        let input_ids = Tensor::from_slice(&prefix_ids[..cached_length as usize])
+            .to(self.get_device())
+            .unsqueeze(0);
+        let attention_mask = input_ids.ones_like();
+        let position_ids = Tensor::arange(cached_length, (Int64, self.get_device())).unsqueeze(0);
+        let output = no_grad(|| {
+            self.forward_t(
+                Some(&input_ids),
+                Cache::None,
+                Some(&attention_mask),
+                None,
+                Some(&position_ids),
+                None,
+                None,
+                None,
+                false,
+            )
+        })?;
+        match output.cache {
+            Cache::GPT2Cache(Some(_)) | Cache::GPTNeoCache(Some(_)) | Cache::GPTJCache(Some(_)) => {
+                Ok(PrefixCache {
+                    prefix_ids: prefix_ids.to_vec(),
+                    cache: output.cache,
+                })
+            }
+            _ => Err(RustBertError::InvalidConfigurationError(
+                "Prefix caching is not supported for this model".to_string(),
+            )),
+        }
     }
 
     /// Generate token indices given a list of indices (useful when the input has been pre-tokenized).
@@ -1987,6 +2209,7 @@ pub trait LanguageGenerator: PrivateLanguageGenerator {
         let prefix_allowed_tokens_fn =
             generate_options.and_then(|opts| opts.prefix_allowed_tokens_fn);
         let output_scores = generate_options.map_or(false, |opts| opts.output_scores);
+        let prefix_cache = generate_options.and_then(|opts| opts.prefix_cache);
 
         let pad_token_id = match self.get_pad_id() {
             Some(value) => Some(value),
@@ -2127,6 +2350,12 @@ pub trait LanguageGenerator: PrivateLanguageGenerator {
         };
 
         let generated_output_with_scores = no_grad(|| {
+            let past = match prefix_cache {
+                Some(prefix_cache) if !self.is_encoder_decoder() => {
+                    self.prefix_cache_past(prefix_cache, &input_ids, &attention_mask)
+                }
+                _ => Cache::None,
+            };
             if num_beams > 1 {
                 self.generate_beam_search(
                     input_ids,
@@ -2137,6 +2366,7 @@ pub trait LanguageGenerator: PrivateLanguageGenerator {
                     gen_opt,
                     prefix_allowed_tokens_fn,
                     output_scores,
+                    past,
                 )
             } else {
                 self.generate_no_beam_search(
@@ -2148,6 +2378,7 @@ pub trait LanguageGenerator: PrivateLanguageGenerator {
                     gen_opt,
                     prefix_allowed_tokens_fn,
                     output_scores,
+                    past,
                 )
             }
         });
@@ -2227,13 +2458,15 @@ pub trait LanguageGenerator: PrivateLanguageGenerator {
     }
 
     fn half(&mut self) -> Result<(), RustBertError> {
-        self.get_var_store_mut()?.half();
-        Ok(())
+        cast_var_store(Some(self.get_var_store_mut()?), Kind::Half)
+    }
+
+    fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(Some(self.get_var_store_mut()?), Kind::BFloat16)
     }
 
     fn float(&mut self) -> Result<(), RustBertError> {
-        self.get_var_store_mut()?.float();
-        Ok(())
+        cast_var_store(Some(self.get_var_store_mut()?), Kind::Float)
     }
 
     fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
@@ -1,6 +1,14 @@
+mod noun_phrases;
 mod pipeline;
 mod scorer;
+mod statistical_scorer;
 mod stopwords;
 mod tokenizer;
 
-pub use pipeline::{Keyword, KeywordExtractionConfig, KeywordExtractionModel, KeywordScorerType};
+pub use noun_phrases::NounPhrasesConfig;
+pub use pipeline::{
+    Keyword, KeywordCandidatesType, KeywordExtractionConfig, KeywordExtractionModel,
+    KeywordScorerType, StatisticalKeywordExtractionConfig, StatisticalKeywordExtractionModel,
+};
+pub use statistical_scorer::StatisticalScorerType;
+pub use stopwords::get_stopwords;
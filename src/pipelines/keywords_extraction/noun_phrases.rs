@@ -0,0 +1,161 @@
+use crate::pipelines::keywords_extraction::tokenizer::StopWordsTokenizer;
+use crate::pipelines::pos_tagging::{POSConfig, POSModel};
+use crate::pipelines::token_classification::Token;
+use crate::RustBertError;
+use rust_tokenizers::{Offset, OffsetSize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+// Adjective labels for the Universal Dependencies (UPOS) and Penn Treebank tag sets
+const DEFAULT_MODIFIER_LABELS: [&str; 4] = ["ADJ", "JJ", "JJR", "JJS"];
+// Noun labels for the Universal Dependencies (UPOS) and Penn Treebank tag sets
+const DEFAULT_NOUN_LABELS: [&str; 6] = ["NOUN", "PROPN", "NN", "NNS", "NNP", "NNPS"];
+
+/// # Configuration for noun phrase keyword candidates
+/// Noun phrases are identified as sequences of optional modifiers (e.g. adjectives) followed by one
+/// or more nouns, using the part-of-speech tags predicted by a `POSModel`.
+pub struct NounPhrasesConfig {
+    /// `POSConfig` defining the part-of-speech model to use. Should match the language of the documents.
+    pub pos_config: POSConfig,
+    /// Optional part-of-speech labels of the words allowed before the nouns of a phrase.
+    /// Defaults to adjectives from the Universal Dependencies and Penn Treebank tag sets.
+    pub modifier_labels: Option<HashSet<String>>,
+    /// Optional part-of-speech labels of the nouns ending a phrase.
+    /// Defaults to nouns and proper nouns from the Universal Dependencies and Penn Treebank tag sets.
+    pub noun_labels: Option<HashSet<String>>,
+}
+
+impl From<POSConfig> for NounPhrasesConfig {
+    fn from(pos_config: POSConfig) -> Self {
+        NounPhrasesConfig {
+            pos_config,
+            modifier_labels: None,
+            noun_labels: None,
+        }
+    }
+}
+
+#[cfg(feature = "remote")]
+impl Default for NounPhrasesConfig {
+    /// Provides a noun phrase configuration based on the default (English) part-of-speech model
+    fn default() -> Self {
+        NounPhrasesConfig::from(POSConfig::default())
+    }
+}
+
+/// # Keyword candidates generator extracting noun phrases from documents
+pub struct NounPhraseExtractor {
+    pos_model: POSModel,
+    modifier_labels: HashSet<String>,
+    noun_labels: HashSet<String>,
+}
+
+impl NounPhraseExtractor {
+    pub fn new(config: NounPhrasesConfig) -> Result<Self, RustBertError> {
+        let pos_model = POSModel::new(config.pos_config)?;
+        let modifier_labels = config.modifier_labels.unwrap_or_else(|| {
+            DEFAULT_MODIFIER_LABELS
+                .iter()
+                .map(|label| label.to_string())
+                .collect()
+        });
+        let noun_labels = config.noun_labels.unwrap_or_else(|| {
+            DEFAULT_NOUN_LABELS
+                .iter()
+                .map(|label| label.to_string())
+                .collect()
+        });
+        Ok(Self {
+            pos_model,
+            modifier_labels,
+            noun_labels,
+        })
+    }
+
+    fn extract_phrases<'b>(
+        &self,
+        text: &'b str,
+        tokens: &[Token],
+        tokenizer: &StopWordsTokenizer,
+    ) -> HashMap<Cow<'b, str>, Vec<Offset>> {
+        // Token offsets are expressed in characters, keyword offsets in bytes
+        let byte_positions = text
+            .char_indices()
+            .map(|(position, _)| position)
+            .chain(std::iter::once(text.len()))
+            .collect::<Vec<usize>>();
+
+        let mut phrases: Vec<(usize, usize)> = Vec::new();
+        let mut phrase_begin: Option<usize> = None;
+        let mut phrase_end: Option<usize> = None;
+        for token in tokens {
+            let offset = match token.offset {
+                Some(offset) => offset,
+                None => continue,
+            };
+            let begin = byte_positions[(offset.begin as usize).min(byte_positions.len() - 1)];
+            let end = byte_positions[(offset.end as usize).min(byte_positions.len() - 1)];
+
+            let word = if tokenizer.do_lower_case() {
+                Cow::from(text[begin..end].to_lowercase())
+            } else {
+                Cow::from(&text[begin..end])
+            };
+            let is_stopword = tokenizer.is_stopword(&word);
+            let is_noun = self.noun_labels.contains(&token.label) && !is_stopword;
+            let is_modifier = self.modifier_labels.contains(&token.label) && !is_stopword;
+
+            if is_noun {
+                phrase_begin.get_or_insert(begin);
+                phrase_end = Some(end);
+            } else if is_modifier && phrase_end.is_none() {
+                phrase_begin.get_or_insert(begin);
+            } else {
+                if let (Some(phrase_begin), Some(phrase_end)) = (phrase_begin, phrase_end) {
+                    phrases.push((phrase_begin, phrase_end));
+                }
+                // A modifier following a noun starts a new phrase
+                phrase_begin = if is_modifier { Some(begin) } else { None };
+                phrase_end = None;
+            }
+        }
+        if let (Some(phrase_begin), Some(phrase_end)) = (phrase_begin, phrase_end) {
+            phrases.push((phrase_begin, phrase_end));
+        }
+
+        let mut candidates = HashMap::new();
+        for (begin, end) in phrases {
+            let mut phrase_text = Cow::from(&text[begin..end]);
+            if tokenizer.do_lower_case() {
+                phrase_text = Cow::from(phrase_text.to_lowercase());
+            }
+            let pos = Offset {
+                begin: begin as OffsetSize,
+                end: end as OffsetSize,
+            };
+            candidates
+                .entry(phrase_text)
+                .and_modify(|pos_vec: &mut Vec<Offset>| pos_vec.push(pos))
+                .or_insert_with(|| vec![pos]);
+        }
+        candidates
+    }
+
+    /// Extracts the noun phrases of a list of documents, excluding the stopwords of the tokenizer
+    /// (stopwords interrupt noun phrases).
+    pub fn extract_list<'b, S>(
+        &self,
+        texts: &'b [S],
+        tokenizer: &StopWordsTokenizer,
+    ) -> Vec<HashMap<Cow<'b, str>, Vec<Offset>>>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let tokens = self.pos_model.predict_tokens(texts);
+        texts
+            .iter()
+            .zip(tokens.iter())
+            .map(|(text, tokens)| self.extract_phrases(text.as_ref(), tokens, tokenizer))
+            .collect()
+    }
+}
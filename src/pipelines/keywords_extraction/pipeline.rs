@@ -21,6 +21,8 @@ use crate::pipelines::common::TokenizerOption;
 /// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 /// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 /// SOFTWARE.
+use crate::pipelines::keywords_extraction::noun_phrases::{NounPhraseExtractor, NounPhrasesConfig};
+use crate::pipelines::keywords_extraction::statistical_scorer::StatisticalScorerType;
 use crate::pipelines::keywords_extraction::tokenizer::StopWordsTokenizer;
 #[cfg(feature = "remote")]
 use crate::pipelines::sentence_embeddings::SentenceEmbeddingsModelType;
@@ -28,12 +30,14 @@ use crate::pipelines::sentence_embeddings::{
     SentenceEmbeddingsConfig, SentenceEmbeddingsModel, SentenceEmbeddingsSentenceBertConfig,
     SentenceEmbeddingsTokenizerConfig,
 };
+use crate::pipelines::translation::Language;
 use crate::{Config, RustBertError};
 use regex::Regex;
 use rust_tokenizers::Offset;
 use std::borrow::Cow;
 use std::cmp::min;
 use std::collections::{HashMap, HashSet};
+use tch::Tensor;
 
 /// # Keyword generated by a `KeywordExtractionModel`
 #[derive(Debug, Clone)]
@@ -63,16 +67,41 @@ pub enum KeywordScorerType {
     /// of keywords to return. High values of `max_sum_candidates` will lead to a high number of keyword
     /// candidates and increase the computational cost / memory requirements.
     MaxSum,
+    /// Hybrid ranker, blending the cosine similarity between the document and keywords embeddings with
+    /// a statistical score (normalized by the maximum statistical score of the document candidates).
+    /// The final score is `embeddings_weight * cosine_similarity + (1 - embeddings_weight) * statistical_score`.
+    Hybrid {
+        /// Statistical scorer blended with the embeddings similarity
+        statistical_scorer: StatisticalScorerType,
+        /// Weight of the embeddings similarity in the final score (between 0 and 1)
+        embeddings_weight: f64,
+    },
+}
+
+/// # Keyword candidates generation variants
+pub enum KeywordCandidatesType {
+    /// Word n-grams identified by the tokenizer pattern within the `ngram_range` of the configuration.
+    /// N-grams that are stopwords or containing a stopword are excluded.
+    Ngrams,
+    /// Noun phrases (sequences of modifiers followed by nouns) identified by a part-of-speech model.
+    /// The length of noun phrases is not limited by the `ngram_range` of the configuration, and stopwords
+    /// interrupt noun phrases. This allows extracting multi-word keyphrases for languages where
+    /// n-grams are not a good approximation of phrases.
+    NounPhrases(NounPhrasesConfig),
 }
 
 /// # Configuration for Keyword extraction
 pub struct KeywordExtractionConfig<'a> {
     /// `SentenceEmbeddingsConfig` defining the sentence embeddings model to use
     pub sentence_embeddings_config: SentenceEmbeddingsConfig,
-    /// Optional list of tokenizer stopwords to exclude from the keywords candidate list. Default to a list of English stopwords.
+    /// Optional list of tokenizer stopwords to exclude from the keywords candidate list. Default to the list of stopwords for the `tokenizer_language`.
     pub tokenizer_stopwords: Option<HashSet<&'a str>>,
-    /// Optional tokenization regex pattern. Defaults to sequence of word characters.
+    /// Optional tokenization regex pattern. Defaults to sequence of word characters (individual characters for Chinese and Japanese Han characters).
     pub tokenizer_pattern: Option<Regex>,
+    /// Optional language of the documents, used to select the default stopwords and tokenization pattern. Defaults to English.
+    pub tokenizer_language: Option<Language>,
+    /// `KeywordCandidatesType` used to generate the keyword candidates (n-grams or noun phrases).
+    pub candidates_type: KeywordCandidatesType,
     /// `KeywordScorerType` used to rank keywords.
     pub scorer_type: KeywordScorerType,
     /// N-gram range (inclusive) for keywords. (1, 2) would consider all 1 and 2 word gram for keyword candidates.
@@ -99,6 +128,8 @@ impl Default for KeywordExtractionConfig<'_> {
             sentence_embeddings_config,
             tokenizer_stopwords: None,
             tokenizer_pattern: None,
+            tokenizer_language: None,
+            candidates_type: KeywordCandidatesType::Ngrams,
             scorer_type: KeywordScorerType::CosineSimilarity,
             ngram_range: (1, 1),
             num_keywords: 5,
@@ -112,11 +143,13 @@ impl Default for KeywordExtractionConfig<'_> {
 ///
 /// It contains a sentence embeddings model to compute word-document similarities,
 /// a tokenizer to define a keyword candidates list and a scorer to rank these keywords.
-/// - `sentence_embeddings_model`: Sentence embeddings model
+/// - `sentence_embeddings_model`: Sentence embeddings model (Torch or ONNX encoder, see `SentenceEmbeddingsConfig`)
 /// - `tokenizer`: tokenizer used to generate the list of candidates (differs from the transformer tokenizer)
+/// - `noun_phrase_extractor`: optional part-of-speech based generator of noun phrase candidates, replacing the tokenizer n-grams
 pub struct KeywordExtractionModel<'a> {
     pub sentence_embeddings_model: SentenceEmbeddingsModel,
     pub tokenizer: StopWordsTokenizer<'a>,
+    noun_phrase_extractor: Option<NounPhraseExtractor>,
     scorer_type: KeywordScorerType,
     ngram_range: (usize, usize),
     num_keywords: usize,
@@ -144,6 +177,16 @@ impl<'a> KeywordExtractionModel<'a> {
     pub fn new(
         config: KeywordExtractionConfig<'a>,
     ) -> Result<KeywordExtractionModel<'a>, RustBertError> {
+        if let KeywordScorerType::Hybrid {
+            embeddings_weight, ..
+        } = config.scorer_type
+        {
+            if !(0.0..=1.0).contains(&embeddings_weight) {
+                return Err(RustBertError::InvalidConfigurationError(format!(
+                    "The embeddings weight of the hybrid scorer must be between 0 and 1, got {embeddings_weight}"
+                )));
+            }
+        }
         let tokenizer_config = SentenceEmbeddingsTokenizerConfig::from_file(
             config
                 .sentence_embeddings_config
@@ -163,14 +206,29 @@ impl<'a> KeywordExtractionModel<'a> {
             .do_lower_case
             .unwrap_or(sentence_bert_config.do_lower_case);
 
-        let tokenizer = StopWordsTokenizer::new(
-            config.tokenizer_stopwords,
-            config.tokenizer_pattern,
-            do_lower_case,
-        );
+        let tokenizer = match config.tokenizer_language {
+            Some(language) => StopWordsTokenizer::new_with_language(
+                language,
+                config.tokenizer_stopwords,
+                config.tokenizer_pattern,
+                do_lower_case,
+            )?,
+            None => StopWordsTokenizer::new(
+                config.tokenizer_stopwords,
+                config.tokenizer_pattern,
+                do_lower_case,
+            ),
+        };
+        let noun_phrase_extractor = match config.candidates_type {
+            KeywordCandidatesType::Ngrams => None,
+            KeywordCandidatesType::NounPhrases(noun_phrases_config) => {
+                Some(NounPhraseExtractor::new(noun_phrases_config)?)
+            }
+        };
         Ok(Self {
             sentence_embeddings_model,
             tokenizer,
+            noun_phrase_extractor,
             scorer_type: config.scorer_type,
             ngram_range: config.ngram_range,
             num_keywords: config.num_keywords,
@@ -218,14 +276,48 @@ impl<'a> KeywordExtractionModel<'a> {
     where
         S: AsRef<str> + Sync,
     {
-        let words = self.tokenizer.tokenize_list(inputs, self.ngram_range);
-        let (flat_word_list, document_boundaries) =
-            KeywordExtractionModel::flatten_word_list(&words);
-
         let document_embeddings = self
             .sentence_embeddings_model
             .encode_as_tensor(inputs)?
             .embeddings;
+        self.predict_with_embeddings(inputs, &document_embeddings)
+    }
+
+    /// Extract keywords from a list of input texts, using pre-computed document embeddings
+    /// (for example the centroid of the embeddings of a group of documents) to rank the keyword candidates.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - slice of string-like input texts to extract keywords from
+    /// * `document_embeddings` - Tensor of shape (number of inputs, embeddings dimension) containing the embedding
+    /// of each input, computed with the model sentence embeddings model
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Vec<Keyword>>, RustBertError>` containing a list of keyword for each input text
+    pub fn predict_with_embeddings<S>(
+        &self,
+        inputs: &[S],
+        document_embeddings: &Tensor,
+    ) -> Result<Vec<Vec<Keyword>>, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        if document_embeddings.size()[0] != inputs.len() as i64 {
+            return Err(RustBertError::ValueError(format!(
+                "Number of document embeddings ({}) does not match the number of inputs ({})",
+                document_embeddings.size()[0],
+                inputs.len()
+            )));
+        }
+        let words = match &self.noun_phrase_extractor {
+            Some(noun_phrase_extractor) => {
+                noun_phrase_extractor.extract_list(inputs, &self.tokenizer)
+            }
+            None => self.tokenizer.tokenize_list(inputs, self.ngram_range),
+        };
+        let (flat_word_list, document_boundaries) =
+            KeywordExtractionModel::flatten_word_list(&words);
 
         let word_embeddings = self
             .sentence_embeddings_model
@@ -241,12 +333,29 @@ impl<'a> KeywordExtractionModel<'a> {
                 .embeddings
                 .slice(0, start as i64, end as i64, 1);
             let num_keywords = min(self.num_keywords, word_embeddings.size()[0] as usize);
+            let statistical_scores = match &self.scorer_type {
+                KeywordScorerType::Hybrid {
+                    statistical_scorer, ..
+                } => {
+                    let candidates = flat_word_list[start..end]
+                        .iter()
+                        .map(|word| (word.as_ref(), words[document_index][*word].len()))
+                        .collect::<Vec<(&str, usize)>>();
+                    Some(statistical_scorer.score_candidates(
+                        inputs[document_index].as_ref(),
+                        &candidates,
+                        &self.tokenizer,
+                    ))
+                }
+                _ => None,
+            };
             let local_top_word_indices = self.scorer_type.score_keywords(
                 document_embedding,
                 word_embeddings,
                 num_keywords,
                 self.diversity,
                 self.max_sum_candidates,
+                statistical_scores.as_deref(),
             );
             for (index, score) in local_top_word_indices {
                 let word = flat_word_list[start + index];
@@ -256,7 +365,7 @@ impl<'a> KeywordExtractionModel<'a> {
                     offsets: words[document_index].get(word).unwrap().clone(),
                 });
             }
-            document_keywords.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+            document_keywords.sort_by(|a, b| b.score.total_cmp(&a.score));
             output_keywords.push(document_keywords)
         }
 
@@ -279,3 +388,148 @@ impl<'a> KeywordExtractionModel<'a> {
         (flat_word_list, doc_boundaries)
     }
 }
+
+/// # Configuration for statistical keyword extraction
+pub struct StatisticalKeywordExtractionConfig<'a> {
+    /// Optional list of tokenizer stopwords to exclude from the keywords candidate list. Default to the list of stopwords for the `tokenizer_language`.
+    pub tokenizer_stopwords: Option<HashSet<&'a str>>,
+    /// Optional tokenization regex pattern. Defaults to sequence of word characters (individual characters for Chinese and Japanese Han characters).
+    pub tokenizer_pattern: Option<Regex>,
+    /// Optional language of the documents, used to select the default stopwords and tokenization pattern. Defaults to English.
+    pub tokenizer_language: Option<Language>,
+    /// Automatically lower case keywords
+    pub do_lower_case: bool,
+    /// `StatisticalScorerType` used to rank keywords.
+    pub scorer_type: StatisticalScorerType,
+    /// N-gram range (inclusive) for keywords. (1, 2) would consider all 1 and 2 word gram for keyword candidates.
+    pub ngram_range: (usize, usize),
+    /// Number of keywords to return
+    pub num_keywords: usize,
+}
+
+impl Default for StatisticalKeywordExtractionConfig<'_> {
+    fn default() -> Self {
+        Self {
+            tokenizer_stopwords: None,
+            tokenizer_pattern: None,
+            tokenizer_language: None,
+            do_lower_case: true,
+            scorer_type: StatisticalScorerType::Yake,
+            ngram_range: (1, 1),
+            num_keywords: 5,
+        }
+    }
+}
+
+/// # StatisticalKeywordExtractionModel to extract keywords from input texts without a transformer model
+///
+/// Keyword candidates are generated by a `StopWordsTokenizer` and ranked with a statistical scorer (YAKE or TextRank)
+/// relying only on the words statistics of each document. This model is significantly cheaper than the
+/// `KeywordExtractionModel` and suited to the processing of large document collections.
+pub struct StatisticalKeywordExtractionModel<'a> {
+    pub tokenizer: StopWordsTokenizer<'a>,
+    scorer_type: StatisticalScorerType,
+    ngram_range: (usize, usize),
+    num_keywords: usize,
+}
+
+impl<'a> StatisticalKeywordExtractionModel<'a> {
+    /// Build a new `StatisticalKeywordExtractionModel`
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - `StatisticalKeywordExtractionConfig` object containing the tokenizer and scorer options
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::keywords_extraction::StatisticalKeywordExtractionModel;
+    ///
+    /// let keyword_extraction_model = StatisticalKeywordExtractionModel::new(Default::default())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(
+        config: StatisticalKeywordExtractionConfig<'a>,
+    ) -> Result<StatisticalKeywordExtractionModel<'a>, RustBertError> {
+        let tokenizer = match config.tokenizer_language {
+            Some(language) => StopWordsTokenizer::new_with_language(
+                language,
+                config.tokenizer_stopwords,
+                config.tokenizer_pattern,
+                config.do_lower_case,
+            )?,
+            None => StopWordsTokenizer::new(
+                config.tokenizer_stopwords,
+                config.tokenizer_pattern,
+                config.do_lower_case,
+            ),
+        };
+        Ok(Self {
+            tokenizer,
+            scorer_type: config.scorer_type,
+            ngram_range: config.ngram_range,
+            num_keywords: config.num_keywords,
+        })
+    }
+
+    /// Extract keywords from a list of input texts.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - slice of string-like input texts to extract keywords from
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Vec<Keyword>>` containing a list of keyword for each input text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::keywords_extraction::StatisticalKeywordExtractionModel;
+    ///
+    /// let keyword_extraction_model = StatisticalKeywordExtractionModel::new(Default::default())?;
+    /// let input = [
+    ///     "This is a first sentence to extract keywords from.",
+    ///     "Some keywords will be extracted from this text too.",
+    /// ];
+    /// let output = keyword_extraction_model.predict(&input);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict<S>(&self, inputs: &[S]) -> Vec<Vec<Keyword>>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let words = self.tokenizer.tokenize_list(inputs, self.ngram_range);
+
+        inputs
+            .iter()
+            .zip(words)
+            .map(|(input, document_words)| {
+                let candidates = document_words
+                    .iter()
+                    .map(|(word, offsets)| (word.as_ref(), offsets.len()))
+                    .collect::<Vec<(&str, usize)>>();
+                let scores =
+                    self.scorer_type
+                        .score_candidates(input.as_ref(), &candidates, &self.tokenizer);
+
+                let mut document_keywords = document_words
+                    .iter()
+                    .zip(scores)
+                    .map(|((word, offsets), score)| Keyword {
+                        text: word.to_string(),
+                        score,
+                        offsets: offsets.clone(),
+                    })
+                    .collect::<Vec<Keyword>>();
+                document_keywords.sort_by(|a, b| b.score.total_cmp(&a.score));
+                document_keywords.truncate(self.num_keywords);
+                document_keywords
+            })
+            .collect()
+    }
+}
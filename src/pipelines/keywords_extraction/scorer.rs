@@ -33,6 +33,7 @@ impl KeywordScorerType {
         num_keywords: usize,
         diversity: Option<f64>,
         max_sum_candidates: Option<usize>,
+        statistical_scores: Option<&[f32]>,
     ) -> Vec<(usize, f32)> {
         match self {
             KeywordScorerType::CosineSimilarity => {
@@ -56,6 +57,15 @@ impl KeywordScorerType {
                     ),
                 )
             }
+            KeywordScorerType::Hybrid {
+                embeddings_weight, ..
+            } => hybrid_score(
+                document_embedding,
+                word_embeddings,
+                statistical_scores.expect("Statistical scores are required for the hybrid scorer"),
+                num_keywords,
+                *embeddings_weight,
+            ),
         }
     }
 }
@@ -87,6 +97,29 @@ fn cosine_similarity_score(
         .collect()
 }
 
+fn hybrid_score(
+    document_embedding: Tensor,
+    word_embeddings: Tensor,
+    statistical_scores: &[f32],
+    num_keywords: usize,
+    embeddings_weight: f64,
+) -> Vec<(usize, f32)> {
+    let similarities = cosine_similarity(Some(&document_embedding), &word_embeddings).view([-1]);
+    let statistical_scores = Tensor::from_slice(statistical_scores)
+        .to_kind(similarities.kind())
+        .to(similarities.device());
+    let statistical_scores = &statistical_scores / statistical_scores.max().clamp_min(1e-12);
+    let scores = similarities * embeddings_weight + statistical_scores * (1.0 - embeddings_weight);
+
+    let (top_scores, top_keywords) = scores.topk(num_keywords as i64, 0, true, false);
+    top_scores
+        .iter::<f64>()
+        .unwrap()
+        .zip(top_keywords.iter::<i64>().unwrap())
+        .map(|(score, pos)| (pos as usize, score as f32))
+        .collect()
+}
+
 fn maximal_margin_relevance_score(
     document_embedding: Tensor,
     word_embeddings: Tensor,
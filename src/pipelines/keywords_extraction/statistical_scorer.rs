@@ -0,0 +1,377 @@
+use crate::pipelines::keywords_extraction::tokenizer::StopWordsTokenizer;
+use std::collections::{HashMap, HashSet};
+
+// Characters ending a sentence, used to compute the sentence-level YAKE features and limit the TextRank co-occurrence windows
+const SENTENCE_DELIMITERS: [char; 7] = ['.', '!', '?', '\n', '。', '！', '？'];
+// Number of words before and after a word considered for the YAKE co-occurrence features
+const YAKE_WINDOW_SIZE: usize = 2;
+// Number of consecutive words (including stopwords) within which words are connected in the TextRank graph
+const TEXTRANK_WINDOW_SIZE: usize = 3;
+const TEXTRANK_DAMPING_FACTOR: f64 = 0.85;
+const TEXTRANK_MAX_ITERATIONS: usize = 100;
+const TEXTRANK_TOLERANCE: f64 = 1e-6;
+
+/// # Statistical scoring function variants for keyword ranking
+/// These scorers only rely on the statistics of the words in the document and do not require a transformer model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatisticalScorerType {
+    /// YAKE! (Campos et al., 2020) ranker, combining the casing, position, frequency, relatedness to context and
+    /// spread across sentences of the words of a keyword. The YAKE score `S` (lower is better) is returned as `1 / (1 + S)`.
+    Yake,
+    /// TextRank (Mihalcea & Tarau, 2004) ranker. Words co-occurring in a small window are connected in a graph
+    /// ranked with PageRank. The score of a keyword is the sum of the scores of its words.
+    TextRank,
+}
+
+/// Word of a document with the features used by the statistical scorers
+struct DocumentWord {
+    text: String,
+    is_stopword: bool,
+    is_uppercase: bool,
+    sentence: usize,
+}
+
+impl StatisticalScorerType {
+    /// Scores keyword candidates of a document. Candidates are provided with their frequency in the document,
+    /// and the scores returned (aligned with the candidates) are higher for more relevant keywords.
+    pub(crate) fn score_candidates(
+        &self,
+        text: &str,
+        candidates: &[(&str, usize)],
+        tokenizer: &StopWordsTokenizer,
+    ) -> Vec<f32> {
+        let words = get_document_words(text, tokenizer);
+        let word_scores = match self {
+            StatisticalScorerType::Yake => yake_word_scores(&words),
+            StatisticalScorerType::TextRank => textrank_word_scores(&words),
+        };
+
+        candidates
+            .iter()
+            .map(|(candidate, frequency)| {
+                let candidate_word_scores = tokenizer
+                    .tokenize_words(candidate)
+                    .iter()
+                    .filter_map(|offset| {
+                        word_scores
+                            .get(
+                                &candidate[offset.begin as usize..offset.end as usize]
+                                    .to_lowercase(),
+                            )
+                            .copied()
+                    })
+                    .collect::<Vec<f64>>();
+                if candidate_word_scores.is_empty() {
+                    return 0f32;
+                }
+                match self {
+                    StatisticalScorerType::Yake => {
+                        let product = candidate_word_scores.iter().product::<f64>();
+                        let sum = candidate_word_scores.iter().sum::<f64>();
+                        let keyword_score = product / ((*frequency).max(1) as f64 * (1.0 + sum));
+                        (1.0 / (1.0 + keyword_score)) as f32
+                    }
+                    StatisticalScorerType::TextRank => {
+                        candidate_word_scores.iter().sum::<f64>() as f32
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+fn get_document_words(text: &str, tokenizer: &StopWordsTokenizer) -> Vec<DocumentWord> {
+    let mut words = Vec::new();
+    let mut sentence = 0;
+    let mut previous_end = 0;
+    for offset in tokenizer.tokenize_words(text) {
+        let (begin, end) = (offset.begin as usize, offset.end as usize);
+        let sentence_start = text[previous_end..begin].contains(&SENTENCE_DELIMITERS[..]);
+        if sentence_start && !words.is_empty() {
+            sentence += 1;
+        }
+        let word = &text[begin..end];
+        // Capitalized words are only considered if they do not start a sentence, acronyms are always considered
+        let is_acronym = word.chars().count() > 1 && word.chars().all(|c| !c.is_lowercase());
+        let is_capitalized = !words.is_empty()
+            && !sentence_start
+            && word.chars().next().map_or(false, |c| c.is_uppercase());
+        words.push(DocumentWord {
+            text: word.to_lowercase(),
+            is_stopword: tokenizer.is_stopword(word),
+            is_uppercase: is_acronym | is_capitalized,
+            sentence,
+        });
+        previous_end = end;
+    }
+    words
+}
+
+#[derive(Default)]
+struct YakeWordStatistics {
+    frequency: usize,
+    uppercase_frequency: usize,
+    sentences: Vec<usize>,
+    left_context: Vec<usize>,
+    right_context: Vec<usize>,
+}
+
+fn yake_word_scores(words: &[DocumentWord]) -> HashMap<String, f64> {
+    let mut word_indices: HashMap<&str, usize> = HashMap::new();
+    let mut statistics: Vec<YakeWordStatistics> = Vec::new();
+    let word_ids = words
+        .iter()
+        .map(|word| {
+            let next_index = word_indices.len();
+            let index = *word_indices.entry(word.text.as_str()).or_insert(next_index);
+            if index == statistics.len() {
+                statistics.push(YakeWordStatistics::default());
+            }
+            index
+        })
+        .collect::<Vec<usize>>();
+
+    for (position, (word, word_id)) in words.iter().zip(word_ids.iter()).enumerate() {
+        let word_statistics = &mut statistics[*word_id];
+        word_statistics.frequency += 1;
+        if word.is_uppercase {
+            word_statistics.uppercase_frequency += 1;
+        }
+        word_statistics.sentences.push(word.sentence);
+        if word.is_stopword {
+            continue;
+        }
+        for context_position in position.saturating_sub(YAKE_WINDOW_SIZE)..position {
+            if words[context_position].sentence == word.sentence
+                && !words[context_position].is_stopword
+            {
+                let context_id = word_ids[context_position];
+                statistics[*word_id].left_context.push(context_id);
+                statistics[context_id].right_context.push(*word_id);
+            }
+        }
+    }
+
+    let num_sentences = words.last().map_or(1, |word| word.sentence + 1) as f64;
+    let candidate_ids = words
+        .iter()
+        .zip(word_ids.iter())
+        .filter(|(word, _)| !word.is_stopword)
+        .map(|(word, word_id)| (*word_id, word.text.as_str()))
+        .collect::<HashMap<usize, &str>>();
+    if candidate_ids.is_empty() {
+        return HashMap::new();
+    }
+    let candidate_frequencies = candidate_ids
+        .keys()
+        .map(|word_id| statistics[*word_id].frequency as f64)
+        .collect::<Vec<f64>>();
+    let max_frequency = candidate_frequencies.iter().cloned().fold(0f64, f64::max);
+    let mean_frequency =
+        candidate_frequencies.iter().sum::<f64>() / candidate_frequencies.len() as f64;
+    let std_frequency = (candidate_frequencies
+        .iter()
+        .map(|frequency| (frequency - mean_frequency).powi(2))
+        .sum::<f64>()
+        / candidate_frequencies.len() as f64)
+        .sqrt();
+
+    let context_dispersion = |context: &[usize]| {
+        if context.is_empty() {
+            0f64
+        } else {
+            context.iter().collect::<HashSet<&usize>>().len() as f64 / context.len() as f64
+        }
+    };
+
+    candidate_ids
+        .into_iter()
+        .map(|(word_id, word)| {
+            let word_statistics = &statistics[word_id];
+            let frequency = word_statistics.frequency as f64;
+
+            let casing = word_statistics.uppercase_frequency as f64 / (1.0 + frequency.ln());
+            let mut sentences = word_statistics.sentences.clone();
+            sentences.sort_unstable();
+            let median_sentence = if sentences.len() % 2 == 1 {
+                sentences[sentences.len() / 2] as f64
+            } else {
+                (sentences[sentences.len() / 2 - 1] + sentences[sentences.len() / 2]) as f64 / 2.0
+            };
+            let position = (3.0 + median_sentence).ln().ln();
+            let normalized_frequency = frequency / (mean_frequency + std_frequency);
+            let relatedness = 1.0
+                + (context_dispersion(&word_statistics.left_context)
+                    + context_dispersion(&word_statistics.right_context))
+                    * frequency
+                    / max_frequency;
+            sentences.dedup();
+            let spread = sentences.len() as f64 / num_sentences;
+
+            let score = relatedness * position
+                / (casing + normalized_frequency / relatedness + spread / relatedness);
+            (word.to_string(), score)
+        })
+        .collect()
+}
+
+fn textrank_word_scores(words: &[DocumentWord]) -> HashMap<String, f64> {
+    let mut word_indices: HashMap<&str, usize> = HashMap::new();
+    for word in words.iter().filter(|word| !word.is_stopword) {
+        let next_index = word_indices.len();
+        word_indices.entry(word.text.as_str()).or_insert(next_index);
+    }
+    let num_nodes = word_indices.len();
+    if num_nodes == 0 {
+        return HashMap::new();
+    }
+
+    let mut edges: Vec<HashMap<usize, f64>> = vec![HashMap::new(); num_nodes];
+    for (position, word) in words.iter().enumerate() {
+        if word.is_stopword {
+            continue;
+        }
+        let node = word_indices[word.text.as_str()];
+        for other_word in words
+            .iter()
+            .skip(position + 1)
+            .take(TEXTRANK_WINDOW_SIZE - 1)
+            .take_while(|other_word| other_word.sentence == word.sentence)
+            .filter(|other_word| !other_word.is_stopword)
+        {
+            let other_node = word_indices[other_word.text.as_str()];
+            if other_node != node {
+                *edges[node].entry(other_node).or_insert(0.0) += 1.0;
+                *edges[other_node].entry(node).or_insert(0.0) += 1.0;
+            }
+        }
+    }
+    let out_weights = edges
+        .iter()
+        .map(|node_edges| node_edges.values().sum::<f64>())
+        .collect::<Vec<f64>>();
+
+    let mut scores = vec![1f64; num_nodes];
+    for _ in 0..TEXTRANK_MAX_ITERATIONS {
+        let new_scores = (0..num_nodes)
+            .map(|node| {
+                (1.0 - TEXTRANK_DAMPING_FACTOR)
+                    + TEXTRANK_DAMPING_FACTOR
+                        * edges[node]
+                            .iter()
+                            .map(|(other_node, weight)| {
+                                weight / out_weights[*other_node] * scores[*other_node]
+                            })
+                            .sum::<f64>()
+            })
+            .collect::<Vec<f64>>();
+        let delta = new_scores
+            .iter()
+            .zip(scores.iter())
+            .map(|(new_score, score)| (new_score - score).abs())
+            .fold(0f64, f64::max);
+        scores = new_scores;
+        if delta < TEXTRANK_TOLERANCE {
+            break;
+        }
+    }
+
+    word_indices
+        .into_iter()
+        .map(|(word, index)| (word.to_string(), scores[index]))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_tokenizer() -> StopWordsTokenizer<'static> {
+        StopWordsTokenizer::new(Some(HashSet::from(["is", "a", "the"])), None, true)
+    }
+
+    #[test]
+    fn test_single_word_document() {
+        let tokenizer = get_tokenizer();
+        let words = get_document_words("Rust", &tokenizer);
+
+        // Single occurrence in the first sentence, without casing or context: ln(ln(3)) / (1 + 1)
+        let yake_scores = yake_word_scores(&words);
+        assert_eq!(yake_scores.len(), 1);
+        assert!((yake_scores["rust"] - 3f64.ln().ln() / 2.0).abs() < 1e-9);
+
+        // Isolated node of the graph: only the teleportation term remains
+        let textrank_scores = textrank_word_scores(&words);
+        assert_eq!(textrank_scores.len(), 1);
+        assert!((textrank_scores["rust"] - 0.15).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_empty_document() {
+        let tokenizer = get_tokenizer();
+        for text in ["", "Is the a?"] {
+            let words = get_document_words(text, &tokenizer);
+            assert!(yake_word_scores(&words).is_empty());
+            assert!(textrank_word_scores(&words).is_empty());
+            for scorer_type in [StatisticalScorerType::Yake, StatisticalScorerType::TextRank] {
+                let scores = scorer_type.score_candidates(text, &[("rust", 1)], &tokenizer);
+                assert_eq!(scores, vec![0f32]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_textrank_repeated_term() {
+        let tokenizer = get_tokenizer();
+        let words = get_document_words("Rust memory. Rust speed. Rust safety.", &tokenizer);
+        let scores = textrank_word_scores(&words);
+
+        // Star graph: c = 0.15 + 0.85 * 3 * l and l = 0.15 + 0.85 * c / 3
+        let center = (0.15 + 0.85 * 0.15 * 3.0) / (1.0 - 0.85 * 0.85);
+        let leaf = 0.15 + 0.85 * center / 3.0;
+        assert_eq!(scores.len(), 4);
+        assert!((scores["rust"] - center).abs() < 1e-4);
+        for word in ["memory", "speed", "safety"] {
+            assert!((scores[word] - leaf).abs() < 1e-4);
+            assert!(scores["rust"] > scores[word]);
+        }
+        assert!((scores.values().sum::<f64>() - 4.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_candidates_scores() {
+        let tokenizer = get_tokenizer();
+        let text = "Rust is a language. The Rust compiler is fast.";
+        let words = get_document_words(text, &tokenizer);
+        let candidates = [
+            ("rust", 2),
+            ("rust compiler", 1),
+            ("compiler", 1),
+            ("the", 1),
+        ];
+
+        let yake_scores = yake_word_scores(&words);
+        let (rust, compiler) = (yake_scores["rust"], yake_scores["compiler"]);
+        let expected_scores = [
+            1.0 / (1.0 + rust / (2.0 * (1.0 + rust))),
+            1.0 / (1.0 + rust * compiler / (1.0 + rust + compiler)),
+            1.0 / (1.0 + compiler / (1.0 + compiler)),
+            0.0,
+        ];
+        let scores = StatisticalScorerType::Yake.score_candidates(text, &candidates, &tokenizer);
+        for (score, expected_score) in scores.iter().zip(expected_scores) {
+            assert!((*score as f64 - expected_score).abs() < 1e-6);
+        }
+        // The repeated candidate ranks first, the keyword made of a stopword is not scored
+        assert!(scores[0] > scores[1] && scores[1] > scores[2] && scores[2] > scores[3]);
+
+        let textrank_scores = textrank_word_scores(&words);
+        let scores =
+            StatisticalScorerType::TextRank.score_candidates(text, &candidates, &tokenizer);
+        assert!(
+            (scores[1] as f64 - (textrank_scores["rust"] + textrank_scores["compiler"])).abs()
+                < 1e-6
+        );
+        assert_eq!(scores[3], 0.0);
+    }
+}
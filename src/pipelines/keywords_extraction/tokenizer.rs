@@ -1,10 +1,16 @@
-use crate::pipelines::keywords_extraction::stopwords::ENGLISH_STOPWORDS;
+use crate::pipelines::keywords_extraction::stopwords::{get_stopwords, ENGLISH_STOPWORDS};
+use crate::pipelines::translation::Language;
+use crate::RustBertError;
 use regex::Regex;
 use rust_tokenizers::{Offset, OffsetSize};
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 
 const DEFAULT_REGEX_PATTERN: &str = r"(?u)\b\w\w+\b";
+// Chinese and Japanese are not delimited by white spaces: Han characters are split individually,
+// Hiragana and Katakana are grouped in runs, and other scripts follow the default pattern.
+const CJK_REGEX_PATTERN: &str =
+    r"(?u)\p{Han}|[\p{Katakana}ー]+|\p{Hiragana}+|[^\W\p{Han}\p{Hiragana}\p{Katakana}]{2,}";
 
 pub struct StopWordsTokenizer<'a> {
     stopwords: HashSet<&'a str>,
@@ -28,6 +34,56 @@ impl<'a> StopWordsTokenizer<'a> {
         }
     }
 
+    /// Creates a new tokenizer with stopwords and tokenization pattern defaults for a given language.
+    /// Custom stopwords and pattern take precedence over the language defaults if provided.
+    pub fn new_with_language(
+        language: Language,
+        stopwords: Option<HashSet<&'a str>>,
+        pattern: Option<Regex>,
+        do_lower_case: bool,
+    ) -> Result<Self, RustBertError> {
+        let stopwords = match stopwords {
+            Some(stopwords) => stopwords,
+            None => get_stopwords(language)?,
+        };
+        let pattern = match pattern {
+            Some(pattern) => pattern,
+            None => match language {
+                Language::Chinese
+                | Language::ChineseMandarin
+                | Language::YueChinese
+                | Language::Japanese => Regex::new(CJK_REGEX_PATTERN).unwrap(),
+                _ => Regex::new(DEFAULT_REGEX_PATTERN).unwrap(),
+            },
+        };
+
+        Ok(Self {
+            stopwords,
+            pattern,
+            do_lower_case,
+        })
+    }
+
+    /// Stopwords are matched case-insensitively, stopword lists are expected to be lower-cased.
+    pub(crate) fn is_stopword(&self, token: &str) -> bool {
+        self.stopwords.contains(token) || self.stopwords.contains(token.to_lowercase().as_str())
+    }
+
+    pub(crate) fn do_lower_case(&self) -> bool {
+        self.do_lower_case
+    }
+
+    /// Returns the (byte) offsets of the words identified by the tokenization pattern, including stopwords.
+    pub fn tokenize_words(&self, text: &str) -> Vec<Offset> {
+        self.pattern
+            .find_iter(text)
+            .map(|hit| Offset {
+                begin: hit.start() as OffsetSize,
+                end: hit.end() as OffsetSize,
+            })
+            .collect()
+    }
+
     pub fn tokenize<'b>(
         &self,
         text: &'b str,
@@ -35,14 +91,7 @@ impl<'a> StopWordsTokenizer<'a> {
     ) -> HashMap<Cow<'b, str>, Vec<Offset>> {
         let mut tokenized_text = HashMap::new();
 
-        let mut tokens_list = Vec::new();
-        for hit in self.pattern.find_iter(text) {
-            let pos = Offset {
-                begin: hit.start() as OffsetSize,
-                end: hit.end() as OffsetSize,
-            };
-            tokens_list.push(pos);
-        }
+        let tokens_list = self.tokenize_words(text);
         for ngram_size in ngram_range.0..ngram_range.1 + 1 {
             'ngram_loop: for ngram in tokens_list.windows(ngram_size) {
                 let pos = Offset {
@@ -53,7 +102,7 @@ impl<'a> StopWordsTokenizer<'a> {
                 if self.do_lower_case {
                     ngram_text = Cow::from(ngram_text.to_lowercase());
                 }
-                if self.stopwords.contains(&*ngram_text) {
+                if self.is_stopword(&ngram_text) {
                     continue;
                 }
                 if ngram_size > 1 {
@@ -62,11 +111,14 @@ impl<'a> StopWordsTokenizer<'a> {
                         if self.do_lower_case {
                             token = Cow::from(token.to_lowercase());
                         }
-                        if self.stopwords.contains(&*token) {
+                        if self.is_stopword(&token) {
                             continue 'ngram_loop;
                         }
                     }
-                    if ngram.last().unwrap().begin > ngram[0].end + 1 {
+                    if ngram
+                        .windows(2)
+                        .any(|tokens| tokens[1].begin > tokens[0].end + 1)
+                    {
                         continue;
                     }
                 }
@@ -93,3 +145,21 @@ impl<'a> StopWordsTokenizer<'a> {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ngram_gaps() {
+        let tokenizer = StopWordsTokenizer::new(Some(HashSet::new()), None, true);
+        let ngrams = tokenizer.tokenize("Rust programming language. Memory safety", (1, 3));
+
+        // Every pair of consecutive words is checked for gaps, not only the first and last words
+        assert!(ngrams.contains_key("rust programming language"));
+        assert!(ngrams.contains_key("programming language"));
+        assert!(!ngrams.contains_key("language. memory"));
+        assert!(!ngrams.contains_key("programming language. memory"));
+        assert!(!ngrams.contains_key("language. memory safety"));
+    }
+}
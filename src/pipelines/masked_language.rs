@@ -52,7 +52,8 @@ use crate::deberta::DebertaForMaskedLM;
 use crate::deberta_v2::DebertaV2ForMaskedLM;
 use crate::fnet::FNetForMaskedLM;
 use crate::pipelines::common::{
-    get_device, ConfigOption, ModelResource, ModelType, TokenizerOption,
+    cast_var_store, get_device, set_var_store_device, ConfigOption, ModelResource, ModelType,
+    TokenizerOption,
 };
 use crate::resources::ResourceProvider;
 use crate::roberta::RobertaForMaskedLM;
@@ -67,7 +68,7 @@ use crate::{
     resources::RemoteResource,
 };
 use tch::nn::VarStore;
-use tch::{no_grad, Device, Tensor};
+use tch::{no_grad, Device, Kind, Tensor};
 
 #[derive(Debug, Clone)]
 /// Output container for masked language model pipeline.
@@ -193,16 +194,25 @@ impl MaskedLanguageOption {
     /// * `MaskedLanguageConfig` - Masked language model pipeline configuration. The type of model created will be inferred from the
     ///     `ModelResources` (Torch or ONNX) and `ModelType` (Architecture for Torch models) variants provided and
     pub fn new(config: &MaskedLanguageConfig) -> Result<Self, RustBertError> {
+        Ok(Self::new_with_var_store(config)?.0)
+    }
+
+    /// Instantiate a new masked language model, also returning the `VarStore` holding the weights of Torch models
+    pub(crate) fn new_with_var_store(
+        config: &MaskedLanguageConfig,
+    ) -> Result<(Self, Option<VarStore>), RustBertError> {
         match config.model_resource {
-            ModelResource::Torch(_) => Self::new_torch(config),
+            ModelResource::Torch(_) => {
+                let (model, var_store) = Self::new_torch(config)?;
+                Ok((model, Some(var_store)))
+            }
             #[cfg(feature = "onnx")]
-            ModelResource::ONNX(_) => Self::new_onnx(config),
+            ModelResource::ONNX(_) => Ok((Self::new_onnx(config)?, None)),
         }
     }
 
-    fn new_torch(config: &MaskedLanguageConfig) -> Result<Self, RustBertError> {
+    fn new_torch(config: &MaskedLanguageConfig) -> Result<(Self, VarStore), RustBertError> {
         let device = config.device;
-        let weights_path = config.model_resource.get_torch_local_path()?;
         let mut var_store = VarStore::new(device);
         let model_config =
             &ConfigOption::from_file(config.model_type, config.config_resource.get_local_path()?);
@@ -284,8 +294,8 @@ impl MaskedLanguageOption {
                 "Masked Language is not implemented for {model_type:?}!",
             ))),
         }?;
-        var_store.load(weights_path)?;
-        Ok(model)
+        crate::resources::load_weights(&config.model_resource, &mut var_store)?;
+        Ok((model, var_store))
     }
 
     #[cfg(feature = "onnx")]
@@ -419,6 +429,7 @@ pub struct MaskedLanguageModel {
     tokenizer: TokenizerOption,
     language_encode: MaskedLanguageOption,
     mask_token: Option<String>,
+    var_store: Option<VarStore>,
     device: Device,
     max_length: usize,
 }
@@ -488,7 +499,7 @@ impl MaskedLanguageModel {
         config: MaskedLanguageConfig,
         tokenizer: TokenizerOption,
     ) -> Result<MaskedLanguageModel, RustBertError> {
-        let language_encode = MaskedLanguageOption::new(&config)?;
+        let (language_encode, var_store) = MaskedLanguageOption::new_with_var_store(&config)?;
         let config_path = config.config_resource.get_local_path()?;
         let model_config = ConfigOption::from_file(config.model_type, config_path);
         let max_length = model_config
@@ -502,6 +513,7 @@ impl MaskedLanguageModel {
             tokenizer,
             language_encode,
             mask_token,
+            var_store,
             device,
             max_length,
         })
@@ -517,6 +529,28 @@ impl MaskedLanguageModel {
         &mut self.tokenizer
     }
 
+    /// Casts the model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::Half)
+    }
+
+    /// Casts the model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::BFloat16)
+    }
+
+    /// Casts the model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::Float)
+    }
+
+    /// Moves the model weights to the target device, inputs are placed on this device for subsequent predictions.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        set_var_store_device(self.var_store.as_mut(), device)?;
+        self.device = device;
+        Ok(())
+    }
+
     /// Replace custom user-provided mask token by language model mask token.
     fn replace_mask_token<'a, S>(
         &self,
@@ -599,16 +633,18 @@ impl MaskedLanguageModel {
         let mask_token_mask = input_ids.eq(mask_token_id);
 
         let output = no_grad(|| {
-            self.language_encode.forward_t(
-                Some(&input_ids),
-                None,
-                Some(&token_type_ids),
-                None,
-                None,
-                None,
-                None,
-                false,
-            )
+            self.language_encode
+                .forward_t(
+                    Some(&input_ids),
+                    None,
+                    Some(&token_type_ids),
+                    None,
+                    None,
+                    None,
+                    None,
+                    false,
+                )
+                .to_kind(Kind::Float)
         });
 
         let mut output_tokens = Vec::with_capacity(input.as_ref().len());
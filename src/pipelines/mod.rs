@@ -473,21 +473,168 @@
 //! ]
 //! # ;
 //! ```
+//!
+//! #### 12. Multiple choice
+//! Scores candidate answers for a given context (SWAG/RACE-style questions) using a model with a multiple choice head.
+//! ```ignore
+//! # use rust_bert::pipelines::multiple_choice::MultipleChoiceModel;
+//! # fn main() -> anyhow::Result<()> {
+//! #   let config = unimplemented!();
+//!     let model = MultipleChoiceModel::new(config)?;
+//!
+//!     let context = "The dog is running around trying to avoid a bath. She";
+//!     let choices = [
+//!         "uses a hose to keep it from getting soapy.",
+//!         "gets the dog wet, then it runs away again.",
+//!     ];
+//!
+//!     let output = model.predict(context, &choices)?;
+//! #   Ok(())
+//! # }
+//! ```
+//! Output:
+//! ```ignore
+//! # use rust_bert::pipelines::multiple_choice::Choice;
+//! # let output =
+//! [
+//!     Choice { text: String::from("uses a hose to keep it from getting soapy."), score: 0.0466, index: 0 },
+//!     Choice { text: String::from("gets the dog wet, then it runs away again."), score: 0.9534, index: 1 },
+//! ]
+//! # ;
+//! ```
+//!
+//! #### 13. Relation extraction
+//! Extracts (head entity, relation, tail entity) triples between the entities recognized by a `NERModel`,
+//! classifying the input text with entity markers inserted around each candidate pair of entities.
+//! ```ignore
+//! # use rust_bert::pipelines::relation_extraction::RelationExtractionModel;
+//! # fn main() -> anyhow::Result<()> {
+//! #   let config = unimplemented!();
+//!     let relation_extraction_model = RelationExtractionModel::new(config)?;
+//!
+//!     let input = ["Amy lives in Paris, the capital of France."];
+//!     let output = relation_extraction_model.predict(&input);
+//! #   Ok(())
+//! # }
+//! ```
+//! Output (head entity, relation, tail entity):
+//! ```ignore
+//! [[
+//!     ("Amy", "lives_in", "Paris", 0.9412),
+//!     ("Paris", "capital_of", "France", 0.9720),
+//! ]]
+//! ```
+//!
+//! #### 14. Entity linking
+//! Links the entities recognized by a `NERModel` to the entries of a local knowledge base (identifier, name, aliases and description),
+//! matching the mentions and their context to the knowledge base entries using a `SentenceEmbeddingsModel`.
+//! ```ignore
+//! # use rust_bert::pipelines::entity_linking::EntityLinkingModel;
+//! # fn main() -> anyhow::Result<()> {
+//! #   let knowledge_base = unimplemented!();
+//!     let entity_linking_model = EntityLinkingModel::new(Default::default(), knowledge_base)?;
+//!
+//!     let input = ["Amy visited the Eiffel Tower in Paris, France."];
+//!     let output = entity_linking_model.predict(&input)?;
+//! #   Ok(())
+//! # }
+//! ```
+//! Output (entity, knowledge base identifier, similarity):
+//! ```ignore
+//! [[
+//!     ("Amy", None, 0.1247),
+//!     ("Paris", Some("Q90"), 0.7128),
+//! ]]
+//! ```
+//!
+//! #### 15. Reranking
+//! Sorts candidate passages by relevance to a query, scoring each (query, passage) pair jointly with a cross-encoder.
+//! ```ignore
+//! # use rust_bert::pipelines::reranker::RerankerModel;
+//! # fn main() -> anyhow::Result<()> {
+//! #   let config = unimplemented!();
+//!     let reranker_model = RerankerModel::new(config)?;
+//!
+//!     let query = "How many people live in Berlin?";
+//!     let passages = [
+//!         "New York City is famous for the Metropolitan Museum of Art.",
+//!         "Berlin has a population of 3,520,031 registered inhabitants.",
+//!     ];
+//!     let output = reranker_model.rerank(query, &passages, 32)?;
+//! #   Ok(())
+//! # }
+//! ```
+//! Output (passage index, raw score, probability):
+//! ```ignore
+//! [
+//!     (1, 8.8459, 0.9999),
+//!     (0, -11.2453, 0.0000),
+//! ]
+//! ```
+//!
+//! #### 16. Topic modeling
+//! Groups the documents of a corpus into topics by clustering their sentence embeddings, and describes each topic with keywords.
+//! ```ignore
+//! # use rust_bert::pipelines::topic_modeling::TopicModelingModel;
+//! # fn main() -> anyhow::Result<()> {
+//!     let topic_modeling_model = TopicModelingModel::new(Default::default())?;
+//! #   let input: Vec<String> = unimplemented!();
+//!     let output = topic_modeling_model.predict(&input)?;
+//! #   Ok(())
+//! # }
+//! ```
+//! Output (topic of each document, keywords of each topic):
+//! ```ignore
+//! [Some(0), None, Some(1), Some(0), ...]
+//! [
+//!     (0, ["football", "championship", "team", "match", "league"]),
+//!     (1, ["inflation", "bank", "rates", "interest", "economy"]),
+//! ]
+//! ```
+//!
+//! #### 17. Semantic deduplication
+//! Groups near-duplicate texts of a stream of documents, comparing their sentence embeddings within locality-sensitive hashing buckets.
+//! ```ignore
+//! # use rust_bert::pipelines::deduplication::SemanticDeduplicationModel;
+//! # fn main() -> anyhow::Result<()> {
+//!     let mut deduplication_model = SemanticDeduplicationModel::new(Default::default())?;
+//!
+//!     let input = [
+//!         "How do I reset my password?",
+//!         "The delivery arrived two days late.",
+//!         "How can I reset my password?",
+//!     ];
+//!     let duplicate_of = deduplication_model.add(&input)?;
+//!     let clusters = deduplication_model.clusters();
+//! #   Ok(())
+//! # }
+//! ```
+//! Output (representative of each text duplicating an earlier text, duplicate clusters):
+//! ```ignore
+//! [None, None, Some(0)]
+//! [(0, [0, 2])]
+//! ```
 
 pub mod common;
 pub mod conversation;
+pub mod deduplication;
+pub mod entity_linking;
 pub mod generation_utils;
 pub mod keywords_extraction;
 pub mod masked_language;
+pub mod multiple_choice;
 pub mod ner;
 pub mod pos_tagging;
 pub mod question_answering;
+pub mod relation_extraction;
+pub mod reranker;
 pub mod sentence_embeddings;
 pub mod sentiment;
 pub mod sequence_classification;
 pub mod summarization;
 pub mod text_generation;
 pub mod token_classification;
+pub mod topic_modeling;
 pub mod translation;
 pub mod zero_shot_classification;
 
@@ -0,0 +1,753 @@
+// Copyright 2018 The Google AI Language Team Authors and The HuggingFace Inc. team.
+// Copyright 2018-2023 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Multiple choice pipeline
+//! Scores a set of candidate answers given a context (SWAG/RACE-style questions).
+//! Each context is paired with every candidate choice, the pairs are encoded jointly and
+//! a model with a multiple choice head returns a score for each choice.
+//! All models with a `*ForMultipleChoice` head are supported (BERT, RoBERTa, XLM-RoBERTa, ALBERT,
+//! XLNet, Longformer, FNet and MobileBERT). For RACE-style inputs, the question is usually prepended
+//! to each of the choices.
+//!
+//! ```no_run
+//! use rust_bert::pipelines::common::{ModelResource, ModelType};
+//! use rust_bert::pipelines::multiple_choice::{MultipleChoiceConfig, MultipleChoiceModel};
+//! use rust_bert::resources::LocalResource;
+//! use std::path::PathBuf;
+//! # fn main() -> anyhow::Result<()> {
+//! let config = MultipleChoiceConfig::new(
+//!     ModelType::Bert,
+//!     ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from(
+//!         "path/to/rust_model.ot",
+//!     )))),
+//!     LocalResource::from(PathBuf::from("path/to/config.json")),
+//!     LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+//!     None,
+//!     true,
+//!     None,
+//!     None,
+//! );
+//! let multiple_choice_model = MultipleChoiceModel::new(config)?;
+//!
+//! let context = "A woman is outside with a bucket and a dog. The dog is running around trying to avoid a bath. She";
+//! let choices = [
+//!     "rinses the bucket off with soap and blow dry the dog's head.",
+//!     "uses a hose to keep it from getting soapy.",
+//!     "gets the dog wet, then it runs away again.",
+//!     "gets into a bath tub with the dog.",
+//! ];
+//! let output = multiple_choice_model.predict(context, &choices)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Output: \
+//! ```no_run
+//! # use rust_bert::pipelines::multiple_choice::Choice;
+//! # let output =
+//! [
+//!     Choice { text: String::from("rinses the bucket off with soap and blow dry the dog's head."), score: 0.0121, index: 0 },
+//!     Choice { text: String::from("uses a hose to keep it from getting soapy."), score: 0.0454, index: 1 },
+//!     Choice { text: String::from("gets the dog wet, then it runs away again."), score: 0.9301, index: 2 },
+//!     Choice { text: String::from("gets into a bath tub with the dog."), score: 0.0124, index: 3 },
+//! ]
+//! # ;
+//! ```
+use crate::albert::AlbertForMultipleChoice;
+use crate::bert::BertForMultipleChoice;
+use crate::common::error::RustBertError;
+use crate::fnet::FNetForMultipleChoice;
+use crate::longformer::LongformerForMultipleChoice;
+use crate::mobilebert::MobileBertForMultipleChoice;
+use crate::pipelines::common::{
+    cast_var_store, get_device, set_var_store_device, ConfigOption, ModelResource, ModelType,
+    TokenizerOption,
+};
+use crate::resources::ResourceProvider;
+use crate::roberta::RobertaForMultipleChoice;
+use crate::xlnet::XLNetForMultipleChoice;
+use rust_tokenizers::tokenizer::TruncationStrategy;
+use rust_tokenizers::TokenizedInput;
+use serde::{Deserialize, Serialize};
+use std::cmp::min;
+use tch::nn::VarStore;
+use tch::{no_grad, Device, Kind, Tensor};
+
+#[derive(Debug, Clone)]
+/// # Input for multiple choice
+/// Context and candidate choices to score
+pub struct MultipleChoiceInput {
+    /// Context (e.g. passage, or passage and question) shared by all choices
+    pub context: String,
+    /// Candidate choices to score against the context
+    pub choices: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+/// # Scored choice generated by a `MultipleChoiceModel`
+pub struct Choice {
+    /// Choice String representation
+    pub text: String,
+    /// Probability of the choice being the correct answer (normalized across the choices of a given context)
+    pub score: f64,
+    /// Position of the choice in the list of candidates provided
+    pub index: usize,
+}
+
+/// # Configuration for MultipleChoiceModel
+/// Contains information regarding the model to load and device to place the model on.
+pub struct MultipleChoiceConfig {
+    /// Model type
+    pub model_type: ModelType,
+    /// Model weights resource
+    pub model_resource: ModelResource,
+    /// Config resource
+    pub config_resource: Box<dyn ResourceProvider + Send>,
+    /// Vocab resource
+    pub vocab_resource: Box<dyn ResourceProvider + Send>,
+    /// Merges resource (default: None)
+    pub merges_resource: Option<Box<dyn ResourceProvider + Send>>,
+    /// Automatically lower case all input upon tokenization (assumes a lower-cased model)
+    pub lower_case: bool,
+    /// Flag indicating if the tokenizer should strip accents (normalization). Only used for BERT / ALBERT models
+    pub strip_accents: Option<bool>,
+    /// Flag indicating if the tokenizer should add a white space before each tokenized input (needed for some Roberta models)
+    pub add_prefix_space: Option<bool>,
+    /// Device to place the model on (default: CUDA/GPU when available)
+    pub device: Device,
+}
+
+impl MultipleChoiceConfig {
+    /// Instantiate a new multiple choice configuration of the supplied type.
+    ///
+    /// # Arguments
+    ///
+    /// * `model_type` - `ModelType` indicating the model type to load (must match with the actual data to be loaded!)
+    /// * model - The `ResourceProvider` pointing to the model to load (e.g.  model.ot)
+    /// * config - The `ResourceProvider` pointing to the model configuration to load (e.g. config.json)
+    /// * vocab - The `ResourceProvider` pointing to the tokenizer's vocabulary to load (e.g.  vocab.txt/vocab.json)
+    /// * merges - An optional `ResourceProvider` pointing to the tokenizer's merge file to load (e.g.  merges.txt), needed only for Roberta.
+    /// * lower_case - A `bool` indicating whether the tokenizer should lower case all input (in case of a lower-cased model)
+    pub fn new<RC, RV>(
+        model_type: ModelType,
+        model_resource: ModelResource,
+        config_resource: RC,
+        vocab_resource: RV,
+        merges_resource: Option<RV>,
+        lower_case: bool,
+        strip_accents: impl Into<Option<bool>>,
+        add_prefix_space: impl Into<Option<bool>>,
+    ) -> MultipleChoiceConfig
+    where
+        RC: ResourceProvider + Send + 'static,
+        RV: ResourceProvider + Send + 'static,
+    {
+        MultipleChoiceConfig {
+            model_type,
+            model_resource,
+            config_resource: Box::new(config_resource),
+            vocab_resource: Box::new(vocab_resource),
+            merges_resource: merges_resource.map(|r| Box::new(r) as Box<_>),
+            lower_case,
+            strip_accents: strip_accents.into(),
+            add_prefix_space: add_prefix_space.into(),
+            device: Device::cuda_if_available(),
+        }
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+/// # Abstraction that holds one particular multiple choice model, for any of the supported models
+pub enum MultipleChoiceOption {
+    /// Bert for Multiple Choice
+    Bert(BertForMultipleChoice),
+    /// Roberta for Multiple Choice
+    Roberta(RobertaForMultipleChoice),
+    /// XLMRoberta for Multiple Choice
+    XLMRoberta(RobertaForMultipleChoice),
+    /// Albert for Multiple Choice
+    Albert(AlbertForMultipleChoice),
+    /// XLNet for Multiple Choice
+    XLNet(XLNetForMultipleChoice),
+    /// Longformer for Multiple Choice
+    Longformer(LongformerForMultipleChoice),
+    /// FNet for Multiple Choice
+    FNet(FNetForMultipleChoice),
+    /// MobileBert for Multiple Choice
+    MobileBert(MobileBertForMultipleChoice),
+}
+
+impl MultipleChoiceOption {
+    /// Instantiate a new multiple choice model of the supplied type.
+    ///
+    /// # Arguments
+    ///
+    /// * `MultipleChoiceConfig` - Multiple choice pipeline configuration. The type of model created will be inferred from the
+    ///     `ModelType` provided. Only Torch-based models are supported.
+    pub fn new(config: &MultipleChoiceConfig) -> Result<Self, RustBertError> {
+        Ok(Self::new_with_var_store(config)?.0)
+    }
+
+    /// Instantiate a new multiple choice model, also returning the `VarStore` holding its weights
+    pub(crate) fn new_with_var_store(
+        config: &MultipleChoiceConfig,
+    ) -> Result<(Self, VarStore), RustBertError> {
+        match config.model_resource {
+            ModelResource::Torch(_) => Self::new_torch(config),
+            #[cfg(feature = "onnx")]
+            ModelResource::ONNX(_) => Err(RustBertError::InvalidConfigurationError(
+                "Multiple choice is not supported for ONNX models".to_string(),
+            )),
+        }
+    }
+
+    fn new_torch(config: &MultipleChoiceConfig) -> Result<(Self, VarStore), RustBertError> {
+        let device = config.device;
+        let mut var_store = VarStore::new(device);
+        let model_config =
+            &ConfigOption::from_file(config.model_type, config.config_resource.get_local_path()?);
+        let model_type = config.model_type;
+        let model = match model_type {
+            ModelType::Bert => {
+                if let ConfigOption::Bert(config) = model_config {
+                    Ok(Self::Bert(BertForMultipleChoice::new(
+                        var_store.root(),
+                        config,
+                    )))
+                } else {
+                    Err(RustBertError::InvalidConfigurationError(
+                        "You can only supply a BertConfig for Bert!".to_string(),
+                    ))
+                }
+            }
+            ModelType::Roberta => {
+                if let ConfigOption::Roberta(config) = model_config {
+                    Ok(Self::Roberta(RobertaForMultipleChoice::new(
+                        var_store.root(),
+                        config,
+                    )))
+                } else {
+                    Err(RustBertError::InvalidConfigurationError(
+                        "You can only supply a RobertaConfig for Roberta!".to_string(),
+                    ))
+                }
+            }
+            ModelType::XLMRoberta => {
+                if let ConfigOption::Roberta(config) = model_config {
+                    Ok(Self::XLMRoberta(RobertaForMultipleChoice::new(
+                        var_store.root(),
+                        config,
+                    )))
+                } else {
+                    Err(RustBertError::InvalidConfigurationError(
+                        "You can only supply a RobertaConfig for Roberta!".to_string(),
+                    ))
+                }
+            }
+            ModelType::Albert => {
+                if let ConfigOption::Albert(config) = model_config {
+                    Ok(Self::Albert(AlbertForMultipleChoice::new(
+                        var_store.root(),
+                        config,
+                    )))
+                } else {
+                    Err(RustBertError::InvalidConfigurationError(
+                        "You can only supply an AlbertConfig for Albert!".to_string(),
+                    ))
+                }
+            }
+            ModelType::XLNet => {
+                if let ConfigOption::XLNet(config) = model_config {
+                    Ok(Self::XLNet(XLNetForMultipleChoice::new(
+                        var_store.root(),
+                        config,
+                    )?))
+                } else {
+                    Err(RustBertError::InvalidConfigurationError(
+                        "You can only supply an XLNetConfig for XLNet!".to_string(),
+                    ))
+                }
+            }
+            ModelType::Longformer => {
+                if let ConfigOption::Longformer(config) = model_config {
+                    Ok(Self::Longformer(LongformerForMultipleChoice::new(
+                        var_store.root(),
+                        config,
+                    )))
+                } else {
+                    Err(RustBertError::InvalidConfigurationError(
+                        "You can only supply a LongformerConfig for Longformer!".to_string(),
+                    ))
+                }
+            }
+            ModelType::FNet => {
+                if let ConfigOption::FNet(config) = model_config {
+                    Ok(Self::FNet(FNetForMultipleChoice::new(
+                        var_store.root(),
+                        config,
+                    )))
+                } else {
+                    Err(RustBertError::InvalidConfigurationError(
+                        "You can only supply a FNetConfig for FNet!".to_string(),
+                    ))
+                }
+            }
+            ModelType::MobileBert => {
+                if let ConfigOption::MobileBert(config) = model_config {
+                    Ok(Self::MobileBert(MobileBertForMultipleChoice::new(
+                        var_store.root(),
+                        config,
+                    )))
+                } else {
+                    Err(RustBertError::InvalidConfigurationError(
+                        "You can only supply a MobileBertConfig for MobileBert!".to_string(),
+                    ))
+                }
+            }
+            _ => Err(RustBertError::InvalidConfigurationError(format!(
+                "Multiple choice not implemented for {model_type:?}!",
+            ))),
+        }?;
+        crate::resources::load_weights(&config.model_resource, &mut var_store)?;
+        Ok((model, var_store))
+    }
+
+    /// Returns the `ModelType` for this MultipleChoiceOption
+    pub fn model_type(&self) -> ModelType {
+        match *self {
+            Self::Bert(_) => ModelType::Bert,
+            Self::Roberta(_) => ModelType::Roberta,
+            Self::XLMRoberta(_) => ModelType::XLMRoberta,
+            Self::Albert(_) => ModelType::Albert,
+            Self::XLNet(_) => ModelType::XLNet,
+            Self::Longformer(_) => ModelType::Longformer,
+            Self::FNet(_) => ModelType::FNet,
+            Self::MobileBert(_) => ModelType::MobileBert,
+        }
+    }
+
+    /// Interface method to forward_t() of the particular models.
+    ///
+    /// Inputs are expected with shape (*batch size*, *num_choices*, *sequence_length*) and the
+    /// returned logits have shape (*batch size*, *num_choices*).
+    pub fn forward_t(
+        &self,
+        input_ids: &Tensor,
+        mask: Option<&Tensor>,
+        token_type_ids: Option<&Tensor>,
+        train: bool,
+    ) -> Result<Tensor, RustBertError> {
+        Ok(match *self {
+            Self::Bert(ref model) => {
+                model
+                    .forward_t(input_ids, mask, token_type_ids, None, train)
+                    .logits
+            }
+            Self::Roberta(ref model) | Self::XLMRoberta(ref model) => {
+                model
+                    .forward_t(input_ids, mask, token_type_ids, None, train)
+                    .logits
+            }
+            Self::Albert(ref model) => {
+                model
+                    .forward_t(Some(input_ids), mask, token_type_ids, None, None, train)?
+                    .logits
+            }
+            Self::XLNet(ref model) => {
+                model
+                    .forward_t(
+                        Some(input_ids),
+                        mask,
+                        None,
+                        None,
+                        None,
+                        token_type_ids,
+                        None,
+                        train,
+                    )
+                    .logits
+            }
+            Self::Longformer(ref model) => {
+                model
+                    .forward_t(
+                        Some(input_ids),
+                        mask,
+                        None,
+                        token_type_ids,
+                        None,
+                        None,
+                        train,
+                    )?
+                    .logits
+            }
+            Self::FNet(ref model) => {
+                model
+                    .forward_t(Some(input_ids), token_type_ids, None, None, train)?
+                    .logits
+            }
+            Self::MobileBert(ref model) => {
+                model
+                    .forward_t(Some(input_ids), token_type_ids, None, None, mask, train)?
+                    .logits
+            }
+        })
+    }
+}
+
+/// # MultipleChoiceModel to score candidate answers given a context
+pub struct MultipleChoiceModel {
+    tokenizer: TokenizerOption,
+    multiple_choice_model: MultipleChoiceOption,
+    var_store: VarStore,
+    device: Device,
+    max_length: usize,
+}
+
+impl MultipleChoiceModel {
+    /// Build a new `MultipleChoiceModel`
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - `MultipleChoiceConfig` object containing the resource references (model, vocabulary, configuration) and device placement (CPU/GPU)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::common::{ModelResource, ModelType};
+    /// use rust_bert::pipelines::multiple_choice::{MultipleChoiceConfig, MultipleChoiceModel};
+    /// use rust_bert::resources::LocalResource;
+    /// use std::path::PathBuf;
+    ///
+    /// let config = MultipleChoiceConfig::new(
+    ///     ModelType::Bert,
+    ///     ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from(
+    ///         "path/to/rust_model.ot",
+    ///     )))),
+    ///     LocalResource::from(PathBuf::from("path/to/config.json")),
+    ///     LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+    ///     None,
+    ///     true,
+    ///     None,
+    ///     None,
+    /// );
+    /// let model = MultipleChoiceModel::new(config)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(config: MultipleChoiceConfig) -> Result<MultipleChoiceModel, RustBertError> {
+        let vocab_path = config.vocab_resource.get_local_path()?;
+        let merges_path = config
+            .merges_resource
+            .as_ref()
+            .map(|resource| resource.get_local_path())
+            .transpose()?;
+
+        let tokenizer = TokenizerOption::from_file(
+            config.model_type,
+            vocab_path.to_str().unwrap(),
+            merges_path.as_deref().map(|path| path.to_str().unwrap()),
+            config.lower_case,
+            config.strip_accents,
+            config.add_prefix_space,
+        )?;
+        Self::new_with_tokenizer(config, tokenizer)
+    }
+
+    /// Build a new `MultipleChoiceModel` with a provided tokenizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - `MultipleChoiceConfig` object containing the resource references (model, vocabulary, configuration) and device placement (CPU/GPU)
+    /// * `tokenizer` - `TokenizerOption` tokenizer to use for multiple choice.
+    pub fn new_with_tokenizer(
+        config: MultipleChoiceConfig,
+        tokenizer: TokenizerOption,
+    ) -> Result<MultipleChoiceModel, RustBertError> {
+        let config_path = config.config_resource.get_local_path()?;
+        let (multiple_choice_model, var_store) = MultipleChoiceOption::new_with_var_store(&config)?;
+
+        let model_config = ConfigOption::from_file(config.model_type, config_path);
+        let max_length = model_config
+            .get_max_len()
+            .map(|v| v as usize)
+            .unwrap_or(usize::MAX);
+        let device = get_device(config.model_resource, config.device);
+        Ok(MultipleChoiceModel {
+            tokenizer,
+            multiple_choice_model,
+            var_store,
+            device,
+            max_length,
+        })
+    }
+
+    /// Get a reference to the model tokenizer.
+    pub fn get_tokenizer(&self) -> &TokenizerOption {
+        &self.tokenizer
+    }
+
+    /// Get a mutable reference to the model tokenizer.
+    pub fn get_tokenizer_mut(&mut self) -> &mut TokenizerOption {
+        &mut self.tokenizer
+    }
+
+    /// Casts the model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(Some(&mut self.var_store), Kind::Half)
+    }
+
+    /// Casts the model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(Some(&mut self.var_store), Kind::BFloat16)
+    }
+
+    /// Casts the model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(Some(&mut self.var_store), Kind::Float)
+    }
+
+    /// Moves the model weights to the target device, inputs are placed on this device for subsequent predictions.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        set_var_store_device(Some(&mut self.var_store), device)?;
+        self.device = device;
+        Ok(())
+    }
+
+    fn prepare_for_model(
+        &self,
+        inputs: &[MultipleChoiceInput],
+    ) -> Result<(Tensor, Tensor, Tensor), RustBertError> {
+        let text_pair_list = inputs
+            .iter()
+            .flat_map(|input| {
+                input
+                    .choices
+                    .iter()
+                    .map(move |choice| (input.context.as_str(), choice.as_str()))
+            })
+            .collect::<Vec<(&str, &str)>>();
+
+        let tokenized_input: Vec<TokenizedInput> = self.tokenizer.encode_pair_list(
+            text_pair_list.as_ref(),
+            self.max_length,
+            &TruncationStrategy::OnlyFirst,
+            0,
+        );
+        if tokenized_input.is_empty() {
+            return Err(RustBertError::ValueError(
+                "Got empty iterator as input".to_string(),
+            ));
+        }
+        let pad_id = self
+            .tokenizer
+            .get_pad_id()
+            .expect("The Tokenizer used for multiple choice should contain a PAD id");
+        // XLNet computes the choice summary from the last token: sequences are padded on the left
+        let left_padding = self.multiple_choice_model.model_type() == ModelType::XLNet;
+        let (input_ids, token_type_ids) =
+            self.tokenizer
+                .pad_tokenized_input(tokenized_input, left_padding, self.device);
+
+        // All (context, choice) pairs of the batch are scored independently by the classification head:
+        // they are flattened along the choices dimension and split per context after the forward pass.
+        let input_ids = input_ids.unsqueeze(0);
+        let token_type_ids = token_type_ids.unsqueeze(0);
+        let mask = input_ids.ne(pad_id).to_kind(Kind::Int64);
+
+        Ok((input_ids, mask, token_type_ids))
+    }
+
+    /// Score the candidate choices for a single context.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - `&str` context shared by all choices (for RACE-style inputs, the passage).
+    /// * `choices` - `&[&str]` candidate choices. For RACE-style inputs, these usually include the question.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Choice>, RustBertError>` containing a scored `Choice` for each candidate, in the order provided.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::multiple_choice::{MultipleChoiceConfig, MultipleChoiceModel};
+    /// # use rust_bert::pipelines::common::{ModelResource, ModelType};
+    /// # use rust_bert::resources::LocalResource;
+    /// # use std::path::PathBuf;
+    /// # let config = MultipleChoiceConfig::new(
+    /// #     ModelType::Bert,
+    /// #     ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from("path/to/rust_model.ot")))),
+    /// #     LocalResource::from(PathBuf::from("path/to/config.json")),
+    /// #     LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+    /// #     None,
+    /// #     true,
+    /// #     None,
+    /// #     None,
+    /// # );
+    /// let multiple_choice_model = MultipleChoiceModel::new(config)?;
+    /// let context = "The dog is running around trying to avoid a bath. She";
+    /// let choices = [
+    ///     "uses a hose to keep it from getting soapy.",
+    ///     "gets the dog wet, then it runs away again.",
+    /// ];
+    /// let output = multiple_choice_model.predict(context, &choices)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict<'a, S>(&self, context: &str, choices: S) -> Result<Vec<Choice>, RustBertError>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        let input = MultipleChoiceInput {
+            context: context.to_string(),
+            choices: choices.as_ref().iter().map(|s| s.to_string()).collect(),
+        };
+        Ok(self.predict_batch(&[input], 1)?.pop().unwrap_or_default())
+    }
+
+    /// Score the candidate choices for a batch of contexts. The contexts may have a different number of choices.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - `&[MultipleChoiceInput]` contexts and their candidate choices.
+    /// * `batch_size` - maximum number of contexts scored in a single forward pass.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Vec<Choice>>, RustBertError>` containing the scored choices for each input, in the order provided.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::multiple_choice::{MultipleChoiceConfig, MultipleChoiceInput, MultipleChoiceModel};
+    /// # use rust_bert::pipelines::common::{ModelResource, ModelType};
+    /// # use rust_bert::resources::LocalResource;
+    /// # use std::path::PathBuf;
+    /// # let config = MultipleChoiceConfig::new(
+    /// #     ModelType::Bert,
+    /// #     ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from("path/to/rust_model.ot")))),
+    /// #     LocalResource::from(PathBuf::from("path/to/config.json")),
+    /// #     LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+    /// #     None,
+    /// #     true,
+    /// #     None,
+    /// #     None,
+    /// # );
+    /// let multiple_choice_model = MultipleChoiceModel::new(config)?;
+    /// let inputs = [
+    ///     MultipleChoiceInput {
+    ///         context: "The capital of France is".to_string(),
+    ///         choices: vec!["Paris.".to_string(), "Berlin.".to_string()],
+    ///     },
+    ///     MultipleChoiceInput {
+    ///         context: "Water boils at".to_string(),
+    ///         choices: vec![
+    ///             "100 degrees Celsius.".to_string(),
+    ///             "10 degrees Celsius.".to_string(),
+    ///             "0 degrees Celsius.".to_string(),
+    ///         ],
+    ///     },
+    /// ];
+    /// let output = multiple_choice_model.predict_batch(&inputs, 8)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_batch(
+        &self,
+        inputs: &[MultipleChoiceInput],
+        batch_size: usize,
+    ) -> Result<Vec<Vec<Choice>>, RustBertError> {
+        let mut output: Vec<Vec<Choice>> = Vec::with_capacity(inputs.len());
+        let batch_size = batch_size.max(1);
+        let mut start = 0usize;
+
+        while start < inputs.len() {
+            let end = start + min(inputs.len() - start, batch_size);
+            let batch_inputs = &inputs[start..end];
+            start = end;
+
+            let num_choices = batch_inputs
+                .iter()
+                .map(|input| input.choices.len() as i64)
+                .collect::<Vec<i64>>();
+            if num_choices.iter().sum::<i64>() == 0 {
+                output.extend(batch_inputs.iter().map(|_| vec![]));
+                continue;
+            }
+
+            let (input_ids, mask, token_type_ids) = self.prepare_for_model(batch_inputs)?;
+            let logits = no_grad(|| {
+                self.multiple_choice_model.forward_t(
+                    &input_ids,
+                    Some(&mask),
+                    Some(&token_type_ids),
+                    false,
+                )
+            })?
+            .view(-1)
+            .to(Device::Cpu);
+
+            for (input, choice_logits) in batch_inputs
+                .iter()
+                .zip(logits.split_with_sizes(num_choices.as_slice(), 0))
+            {
+                let scores = choice_logits
+                    .softmax(-1, Kind::Float)
+                    .iter::<f64>()?
+                    .collect::<Vec<f64>>();
+                output.push(
+                    input
+                        .choices
+                        .iter()
+                        .zip(scores)
+                        .enumerate()
+                        .map(|(index, (text, score))| Choice {
+                            text: text.clone(),
+                            score,
+                            index,
+                        })
+                        .collect(),
+                );
+            }
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::resources::LocalResource;
+    use std::path::PathBuf;
+
+    #[test]
+    #[ignore] // no need to run, compilation is enough to verify it is Send
+    fn test() {
+        let config = MultipleChoiceConfig::new(
+            ModelType::Bert,
+            ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from(
+                "path/to/rust_model.ot",
+            )))),
+            LocalResource::from(PathBuf::from("path/to/config.json")),
+            LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+            None,
+            true,
+            None,
+            None,
+        );
+        let _: Box<dyn Send> = Box::new(MultipleChoiceModel::new(config));
+    }
+}
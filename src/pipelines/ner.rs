@@ -130,10 +130,12 @@
 use crate::common::error::RustBertError;
 use crate::pipelines::common::TokenizerOption;
 use crate::pipelines::token_classification::{
-    Token, TokenClassificationConfig, TokenClassificationModel,
+    parse_label, ScoreAggregationOption, Tag, Token, TokenClassificationConfig,
+    TokenClassificationModel,
 };
 use rust_tokenizers::Offset;
 use serde::{Deserialize, Serialize};
+use tch::Device;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// # Entity generated by a `NERModel`
@@ -154,6 +156,7 @@ type NERConfig = TokenClassificationConfig;
 /// # NERModel to extract named entities
 pub struct NERModel {
     token_classification_model: TokenClassificationModel,
+    entity_score_aggregation: ScoreAggregationOption,
 }
 
 impl NERModel {
@@ -174,9 +177,11 @@ impl NERModel {
     /// # }
     /// ```
     pub fn new(ner_config: NERConfig) -> Result<NERModel, RustBertError> {
+        let entity_score_aggregation = ner_config.entity_score_aggregation;
         let model = TokenClassificationModel::new(ner_config)?;
         Ok(NERModel {
             token_classification_model: model,
+            entity_score_aggregation,
         })
     }
 
@@ -209,9 +214,11 @@ impl NERModel {
         ner_config: NERConfig,
         tokenizer: TokenizerOption,
     ) -> Result<NERModel, RustBertError> {
+        let entity_score_aggregation = ner_config.entity_score_aggregation;
         let model = TokenClassificationModel::new_with_tokenizer(ner_config, tokenizer)?;
         Ok(NERModel {
             token_classification_model: model,
+            entity_score_aggregation,
         })
     }
 
@@ -225,6 +232,26 @@ impl NERModel {
         self.token_classification_model.get_tokenizer_mut()
     }
 
+    /// Casts the model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        self.token_classification_model.half()
+    }
+
+    /// Casts the model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        self.token_classification_model.bfloat16()
+    }
+
+    /// Casts the model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        self.token_classification_model.float()
+    }
+
+    /// Moves the model weights to the target device.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        self.token_classification_model.set_device(device)
+    }
+
     /// Extract entities from a text
     ///
     /// # Arguments
@@ -275,6 +302,8 @@ impl NERModel {
     /// Extract full entities from a text performing entity chunking. Follows the algorithm for entities
     /// chunking described in [Erik F. Tjong Kim Sang, Jorn Veenstra, Representing Text Chunks](https://www.aclweb.org/anthology/E99-1023/)
     /// The proposed implementation is inspired by the [Python seqeval library](https://github.com/chakki-works/seqeval) (shared under MIT license).
+    /// Labels following the BIO, BIOES and BILOU tagging schemes are supported. Illegal label sequences can be avoided by enabling
+    /// `constrained_decoding` in the model configuration, and the entity score is aggregated following the configuration `entity_score_aggregation`.
     ///
     /// # Arguments
     ///
@@ -329,15 +358,15 @@ impl NERModel {
         let mut entities: Vec<Vec<Entity>> = Vec::new();
 
         for sequence_tokens in tokens {
-            entities.push(Self::consolidate_entities(&sequence_tokens));
+            entities.push(self.consolidate_entities(&sequence_tokens));
         }
         entities
     }
 
-    fn consolidate_entities(tokens: &[Token]) -> Vec<Entity> {
+    fn consolidate_entities(&self, tokens: &[Token]) -> Vec<Entity> {
         let mut entities: Vec<Entity> = Vec::new();
 
-        let mut entity_builder = EntityBuilder::new();
+        let mut entity_builder = EntityBuilder::new(self.entity_score_aggregation);
         for (position, token) in tokens.iter().enumerate() {
             let tag = token.get_tag();
             let label = token.get_label();
@@ -354,12 +383,14 @@ impl NERModel {
 
 struct EntityBuilder<'a> {
     previous_node: Option<(usize, Tag, &'a str)>,
+    score_aggregation: ScoreAggregationOption,
 }
 
 impl<'a> EntityBuilder<'a> {
-    fn new() -> Self {
+    fn new(score_aggregation: ScoreAggregationOption) -> Self {
         EntityBuilder {
             previous_node: None,
+            score_aggregation,
         }
     }
 
@@ -406,7 +437,9 @@ impl<'a> EntityBuilder<'a> {
                     .map(|token| token.text.as_str())
                     .collect::<Vec<&str>>()
                     .join(" "),
-                score: entity_tokens.iter().map(|token| token.score).product(),
+                score: self
+                    .score_aggregation
+                    .aggregate(entity_tokens.iter().map(|token| token.score)),
                 label: label.to_string(),
                 offset: Offset {
                     begin: entity_tokens.first()?.offset?.begin,
@@ -425,34 +458,16 @@ impl<'a> EntityBuilder<'a> {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum Tag {
-    Begin,
-    Inside,
-    Outside,
-    End,
-    Single,
-}
-
 impl Token {
     fn get_tag(&self) -> Tag {
-        match self.label.split('-').collect::<Vec<&str>>()[0] {
-            "B" => Tag::Begin,
-            "I" => Tag::Inside,
-            "O" => Tag::Outside,
-            "E" => Tag::End,
-            "S" => Tag::Single,
-            _ => panic!("Invalid tag encountered for token {:?}", self),
+        match parse_label(&self.label) {
+            Some((tag, _)) => tag,
+            None => panic!("Invalid tag encountered for token {:?}", self),
         }
     }
 
     fn get_label(&self) -> &str {
-        let split_label = self.label.split('-').collect::<Vec<&str>>();
-        if split_label.len() > 1 {
-            split_label[1]
-        } else {
-            ""
-        }
+        parse_label(&self.label).map_or("", |(_, label)| label)
     }
 }
 
@@ -1,53 +1,187 @@
+//! Conversion between ONNX Runtime and Torch tensors.
+//!
+//! The ONNX Runtime bindings take ownership of the `ndarray` buffers passed as inputs: Torch tensors are copied
+//! exactly once into the buffer handed over to ONNX Runtime. Outputs are either copied once into Torch tensors
+//! (`ort_tensor_to_tch`), or read in place by Torch tensors created over the ONNX Runtime buffers while the session
+//! outputs are kept alive (`OrtOutputs`), which is used for the cached keys and values of decoders.
 use crate::RustBertError;
 use ndarray::IxDyn;
-use ort::tensor::{DynOrtTensor, FromArray, InputTensor};
-use std::convert::{TryFrom, TryInto};
-use tch::{Kind, Tensor};
+use ort::tensor::{DynOrtTensor, FromArray, InputTensor, TensorDataToType, TensorElementDataType};
+use ort::Session;
+use std::convert::TryInto;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use tch::kind::Element;
+use tch::{Device, Kind, Tensor};
 
+/// Copies an ONNX Runtime output into a Torch tensor, preserving its element type.
+/// Unsigned integer types not supported by Torch (`u16`, `u32`, `u64`) are widened to `Int64`.
 pub(crate) fn ort_tensor_to_tch(ort_tensor: &DynOrtTensor<IxDyn>) -> Result<Tensor, RustBertError> {
-    let ort_tensor = ort_tensor.try_extract::<f32>()?.view().to_owned();
-    Ok(Tensor::try_from(ort_tensor)?)
+    match ort_tensor.data_type() {
+        TensorElementDataType::Float32 => ort_data_to_tch::<f32>(ort_tensor),
+        TensorElementDataType::Float64 => ort_data_to_tch::<f64>(ort_tensor),
+        TensorElementDataType::Float16 => ort_data_to_tch::<half::f16>(ort_tensor),
+        TensorElementDataType::Bfloat16 => ort_data_to_tch::<half::bf16>(ort_tensor),
+        TensorElementDataType::Int64 => ort_data_to_tch::<i64>(ort_tensor),
+        TensorElementDataType::Int32 => ort_data_to_tch::<i32>(ort_tensor),
+        TensorElementDataType::Int16 => ort_data_to_tch::<i16>(ort_tensor),
+        TensorElementDataType::Int8 => ort_data_to_tch::<i8>(ort_tensor),
+        TensorElementDataType::Uint8 => ort_data_to_tch::<u8>(ort_tensor),
+        TensorElementDataType::Uint16 => widened_ort_data_to_tch::<u16>(ort_tensor),
+        TensorElementDataType::Uint32 => widened_ort_data_to_tch::<u32>(ort_tensor),
+        TensorElementDataType::Uint64 => widened_ort_data_to_tch::<u64>(ort_tensor),
+        data_type => Err(RustBertError::ValueError(format!(
+            "Type not supported: attempted to convert ONNX tensor of type {data_type:?} to a torch tensor",
+        ))),
+    }
 }
 
-pub(crate) fn tch_tensor_to_ort(tch_tensor: &Tensor) -> Result<InputTensor, RustBertError> {
-    let kind = tch_tensor.kind();
-    Ok(match kind{
-        Kind::Int64 => {
-            let array: ndarray::ArrayD<i64> = tch_tensor.try_into()?;
-            InputTensor::from_array(array)
-        }
-        Kind::Float => {
-            let array: ndarray::ArrayD<f32> = tch_tensor.try_into()?;
-            InputTensor::from_array(array)
-        }
-        Kind::Int => {
-            let array: ndarray::ArrayD<i32> = tch_tensor.try_into()?;
-            InputTensor::from_array(array)
-        }
-        Kind::Double => {
-            let array: ndarray::ArrayD<f64> = tch_tensor.try_into()?;
-            InputTensor::from_array(array)
-        }
-        Kind::Half => {
-            let array: ndarray::ArrayD<half::f16> = tch_tensor.try_into()?;
-            InputTensor::from_array(array)
-        }
-        Kind::Int16 => {
-            let array: ndarray::ArrayD<i16> = tch_tensor.try_into()?;
-            InputTensor::from_array(array)
-        }
-        Kind::Int8 => {
-            let array: ndarray::ArrayD<i8> = tch_tensor.try_into()?;
-            InputTensor::from_array(array)
+fn ort_data_to_tch<T>(ort_tensor: &DynOrtTensor<IxDyn>) -> Result<Tensor, RustBertError>
+where
+    T: TensorDataToType + Element + Clone + Debug,
+{
+    let ort_tensor = ort_tensor.try_extract::<T>()?;
+    let view = ort_tensor.view();
+    let shape = view
+        .shape()
+        .iter()
+        .map(|dim| *dim as i64)
+        .collect::<Vec<i64>>();
+    let tensor = match view.as_slice() {
+        Some(data) => Tensor::f_from_slice(data)?,
+        None => Tensor::f_from_slice(&view.iter().cloned().collect::<Vec<T>>())?,
+    };
+    Ok(tensor.f_view(shape.as_slice())?)
+}
+
+fn widened_ort_data_to_tch<T>(ort_tensor: &DynOrtTensor<IxDyn>) -> Result<Tensor, RustBertError>
+where
+    T: TensorDataToType + TryInto<i64> + Copy + Debug,
+{
+    let ort_tensor = ort_tensor.try_extract::<T>()?;
+    let view = ort_tensor.view();
+    let shape = view
+        .shape()
+        .iter()
+        .map(|dim| *dim as i64)
+        .collect::<Vec<i64>>();
+    let data = view
+        .iter()
+        .map(|value| {
+            (*value).try_into().map_err(|_| {
+                RustBertError::ValueError(format!(
+                    "Value {value:?} of the ONNX tensor exceeds the range of Torch Int64 tensors"
+                ))
+            })
+        })
+        .collect::<Result<Vec<i64>, RustBertError>>()?;
+    Ok(Tensor::f_from_slice(&data)?.f_view(shape.as_slice())?)
+}
+
+/// Outputs of an ONNX Runtime session run, kept alive while Torch tensors read their buffers in place.
+pub(crate) struct OrtOutputs {
+    // Declared before the session so that the outputs are released first
+    outputs: Vec<DynOrtTensor<'static, IxDyn>>,
+    _session: Arc<Session>,
+}
+
+// SAFETY: ONNX Runtime values are not bound to the thread that created them and are only read after creation.
+unsafe impl Send for OrtOutputs {}
+unsafe impl Sync for OrtOutputs {}
+
+impl Debug for OrtOutputs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OrtOutputs")
+            .field("num_outputs", &self.outputs.len())
+            .finish()
+    }
+}
+
+impl OrtOutputs {
+    pub(crate) fn new(session: &Arc<Session>, outputs: Vec<DynOrtTensor<'_, IxDyn>>) -> Self {
+        // SAFETY: the outputs borrow the memory information of the session, which is kept alive (and dropped
+        // after the outputs) by the reference held alongside them.
+        let outputs = unsafe {
+            std::mem::transmute::<Vec<DynOrtTensor<'_, IxDyn>>, Vec<DynOrtTensor<'static, IxDyn>>>(
+                outputs,
+            )
+        };
+        OrtOutputs {
+            outputs,
+            _session: session.clone(),
         }
-        Kind::Uint8 => {
-            let array: ndarray::ArrayD<u8> = tch_tensor.try_into()?;
-            InputTensor::from_array(array)
+    }
+
+    /// Creates a Torch tensor reading the buffer of the output at `index` without copy. Outputs of types not
+    /// supported by Torch are copied (see `ort_tensor_to_tch`).
+    ///
+    /// # Safety
+    /// The returned tensor, and any tensor sharing its storage, must not be used once these outputs are dropped.
+    pub(crate) unsafe fn borrow_tensor(&self, index: usize) -> Result<Tensor, RustBertError> {
+        let ort_tensor = self.outputs.get(index).ok_or_else(|| {
+            RustBertError::OrtError(format!(
+                "Output {index} not found ({} outputs)",
+                self.outputs.len()
+            ))
+        })?;
+        match ort_tensor.data_type() {
+            TensorElementDataType::Float32 => borrow_ort_data::<f32>(ort_tensor),
+            TensorElementDataType::Float64 => borrow_ort_data::<f64>(ort_tensor),
+            TensorElementDataType::Float16 => borrow_ort_data::<half::f16>(ort_tensor),
+            TensorElementDataType::Bfloat16 => borrow_ort_data::<half::bf16>(ort_tensor),
+            TensorElementDataType::Int64 => borrow_ort_data::<i64>(ort_tensor),
+            TensorElementDataType::Int32 => borrow_ort_data::<i32>(ort_tensor),
+            TensorElementDataType::Int16 => borrow_ort_data::<i16>(ort_tensor),
+            TensorElementDataType::Int8 => borrow_ort_data::<i8>(ort_tensor),
+            TensorElementDataType::Uint8 => borrow_ort_data::<u8>(ort_tensor),
+            _ => ort_tensor_to_tch(ort_tensor),
         }
-        Kind::BFloat16 => {
-            let array: ndarray::ArrayD<half::bf16> = tch_tensor.try_into()?;
-            InputTensor::from_array(array)
+    }
+}
+
+unsafe fn borrow_ort_data<T>(ort_tensor: &DynOrtTensor<IxDyn>) -> Result<Tensor, RustBertError>
+where
+    T: TensorDataToType + Element + Clone + Debug,
+{
+    let extracted = ort_tensor.try_extract::<T>()?;
+    let view = extracted.view();
+    match view.as_slice() {
+        // Empty outputs may not be backed by an allocated buffer
+        Some(data) if !data.is_empty() => {
+            let shape = view
+                .shape()
+                .iter()
+                .map(|dim| *dim as i64)
+                .collect::<Vec<i64>>();
+            let mut strides = vec![1i64; shape.len()];
+            for dim in (0..shape.len().saturating_sub(1)).rev() {
+                strides[dim] = strides[dim + 1] * shape[dim + 1];
+            }
+            Ok(Tensor::f_from_blob(
+                data.as_ptr() as *const u8,
+                shape.as_slice(),
+                strides.as_slice(),
+                T::KIND,
+                Device::Cpu,
+            )?)
         }
+        _ => ort_data_to_tch::<T>(ort_tensor),
+    }
+}
+
+/// Copies a Torch tensor (on any device, contiguous or not) into an ONNX Runtime input.
+pub(crate) fn tch_tensor_to_ort(tch_tensor: &Tensor) -> Result<InputTensor, RustBertError> {
+    let kind = tch_tensor.kind();
+    Ok(match kind {
+        Kind::Int64 => InputTensor::from_array(tch_data_to_array::<i64>(tch_tensor)?),
+        Kind::Float => InputTensor::from_array(tch_data_to_array::<f32>(tch_tensor)?),
+        Kind::Int => InputTensor::from_array(tch_data_to_array::<i32>(tch_tensor)?),
+        Kind::Double => InputTensor::from_array(tch_data_to_array::<f64>(tch_tensor)?),
+        Kind::Half => InputTensor::from_array(tch_data_to_array::<half::f16>(tch_tensor)?),
+        Kind::Int16 => InputTensor::from_array(tch_data_to_array::<i16>(tch_tensor)?),
+        Kind::Int8 => InputTensor::from_array(tch_data_to_array::<i8>(tch_tensor)?),
+        Kind::Uint8 => InputTensor::from_array(tch_data_to_array::<u8>(tch_tensor)?),
+        Kind::BFloat16 => InputTensor::from_array(tch_data_to_array::<half::bf16>(tch_tensor)?),
         _ => {
             return Err(RustBertError::ValueError(format!(
                 "Type not supported: attempted to get convert torch tensor to ndarray infinity for {kind:?}",
@@ -55,3 +189,170 @@ pub(crate) fn tch_tensor_to_ort(tch_tensor: &Tensor) -> Result<InputTensor, Rust
         }
     })
 }
+
+fn tch_data_to_array<T: Element>(tch_tensor: &Tensor) -> Result<ndarray::ArrayD<T>, RustBertError> {
+    let shape = tch_tensor
+        .size()
+        .iter()
+        .map(|dim| *dim as usize)
+        .collect::<Vec<usize>>();
+    let numel = tch_tensor.numel();
+    let mut data = vec![T::ZERO; numel];
+    // No-ops for contiguous CPU tensors, the data is then copied once into the buffer handed over to ONNX Runtime
+    tch_tensor
+        .f_to_device(Device::Cpu)?
+        .f_contiguous()?
+        .f_copy_data(&mut data, numel)?;
+    Ok(ndarray::ArrayD::from_shape_vec(IxDyn(&shape), data)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pipelines::onnx::config::ONNXEnvironmentConfig;
+    use crate::pipelines::onnx::export::proto::{
+        Attribute, DataType, Dimension, Graph, Model, Node, ValueInfo, OPSET_VERSION,
+    };
+    use std::fs::File;
+
+    fn value_info(name: String, data_type: DataType) -> ValueInfo {
+        ValueInfo {
+            name,
+            data_type,
+            shape: vec![Dimension::Param("rows"), Dimension::Param("columns")],
+        }
+    }
+
+    fn build_session(
+        nodes: Vec<Node>,
+        inputs: Vec<ValueInfo>,
+        outputs: Vec<ValueInfo>,
+    ) -> Result<Arc<Session>, RustBertError> {
+        let model_dir = tempfile::tempdir()?;
+        let model_path = model_dir.path().join("model.onnx");
+        Model {
+            graph: Graph {
+                name: "conversion".to_string(),
+                nodes,
+                initializers: vec![],
+                inputs,
+                outputs,
+            },
+            opset_version: OPSET_VERSION,
+        }
+        .write_to(&mut File::create(&model_path)?)?;
+
+        let onnx_config = ONNXEnvironmentConfig::from_device(Device::Cpu);
+        let environment = onnx_config.get_environment()?;
+        let session = onnx_config
+            .get_session_builder(&environment)?
+            .with_model_from_file(&model_path)?;
+        Ok(Arc::new(session))
+    }
+
+    #[test]
+    fn test_round_trip_conversion() -> Result<(), RustBertError> {
+        let types = [
+            (Kind::Float, DataType::Float),
+            (Kind::Double, DataType::Double),
+            (Kind::Half, DataType::Float16),
+            (Kind::BFloat16, DataType::Bfloat16),
+            (Kind::Int64, DataType::Int64),
+            (Kind::Int, DataType::Int32),
+            (Kind::Int16, DataType::Int16),
+            (Kind::Int8, DataType::Int8),
+            (Kind::Uint8, DataType::Uint8),
+        ];
+        let nodes = (0..types.len())
+            .map(|index| Node {
+                name: format!("identity_{index}"),
+                op_type: "Identity",
+                inputs: vec![format!("input_{index}")],
+                outputs: vec![format!("output_{index}")],
+                attributes: vec![],
+            })
+            .collect();
+        let inputs = types
+            .iter()
+            .enumerate()
+            .map(|(index, (_, data_type))| value_info(format!("input_{index}"), *data_type))
+            .collect();
+        let outputs = types
+            .iter()
+            .enumerate()
+            .map(|(index, (_, data_type))| value_info(format!("output_{index}"), *data_type))
+            .collect();
+        let session = build_session(nodes, inputs, outputs)?;
+
+        // Non-contiguous tensor of shape (3, 2)
+        let values = Tensor::from_slice(&[0f64, 1.0, 2.0, 3.0, 4.0, 100.0])
+            .view([2, 3])
+            .transpose(0, 1);
+        let ort_inputs = types
+            .iter()
+            .map(|(kind, _)| tch_tensor_to_ort(&values.to_kind(*kind)))
+            .collect::<Result<Vec<InputTensor>, RustBertError>>()?;
+        let ort_outputs = session.run(ort_inputs)?;
+
+        for (index, (kind, _)) in types.iter().enumerate() {
+            let output = ort_tensor_to_tch(&ort_outputs[index])?;
+            assert_eq!(output.kind(), *kind);
+            assert_eq!(output.size(), vec![3, 2]);
+            assert!(output.to_kind(Kind::Double).equal(&values));
+        }
+
+        let ort_outputs = OrtOutputs::new(&session, ort_outputs);
+        for (index, (kind, _)) in types.iter().enumerate() {
+            let output = unsafe { ort_outputs.borrow_tensor(index)? };
+            assert_eq!(output.kind(), *kind);
+            assert_eq!(output.size(), vec![3, 2]);
+            assert!(output.to_kind(Kind::Double).equal(&values));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsigned_widening() -> Result<(), RustBertError> {
+        let types = [DataType::Uint16, DataType::Uint32, DataType::Uint64];
+        let nodes = types
+            .iter()
+            .enumerate()
+            .map(|(index, data_type)| Node {
+                name: format!("cast_{index}"),
+                op_type: "Cast",
+                inputs: vec!["input".to_string()],
+                outputs: vec![format!("output_{index}")],
+                attributes: vec![Attribute::int("to", *data_type as i64)],
+            })
+            .collect();
+        let outputs = types
+            .iter()
+            .enumerate()
+            .map(|(index, data_type)| value_info(format!("output_{index}"), *data_type))
+            .collect();
+        let session = build_session(
+            nodes,
+            vec![value_info("input".to_string(), DataType::Float)],
+            outputs,
+        )?;
+
+        let values = Tensor::from_slice(&[0f32, 1.0, 2.0, 100.0]).view([2, 2]);
+        let ort_outputs = session.run(vec![tch_tensor_to_ort(&values)?])?;
+        for ort_output in ort_outputs.iter() {
+            let output = ort_tensor_to_tch(ort_output)?;
+            assert_eq!(output.kind(), Kind::Int64);
+            assert!(output.equal(&values.to_kind(Kind::Int64)));
+        }
+        // Unsigned types are copied when borrowed
+        let ort_outputs = OrtOutputs::new(&session, ort_outputs);
+        let output = unsafe { ort_outputs.borrow_tensor(2)? };
+        assert_eq!(output.kind(), Kind::Int64);
+
+        // Values exceeding the range of Int64 are rejected
+        let values = Tensor::from_slice(&[1e19f32]).view([1, 1]);
+        let ort_outputs = session.run(vec![tch_tensor_to_ort(&values)?])?;
+        assert!(ort_tensor_to_tch(&ort_outputs[0]).is_ok());
+        assert!(ort_tensor_to_tch(&ort_outputs[2]).is_err());
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use crate::pipelines::onnx::config::{
     ONNXEnvironmentConfig, ATTENTION_MASK_NAME, ENCODER_ATTENTION_MASK_NAME,
     ENCODER_HIDDEN_STATES_NAME, INPUT_IDS_NAME, POSITION_IDS,
 };
-use crate::pipelines::onnx::conversion::{ort_tensor_to_tch, tch_tensor_to_ort};
+use crate::pipelines::onnx::conversion::{ort_tensor_to_tch, tch_tensor_to_ort, OrtOutputs};
 use crate::pipelines::onnx::models::ONNXLayerCache;
 use crate::RustBertError;
 use ort::{Environment, Session};
@@ -14,7 +14,7 @@ use std::sync::Arc;
 use tch::Tensor;
 
 pub struct ONNXDecoder {
-    session: Session,
+    session: Arc<Session>,
     name_mapping: InputOutputNameMapping,
     use_cache: bool,
 }
@@ -31,7 +31,7 @@ impl ONNXDecoder {
             .with_model_from_file(model_file)?;
         let name_mapping = get_input_output_mapping(&session);
         Ok(Self {
-            session,
+            session: Arc::new(session),
             name_mapping,
             use_cache,
         })
@@ -100,8 +100,9 @@ impl ONNXDecoder {
         let lm_logits =
             ort_tensor_to_tch(&outputs[*self.name_mapping.output_names.get("logits").unwrap()])?;
         let cache = if self.use_cache {
-            Cache::ONNXCache(ONNXLayerCache::from_ort_output(
-                &outputs,
+            // The cached keys and values are read in place from the outputs, owned by the cache
+            Cache::ONNXCache(ONNXLayerCache::from_shared_ort_output(
+                OrtOutputs::new(&self.session, outputs),
                 &self.name_mapping.key_value_output_names,
             )?)
         } else {
@@ -0,0 +1,560 @@
+use crate::bert::BertConfig;
+use crate::common::activations::Activation;
+use crate::pipelines::common::ConfigOption;
+use crate::pipelines::onnx::config::{
+    ATTENTION_MASK_NAME, INPUT_IDS_NAME, LAST_HIDDEN_STATE, LOGITS, TOKEN_TYPE_IDS,
+};
+use crate::pipelines::onnx::export::proto::{
+    Attribute, DataType, Dimension, Graph, Model, Node, TensorProto, ValueInfo,
+};
+use crate::pipelines::onnx::export::ONNXExportTask;
+use crate::RustBertError;
+use std::collections::HashMap;
+use tch::{Device, Kind, Tensor};
+
+const BATCH_SIZE: &str = "batch_size";
+const SEQUENCE_LENGTH: &str = "sequence_length";
+const LAYER_NORM_EPS: f32 = 1e-12;
+const ROBERTA_PADDING_INDEX: i64 = 1;
+
+/// Accumulates the nodes and initializers (weights and constants) of a graph.
+/// Every node has a single output, named after the node.
+struct GraphBuilder<'a> {
+    variables: &'a HashMap<String, Tensor>,
+    nodes: Vec<Node>,
+    initializers: Vec<TensorProto>,
+    num_values: usize,
+}
+
+impl<'a> GraphBuilder<'a> {
+    fn new(variables: &'a HashMap<String, Tensor>) -> Self {
+        GraphBuilder {
+            variables,
+            nodes: vec![],
+            initializers: vec![],
+            num_values: 0,
+        }
+    }
+
+    fn next_name(&mut self, prefix: &str) -> String {
+        self.num_values += 1;
+        format!("{prefix}_{}", self.num_values)
+    }
+
+    fn variable(&self, name: &str) -> Result<&'a Tensor, RustBertError> {
+        self.variables.get(name).ok_or_else(|| {
+            RustBertError::InvalidConfigurationError(format!(
+                "Variable {name} not found in the variable store, the variable store does not match the architecture exported"
+            ))
+        })
+    }
+
+    fn float_initializer(&mut self, name: &str, tensor: &Tensor) -> Result<String, RustBertError> {
+        let tensor = tensor
+            .f_to_kind(Kind::Float)?
+            .f_to_device(Device::Cpu)?
+            .f_contiguous()?;
+        let numel = tensor.numel();
+        let mut data = vec![0f32; numel];
+        tensor.f_copy_data(&mut data, numel)?;
+        let mut raw_data = Vec::with_capacity(numel * 4);
+        for value in data {
+            raw_data.extend_from_slice(&value.to_le_bytes());
+        }
+        self.initializers.push(TensorProto {
+            name: name.to_string(),
+            dims: tensor.size(),
+            data_type: DataType::Float,
+            raw_data,
+        });
+        Ok(name.to_string())
+    }
+
+    /// Adds the variable `name` of the variable store as an initializer
+    fn weight(&mut self, name: &str) -> Result<String, RustBertError> {
+        let tensor = self.variable(name)?;
+        self.float_initializer(name, tensor)
+    }
+
+    fn int_constant(&mut self, values: &[i64], dims: &[i64]) -> String {
+        let name = self.next_name("constant");
+        let mut raw_data = Vec::with_capacity(values.len() * 8);
+        for value in values {
+            raw_data.extend_from_slice(&value.to_le_bytes());
+        }
+        self.initializers.push(TensorProto {
+            name: name.clone(),
+            dims: dims.to_vec(),
+            data_type: DataType::Int64,
+            raw_data,
+        });
+        name
+    }
+
+    fn float_constant(&mut self, value: f32) -> String {
+        let name = self.next_name("constant");
+        self.initializers.push(TensorProto {
+            name: name.clone(),
+            dims: vec![],
+            data_type: DataType::Float,
+            raw_data: value.to_le_bytes().to_vec(),
+        });
+        name
+    }
+
+    fn node(
+        &mut self,
+        op_type: &'static str,
+        inputs: &[&str],
+        attributes: Vec<Attribute>,
+    ) -> String {
+        let name = self.next_name(op_type);
+        self.nodes.push(Node {
+            name: name.clone(),
+            op_type,
+            inputs: inputs.iter().map(|input| input.to_string()).collect(),
+            outputs: vec![name.clone()],
+            attributes,
+        });
+        name
+    }
+
+    /// Exposes a value as a graph output
+    fn output(&mut self, name: &str, value: &str) {
+        self.nodes.push(Node {
+            name: format!("{name}_identity"),
+            op_type: "Identity",
+            inputs: vec![value.to_string()],
+            outputs: vec![name.to_string()],
+            attributes: vec![],
+        });
+    }
+
+    /// Linear layer `name`. The weight is stored transposed so that it can be applied with a single `MatMul`.
+    fn linear(&mut self, input: &str, name: &str) -> Result<String, RustBertError> {
+        let weight_name = format!("{name}.weight");
+        let weight = self.variable(&weight_name)?.f_transpose(0, 1)?;
+        let weight = self.float_initializer(&weight_name, &weight)?;
+        let bias = self.weight(&format!("{name}.bias"))?;
+        let output = self.node("MatMul", &[input, &weight], vec![]);
+        Ok(self.node("Add", &[&output, &bias], vec![]))
+    }
+
+    fn layer_norm(&mut self, input: &str, name: &str) -> Result<String, RustBertError> {
+        let weight = self.weight(&format!("{name}.weight"))?;
+        let bias = self.weight(&format!("{name}.bias"))?;
+        Ok(self.node(
+            "LayerNormalization",
+            &[input, &weight, &bias],
+            vec![
+                Attribute::int("axis", -1),
+                Attribute::float("epsilon", LAYER_NORM_EPS),
+            ],
+        ))
+    }
+
+    fn activation(&mut self, input: &str, activation: Activation) -> String {
+        match activation {
+            Activation::gelu => {
+                let sqrt_2 = self.float_constant(std::f32::consts::SQRT_2);
+                let one = self.float_constant(1.0);
+                let half = self.float_constant(0.5);
+                let scaled = self.node("Div", &[input, &sqrt_2], vec![]);
+                let erf = self.node("Erf", &[&scaled], vec![]);
+                let erf = self.node("Add", &[&erf, &one], vec![]);
+                let output = self.node("Mul", &[input, &erf], vec![]);
+                self.node("Mul", &[&output, &half], vec![])
+            }
+            Activation::gelu_new => {
+                let coefficient = self.float_constant(0.044715);
+                let sqrt_2_over_pi = self.float_constant((2.0 / std::f32::consts::PI).sqrt());
+                let one = self.float_constant(1.0);
+                let half = self.float_constant(0.5);
+                let squared = self.node("Mul", &[input, input], vec![]);
+                let cubed = self.node("Mul", &[&squared, input], vec![]);
+                let cubed = self.node("Mul", &[&cubed, &coefficient], vec![]);
+                let inner = self.node("Add", &[input, &cubed], vec![]);
+                let inner = self.node("Mul", &[&inner, &sqrt_2_over_pi], vec![]);
+                let tanh = self.node("Tanh", &[&inner], vec![]);
+                let tanh = self.node("Add", &[&tanh, &one], vec![]);
+                let output = self.node("Mul", &[input, &tanh], vec![]);
+                self.node("Mul", &[&output, &half], vec![])
+            }
+            Activation::relu => self.node("Relu", &[input], vec![]),
+            Activation::swish => {
+                let sigmoid = self.node("Sigmoid", &[input], vec![]);
+                self.node("Mul", &[input, &sigmoid], vec![])
+            }
+            Activation::mish => {
+                let softplus = self.node("Softplus", &[input], vec![]);
+                let tanh = self.node("Tanh", &[&softplus], vec![]);
+                self.node("Mul", &[input, &tanh], vec![])
+            }
+            Activation::tanh => self.node("Tanh", &[input], vec![]),
+            Activation::identity => input.to_string(),
+        }
+    }
+
+    /// Selects the hidden state of the first token of each sequence
+    fn first_token(&mut self, hidden_state: &str) -> String {
+        let index = self.int_constant(&[0], &[]);
+        self.node(
+            "Gather",
+            &[hidden_state, &index],
+            vec![Attribute::int("axis", 1)],
+        )
+    }
+
+    fn finish(self, name: &str, inputs: Vec<ValueInfo>, outputs: Vec<ValueInfo>) -> Graph {
+        Graph {
+            name: name.to_string(),
+            nodes: self.nodes,
+            initializers: self.initializers,
+            inputs,
+            outputs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EncoderArchitecture {
+    Bert,
+    Roberta,
+    DistilBert,
+}
+
+/// Variable names of a transformer layer, relative to the layer path
+struct LayerVariables {
+    query: &'static str,
+    key: &'static str,
+    value: &'static str,
+    attention_output: &'static str,
+    attention_layer_norm: &'static str,
+    intermediate: &'static str,
+    output: &'static str,
+    output_layer_norm: &'static str,
+}
+
+const BERT_LAYER_VARIABLES: LayerVariables = LayerVariables {
+    query: "attention.self.query",
+    key: "attention.self.key",
+    value: "attention.self.value",
+    attention_output: "attention.output.dense",
+    attention_layer_norm: "attention.output.LayerNorm",
+    intermediate: "intermediate.dense",
+    output: "output.dense",
+    output_layer_norm: "output.LayerNorm",
+};
+
+const DISTILBERT_LAYER_VARIABLES: LayerVariables = LayerVariables {
+    query: "attention.q_lin",
+    key: "attention.k_lin",
+    value: "attention.v_lin",
+    attention_output: "attention.out_lin",
+    attention_layer_norm: "sa_layer_norm",
+    intermediate: "ffn.lin1",
+    output: "ffn.lin2",
+    output_layer_norm: "output_layer_norm",
+};
+
+struct EncoderConfig {
+    architecture: EncoderArchitecture,
+    hidden_size: i64,
+    num_attention_heads: i64,
+    num_hidden_layers: i64,
+    activation: Activation,
+}
+
+impl EncoderConfig {
+    fn new(config: &ConfigOption) -> Result<Self, RustBertError> {
+        Ok(match config {
+            ConfigOption::Bert(config) => Self::from_bert_config(EncoderArchitecture::Bert, config),
+            ConfigOption::Roberta(config) => {
+                Self::from_bert_config(EncoderArchitecture::Roberta, config)
+            }
+            ConfigOption::DistilBert(config) => EncoderConfig {
+                architecture: EncoderArchitecture::DistilBert,
+                hidden_size: config.dim,
+                num_attention_heads: config.n_heads,
+                num_hidden_layers: config.n_layers,
+                activation: config.activation,
+            },
+            _ => {
+                return Err(RustBertError::InvalidConfigurationError(
+                    "ONNX export is only supported for BERT, RoBERTa and DistilBERT models"
+                        .to_string(),
+                ));
+            }
+        })
+    }
+
+    fn from_bert_config(architecture: EncoderArchitecture, config: &BertConfig) -> Self {
+        EncoderConfig {
+            architecture,
+            hidden_size: config.hidden_size,
+            num_attention_heads: config.num_attention_heads,
+            num_hidden_layers: config.num_hidden_layers,
+            activation: config.hidden_act,
+        }
+    }
+}
+
+/// Builds the ONNX model of a BERT-like encoder and the head of the task from the variables of a variable store.
+///
+/// The encoder variables are looked up relatively to the path of the embeddings (e.g. `bert.` for a BERT model
+/// with a sequence classification head or no prefix for a bare `BertModel`), the heads variables from the root of the
+/// variable store, following the naming of the corresponding rust-bert models.
+pub(crate) fn build_encoder_model(
+    variables: &HashMap<String, Tensor>,
+    config: &ConfigOption,
+    task: ONNXExportTask,
+    opset_version: i64,
+) -> Result<Model, RustBertError> {
+    let config = EncoderConfig::new(config)?;
+    let embeddings_suffix = "embeddings.word_embeddings.weight";
+    let prefix = variables
+        .keys()
+        .filter_map(|name| name.strip_suffix(embeddings_suffix))
+        .min_by_key(|prefix| prefix.len())
+        .ok_or_else(|| {
+            RustBertError::InvalidConfigurationError(format!(
+                "No word embeddings ({embeddings_suffix}) found in the variable store"
+            ))
+        })?
+        .to_string();
+
+    let mut graph = GraphBuilder::new(variables);
+    let use_token_type_ids = config.architecture == EncoderArchitecture::Bert;
+    let mut hidden_state = embeddings(&mut graph, &config, &prefix, use_token_type_ids)?;
+
+    // Additive attention mask of shape (batch size, 1, 1, sequence length)
+    let mask_value = match config.architecture {
+        EncoderArchitecture::Bert | EncoderArchitecture::Roberta => -10000.0,
+        EncoderArchitecture::DistilBert => f32::MIN,
+    };
+    let mask = graph.node(
+        "Cast",
+        &[ATTENTION_MASK_NAME],
+        vec![Attribute::int("to", DataType::Float as i64)],
+    );
+    let one = graph.float_constant(1.0);
+    let mask = graph.node("Sub", &[&one, &mask], vec![]);
+    let mask_value = graph.float_constant(mask_value);
+    let mask = graph.node("Mul", &[&mask, &mask_value], vec![]);
+    let mask_axes = graph.int_constant(&[1, 2], &[2]);
+    let mask = graph.node("Unsqueeze", &[&mask, &mask_axes], vec![]);
+
+    let (layers_path, layer_variables) = match config.architecture {
+        EncoderArchitecture::Bert | EncoderArchitecture::Roberta => {
+            ("encoder.layer", &BERT_LAYER_VARIABLES)
+        }
+        EncoderArchitecture::DistilBert => ("transformer.layer", &DISTILBERT_LAYER_VARIABLES),
+    };
+    for layer_index in 0..config.num_hidden_layers {
+        hidden_state = transformer_layer(
+            &mut graph,
+            &config,
+            &format!("{prefix}{layers_path}.{layer_index}."),
+            layer_variables,
+            &hidden_state,
+            &mask,
+        )?;
+    }
+
+    let (output_name, output_shape) = match task {
+        ONNXExportTask::Encoder => {
+            graph.output(LAST_HIDDEN_STATE, &hidden_state);
+            (
+                LAST_HIDDEN_STATE,
+                vec![
+                    Dimension::Param(BATCH_SIZE),
+                    Dimension::Param(SEQUENCE_LENGTH),
+                    Dimension::Value(config.hidden_size),
+                ],
+            )
+        }
+        ONNXExportTask::SequenceClassification => {
+            let first_token = graph.first_token(&hidden_state);
+            let (logits, classifier) = match config.architecture {
+                EncoderArchitecture::Bert => {
+                    let pooled = graph.linear(&first_token, &format!("{prefix}pooler.dense"))?;
+                    let pooled = graph.node("Tanh", &[&pooled], vec![]);
+                    (graph.linear(&pooled, "classifier")?, "classifier")
+                }
+                EncoderArchitecture::Roberta => {
+                    let pooled = graph.linear(&first_token, "classifier.dense")?;
+                    let pooled = graph.node("Tanh", &[&pooled], vec![]);
+                    (
+                        graph.linear(&pooled, "classifier.out_proj")?,
+                        "classifier.out_proj",
+                    )
+                }
+                EncoderArchitecture::DistilBert => {
+                    let pooled = graph.linear(&first_token, "pre_classifier")?;
+                    let pooled = graph.node("Relu", &[&pooled], vec![]);
+                    (graph.linear(&pooled, "classifier")?, "classifier")
+                }
+            };
+            graph.output(LOGITS, &logits);
+            let num_labels = graph.variable(&format!("{classifier}.weight"))?.size()[0];
+            (
+                LOGITS,
+                vec![Dimension::Param(BATCH_SIZE), Dimension::Value(num_labels)],
+            )
+        }
+        ONNXExportTask::TokenClassification => {
+            let logits = graph.linear(&hidden_state, "classifier")?;
+            graph.output(LOGITS, &logits);
+            let num_labels = graph.variable("classifier.weight")?.size()[0];
+            (
+                LOGITS,
+                vec![
+                    Dimension::Param(BATCH_SIZE),
+                    Dimension::Param(SEQUENCE_LENGTH),
+                    Dimension::Value(num_labels),
+                ],
+            )
+        }
+    };
+
+    let mut input_names = vec![INPUT_IDS_NAME, ATTENTION_MASK_NAME];
+    if use_token_type_ids {
+        input_names.push(TOKEN_TYPE_IDS);
+    }
+    let inputs = input_names
+        .into_iter()
+        .map(|name| ValueInfo {
+            name: name.to_string(),
+            data_type: DataType::Int64,
+            shape: vec![
+                Dimension::Param(BATCH_SIZE),
+                Dimension::Param(SEQUENCE_LENGTH),
+            ],
+        })
+        .collect();
+    let outputs = vec![ValueInfo {
+        name: output_name.to_string(),
+        data_type: DataType::Float,
+        shape: output_shape,
+    }];
+
+    Ok(Model {
+        graph: graph.finish("rust-bert-encoder", inputs, outputs),
+        opset_version,
+    })
+}
+
+fn embeddings(
+    graph: &mut GraphBuilder,
+    config: &EncoderConfig,
+    prefix: &str,
+    use_token_type_ids: bool,
+) -> Result<String, RustBertError> {
+    let path = format!("{prefix}embeddings");
+    let word_embeddings = graph.weight(&format!("{path}.word_embeddings.weight"))?;
+    let position_embeddings = graph.weight(&format!("{path}.position_embeddings.weight"))?;
+    let mut embeddings = graph.node("Gather", &[&word_embeddings, INPUT_IDS_NAME], vec![]);
+
+    let position_ids = match config.architecture {
+        EncoderArchitecture::Bert | EncoderArchitecture::DistilBert => {
+            let shape = graph.node("Shape", &[INPUT_IDS_NAME], vec![]);
+            let sequence_dim = graph.int_constant(&[1], &[]);
+            let sequence_length = graph.node("Gather", &[&shape, &sequence_dim], vec![]);
+            let start = graph.int_constant(&[0], &[]);
+            let step = graph.int_constant(&[1], &[]);
+            graph.node("Range", &[&start, &sequence_length, &step], vec![])
+        }
+        EncoderArchitecture::Roberta => {
+            // Positions are counted from the padding index, skipping padding tokens
+            let padding_index = graph.int_constant(&[ROBERTA_PADDING_INDEX], &[]);
+            let is_padding = graph.node("Equal", &[INPUT_IDS_NAME, &padding_index], vec![]);
+            let not_padding = graph.node("Not", &[&is_padding], vec![]);
+            let not_padding = graph.node(
+                "Cast",
+                &[&not_padding],
+                vec![Attribute::int("to", DataType::Int64 as i64)],
+            );
+            let sequence_dim = graph.int_constant(&[1], &[]);
+            let positions = graph.node("CumSum", &[&not_padding, &sequence_dim], vec![]);
+            let positions = graph.node("Mul", &[&positions, &not_padding], vec![]);
+            graph.node("Add", &[&positions, &padding_index], vec![])
+        }
+    };
+    let positions = graph.node("Gather", &[&position_embeddings, &position_ids], vec![]);
+    embeddings = graph.node("Add", &[&embeddings, &positions], vec![]);
+
+    match config.architecture {
+        EncoderArchitecture::Bert | EncoderArchitecture::Roberta => {
+            let token_type_embeddings =
+                graph.weight(&format!("{path}.token_type_embeddings.weight"))?;
+            let token_types = if use_token_type_ids {
+                graph.node("Gather", &[&token_type_embeddings, TOKEN_TYPE_IDS], vec![])
+            } else {
+                let token_type = graph.int_constant(&[0], &[]);
+                graph.node("Gather", &[&token_type_embeddings, &token_type], vec![])
+            };
+            embeddings = graph.node("Add", &[&embeddings, &token_types], vec![]);
+        }
+        EncoderArchitecture::DistilBert => {}
+    }
+    graph.layer_norm(&embeddings, &format!("{path}.LayerNorm"))
+}
+
+fn transformer_layer(
+    graph: &mut GraphBuilder,
+    config: &EncoderConfig,
+    path: &str,
+    variables: &LayerVariables,
+    hidden_state: &str,
+    mask: &str,
+) -> Result<String, RustBertError> {
+    let head_dim = config.hidden_size / config.num_attention_heads;
+    let heads_shape = graph.int_constant(&[0, 0, config.num_attention_heads, head_dim], &[4]);
+    let split_heads = |graph: &mut GraphBuilder,
+                       name: &str,
+                       permutation: &[i64]|
+     -> Result<String, RustBertError> {
+        let projection = graph.linear(hidden_state, &format!("{path}{name}"))?;
+        let projection = graph.node("Reshape", &[&projection, &heads_shape], vec![]);
+        Ok(graph.node(
+            "Transpose",
+            &[&projection],
+            vec![Attribute::ints("perm", permutation)],
+        ))
+    };
+    // Query and values of shape (batch size, heads, sequence length, head dim), keys (batch size, heads, head dim, sequence length)
+    let query = split_heads(graph, variables.query, &[0, 2, 1, 3])?;
+    let key = split_heads(graph, variables.key, &[0, 2, 3, 1])?;
+    let value = split_heads(graph, variables.value, &[0, 2, 1, 3])?;
+
+    let scale = graph.float_constant((head_dim as f32).sqrt());
+    let scores = graph.node("MatMul", &[&query, &key], vec![]);
+    let scores = graph.node("Div", &[&scores, &scale], vec![]);
+    let scores = graph.node("Add", &[&scores, mask], vec![]);
+    let weights = graph.node("Softmax", &[&scores], vec![Attribute::int("axis", -1)]);
+    let context = graph.node("MatMul", &[&weights, &value], vec![]);
+    let context = graph.node(
+        "Transpose",
+        &[&context],
+        vec![Attribute::ints("perm", &[0, 2, 1, 3])],
+    );
+    let hidden_shape = graph.int_constant(&[0, 0, config.hidden_size], &[3]);
+    let context = graph.node("Reshape", &[&context, &hidden_shape], vec![]);
+
+    let attention_output =
+        graph.linear(&context, &format!("{path}{}", variables.attention_output))?;
+    let attention_output = graph.node("Add", &[&attention_output, hidden_state], vec![]);
+    let attention_output = graph.layer_norm(
+        &attention_output,
+        &format!("{path}{}", variables.attention_layer_norm),
+    )?;
+
+    let intermediate = graph.linear(
+        &attention_output,
+        &format!("{path}{}", variables.intermediate),
+    )?;
+    let intermediate = graph.activation(&intermediate, config.activation);
+    let output = graph.linear(&intermediate, &format!("{path}{}", variables.output))?;
+    let output = graph.node("Add", &[&output, &attention_output], vec![]);
+    graph.layer_norm(&output, &format!("{path}{}", variables.output_layer_norm))
+}
@@ -0,0 +1,83 @@
+//! # Export of Torch models to ONNX
+//!
+//! Torch models loaded in a `VarStore` can be exported to an ONNX graph, allowing models fine-tuned or converted with
+//! rust-bert to be deployed with ONNX Runtime without a Python environment. The export is supported for BERT, RoBERTa
+//! (including XLM-RoBERTa) and DistilBERT encoders, optionally with their sequence or token classification heads.
+//!
+//! The exported graphs use the inputs (`input_ids`, `attention_mask` and `token_type_ids` for BERT) and outputs
+//! (`last_hidden_state` or `logits`) naming expected by `ONNXEncoder`, and can be used in the pipelines with a
+//! `ModelResource::ONNX` along with the configuration and vocabulary files of the original model.
+//! Weights are embedded in the ONNX file, limiting the size of the models that can be exported to 2GB.
+//!
+//! ```no_run
+//! # fn main() -> anyhow::Result<()> {
+//! use rust_bert::distilbert::{DistilBertConfig, DistilBertModelClassifier};
+//! use rust_bert::pipelines::common::ConfigOption;
+//! use rust_bert::pipelines::onnx::export::{export_to_onnx, ONNXExportTask};
+//! use rust_bert::Config;
+//! use tch::{nn, Device};
+//!
+//! let config = DistilBertConfig::from_file("path/to/config.json");
+//! let mut var_store = nn::VarStore::new(Device::Cpu);
+//! let _model = DistilBertModelClassifier::new(var_store.root(), &config)?;
+//! var_store.load("path/to/rust_model.ot")?;
+//!
+//! export_to_onnx(
+//!     &var_store,
+//!     &ConfigOption::DistilBert(config),
+//!     ONNXExportTask::SequenceClassification,
+//!     "path/to/model.onnx",
+//! )?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod graph;
+pub(crate) mod proto;
+
+use crate::pipelines::common::ConfigOption;
+use crate::pipelines::onnx::export::graph::build_encoder_model;
+use crate::pipelines::onnx::export::proto::OPSET_VERSION;
+use crate::RustBertError;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use tch::nn;
+
+/// # Model head exported on top of the encoder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ONNXExportTask {
+    /// Bare encoder (e.g. for sentence embeddings), outputs the `last_hidden_state`
+    Encoder,
+    /// Sequence classification head, outputs `logits` of shape (*batch size*, *num_labels*)
+    SequenceClassification,
+    /// Token classification head, outputs `logits` of shape (*batch size*, *sequence_length*, *num_labels*)
+    TokenClassification,
+}
+
+/// Exports a Torch encoder model to an ONNX file
+///
+/// # Arguments
+///
+/// * `var_store` - `VarStore` containing the weights of the model. Variables are expected to follow the naming of the
+///   rust-bert model for the task (e.g. `BertForSequenceClassification` for a BERT sequence classification model)
+/// * `config` - `ConfigOption` of the model (`Bert`, `Roberta` or `DistilBert`)
+/// * `task` - `ONNXExportTask` defining the head of the model to export
+/// * `output_path` - path of the ONNX file to create
+///
+/// # Returns
+///
+/// * `Result<(), RustBertError>` returning an error if the variables of the model could not be found or the file could not be written
+pub fn export_to_onnx<P: AsRef<Path>>(
+    var_store: &nn::VarStore,
+    config: &ConfigOption,
+    task: ONNXExportTask,
+    output_path: P,
+) -> Result<(), RustBertError> {
+    let variables = var_store.variables();
+    let model = tch::no_grad(|| build_encoder_model(&variables, config, task, OPSET_VERSION))?;
+    let mut output = BufWriter::new(File::create(output_path)?);
+    model.write_to(&mut output)?;
+    output.flush()?;
+    Ok(())
+}
@@ -0,0 +1,359 @@
+//! Minimal protocol buffers serialization of the ONNX messages (`onnx.proto`) required to describe an inference graph.
+//! Only the fields used by the exporter are supported. Repeated scalar fields are written unpacked, which all
+//! protocol buffers parsers accept.
+
+use crate::RustBertError;
+use std::io::Write;
+
+const VARINT: u64 = 0;
+const FIXED_32: u64 = 5;
+const LENGTH_DELIMITED: u64 = 2;
+
+/// ONNX IR version supported by the exporter (ONNX 1.13)
+const IR_VERSION: i64 = 8;
+/// Default ONNX operator set version, the first version providing `LayerNormalization`
+pub(crate) const OPSET_VERSION: i64 = 17;
+
+#[derive(Default)]
+struct ProtoWriter {
+    buffer: Vec<u8>,
+}
+
+impl ProtoWriter {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buffer.push((value as u8 & 0x7F) | 0x80);
+            value >>= 7;
+        }
+        self.buffer.push(value as u8);
+    }
+
+    fn key(&mut self, field: u64, wire_type: u64) {
+        self.varint((field << 3) | wire_type);
+    }
+
+    fn int(&mut self, field: u64, value: i64) {
+        self.key(field, VARINT);
+        self.varint(value as u64);
+    }
+
+    fn float(&mut self, field: u64, value: f32) {
+        self.key(field, FIXED_32);
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn bytes(&mut self, field: u64, value: &[u8]) {
+        self.key(field, LENGTH_DELIMITED);
+        self.varint(value.len() as u64);
+        self.buffer.extend_from_slice(value);
+    }
+
+    fn string(&mut self, field: u64, value: &str) {
+        self.bytes(field, value.as_bytes());
+    }
+
+    fn message<M: Message>(&mut self, field: u64, message: &M) {
+        let mut writer = ProtoWriter::default();
+        message.write(&mut writer);
+        self.bytes(field, &writer.buffer);
+    }
+}
+
+trait Message {
+    fn write(&self, writer: &mut ProtoWriter);
+}
+
+/// Element types of ONNX tensors (`TensorProto.DataType`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// The exported models only use `Float` and `Int64`, other types are used by the tensor conversion tests
+#[allow(dead_code)]
+pub(crate) enum DataType {
+    Float = 1,
+    Uint8 = 2,
+    Int8 = 3,
+    Uint16 = 4,
+    Int16 = 5,
+    Int32 = 6,
+    Int64 = 7,
+    Float16 = 10,
+    Double = 11,
+    Uint32 = 12,
+    Uint64 = 13,
+    Bfloat16 = 16,
+}
+
+/// Tensor with its data stored as little-endian raw bytes (`TensorProto`)
+pub(crate) struct TensorProto {
+    pub name: String,
+    pub dims: Vec<i64>,
+    pub data_type: DataType,
+    pub raw_data: Vec<u8>,
+}
+
+impl Message for TensorProto {
+    fn write(&self, writer: &mut ProtoWriter) {
+        for dim in &self.dims {
+            writer.int(1, *dim);
+        }
+        writer.int(2, self.data_type as i64);
+        writer.string(8, &self.name);
+        writer.bytes(9, &self.raw_data);
+    }
+}
+
+/// Node attribute value (`AttributeProto`)
+pub(crate) enum AttributeValue {
+    Float(f32),
+    Int(i64),
+    Ints(Vec<i64>),
+}
+
+pub(crate) struct Attribute {
+    pub name: &'static str,
+    pub value: AttributeValue,
+}
+
+impl Attribute {
+    pub(crate) fn float(name: &'static str, value: f32) -> Self {
+        Attribute {
+            name,
+            value: AttributeValue::Float(value),
+        }
+    }
+
+    pub(crate) fn int(name: &'static str, value: i64) -> Self {
+        Attribute {
+            name,
+            value: AttributeValue::Int(value),
+        }
+    }
+
+    pub(crate) fn ints(name: &'static str, values: &[i64]) -> Self {
+        Attribute {
+            name,
+            value: AttributeValue::Ints(values.to_vec()),
+        }
+    }
+}
+
+impl Message for Attribute {
+    fn write(&self, writer: &mut ProtoWriter) {
+        writer.string(1, self.name);
+        // Values are followed by their `AttributeProto.AttributeType`
+        match &self.value {
+            AttributeValue::Float(value) => {
+                writer.float(2, *value);
+                writer.int(20, 1);
+            }
+            AttributeValue::Int(value) => {
+                writer.int(3, *value);
+                writer.int(20, 2);
+            }
+            AttributeValue::Ints(values) => {
+                for value in values {
+                    writer.int(8, *value);
+                }
+                writer.int(20, 7);
+            }
+        }
+    }
+}
+
+/// Operation of the graph (`NodeProto`)
+pub(crate) struct Node {
+    pub name: String,
+    pub op_type: &'static str,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    pub attributes: Vec<Attribute>,
+}
+
+impl Message for Node {
+    fn write(&self, writer: &mut ProtoWriter) {
+        for input in &self.inputs {
+            writer.string(1, input);
+        }
+        for output in &self.outputs {
+            writer.string(2, output);
+        }
+        writer.string(3, &self.name);
+        writer.string(4, self.op_type);
+        for attribute in &self.attributes {
+            writer.message(5, attribute);
+        }
+    }
+}
+
+/// Dimension of a graph input or output, either fixed or symbolic (e.g. `batch_size`)
+pub(crate) enum Dimension {
+    Value(i64),
+    Param(&'static str),
+}
+
+impl Message for Dimension {
+    fn write(&self, writer: &mut ProtoWriter) {
+        match self {
+            Dimension::Value(value) => writer.int(1, *value),
+            Dimension::Param(param) => writer.string(2, param),
+        }
+    }
+}
+
+struct TensorShape<'a>(&'a [Dimension]);
+
+impl Message for TensorShape<'_> {
+    fn write(&self, writer: &mut ProtoWriter) {
+        for dimension in self.0 {
+            writer.message(1, dimension);
+        }
+    }
+}
+
+struct TensorType<'a> {
+    data_type: DataType,
+    shape: &'a [Dimension],
+}
+
+impl Message for TensorType<'_> {
+    fn write(&self, writer: &mut ProtoWriter) {
+        writer.int(1, self.data_type as i64);
+        writer.message(2, &TensorShape(self.shape));
+    }
+}
+
+struct Type<'a>(TensorType<'a>);
+
+impl Message for Type<'_> {
+    fn write(&self, writer: &mut ProtoWriter) {
+        writer.message(1, &self.0);
+    }
+}
+
+/// Graph input or output (`ValueInfoProto`)
+pub(crate) struct ValueInfo {
+    pub name: String,
+    pub data_type: DataType,
+    pub shape: Vec<Dimension>,
+}
+
+impl Message for ValueInfo {
+    fn write(&self, writer: &mut ProtoWriter) {
+        writer.string(1, &self.name);
+        writer.message(
+            2,
+            &Type(TensorType {
+                data_type: self.data_type,
+                shape: &self.shape,
+            }),
+        );
+    }
+}
+
+/// Computation graph (`GraphProto`)
+pub(crate) struct Graph {
+    pub name: String,
+    pub nodes: Vec<Node>,
+    pub initializers: Vec<TensorProto>,
+    pub inputs: Vec<ValueInfo>,
+    pub outputs: Vec<ValueInfo>,
+}
+
+impl Graph {
+    /// Serializes the graph, releasing the initializers data as it is written
+    fn into_bytes(self) -> Vec<u8> {
+        let mut writer = ProtoWriter::default();
+        for node in &self.nodes {
+            writer.message(1, node);
+        }
+        writer.string(2, &self.name);
+        for initializer in self.initializers {
+            writer.message(5, &initializer);
+        }
+        for input in &self.inputs {
+            writer.message(11, input);
+        }
+        for output in &self.outputs {
+            writer.message(12, output);
+        }
+        writer.buffer
+    }
+}
+
+struct OperatorSetId(i64);
+
+impl Message for OperatorSetId {
+    fn write(&self, writer: &mut ProtoWriter) {
+        // Default (`ai.onnx`) domain
+        writer.string(1, "");
+        writer.int(2, self.0);
+    }
+}
+
+/// ONNX model (`ModelProto`)
+pub(crate) struct Model {
+    pub graph: Graph,
+    pub opset_version: i64,
+}
+
+impl Model {
+    /// Writes the model in the protocol buffers binary format
+    pub(crate) fn write_to<W: Write>(self, output: &mut W) -> Result<(), RustBertError> {
+        let graph = self.graph.into_bytes();
+        if graph.len() > i32::MAX as usize {
+            return Err(RustBertError::ValueError(
+                "The model exceeds the 2GB limit of ONNX files with embedded weights".to_string(),
+            ));
+        }
+        let mut writer = ProtoWriter::default();
+        writer.int(1, IR_VERSION);
+        writer.string(2, "rust-bert");
+        writer.string(3, env!("CARGO_PKG_VERSION"));
+        writer.message(8, &OperatorSetId(self.opset_version));
+        writer.key(7, LENGTH_DELIMITED);
+        writer.varint(graph.len() as u64);
+        output.write_all(&writer.buffer)?;
+        output.write_all(&graph)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_varint_encoding() {
+        let mut writer = ProtoWriter::default();
+        writer.int(1, 150);
+        assert_eq!(writer.buffer, vec![0x08, 0x96, 0x01]);
+
+        let mut writer = ProtoWriter::default();
+        writer.int(3, -1);
+        assert_eq!(
+            writer.buffer,
+            vec![0x18, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]
+        );
+    }
+
+    #[test]
+    fn test_node_encoding() {
+        let node = Node {
+            name: "n".to_string(),
+            op_type: "Relu",
+            inputs: vec!["x".to_string()],
+            outputs: vec!["y".to_string()],
+            attributes: vec![Attribute::int("axis", 1)],
+        };
+        let mut writer = ProtoWriter::default();
+        node.write(&mut writer);
+        let expected = [
+            vec![0x0A, 0x01, b'x', 0x12, 0x01, b'y', 0x1A, 0x01, b'n'],
+            vec![0x22, 0x04, b'R', b'e', b'l', b'u'],
+            vec![
+                0x2A, 0x0B, 0x0A, 0x04, b'a', b'x', b'i', b's', 0x18, 0x01, 0xA0, 0x01, 0x02,
+            ],
+        ]
+        .concat();
+        assert_eq!(writer.buffer, expected);
+    }
+}
@@ -36,6 +36,8 @@
 //! Most pipelines are available for ONNX model checkpoints, including sequence classification, zero-shot classification,
 //! token classification (including named entity recognition and part-of-speech tagging), question answering, text generation, summarization and translation.
 //!
+//! BERT, RoBERTa and DistilBERT Torch models loaded in rust-bert can be exported to ONNX with the `export` module.
+//!
 //! These models use the same configuration and tokenizer files as their Pytorch counterparts when used in a pipeline. The following is
 //! an example of a translation model based on a ONNX export of M2M100:
 //! ```no_run
@@ -109,6 +111,7 @@ pub mod config;
 mod conversion;
 mod decoder;
 mod encoder;
+pub mod export;
 mod models;
 
 pub use encoder::{ONNXEncoder, ONNXEncoderModelOutput};
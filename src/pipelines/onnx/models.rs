@@ -9,6 +9,7 @@ use crate::pipelines::onnx::encoder::ONNXEncoder;
 use crate::{Config, RustBertError};
 
 use crate::pipelines::onnx::conversion;
+use crate::pipelines::onnx::conversion::OrtOutputs;
 use ndarray::IxDyn;
 use ort::tensor::DynOrtTensor;
 use ort::Environment;
@@ -1105,8 +1106,26 @@ impl LanguageGenerator for ONNXConditionalGenerator {}
 
 #[derive(Debug)]
 /// Container used to store key-value cached states for efficient decoding.
+///
+/// The cached states returned by the ONNX decoders read the buffers of the ONNX Runtime outputs in place, and are
+/// only valid while the cache is alive: tensors extracted from `values` must be copied (e.g. with `Tensor::copy`)
+/// to be used after the cache is dropped. Cloning the cache copies its states.
 pub struct ONNXLayerCache {
     pub values: HashMap<String, Tensor>,
+    ort_outputs: Option<OrtOutputs>,
+}
+
+impl Clone for ONNXLayerCache {
+    fn clone(&self) -> Self {
+        ONNXLayerCache {
+            values: self
+                .values
+                .iter()
+                .map(|(name, value)| (name.clone(), value.copy()))
+                .collect(),
+            ort_outputs: None,
+        }
+    }
 }
 
 impl ONNXLayerCache {
@@ -1125,6 +1144,31 @@ impl ONNXLayerCache {
             })
             .collect::<Result<HashMap<String, Tensor>, RustBertError>>()?;
 
-        Ok(ONNXLayerCache { values })
+        Ok(ONNXLayerCache {
+            values,
+            ort_outputs: None,
+        })
+    }
+
+    /// Creates a cache layer reading the cached keys and values from the ONNX model outputs without copy.
+    /// The outputs are owned by the cache and released with it.
+    pub(crate) fn from_shared_ort_output(
+        ort_outputs: OrtOutputs,
+        key_value_names: &HashMap<String, usize>,
+    ) -> Result<ONNXLayerCache, RustBertError> {
+        let values = key_value_names
+            .iter()
+            .filter(|(name, _)| name.contains("key") | name.contains("value"))
+            .map(|(name, pos)| {
+                // SAFETY: the tensors are stored alongside the outputs they read and dropped with them
+                let value = unsafe { ort_outputs.borrow_tensor(*pos)? };
+                Ok((name.to_string(), value))
+            })
+            .collect::<Result<HashMap<String, Tensor>, RustBertError>>()?;
+
+        Ok(ONNXLayerCache {
+            values,
+            ort_outputs: Some(ort_outputs),
+        })
     }
 }
@@ -82,8 +82,11 @@
 //! To run the pipeline for another language, change the POSModel configuration from its default (see the NER pipeline for an illustration).
 
 use crate::common::error::RustBertError;
-use crate::pipelines::token_classification::{TokenClassificationConfig, TokenClassificationModel};
+use crate::pipelines::token_classification::{
+    Token, TokenClassificationConfig, TokenClassificationModel,
+};
 use serde::{Deserialize, Serialize};
+use tch::Device;
 
 use crate::pipelines::common::TokenizerOption;
 #[cfg(feature = "remote")]
@@ -94,7 +97,7 @@ use {
         },
         pipelines::{
             common::{ModelResource, ModelType},
-            token_classification::LabelAggregationOption,
+            token_classification::{LabelAggregationOption, ScoreAggregationOption},
         },
         resources::RemoteResource,
     },
@@ -140,6 +143,10 @@ impl Default for POSConfig {
                 device: Device::cuda_if_available(),
                 label_aggregation_function: LabelAggregationOption::First,
                 batch_size: 64,
+                tagging_scheme: None,
+                constrained_decoding: false,
+                entity_score_aggregation: ScoreAggregationOption::Product,
+                crf_resource: None,
             },
         }
     }
@@ -233,6 +240,26 @@ impl POSModel {
         self.token_classification_model.get_tokenizer_mut()
     }
 
+    /// Casts the model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        self.token_classification_model.half()
+    }
+
+    /// Casts the model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        self.token_classification_model.bfloat16()
+    }
+
+    /// Casts the model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        self.token_classification_model.float()
+    }
+
+    /// Moves the model weights to the target device.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        self.token_classification_model.set_device(device)
+    }
+
     /// Extract entities from a text
     ///
     /// # Arguments
@@ -259,6 +286,34 @@ impl POSModel {
     /// # }
     /// ```
     pub fn predict<S>(&self, input: &[S]) -> Vec<Vec<POSTag>>
+    where
+        S: AsRef<str>,
+    {
+        self.predict_tokens(input)
+            .into_iter()
+            .map(|sequence_tokens| {
+                sequence_tokens
+                    .into_iter()
+                    .map(|token| POSTag {
+                        word: token.text,
+                        score: token.score,
+                        label: token.label,
+                    })
+                    .collect::<Vec<POSTag>>()
+            })
+            .collect::<Vec<Vec<POSTag>>>()
+    }
+
+    /// Extract Part of Speech tags from a text, returned as `Token`s including the word offsets (in characters) in the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to extract Part of Speech tags from.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Vec<Token>>` containing a `Token` for each word of the inputs provided, labelled with its Part of Speech tag
+    pub fn predict_tokens<S>(&self, input: &[S]) -> Vec<Vec<Token>>
     where
         S: AsRef<str>,
     {
@@ -277,14 +332,9 @@ impl POSModel {
                         };
                         token
                     })
-                    .map(|token| POSTag {
-                        word: token.text,
-                        score: token.score,
-                        label: token.label,
-                    })
-                    .collect::<Vec<POSTag>>()
+                    .collect::<Vec<Token>>()
             })
-            .collect::<Vec<Vec<POSTag>>>()
+            .collect::<Vec<Vec<Token>>>()
     }
 
     fn is_punctuation(string: &str) -> bool {
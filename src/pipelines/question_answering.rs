@@ -52,7 +52,8 @@ use crate::fnet::FNetForQuestionAnswering;
 use crate::longformer::LongformerForQuestionAnswering;
 use crate::mobilebert::MobileBertForQuestionAnswering;
 use crate::pipelines::common::{
-    get_device, ConfigOption, ModelResource, ModelType, TokenizerOption,
+    cast_var_store, get_device, set_var_store_device, ConfigOption, ModelResource, ModelType,
+    TokenizerOption,
 };
 use crate::reformer::ReformerForQuestionAnswering;
 use crate::resources::ResourceProvider;
@@ -319,16 +320,25 @@ impl QuestionAnsweringOption {
     /// * `QuestionAnsweringConfig` - Question answering pipeline configuration. The type of model created will be inferred from the
     ///     `ModelResources` (Torch or ONNX) and `ModelType` (Architecture for Torch models) variants provided and
     pub fn new(config: &QuestionAnsweringConfig) -> Result<Self, RustBertError> {
+        Ok(Self::new_with_var_store(config)?.0)
+    }
+
+    /// Instantiate a new question answering model, also returning the `VarStore` holding the weights of Torch models
+    pub(crate) fn new_with_var_store(
+        config: &QuestionAnsweringConfig,
+    ) -> Result<(Self, Option<VarStore>), RustBertError> {
         match config.model_resource {
-            ModelResource::Torch(_) => Self::new_torch(config),
+            ModelResource::Torch(_) => {
+                let (model, var_store) = Self::new_torch(config)?;
+                Ok((model, Some(var_store)))
+            }
             #[cfg(feature = "onnx")]
-            ModelResource::ONNX(_) => Self::new_onnx(config),
+            ModelResource::ONNX(_) => Ok((Self::new_onnx(config)?, None)),
         }
     }
 
-    fn new_torch(config: &QuestionAnsweringConfig) -> Result<Self, RustBertError> {
+    fn new_torch(config: &QuestionAnsweringConfig) -> Result<(Self, VarStore), RustBertError> {
         let device = config.device;
-        let weights_path = config.model_resource.get_torch_local_path()?;
         let mut var_store = VarStore::new(device);
         let model_config = &mut ConfigOption::from_file(
             config.model_type,
@@ -473,8 +483,8 @@ impl QuestionAnsweringOption {
                 "QuestionAnswering not implemented for {model_type:?}!",
             ))),
         }?;
-        var_store.load(weights_path)?;
-        Ok(model)
+        crate::resources::load_weights(&config.model_resource, &mut var_store)?;
+        Ok((model, var_store))
     }
 
     #[cfg(feature = "onnx")]
@@ -612,6 +622,7 @@ pub struct QuestionAnsweringModel {
     max_query_length: usize,
     max_answer_len: usize,
     qa_model: QuestionAnsweringOption,
+    var_store: Option<VarStore>,
     device: Device,
 }
 
@@ -682,7 +693,8 @@ impl QuestionAnsweringModel {
         question_answering_config: QuestionAnsweringConfig,
         tokenizer: TokenizerOption,
     ) -> Result<QuestionAnsweringModel, RustBertError> {
-        let qa_model = QuestionAnsweringOption::new(&question_answering_config)?;
+        let (qa_model, var_store) =
+            QuestionAnsweringOption::new_with_var_store(&question_answering_config)?;
 
         let pad_idx = tokenizer
             .get_pad_id()
@@ -718,6 +730,7 @@ impl QuestionAnsweringModel {
             max_query_length: question_answering_config.max_query_length,
             max_answer_len: question_answering_config.max_answer_length,
             qa_model,
+            var_store,
             device,
         })
     }
@@ -732,6 +745,28 @@ impl QuestionAnsweringModel {
         &mut self.tokenizer
     }
 
+    /// Casts the model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::Half)
+    }
+
+    /// Casts the model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::BFloat16)
+    }
+
+    /// Casts the model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::Float)
+    }
+
+    /// Moves the model weights to the target device, inputs are placed on this device for subsequent predictions.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        set_var_store_device(self.var_store.as_mut(), device)?;
+        self.device = device;
+        Ok(())
+    }
+
     /// Perform extractive question answering given a list of `QaInputs`
     ///
     /// # Arguments
@@ -808,8 +843,8 @@ impl QuestionAnsweringModel {
                     false,
                 );
 
-                let start_logits = start_logits.detach();
-                let end_logits = end_logits.detach();
+                let start_logits = start_logits.detach().to_kind(Float);
+                let end_logits = end_logits.detach().to_kind(Float);
                 let example_index_to_feature_end_position: Vec<(usize, i64)> = batch_features
                     .iter()
                     .enumerate()
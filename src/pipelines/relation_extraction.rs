@@ -0,0 +1,575 @@
+// Copyright 2019 The Google Research Authors (Matching the Blanks)
+// Copyright 2023 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Relation extraction pipeline
+//! Extracts (head entity, relation, tail entity) triples from text. Entities are first recognized by a `NERModel`.
+//! For each candidate pair of entities, markers are inserted around the head and tail entities in the input text
+//! (following [Baldini Soares et al., Matching the Blanks](https://arxiv.org/abs/1906.03158)) and the marked text is
+//! classified by a sequence classification model fine-tuned for relation classification.
+//!
+//! The entity markers must match the markers used during the fine-tuning of the relation classifier. Markers may contain a
+//! `{label}` placeholder that is replaced by the entity label (e.g. typed entity markers `"@ * {label} * "`).
+//!
+//! ```no_run
+//! use rust_bert::pipelines::common::{ModelResource, ModelType};
+//! use rust_bert::pipelines::relation_extraction::{RelationExtractionConfig, RelationExtractionModel};
+//! use rust_bert::pipelines::sequence_classification::SequenceClassificationConfig;
+//! use rust_bert::resources::LocalResource;
+//! use std::path::PathBuf;
+//! # fn main() -> anyhow::Result<()> {
+//! let classification_config = SequenceClassificationConfig::new(
+//!     ModelType::Bert,
+//!     ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from(
+//!         "path/to/rust_model.ot",
+//!     )))),
+//!     LocalResource::from(PathBuf::from("path/to/config.json")),
+//!     LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+//!     None,
+//!     false,
+//!     None,
+//!     None,
+//! );
+//! let config = RelationExtractionConfig::new(Default::default(), classification_config);
+//! let relation_extraction_model = RelationExtractionModel::new(config)?;
+//!
+//! let input = ["Amy lives in Paris, the capital of France."];
+//! let output = relation_extraction_model.predict(&input);
+//! # Ok(())
+//! # }
+//! ```
+//! Output: \
+//! ```no_run
+//! # use rust_bert::pipelines::ner::Entity;
+//! # use rust_bert::pipelines::relation_extraction::Relation;
+//! # use rust_tokenizers::Offset;
+//! # let output =
+//! [[
+//!     Relation {
+//!         head: Entity {
+//!             word: String::from("Amy"),
+//!             score: 0.9986,
+//!             label: String::from("PER"),
+//!             offset: Offset { begin: 0, end: 3 },
+//!         },
+//!         relation: String::from("lives_in"),
+//!         tail: Entity {
+//!             word: String::from("Paris"),
+//!             score: 0.9985,
+//!             label: String::from("LOC"),
+//!             offset: Offset { begin: 13, end: 18 },
+//!         },
+//!         score: 0.9412,
+//!     },
+//!     Relation {
+//!         head: Entity {
+//!             word: String::from("Paris"),
+//!             score: 0.9985,
+//!             label: String::from("LOC"),
+//!             offset: Offset { begin: 13, end: 18 },
+//!         },
+//!         relation: String::from("capital_of"),
+//!         tail: Entity {
+//!             word: String::from("France"),
+//!             score: 0.9991,
+//!             label: String::from("LOC"),
+//!             offset: Offset { begin: 35, end: 41 },
+//!         },
+//!         score: 0.9720,
+//!     },
+//! ]]
+//! # ;
+//! ```
+
+use crate::common::error::RustBertError;
+use crate::pipelines::common::TokenizerOption;
+use crate::pipelines::ner::{Entity, NERModel};
+use crate::pipelines::sequence_classification::{
+    SequenceClassificationConfig, SequenceClassificationModel,
+};
+use crate::pipelines::token_classification::TokenClassificationConfig;
+use serde::{Deserialize, Serialize};
+use tch::Device;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// # Relation generated by a `RelationExtractionModel`
+pub struct Relation {
+    /// Head (subject) entity of the relation
+    pub head: Entity,
+    /// Relation label
+    pub relation: String,
+    /// Tail (object) entity of the relation
+    pub tail: Entity,
+    /// Confidence score of the relation classifier
+    pub score: f64,
+}
+
+#[derive(Debug, Clone)]
+/// # Markers inserted around the head and tail entities
+/// Markers are inserted verbatim in the input text. A `{label}` placeholder in a marker is replaced by the entity label.
+pub struct EntityMarkers {
+    /// Marker inserted before the head entity
+    pub head_start: String,
+    /// Marker inserted after the head entity
+    pub head_end: String,
+    /// Marker inserted before the tail entity
+    pub tail_start: String,
+    /// Marker inserted after the tail entity
+    pub tail_end: String,
+}
+
+impl Default for EntityMarkers {
+    /// Provides the `[E1]`, `[/E1]`, `[E2]` and `[/E2]` entity markers
+    fn default() -> Self {
+        EntityMarkers {
+            head_start: "[E1] ".to_string(),
+            head_end: " [/E1]".to_string(),
+            tail_start: "[E2] ".to_string(),
+            tail_end: " [/E2]".to_string(),
+        }
+    }
+}
+
+/// # Configuration for RelationExtractionModel
+/// Contains the configuration of the entity recognition and relation classification models, and relation candidates filtering options.
+pub struct RelationExtractionConfig {
+    /// `TokenClassificationConfig` defining the named entity recognition model
+    pub ner_config: TokenClassificationConfig,
+    /// `SequenceClassificationConfig` defining the relation classification model
+    pub classification_config: SequenceClassificationConfig,
+    /// Markers inserted around the head and tail entities (default: `[E1]`, `[/E1]`, `[E2]`, `[/E2]`)
+    pub entity_markers: EntityMarkers,
+    /// Label of the relation classifier indicating the absence of relation, filtered from the output (default: `no_relation`)
+    pub no_relation_label: Option<String>,
+    /// Minimum relation score for a relation to be returned (default: 0.5)
+    pub min_score: f64,
+    /// Optional maximum distance (in characters) between the head and tail entities of a candidate pair (default: None)
+    pub max_entity_distance: Option<usize>,
+    /// Optional list of (head label, tail label) entity label pairs to consider as relation candidates. If None, all pairs are scored (default: None)
+    pub entity_label_pairs: Option<Vec<(String, String)>>,
+}
+
+impl RelationExtractionConfig {
+    /// Instantiate a new relation extraction configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `ner_config` - `TokenClassificationConfig` defining the named entity recognition model
+    /// * `classification_config` - `SequenceClassificationConfig` defining the relation classification model
+    pub fn new(
+        ner_config: TokenClassificationConfig,
+        classification_config: SequenceClassificationConfig,
+    ) -> RelationExtractionConfig {
+        RelationExtractionConfig {
+            ner_config,
+            classification_config,
+            entity_markers: EntityMarkers::default(),
+            no_relation_label: Some("no_relation".to_string()),
+            min_score: 0.5,
+            max_entity_distance: None,
+            entity_label_pairs: None,
+        }
+    }
+}
+
+/// # RelationExtractionModel to extract relations between named entities
+pub struct RelationExtractionModel {
+    ner_model: NERModel,
+    relation_classifier: SequenceClassificationModel,
+    entity_markers: EntityMarkers,
+    no_relation_label: Option<String>,
+    min_score: f64,
+    max_entity_distance: Option<usize>,
+    entity_label_pairs: Option<Vec<(String, String)>>,
+}
+
+impl RelationExtractionModel {
+    /// Build a new `RelationExtractionModel`
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - `RelationExtractionConfig` object containing the entity recognition and relation classification configurations
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::relation_extraction::{RelationExtractionConfig, RelationExtractionModel};
+    /// # use rust_bert::pipelines::common::{ModelResource, ModelType};
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationConfig;
+    /// # use rust_bert::resources::LocalResource;
+    /// # use std::path::PathBuf;
+    /// # let classification_config = SequenceClassificationConfig::new(
+    /// #     ModelType::Bert,
+    /// #     ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from("path/to/rust_model.ot")))),
+    /// #     LocalResource::from(PathBuf::from("path/to/config.json")),
+    /// #     LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+    /// #     None,
+    /// #     false,
+    /// #     None,
+    /// #     None,
+    /// # );
+    ///
+    /// let config = RelationExtractionConfig::new(Default::default(), classification_config);
+    /// let relation_extraction_model = RelationExtractionModel::new(config)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(config: RelationExtractionConfig) -> Result<RelationExtractionModel, RustBertError> {
+        let ner_model = NERModel::new(config.ner_config)?;
+        let relation_classifier = SequenceClassificationModel::new(config.classification_config)?;
+        Ok(RelationExtractionModel {
+            ner_model,
+            relation_classifier,
+            entity_markers: config.entity_markers,
+            no_relation_label: config.no_relation_label,
+            min_score: config.min_score,
+            max_entity_distance: config.max_entity_distance,
+            entity_label_pairs: config.entity_label_pairs,
+        })
+    }
+
+    /// Get a reference to the relation classifier tokenizer.
+    pub fn get_tokenizer(&self) -> &TokenizerOption {
+        self.relation_classifier.get_tokenizer()
+    }
+
+    /// Get a mutable reference to the relation classifier tokenizer.
+    pub fn get_tokenizer_mut(&mut self) -> &mut TokenizerOption {
+        self.relation_classifier.get_tokenizer_mut()
+    }
+
+    /// Casts the entity recognition and relation classification model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        self.ner_model.half()?;
+        self.relation_classifier.half()
+    }
+
+    /// Casts the entity recognition and relation classification model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        self.ner_model.bfloat16()?;
+        self.relation_classifier.bfloat16()
+    }
+
+    /// Casts the entity recognition and relation classification model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        self.ner_model.float()?;
+        self.relation_classifier.float()
+    }
+
+    /// Moves the entity recognition and relation classification model weights to the target device.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        self.ner_model.set_device(device)?;
+        self.relation_classifier.set_device(device)
+    }
+
+    /// Extract relations from texts
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to extract relations from.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Vec<Relation>>` containing the extracted relations for each input text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::relation_extraction::{RelationExtractionConfig, RelationExtractionModel};
+    /// # use rust_bert::pipelines::common::{ModelResource, ModelType};
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationConfig;
+    /// # use rust_bert::resources::LocalResource;
+    /// # use std::path::PathBuf;
+    /// # let classification_config = SequenceClassificationConfig::new(
+    /// #     ModelType::Bert,
+    /// #     ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from("path/to/rust_model.ot")))),
+    /// #     LocalResource::from(PathBuf::from("path/to/config.json")),
+    /// #     LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+    /// #     None,
+    /// #     false,
+    /// #     None,
+    /// #     None,
+    /// # );
+    /// # let config = RelationExtractionConfig::new(Default::default(), classification_config);
+    /// let relation_extraction_model = RelationExtractionModel::new(config)?;
+    /// let input = [
+    ///     "Amy lives in Paris, the capital of France.",
+    ///     "Acme Corp was founded by John Smith.",
+    /// ];
+    /// let output = relation_extraction_model.predict(&input);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict<S>(&self, input: &[S]) -> Vec<Vec<Relation>>
+    where
+        S: AsRef<str>,
+    {
+        let entities = self.ner_model.predict_full_entities(input);
+        self.predict_from_entities(input, &entities)
+    }
+
+    /// Extract relations between entities already identified in the input texts
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to extract relations from.
+    /// * `entities` - `&[Vec<Entity>]` Entities found in each of the input texts (e.g. by a `NERModel`), with character offsets.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Vec<Relation>>` containing the extracted relations for each input text
+    pub fn predict_from_entities<S>(
+        &self,
+        input: &[S],
+        entities: &[Vec<Entity>],
+    ) -> Vec<Vec<Relation>>
+    where
+        S: AsRef<str>,
+    {
+        let mut candidates: Vec<(usize, &Entity, &Entity)> = Vec::new();
+        let mut marked_texts: Vec<String> = Vec::new();
+        for (input_index, (text, text_entities)) in input.iter().zip(entities).enumerate() {
+            let chars = text.as_ref().chars().collect::<Vec<char>>();
+            for head in text_entities {
+                for tail in text_entities {
+                    if is_candidate_pair(
+                        head,
+                        tail,
+                        self.max_entity_distance,
+                        self.entity_label_pairs.as_deref(),
+                    ) {
+                        marked_texts.push(mark_entities(&chars, head, tail, &self.entity_markers));
+                        candidates.push((input_index, head, tail));
+                    }
+                }
+            }
+        }
+
+        let mut relations: Vec<Vec<Relation>> = vec![Vec::new(); input.len()];
+        if marked_texts.is_empty() {
+            return relations;
+        }
+        let marked_texts = marked_texts
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<&str>>();
+        let labels = self.relation_classifier.predict(&marked_texts);
+
+        for ((input_index, head, tail), label) in candidates.into_iter().zip(labels) {
+            if (label.score < self.min_score)
+                | (self.no_relation_label.as_deref() == Some(label.text.as_str()))
+            {
+                continue;
+            }
+            relations[input_index].push(Relation {
+                head: head.clone(),
+                relation: label.text,
+                tail: tail.clone(),
+                score: label.score,
+            });
+        }
+        relations
+    }
+}
+
+fn is_candidate_pair(
+    head: &Entity,
+    tail: &Entity,
+    max_entity_distance: Option<usize>,
+    entity_label_pairs: Option<&[(String, String)]>,
+) -> bool {
+    // Overlapping (or identical) entities cannot be marked independently
+    if (head.offset.begin < tail.offset.end) & (tail.offset.begin < head.offset.end) {
+        return false;
+    }
+    if let Some(max_entity_distance) = max_entity_distance {
+        let distance = if head.offset.end <= tail.offset.begin {
+            tail.offset.begin - head.offset.end
+        } else {
+            head.offset.begin - tail.offset.end
+        };
+        if distance as usize > max_entity_distance {
+            return false;
+        }
+    }
+    match entity_label_pairs {
+        Some(entity_label_pairs) => entity_label_pairs.iter().any(|(head_label, tail_label)| {
+            (head_label == &head.label) & (tail_label == &tail.label)
+        }),
+        None => true,
+    }
+}
+
+fn mark_entities(
+    chars: &[char],
+    head: &Entity,
+    tail: &Entity,
+    entity_markers: &EntityMarkers,
+) -> String {
+    let markers = [
+        (
+            head.offset.begin,
+            true,
+            entity_markers.head_start.as_str(),
+            head,
+        ),
+        (
+            head.offset.end,
+            false,
+            entity_markers.head_end.as_str(),
+            head,
+        ),
+        (
+            tail.offset.begin,
+            true,
+            entity_markers.tail_start.as_str(),
+            tail,
+        ),
+        (
+            tail.offset.end,
+            false,
+            entity_markers.tail_end.as_str(),
+            tail,
+        ),
+    ];
+    // For adjacent entities, the end marker of the first entity is inserted before the start marker of the second
+    let mut marker_positions = markers.iter().collect::<Vec<_>>();
+    marker_positions.sort_by_key(|(position, is_start, _, _)| (*position, *is_start));
+
+    let mut marked_text = String::with_capacity(chars.len() + 32);
+    let mut cursor = 0usize;
+    for (position, _, marker, entity) in marker_positions {
+        let position = (*position as usize).min(chars.len());
+        marked_text.extend(&chars[cursor..position]);
+        marked_text.push_str(&marker.replace("{label}", &entity.label));
+        cursor = position;
+    }
+    marked_text.extend(&chars[cursor..]);
+    marked_text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pipelines::common::{ModelResource, ModelType};
+    use crate::resources::LocalResource;
+    use rust_tokenizers::Offset;
+    use std::path::PathBuf;
+
+    fn entity(label: &str, begin: u32, end: u32) -> Entity {
+        Entity {
+            word: String::new(),
+            score: 1.0,
+            label: label.to_string(),
+            offset: Offset { begin, end },
+        }
+    }
+
+    #[test]
+    #[ignore] // no need to run, compilation is enough to verify it is Send
+    fn test() {
+        let classification_config = SequenceClassificationConfig::new(
+            ModelType::Bert,
+            ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from(
+                "path/to/rust_model.ot",
+            )))),
+            LocalResource::from(PathBuf::from("path/to/config.json")),
+            LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+            None,
+            false,
+            None,
+            None,
+        );
+        let config = RelationExtractionConfig::new(Default::default(), classification_config);
+        let _: Box<dyn Send> = Box::new(RelationExtractionModel::new(config));
+    }
+
+    #[test]
+    fn test_mark_entities() {
+        let chars = "Amy lives in Paris".chars().collect::<Vec<char>>();
+        let markers = EntityMarkers::default();
+        let amy = entity("PER", 0, 3);
+        let paris = entity("LOC", 13, 18);
+
+        assert_eq!(
+            mark_entities(&chars, &amy, &paris, &markers),
+            "[E1] Amy [/E1] lives in [E2] Paris [/E2]"
+        );
+        // Reversed pair: the tail entity appears first in the text
+        assert_eq!(
+            mark_entities(&chars, &paris, &amy, &markers),
+            "[E2] Amy [/E2] lives in [E1] Paris [/E1]"
+        );
+    }
+
+    #[test]
+    fn test_mark_adjacent_entities() {
+        let chars = "NewYork".chars().collect::<Vec<char>>();
+        let markers = EntityMarkers {
+            head_start: "<h:{label}>".to_string(),
+            head_end: "</h>".to_string(),
+            tail_start: "<t:{label}>".to_string(),
+            tail_end: "</t>".to_string(),
+        };
+        let new = entity("A", 0, 3);
+        let york = entity("B", 3, 7);
+
+        assert_eq!(
+            mark_entities(&chars, &new, &york, &markers),
+            "<h:A>New</h><t:B>York</t>"
+        );
+        assert_eq!(
+            mark_entities(&chars, &york, &new, &markers),
+            "<t:A>New</t><h:B>York</h>"
+        );
+    }
+
+    #[test]
+    fn test_is_candidate_pair() {
+        let amy = entity("PER", 0, 3);
+        let paris = entity("LOC", 13, 18);
+        let adjacent = entity("LOC", 18, 20);
+        let overlapping = entity("LOC", 15, 20);
+
+        // Adjacent and reversed spans are valid candidates
+        assert!(is_candidate_pair(&amy, &paris, None, None));
+        assert!(is_candidate_pair(&paris, &amy, None, None));
+        assert!(is_candidate_pair(&paris, &adjacent, None, None));
+        assert!(is_candidate_pair(&adjacent, &paris, None, None));
+        // Overlapping and identical spans are not
+        assert!(!is_candidate_pair(&paris, &overlapping, None, None));
+        assert!(!is_candidate_pair(&overlapping, &paris, None, None));
+        assert!(!is_candidate_pair(&paris, &paris, None, None));
+
+        // Distance filter, in both directions
+        assert!(is_candidate_pair(&amy, &paris, Some(10), None));
+        assert!(!is_candidate_pair(&amy, &paris, Some(9), None));
+        assert!(!is_candidate_pair(&paris, &amy, Some(9), None));
+        assert!(is_candidate_pair(&paris, &adjacent, Some(0), None));
+
+        // Entity label pairs filter
+        let label_pairs = vec![("PER".to_string(), "LOC".to_string())];
+        assert!(is_candidate_pair(
+            &amy,
+            &paris,
+            None,
+            Some(label_pairs.as_slice())
+        ));
+        assert!(!is_candidate_pair(
+            &paris,
+            &amy,
+            None,
+            Some(label_pairs.as_slice())
+        ));
+    }
+}
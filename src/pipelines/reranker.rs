@@ -0,0 +1,390 @@
+// Copyright 2019-present, the HuggingFace Inc. team, The Google AI Language Team and Facebook, Inc.
+// Copyright 2019-2023 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Reranking pipeline
+//! Scores and sorts candidate passages by relevance to a query using a cross-encoder.
+//! Contrary to the bi-encoder `SentenceEmbeddingsModel`, each (query, passage) pair is encoded jointly
+//! and scored by a sequence classification head, which is slower but more accurate. This pipeline is typically used
+//! as the second stage of a semantic search, re-ordering the top candidates retrieved with sentence embeddings.
+//!
+//! Cross-encoders with a single output label (e.g. MS MARCO cross-encoders) return the relevance logit directly.
+//! For cross-encoders with two output labels, the relevance score is the log-odds of the second (relevant) label.
+//! Any model supported by the sequence classification pipeline may be used.
+//!
+//! ```no_run
+//! use rust_bert::pipelines::common::{ModelResource, ModelType};
+//! use rust_bert::pipelines::reranker::{RerankerConfig, RerankerModel};
+//! use rust_bert::resources::LocalResource;
+//! use std::path::PathBuf;
+//! # fn main() -> anyhow::Result<()> {
+//! let config = RerankerConfig::new(
+//!     ModelType::Bert,
+//!     ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from(
+//!         "path/to/rust_model.ot",
+//!     )))),
+//!     LocalResource::from(PathBuf::from("path/to/config.json")),
+//!     LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+//!     None,
+//!     true,
+//!     None,
+//!     None,
+//! );
+//! let reranker_model = RerankerModel::new(config)?;
+//!
+//! let query = "How many people live in Berlin?";
+//! let passages = [
+//!     "New York City is famous for the Metropolitan Museum of Art.",
+//!     "Berlin has a population of 3,520,031 registered inhabitants in an area of 891.82 square kilometers.",
+//! ];
+//! let output = reranker_model.rerank(query, &passages, 32)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Output: \
+//! ```no_run
+//! # use rust_bert::pipelines::reranker::RankedPassage;
+//! # let output =
+//! [
+//!     RankedPassage { text: String::from("Berlin has a population of 3,520,031 registered inhabitants in an area of 891.82 square kilometers."), score: 8.8459, probability: 0.9999, index: 1 },
+//!     RankedPassage { text: String::from("New York City is famous for the Metropolitan Museum of Art."), score: -11.2453, probability: 0.0000, index: 0 },
+//! ]
+//! # ;
+//! ```
+use crate::common::error::RustBertError;
+use crate::pipelines::common::{
+    cast_var_store, get_device, set_var_store_device, ConfigOption, TokenizerOption,
+};
+use crate::pipelines::sequence_classification::{
+    SequenceClassificationConfig, SequenceClassificationOption,
+};
+use rust_tokenizers::tokenizer::TruncationStrategy;
+use rust_tokenizers::TokenizedInput;
+use serde::{Deserialize, Serialize};
+use std::cmp::{min, Ordering};
+use tch::nn::VarStore;
+use tch::{no_grad, Device, Kind, Tensor};
+
+/// # Configuration for RerankerModel
+/// Identical to `SequenceClassificationConfig`: the cross-encoder is loaded as a sequence classification model.
+pub type RerankerConfig = SequenceClassificationConfig;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+/// # Passage scored by a `RerankerModel`
+pub struct RankedPassage {
+    /// Passage String representation
+    pub text: String,
+    /// Raw relevance score (logit) returned by the cross-encoder
+    pub score: f64,
+    /// Relevance probability (sigmoid of the raw score)
+    pub probability: f64,
+    /// Position of the passage in the list of candidates provided
+    pub index: usize,
+}
+
+/// # RerankerModel to sort candidate passages by relevance to a query
+pub struct RerankerModel {
+    tokenizer: TokenizerOption,
+    cross_encoder: SequenceClassificationOption,
+    var_store: Option<VarStore>,
+    device: Device,
+    max_length: usize,
+}
+
+impl RerankerModel {
+    /// Build a new `RerankerModel`
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - `RerankerConfig` object containing the resource references (model, vocabulary, configuration) and device placement (CPU/GPU)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::common::{ModelResource, ModelType};
+    /// use rust_bert::pipelines::reranker::{RerankerConfig, RerankerModel};
+    /// use rust_bert::resources::LocalResource;
+    /// use std::path::PathBuf;
+    ///
+    /// let config = RerankerConfig::new(
+    ///     ModelType::Bert,
+    ///     ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from(
+    ///         "path/to/rust_model.ot",
+    ///     )))),
+    ///     LocalResource::from(PathBuf::from("path/to/config.json")),
+    ///     LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+    ///     None,
+    ///     true,
+    ///     None,
+    ///     None,
+    /// );
+    /// let model = RerankerModel::new(config)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(config: RerankerConfig) -> Result<RerankerModel, RustBertError> {
+        let vocab_path = config.vocab_resource.get_local_path()?;
+        let merges_path = config
+            .merges_resource
+            .as_ref()
+            .map(|resource| resource.get_local_path())
+            .transpose()?;
+
+        let tokenizer = TokenizerOption::from_file(
+            config.model_type,
+            vocab_path.to_str().unwrap(),
+            merges_path.as_deref().map(|path| path.to_str().unwrap()),
+            config.lower_case,
+            config.strip_accents,
+            config.add_prefix_space,
+        )?;
+        Self::new_with_tokenizer(config, tokenizer)
+    }
+
+    /// Build a new `RerankerModel` with a provided tokenizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - `RerankerConfig` object containing the resource references (model, vocabulary, configuration) and device placement (CPU/GPU)
+    /// * `tokenizer` - `TokenizerOption` tokenizer to use for reranking.
+    pub fn new_with_tokenizer(
+        config: RerankerConfig,
+        tokenizer: TokenizerOption,
+    ) -> Result<RerankerModel, RustBertError> {
+        let config_path = config.config_resource.get_local_path()?;
+        let (cross_encoder, var_store) = SequenceClassificationOption::new_with_var_store(&config)?;
+
+        let model_config = ConfigOption::from_file(config.model_type, config_path);
+        let max_length = model_config
+            .get_max_len()
+            .map(|v| v as usize)
+            .unwrap_or(usize::MAX);
+        let device = get_device(config.model_resource, config.device);
+        Ok(RerankerModel {
+            tokenizer,
+            cross_encoder,
+            var_store,
+            device,
+            max_length,
+        })
+    }
+
+    /// Get a reference to the model tokenizer.
+    pub fn get_tokenizer(&self) -> &TokenizerOption {
+        &self.tokenizer
+    }
+
+    /// Get a mutable reference to the model tokenizer.
+    pub fn get_tokenizer_mut(&mut self) -> &mut TokenizerOption {
+        &mut self.tokenizer
+    }
+
+    /// Casts the model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::Half)
+    }
+
+    /// Casts the model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::BFloat16)
+    }
+
+    /// Casts the model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::Float)
+    }
+
+    /// Moves the model weights to the target device, inputs are placed on this device for subsequent predictions.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        set_var_store_device(self.var_store.as_mut(), device)?;
+        self.device = device;
+        Ok(())
+    }
+
+    fn prepare_for_model(
+        &self,
+        text_pair_list: &[(&str, &str)],
+    ) -> Result<(Tensor, Tensor, Tensor), RustBertError> {
+        if text_pair_list.is_empty() {
+            return Err(RustBertError::ValueError(
+                "Got empty iterator as input".to_string(),
+            ));
+        }
+        let pad_id = self
+            .tokenizer
+            .get_pad_id()
+            .expect("The Tokenizer used for reranking should contain a PAD id");
+        let tokenized_input: Vec<TokenizedInput> = self.tokenizer.encode_pair_list(
+            text_pair_list,
+            self.max_length,
+            &TruncationStrategy::LongestFirst,
+            0,
+        );
+        let (input_ids, token_type_ids) =
+            self.tokenizer
+                .pad_tokenized_input(tokenized_input, false, self.device);
+        let mask = input_ids.ne(pad_id).to_kind(Kind::Int64);
+
+        Ok((input_ids, mask, token_type_ids))
+    }
+
+    /// Computes the raw relevance scores for a list of (query, passage) pairs, in the order provided.
+    ///
+    /// # Arguments
+    ///
+    /// * `text_pair_list` - `&[(&str, &str)]` (query, passage) pairs to score.
+    /// * `batch_size` - maximum number of pairs scored in a single forward pass.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<f64>, RustBertError>` containing the raw relevance score (logit) for each pair.
+    pub fn score_pairs(
+        &self,
+        text_pair_list: &[(&str, &str)],
+        batch_size: usize,
+    ) -> Result<Vec<f64>, RustBertError> {
+        let mut scores: Vec<f64> = Vec::with_capacity(text_pair_list.len());
+        let batch_size = batch_size.max(1);
+        let mut start = 0usize;
+
+        while start < text_pair_list.len() {
+            let end = start + min(text_pair_list.len() - start, batch_size);
+            let (input_ids, mask, token_type_ids) =
+                self.prepare_for_model(&text_pair_list[start..end])?;
+            start = end;
+
+            let logits = no_grad(|| {
+                self.cross_encoder.forward_t(
+                    Some(&input_ids),
+                    Some(&mask),
+                    Some(&token_type_ids),
+                    None,
+                    None,
+                    false,
+                )
+            })
+            .to_kind(Kind::Float)
+            .to(Device::Cpu);
+
+            let logits_shape = logits.size();
+            let batch_scores = match logits_shape.last().copied() {
+                Some(1) => logits.select(-1, 0),
+                Some(2) => logits.select(-1, 1) - logits.select(-1, 0),
+                _ => {
+                    return Err(RustBertError::InvalidConfigurationError(format!(
+                        "Reranking requires a cross-encoder with 1 or 2 output labels, got logits of shape {logits_shape:?}",
+                    )));
+                }
+            };
+            scores.extend(batch_scores.iter::<f64>()?);
+        }
+        Ok(scores)
+    }
+
+    /// Score the candidate passages for a query and sort them by decreasing relevance.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - `&str` query the passages are ranked against.
+    /// * `passages` - `&[&str]` candidate passages to rank.
+    /// * `batch_size` - maximum number of (query, passage) pairs scored in a single forward pass.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<RankedPassage>, RustBertError>` containing a `RankedPassage` for each candidate, sorted by decreasing relevance.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::reranker::{RerankerConfig, RerankerModel};
+    /// # use rust_bert::pipelines::common::{ModelResource, ModelType};
+    /// # use rust_bert::resources::LocalResource;
+    /// # use std::path::PathBuf;
+    /// # let config = RerankerConfig::new(
+    /// #     ModelType::Bert,
+    /// #     ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from("path/to/rust_model.ot")))),
+    /// #     LocalResource::from(PathBuf::from("path/to/config.json")),
+    /// #     LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+    /// #     None,
+    /// #     true,
+    /// #     None,
+    /// #     None,
+    /// # );
+    /// let reranker_model = RerankerModel::new(config)?;
+    /// let query = "How many people live in Berlin?";
+    /// let passages = [
+    ///     "New York City is famous for the Metropolitan Museum of Art.",
+    ///     "Berlin has a population of 3,520,031 registered inhabitants.",
+    /// ];
+    /// let output = reranker_model.rerank(query, &passages, 32)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn rerank<'a, S>(
+        &self,
+        query: &str,
+        passages: S,
+        batch_size: usize,
+    ) -> Result<Vec<RankedPassage>, RustBertError>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        let passages = passages.as_ref();
+        let text_pair_list = passages
+            .iter()
+            .map(|passage| (query, *passage))
+            .collect::<Vec<(&str, &str)>>();
+        let scores = self.score_pairs(&text_pair_list, batch_size)?;
+
+        let mut output = passages
+            .iter()
+            .zip(scores)
+            .enumerate()
+            .map(|(index, (text, score))| RankedPassage {
+                text: text.to_string(),
+                score,
+                probability: 1.0 / (1.0 + (-score).exp()),
+                index,
+            })
+            .collect::<Vec<RankedPassage>>();
+        output.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pipelines::common::{ModelResource, ModelType};
+    use crate::resources::LocalResource;
+    use std::path::PathBuf;
+
+    #[test]
+    #[ignore] // no need to run, compilation is enough to verify it is Send
+    fn test() {
+        let config = RerankerConfig::new(
+            ModelType::Bert,
+            ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from(
+                "path/to/rust_model.ot",
+            )))),
+            LocalResource::from(PathBuf::from("path/to/config.json")),
+            LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+            None,
+            true,
+            None,
+            None,
+        );
+        let _: Box<dyn Send> = Box::new(RerankerModel::new(config));
+    }
+}
@@ -3,9 +3,13 @@ use std::path::PathBuf;
 use serde::Deserialize;
 use tch::Device;
 
-use crate::pipelines::common::ModelType;
+#[cfg(feature = "onnx")]
+use crate::pipelines::common::ONNXModelResources;
+use crate::pipelines::common::{ModelResource, ModelType};
 use crate::pipelines::sentence_embeddings::{
     SentenceEmbeddingsConfig, SentenceEmbeddingsModel, SentenceEmbeddingsModulesConfig,
+    SentenceEmbeddingsPrompts, SentenceEmbeddingsTransformersConfig,
+    SentenceEmbeddingsWindowConfig,
 };
 use crate::{Config, RustBertError};
 
@@ -21,6 +25,9 @@ use crate::{
 /// (configuration and weights).
 pub struct SentenceEmbeddingsBuilder<T> {
     device: Device,
+    prompts: Option<SentenceEmbeddingsPrompts>,
+    truncate_dim: Option<usize>,
+    long_text_windows: Option<SentenceEmbeddingsWindowConfig>,
     inner: T,
 }
 
@@ -29,6 +36,30 @@ impl<T> SentenceEmbeddingsBuilder<T> {
         self.device = device;
         self
     }
+
+    /// Sets the prompts prepended to queries and passages (e.g. `"query: "` and `"passage: "`
+    /// for E5 models), overriding the prompts read from the model configuration.
+    pub fn with_prompts(mut self, prompts: SentenceEmbeddingsPrompts) -> Self {
+        self.prompts = Some(prompts);
+        self
+    }
+
+    /// Truncates the output embeddings to their first `truncate_dim` dimensions (re-normalized),
+    /// for models trained with a Matryoshka representation learning objective.
+    pub fn with_truncate_dim(mut self, truncate_dim: usize) -> Self {
+        self.truncate_dim = Some(truncate_dim);
+        self
+    }
+
+    /// Splits texts longer than the maximum sequence length of the model into overlapping windows,
+    /// aggregating the window embeddings instead of truncating the texts.
+    pub fn with_long_text_windows(
+        mut self,
+        windows_config: SentenceEmbeddingsWindowConfig,
+    ) -> Self {
+        self.long_text_windows = Some(windows_config);
+        self
+    }
 }
 
 pub struct Local {
@@ -46,6 +77,9 @@ impl SentenceEmbeddingsBuilder<Local> {
     pub fn local<P: Into<PathBuf>>(model_dir: P) -> Self {
         Self {
             device: Device::cuda_if_available(),
+            prompts: None,
+            truncate_dim: None,
+            long_text_windows: None,
             inner: Local {
                 model_dir: model_dir.into(),
             },
@@ -61,6 +95,23 @@ impl SentenceEmbeddingsBuilder<Local> {
         let transformer_config = model_dir.join("config.json");
         let transformer_type = ModelConfig::from_file(&transformer_config).model_type;
         let transformer_weights = model_dir.join("rust_model.ot");
+        // Encoders exported to ONNX (e.g. using Optimum) are used if no Torch weights are available
+        #[cfg(feature = "onnx")]
+        let transformer_weights = match [
+            model_dir.join("model.onnx"),
+            model_dir.join("onnx").join("model.onnx"),
+        ]
+        .iter()
+        .find(|path| !transformer_weights.is_file() && path.is_file())
+        {
+            Some(onnx_weights) => ModelResource::ONNX(ONNXModelResources {
+                encoder_resource: Some(onnx_weights.clone().into()),
+                ..Default::default()
+            }),
+            None => ModelResource::Torch(transformer_weights.into()),
+        };
+        #[cfg(not(feature = "onnx"))]
+        let transformer_weights = ModelResource::Torch(transformer_weights.into());
 
         let pooling_config = model_dir
             .join(&modules.pooling_module().path)
@@ -86,6 +137,9 @@ impl SentenceEmbeddingsBuilder<Local> {
             ),
             ModelType::Albert => (model_dir.join("spiece.model"), None),
             ModelType::T5 => (model_dir.join("spiece.model"), None),
+            ModelType::XLMRoberta => (model_dir.join("sentencepiece.bpe.model"), None),
+            ModelType::DebertaV2 => (model_dir.join("spm.model"), None),
+            ModelType::MPNet => (model_dir.join("vocab.txt"), None),
             _ => {
                 return Err(RustBertError::InvalidConfigurationError(format!(
                     "Unsupported transformer model {transformer_type:?} for Sentence Embeddings",
@@ -93,11 +147,25 @@ impl SentenceEmbeddingsBuilder<Local> {
             }
         };
 
+        let prompts = match self.prompts {
+            Some(prompts) => prompts,
+            None => {
+                let sentence_transformers_config =
+                    model_dir.join("config_sentence_transformers.json");
+                if sentence_transformers_config.is_file() {
+                    SentenceEmbeddingsTransformersConfig::from_file(sentence_transformers_config)
+                        .prompts
+                } else {
+                    SentenceEmbeddingsPrompts::default()
+                }
+            }
+        };
+
         let config = SentenceEmbeddingsConfig {
             modules_config_resource: modules_config.into(),
             transformer_type,
             transformer_config_resource: transformer_config.into(),
-            transformer_weights_resource: transformer_weights.into(),
+            transformer_weights_resource: transformer_weights,
             pooling_config_resource: pooling_config.into(),
             dense_config_resource: dense_config.map(|r| r.into()),
             dense_weights_resource: dense_weights.map(|r| r.into()),
@@ -105,10 +173,14 @@ impl SentenceEmbeddingsBuilder<Local> {
             tokenizer_config_resource: tokenizer_config.into(),
             tokenizer_vocab_resource: tokenizer_vocab.into(),
             tokenizer_merges_resource: tokenizer_merges.map(|r| r.into()),
+            prompts,
             device: self.device,
         };
 
-        SentenceEmbeddingsModel::new(config)
+        let mut model = SentenceEmbeddingsModel::new(config)?;
+        model.set_truncate_dim(self.truncate_dim)?;
+        model.set_long_text_windows(self.long_text_windows);
+        Ok(model)
     }
 }
 
@@ -122,6 +194,9 @@ impl SentenceEmbeddingsBuilder<Remote> {
     pub fn remote(model_type: SentenceEmbeddingsModelType) -> Self {
         Self {
             device: Device::cuda_if_available(),
+            prompts: None,
+            truncate_dim: None,
+            long_text_windows: None,
             inner: Remote {
                 config: SentenceEmbeddingsConfig::from(model_type),
             },
@@ -139,7 +214,17 @@ impl SentenceEmbeddingsBuilder<Remote> {
     }
 
     pub fn transformer_weights(mut self, resource: RemoteResource) -> Self {
-        self.inner.config.transformer_weights_resource = Box::new(resource);
+        self.inner.config.transformer_weights_resource = ModelResource::Torch(Box::new(resource));
+        self
+    }
+
+    /// Replaces the transformer weights by an encoder exported to ONNX
+    #[cfg(feature = "onnx")]
+    pub fn transformer_onnx_weights(mut self, resource: RemoteResource) -> Self {
+        self.inner.config.transformer_weights_resource = ModelResource::ONNX(ONNXModelResources {
+            encoder_resource: Some(Box::new(resource)),
+            ..Default::default()
+        });
         self
     }
 
@@ -180,6 +265,12 @@ impl SentenceEmbeddingsBuilder<Remote> {
 
     pub fn create_model(mut self) -> Result<SentenceEmbeddingsModel, RustBertError> {
         self.inner.config.device = self.device;
-        SentenceEmbeddingsModel::new(self.inner.config)
+        if let Some(prompts) = self.prompts {
+            self.inner.config.prompts = prompts;
+        }
+        let mut model = SentenceEmbeddingsModel::new(self.inner.config)?;
+        model.set_truncate_dim(self.truncate_dim)?;
+        model.set_long_text_windows(self.long_text_windows);
+        Ok(model)
     }
 }
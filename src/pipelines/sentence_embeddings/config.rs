@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use tch::Device;
 
-use crate::pipelines::common::ModelType;
+use crate::pipelines::common::{ModelResource, ModelType};
 use crate::resources::ResourceProvider;
 use crate::{Config, RustBertError};
 
@@ -10,6 +10,7 @@ use crate::{
     albert::{AlbertConfigResources, AlbertModelResources, AlbertVocabResources},
     bert::{BertConfigResources, BertModelResources, BertVocabResources},
     distilbert::{DistilBertConfigResources, DistilBertModelResources, DistilBertVocabResources},
+    mpnet::{MPNetConfigResources, MPNetModelResources, MPNetVocabResources},
     pipelines::sentence_embeddings::resources::{
         SentenceEmbeddingsConfigResources, SentenceEmbeddingsModelType,
         SentenceEmbeddingsModulesConfigResources, SentenceEmbeddingsPoolingConfigResources,
@@ -37,8 +38,8 @@ pub struct SentenceEmbeddingsConfig {
     pub transformer_type: ModelType,
     /// Transformer model configuration resource
     pub transformer_config_resource: Box<dyn ResourceProvider + Send>,
-    /// Transformer weights resource
-    pub transformer_weights_resource: Box<dyn ResourceProvider + Send>,
+    /// Transformer weights resource (Torch weights or ONNX encoder)
+    pub transformer_weights_resource: ModelResource,
     /// Pooling layer configuration resource
     pub pooling_config_resource: Box<dyn ResourceProvider + Send>,
     /// Optional dense layer configuration resource
@@ -53,6 +54,8 @@ pub struct SentenceEmbeddingsConfig {
     pub tokenizer_vocab_resource: Box<dyn ResourceProvider + Send>,
     /// Optional transformer's tokenizer merges resource
     pub tokenizer_merges_resource: Option<Box<dyn ResourceProvider + Send>>,
+    /// Prompts prepended to queries and passages for instruction-based models (e.g. E5, BGE)
+    pub prompts: SentenceEmbeddingsPrompts,
     /// Device to place the transformer model on
     pub device: Device,
 }
@@ -69,9 +72,9 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                 transformer_config_resource: Box::new(RemoteResource::from_pretrained(
                     DistilBertConfigResources::DISTILUSE_BASE_MULTILINGUAL_CASED,
                 )),
-                transformer_weights_resource: Box::new(RemoteResource::from_pretrained(
+                transformer_weights_resource: ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
                     DistilBertModelResources::DISTILUSE_BASE_MULTILINGUAL_CASED,
-                )),
+                ))),
                 pooling_config_resource: Box::new(RemoteResource::from_pretrained(
                     SentenceEmbeddingsPoolingConfigResources::DISTILUSE_BASE_MULTILINGUAL_CASED,
                 )),
@@ -91,6 +94,7 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                     DistilBertVocabResources::DISTILUSE_BASE_MULTILINGUAL_CASED,
                 )),
                 tokenizer_merges_resource: None,
+                prompts: SentenceEmbeddingsPrompts::default(),
                 device: Device::cuda_if_available(),
             },
 
@@ -102,9 +106,9 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                 transformer_config_resource: Box::new(RemoteResource::from_pretrained(
                     BertConfigResources::BERT_BASE_NLI_MEAN_TOKENS,
                 )),
-                transformer_weights_resource: Box::new(RemoteResource::from_pretrained(
+                transformer_weights_resource: ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
                     BertModelResources::BERT_BASE_NLI_MEAN_TOKENS,
-                )),
+                ))),
                 pooling_config_resource: Box::new(RemoteResource::from_pretrained(
                     SentenceEmbeddingsPoolingConfigResources::BERT_BASE_NLI_MEAN_TOKENS,
                 )),
@@ -120,6 +124,7 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                     BertVocabResources::BERT_BASE_NLI_MEAN_TOKENS,
                 )),
                 tokenizer_merges_resource: None,
+                prompts: SentenceEmbeddingsPrompts::default(),
                 device: Device::cuda_if_available(),
             },
 
@@ -131,9 +136,9 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                 transformer_config_resource: Box::new(RemoteResource::from_pretrained(
                     BertConfigResources::ALL_MINI_LM_L12_V2,
                 )),
-                transformer_weights_resource: Box::new(RemoteResource::from_pretrained(
+                transformer_weights_resource: ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
                     BertModelResources::ALL_MINI_LM_L12_V2,
-                )),
+                ))),
                 pooling_config_resource: Box::new(RemoteResource::from_pretrained(
                     SentenceEmbeddingsPoolingConfigResources::ALL_MINI_LM_L12_V2,
                 )),
@@ -149,6 +154,7 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                     BertVocabResources::ALL_MINI_LM_L12_V2,
                 )),
                 tokenizer_merges_resource: None,
+                prompts: SentenceEmbeddingsPrompts::default(),
                 device: Device::cuda_if_available(),
             },
 
@@ -160,9 +166,9 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                 transformer_config_resource: Box::new(RemoteResource::from_pretrained(
                     BertConfigResources::ALL_MINI_LM_L6_V2,
                 )),
-                transformer_weights_resource: Box::new(RemoteResource::from_pretrained(
+                transformer_weights_resource: ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
                     BertModelResources::ALL_MINI_LM_L6_V2,
-                )),
+                ))),
                 pooling_config_resource: Box::new(RemoteResource::from_pretrained(
                     SentenceEmbeddingsPoolingConfigResources::ALL_MINI_LM_L6_V2,
                 )),
@@ -178,6 +184,7 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                     BertVocabResources::ALL_MINI_LM_L6_V2,
                 )),
                 tokenizer_merges_resource: None,
+                prompts: SentenceEmbeddingsPrompts::default(),
                 device: Device::cuda_if_available(),
             },
 
@@ -189,9 +196,9 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                 transformer_config_resource: Box::new(RemoteResource::from_pretrained(
                     RobertaConfigResources::ALL_DISTILROBERTA_V1,
                 )),
-                transformer_weights_resource: Box::new(RemoteResource::from_pretrained(
+                transformer_weights_resource: ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
                     RobertaModelResources::ALL_DISTILROBERTA_V1,
-                )),
+                ))),
                 pooling_config_resource: Box::new(RemoteResource::from_pretrained(
                     SentenceEmbeddingsPoolingConfigResources::ALL_DISTILROBERTA_V1,
                 )),
@@ -209,6 +216,7 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                 tokenizer_merges_resource: Some(Box::new(RemoteResource::from_pretrained(
                     RobertaMergesResources::ALL_DISTILROBERTA_V1,
                 ))),
+                prompts: SentenceEmbeddingsPrompts::default(),
                 device: Device::cuda_if_available(),
             },
 
@@ -220,9 +228,9 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                 transformer_config_resource: Box::new(RemoteResource::from_pretrained(
                     AlbertConfigResources::PARAPHRASE_ALBERT_SMALL_V2,
                 )),
-                transformer_weights_resource: Box::new(RemoteResource::from_pretrained(
+                transformer_weights_resource: ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
                     AlbertModelResources::PARAPHRASE_ALBERT_SMALL_V2,
-                )),
+                ))),
                 pooling_config_resource: Box::new(RemoteResource::from_pretrained(
                     SentenceEmbeddingsPoolingConfigResources::PARAPHRASE_ALBERT_SMALL_V2,
                 )),
@@ -238,6 +246,7 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                     AlbertVocabResources::PARAPHRASE_ALBERT_SMALL_V2,
                 )),
                 tokenizer_merges_resource: None,
+                prompts: SentenceEmbeddingsPrompts::default(),
                 device: Device::cuda_if_available(),
             },
 
@@ -249,9 +258,9 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                 transformer_config_resource: Box::new(RemoteResource::from_pretrained(
                     T5ConfigResources::SENTENCE_T5_BASE,
                 )),
-                transformer_weights_resource: Box::new(RemoteResource::from_pretrained(
+                transformer_weights_resource: ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
                     T5ModelResources::SENTENCE_T5_BASE,
-                )),
+                ))),
                 pooling_config_resource: Box::new(RemoteResource::from_pretrained(
                     SentenceEmbeddingsPoolingConfigResources::SENTENCE_T5_BASE,
                 )),
@@ -271,8 +280,71 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                     T5VocabResources::SENTENCE_T5_BASE,
                 )),
                 tokenizer_merges_resource: None,
+                prompts: SentenceEmbeddingsPrompts::default(),
+                device: Device::cuda_if_available(),
+            },
+
+            SentenceEmbeddingsModelType::AllMpnetBaseV2 => SentenceEmbeddingsConfig {
+                modules_config_resource: Box::new(RemoteResource::from_pretrained(
+                    SentenceEmbeddingsModulesConfigResources::ALL_MPNET_BASE_V2,
+                )),
+                transformer_type: ModelType::MPNet,
+                transformer_config_resource: Box::new(RemoteResource::from_pretrained(
+                    MPNetConfigResources::ALL_MPNET_BASE_V2,
+                )),
+                transformer_weights_resource: ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
+                    MPNetModelResources::ALL_MPNET_BASE_V2,
+                ))),
+                pooling_config_resource: Box::new(RemoteResource::from_pretrained(
+                    SentenceEmbeddingsPoolingConfigResources::ALL_MPNET_BASE_V2,
+                )),
+                dense_config_resource: None,
+                dense_weights_resource: None,
+                sentence_bert_config_resource: Box::new(RemoteResource::from_pretrained(
+                    SentenceEmbeddingsConfigResources::ALL_MPNET_BASE_V2,
+                )),
+                tokenizer_config_resource: Box::new(RemoteResource::from_pretrained(
+                    SentenceEmbeddingsTokenizerConfigResources::ALL_MPNET_BASE_V2,
+                )),
+                tokenizer_vocab_resource: Box::new(RemoteResource::from_pretrained(
+                    MPNetVocabResources::ALL_MPNET_BASE_V2,
+                )),
+                tokenizer_merges_resource: None,
+                prompts: SentenceEmbeddingsPrompts::default(),
                 device: Device::cuda_if_available(),
             },
+
+            SentenceEmbeddingsModelType::ParaphraseMultilingualMpnetBaseV2 => {
+                SentenceEmbeddingsConfig {
+                    modules_config_resource: Box::new(RemoteResource::from_pretrained(
+                        SentenceEmbeddingsModulesConfigResources::PARAPHRASE_MULTILINGUAL_MPNET_BASE_V2,
+                    )),
+                    transformer_type: ModelType::XLMRoberta,
+                    transformer_config_resource: Box::new(RemoteResource::from_pretrained(
+                        RobertaConfigResources::PARAPHRASE_MULTILINGUAL_MPNET_BASE_V2,
+                    )),
+                    transformer_weights_resource: ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
+                        RobertaModelResources::PARAPHRASE_MULTILINGUAL_MPNET_BASE_V2,
+                    ))),
+                    pooling_config_resource: Box::new(RemoteResource::from_pretrained(
+                        SentenceEmbeddingsPoolingConfigResources::PARAPHRASE_MULTILINGUAL_MPNET_BASE_V2,
+                    )),
+                    dense_config_resource: None,
+                    dense_weights_resource: None,
+                    sentence_bert_config_resource: Box::new(RemoteResource::from_pretrained(
+                        SentenceEmbeddingsConfigResources::PARAPHRASE_MULTILINGUAL_MPNET_BASE_V2,
+                    )),
+                    tokenizer_config_resource: Box::new(RemoteResource::from_pretrained(
+                        SentenceEmbeddingsTokenizerConfigResources::PARAPHRASE_MULTILINGUAL_MPNET_BASE_V2,
+                    )),
+                    tokenizer_vocab_resource: Box::new(RemoteResource::from_pretrained(
+                        RobertaVocabResources::PARAPHRASE_MULTILINGUAL_MPNET_BASE_V2,
+                    )),
+                    tokenizer_merges_resource: None,
+                    prompts: SentenceEmbeddingsPrompts::default(),
+                    device: Device::cuda_if_available(),
+                }
+            }
         }
     }
 }
@@ -448,6 +520,83 @@ pub struct SentenceEmbeddingsSentenceBertConfig {
 
 impl Config for SentenceEmbeddingsSentenceBertConfig {}
 
+/// Prompts prepended to the inputs of instruction-based embedding models
+///
+/// Models such as E5, BGE or GTE are trained with a task-specific prefix (e.g. `"query: "` and
+/// `"passage: "` for E5) and produce degraded embeddings if it is omitted. The fields follow the
+/// `prompts` entry of the Sentence-Transformers `config_sentence_transformers.json` file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SentenceEmbeddingsPrompts {
+    /// Prefix prepended to search queries
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Prefix prepended to passages (documents) to be retrieved
+    #[serde(default, alias = "document")]
+    pub passage: Option<String>,
+}
+
+impl SentenceEmbeddingsPrompts {
+    /// Create prompts for models expecting prefixed queries and passages
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Optional prefix for queries (e.g. `"query: "`)
+    /// * `passage` - Optional prefix for passages (e.g. `"passage: "`)
+    pub fn new<Q, P>(query: Q, passage: P) -> Self
+    where
+        Q: Into<Option<String>>,
+        P: Into<Option<String>>,
+    {
+        Self {
+            query: query.into(),
+            passage: passage.into(),
+        }
+    }
+}
+
+/// Aggregation of the embeddings of the windows of a long text into a single embedding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SentenceEmbeddingsWindowAggregation {
+    /// Average of the window embeddings
+    Mean,
+    /// Average of the window embeddings, weighted by the number of tokens in each window
+    TokenWeightedMean,
+}
+
+/// Sliding windows configuration for the embedding of long texts
+///
+/// Texts longer than the maximum sequence length of the model are split into overlapping windows
+/// of tokens. Each window is encoded separately and the window embeddings are aggregated into a
+/// single embedding for the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SentenceEmbeddingsWindowConfig {
+    /// Number of tokens shared by consecutive windows, must be lower than the window length
+    pub overlap: usize,
+    /// Aggregation of the window embeddings
+    pub aggregation: SentenceEmbeddingsWindowAggregation,
+    /// Maximum number of windows encoded in a single forward pass
+    pub batch_size: usize,
+}
+
+impl Default for SentenceEmbeddingsWindowConfig {
+    fn default() -> Self {
+        Self {
+            overlap: 32,
+            aggregation: SentenceEmbeddingsWindowAggregation::TokenWeightedMean,
+            batch_size: 32,
+        }
+    }
+}
+
+/// Sentence-Transformers model configuration (`config_sentence_transformers.json`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SentenceEmbeddingsTransformersConfig {
+    #[serde(default)]
+    pub prompts: SentenceEmbeddingsPrompts,
+}
+
+impl Config for SentenceEmbeddingsTransformersConfig {}
+
 /// Configuration for transformer's tokenizer
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SentenceEmbeddingsTokenizerConfig {
@@ -0,0 +1,74 @@
+//! # Late-interaction scoring
+//!
+//! MaxSim scoring of token-level embeddings (as computed by `SentenceEmbeddingsModel::encode_tokens`), following
+//! [ColBERT](https://arxiv.org/abs/2004.12832): each query token is matched with its most similar document token,
+//! and the similarities of the query tokens are summed.
+
+use crate::pipelines::sentence_embeddings::{Embedding, SentenceEmbeddingsTokensOutput};
+use tch::{Kind, Tensor};
+
+/// MaxSim score between the token embeddings of a query and of a document.
+/// Returns 0 if the query or the document has no tokens.
+pub fn max_sim(query: &[Embedding], document: &[Embedding]) -> f32 {
+    if document.is_empty() {
+        return 0.0;
+    }
+    query
+        .iter()
+        .map(|query_token| {
+            document
+                .iter()
+                .map(|document_token| {
+                    query_token
+                        .iter()
+                        .zip(document_token.iter())
+                        .map(|(a, b)| a * b)
+                        .sum::<f32>()
+                })
+                .fold(f32::NEG_INFINITY, f32::max)
+        })
+        .sum()
+}
+
+/// MaxSim scores between batches of queries and documents token embeddings.
+/// Returns a `Tensor` of shape (number of queries, number of documents). Padding tokens (as indicated by the
+/// attention masks) are ignored.
+pub fn max_sim_scores(
+    queries: &SentenceEmbeddingsTokensOutput,
+    documents: &SentenceEmbeddingsTokensOutput,
+) -> Tensor {
+    let similarities = Tensor::einsum(
+        "qid,njd->qnij",
+        &[&queries.tokens_embeddings, &documents.tokens_embeddings],
+        None::<i64>,
+    );
+    let documents_padding = documents
+        .tokens_masks
+        .eq(0)
+        .unsqueeze(0)
+        .unsqueeze(2)
+        .expand_as(&similarities);
+    let max_similarities = similarities
+        .masked_fill(&documents_padding, f64::NEG_INFINITY)
+        .amax([-1].as_slice(), false);
+    // Documents without tokens score 0, consistently with `max_sim`. Padded query tokens are ignored.
+    let max_similarities = max_similarities.masked_fill(&max_similarities.isinf(), 0.0);
+    let queries_mask = queries
+        .tokens_masks
+        .unsqueeze(1)
+        .to_kind(max_similarities.kind());
+    (max_similarities * queries_mask).sum_dim_intlist([-1].as_slice(), false, Kind::Float)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_max_sim() {
+        let query = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+        let document = vec![vec![0.8, 0.6], vec![-0.6, 0.8], vec![1.0, 0.0]];
+        assert!((max_sim(&query, &document) - 1.8).abs() < 1e-6);
+        assert_eq!(max_sim(&query, &[]), 0.0);
+    }
+}
@@ -113,7 +113,7 @@ where
 pub struct Dense {
     linear: nn::Linear,
     activation: TensorFunction,
-    _var_store: nn::VarStore,
+    var_store: nn::VarStore,
 }
 
 impl Dense {
@@ -143,11 +143,16 @@ impl Dense {
         Ok(Dense {
             linear,
             activation,
-            _var_store: vs_dense,
+            var_store: vs_dense,
         })
     }
 
     pub fn forward(&self, x: &Tensor) -> Tensor {
         self.activation.get_fn()(&x.apply(&self.linear))
     }
+
+    /// Moves the layer weights to the target device
+    pub fn set_device(&mut self, device: Device) {
+        self.var_store.set_device(device);
+    }
 }
@@ -27,22 +27,105 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! Instruction-based models (e.g. E5, BGE or GTE) expect queries and passages to be prefixed
+//! with a prompt. These are read from `config_sentence_transformers.json` when available, or can
+//! be provided explicitly and are applied by `encode_queries` and `encode_passages`:
+//!
+//! ```no_run
+//! use rust_bert::pipelines::sentence_embeddings::{
+//!     SentenceEmbeddingsBuilder, SentenceEmbeddingsPrompts,
+//! };
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let model = SentenceEmbeddingsBuilder::local("local/path/to/e5-small-v2")
+//!     .with_prompts(SentenceEmbeddingsPrompts::new(
+//!         "query: ".to_string(),
+//!         "passage: ".to_string(),
+//!     ))
+//!     .create_model()?;
+//!
+//! let query_embeddings = model.encode_queries(&["how much protein should a female eat"])?;
+//! let passage_embeddings = model.encode_passages(&["The CDC recommends 46 grams per day."])?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The storage cost of embedding indices can be reduced by truncating the embeddings of models trained
+//! with a Matryoshka objective to a prefix dimension, and by quantizing them to int8 or binary representations:
+//!
+//! ```no_run
+//! use rust_bert::pipelines::sentence_embeddings::{hamming_distance, SentenceEmbeddingsBuilder};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let model = SentenceEmbeddingsBuilder::local("local/path/to/nomic-embed-text-v1.5")
+//!     .with_truncate_dim(256)
+//!     .create_model()?;
+//!
+//! let embeddings = model.encode_binary(&["This is an example sentence", "Each sentence is converted"])?;
+//! let distance = hamming_distance(&embeddings[0], &embeddings[1]);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Token-level embeddings can be computed for late-interaction (ColBERT-style) retrieval and scored with MaxSim:
+//!
+//! ```no_run
+//! use rust_bert::pipelines::sentence_embeddings::{max_sim, SentenceEmbeddingsBuilder};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let model = SentenceEmbeddingsBuilder::local("local/path/to/colbert-model").create_model()?;
+//!
+//! let query = model.encode_tokens(&["how do I configure the connection pool size?"])?;
+//! let documents = model.encode_tokens(&["The pool size is set with the max_connections option."])?;
+//! let score = max_sim(&query[0], &documents[0]);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! With the `onnx` feature enabled, encoders exported to ONNX (e.g. with Optimum) can be used in place of the
+//! Torch weights by providing a `ModelResource::ONNX` as `transformer_weights_resource`. The pooling, dense and
+//! normalization modules are applied to the ONNX encoder output. Local models without `rust_model.ot` weights
+//! use the `model.onnx` (or `onnx/model.onnx`) file of the model directory:
+//!
+//! ```no_run
+//! # #[cfg(feature = "onnx")]
+//! # fn main() -> anyhow::Result<()> {
+//! use rust_bert::pipelines::sentence_embeddings::SentenceEmbeddingsBuilder;
+//!
+//! let model = SentenceEmbeddingsBuilder::local("local/path/to/all-MiniLM-L6-v2-onnx").create_model()?;
+//! let embeddings = model.encode(&["This is an example sentence"])?;
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "onnx"))]
+//! # fn main() {}
+//! ```
 
 pub mod builder;
 mod config;
+pub mod late_interaction;
 pub mod layers;
 mod pipeline;
+pub mod quantization;
 mod resources;
 
 pub use builder::SentenceEmbeddingsBuilder;
 pub use config::{
     SentenceEmbeddingsConfig, SentenceEmbeddingsModuleConfig, SentenceEmbeddingsModuleType,
-    SentenceEmbeddingsModulesConfig, SentenceEmbeddingsSentenceBertConfig,
-    SentenceEmbeddingsTokenizerConfig,
+    SentenceEmbeddingsModulesConfig, SentenceEmbeddingsPrompts,
+    SentenceEmbeddingsSentenceBertConfig, SentenceEmbeddingsTokenizerConfig,
+    SentenceEmbeddingsTransformersConfig, SentenceEmbeddingsWindowAggregation,
+    SentenceEmbeddingsWindowConfig,
 };
+pub use late_interaction::{max_sim, max_sim_scores};
+pub use quantization::{
+    hamming_distance, int8_cosine_similarity, int8_dot_product, quantize_binary, BinaryEmbedding,
+    Int8Embedding,
+};
+
 pub use pipeline::{
     SentenceEmbeddingsModel, SentenceEmbeddingsModelOutput, SentenceEmbeddingsOption,
-    SentenceEmbeddingsTokenizerOutput,
+    SentenceEmbeddingsTokenizerOutput, SentenceEmbeddingsTokensOutput,
 };
 
 pub use resources::{
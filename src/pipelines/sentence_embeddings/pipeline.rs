@@ -1,18 +1,32 @@
 use std::borrow::Borrow;
+use std::cmp::min;
 use std::convert::{TryFrom, TryInto};
 
 use rust_tokenizers::tokenizer::TruncationStrategy;
-use tch::{nn, Tensor};
+use rust_tokenizers::TokenIdsWithOffsets;
+#[cfg(feature = "onnx")]
+use tch::Device;
+use tch::{nn, Kind, Tensor};
 
 use crate::albert::AlbertForSentenceEmbeddings;
 use crate::bert::BertForSentenceEmbeddings;
+use crate::deberta_v2::DebertaV2ForSentenceEmbeddings;
 use crate::distilbert::DistilBertForSentenceEmbeddings;
-use crate::pipelines::common::{ConfigOption, ModelType, TokenizerOption};
+use crate::mpnet::MPNetForSentenceEmbeddings;
+use crate::pipelines::common::{
+    cast_var_store, set_var_store_device, ConfigOption, ModelResource, ModelType, TokenizerOption,
+};
+#[cfg(feature = "onnx")]
+use crate::pipelines::onnx::{config::ONNXEnvironmentConfig, ONNXEncoder};
 use crate::pipelines::sentence_embeddings::layers::{Dense, DenseConfig, Pooling, PoolingConfig};
+use crate::pipelines::sentence_embeddings::quantization::{
+    quantize_binary, BinaryEmbedding, Int8Embedding,
+};
 use crate::pipelines::sentence_embeddings::{
     AttentionHead, AttentionLayer, AttentionOutput, Embedding, SentenceEmbeddingsConfig,
-    SentenceEmbeddingsModulesConfig, SentenceEmbeddingsSentenceBertConfig,
-    SentenceEmbeddingsTokenizerConfig,
+    SentenceEmbeddingsModulesConfig, SentenceEmbeddingsPrompts,
+    SentenceEmbeddingsSentenceBertConfig, SentenceEmbeddingsTokenizerConfig,
+    SentenceEmbeddingsWindowAggregation, SentenceEmbeddingsWindowConfig,
 };
 use crate::roberta::RobertaForSentenceEmbeddings;
 use crate::t5::T5ForSentenceEmbeddings;
@@ -30,6 +44,15 @@ pub enum SentenceEmbeddingsOption {
     Albert(AlbertForSentenceEmbeddings),
     /// T5 for Sentence Embeddings
     T5(T5ForSentenceEmbeddings),
+    /// XLM-RoBERTa for Sentence Embeddings
+    XLMRoberta(RobertaForSentenceEmbeddings),
+    /// DeBERTa V2 for Sentence Embeddings
+    DebertaV2(DebertaV2ForSentenceEmbeddings),
+    /// MPNet for Sentence Embeddings
+    MPNet(MPNetForSentenceEmbeddings),
+    /// ONNX encoder for Sentence Embeddings
+    #[cfg(feature = "onnx")]
+    ONNX(ONNXEncoder),
 }
 
 impl SentenceEmbeddingsOption {
@@ -63,6 +86,27 @@ impl SentenceEmbeddingsOption {
             )),
             ModelType::Albert => Albert(AlbertForSentenceEmbeddings::new(p, &(config.try_into()?))),
             ModelType::T5 => T5(T5ForSentenceEmbeddings::new(p, &(config.try_into()?))),
+            ModelType::XLMRoberta => {
+                XLMRoberta(RobertaForSentenceEmbeddings::new_with_optional_pooler(
+                    p,
+                    &(config.try_into()?),
+                    false,
+                ))
+            }
+            ModelType::DebertaV2 => {
+                if let ConfigOption::DebertaV2(config) = config {
+                    DebertaV2(DebertaV2ForSentenceEmbeddings::new(p, config))
+                } else {
+                    return Err(RustBertError::InvalidConfigurationError(
+                        "You can only supply a DebertaV2Config for DeBERTa V2!".to_string(),
+                    ));
+                }
+            }
+            ModelType::MPNet => MPNet(MPNetForSentenceEmbeddings::new_with_optional_pooler(
+                p,
+                &(config.try_into()?),
+                false,
+            )),
             _ => {
                 return Err(RustBertError::InvalidConfigurationError(format!(
                     "Unsupported transformer model {transformer_type:?} for Sentence Embeddings"
@@ -73,6 +117,29 @@ impl SentenceEmbeddingsOption {
         Ok(option)
     }
 
+    /// Instantiate a new sentence embeddings transformer from an encoder exported to ONNX.
+    ///
+    /// # Arguments
+    ///
+    /// * `model_resource` - `ModelResource::ONNX` containing the encoder resource
+    /// * `device` - Device used to configure the ONNX Runtime execution providers
+    #[cfg(feature = "onnx")]
+    pub fn new_onnx(model_resource: &ModelResource, device: Device) -> Result<Self, RustBertError> {
+        let onnx_config = ONNXEnvironmentConfig::from_device(device);
+        let environment = onnx_config.get_environment()?;
+        let encoder_file = model_resource.get_onnx_local_paths()?.encoder_path.ok_or(
+            RustBertError::InvalidConfigurationError(
+                "An encoder file must be provided for sentence embeddings ONNX models.".to_string(),
+            ),
+        )?;
+
+        Ok(Self::ONNX(ONNXEncoder::new(
+            encoder_file,
+            &environment,
+            &onnx_config,
+        )?))
+    }
+
     /// Interface method to forward() of the particular transformer models.
     pub fn forward(
         &self,
@@ -105,7 +172,7 @@ impl SentenceEmbeddingsOption {
                         transformer_output.all_attentions,
                     )
                 }),
-            Self::Roberta(transformer) => transformer
+            Self::Roberta(transformer) | Self::XLMRoberta(transformer) => transformer
                 .forward_t(
                     Some(tokens_ids),
                     Some(tokens_masks),
@@ -146,6 +213,49 @@ impl SentenceEmbeddingsOption {
                     )
                 }),
             Self::T5(transformer) => transformer.forward(tokens_ids, tokens_masks),
+            Self::DebertaV2(transformer) => transformer
+                .forward_t(
+                    Some(tokens_ids),
+                    Some(tokens_masks),
+                    None,
+                    None,
+                    None,
+                    false,
+                )
+                .map(|transformer_output| {
+                    (
+                        transformer_output.hidden_state,
+                        transformer_output.all_attentions,
+                    )
+                }),
+            Self::MPNet(transformer) => transformer
+                .forward_t(Some(tokens_ids), Some(tokens_masks), None, None, false)
+                .map(|transformer_output| {
+                    (
+                        transformer_output.hidden_state,
+                        transformer_output.all_attentions,
+                    )
+                }),
+            #[cfg(feature = "onnx")]
+            Self::ONNX(encoder) => {
+                // Token type ids are ignored if the exported encoder does not expect them
+                let token_type_ids = tokens_ids.zeros_like();
+                let hidden_state = encoder
+                    .forward(
+                        Some(tokens_ids),
+                        Some(tokens_masks),
+                        Some(&token_type_ids),
+                        None,
+                        None,
+                    )?
+                    .last_hidden_state
+                    .ok_or_else(|| {
+                        RustBertError::InvalidConfigurationError(
+                            "The ONNX encoder did not output a `last_hidden_state`".to_string(),
+                        )
+                    })?;
+                Ok((hidden_state, None))
+            }
         }
     }
 }
@@ -168,6 +278,9 @@ pub struct SentenceEmbeddingsModel {
     dense_layer: Option<Dense>,
     normalize_embeddings: bool,
     embeddings_dim: i64,
+    truncate_dim: Option<i64>,
+    long_text_windows: Option<SentenceEmbeddingsWindowConfig>,
+    prompts: SentenceEmbeddingsPrompts,
 }
 
 impl SentenceEmbeddingsModel {
@@ -189,6 +302,7 @@ impl SentenceEmbeddingsModel {
             pooling_config_resource,
             dense_config_resource,
             dense_weights_resource,
+            prompts,
             device,
         } = config;
 
@@ -223,14 +337,29 @@ impl SentenceEmbeddingsModel {
         )?;
 
         // Setup transformer
-        let mut var_store = nn::VarStore::new(device);
         let transformer_config = ConfigOption::from_file(
             transformer_type,
             transformer_config_resource.get_local_path()?,
         );
-        let transformer =
-            SentenceEmbeddingsOption::new(transformer_type, var_store.root(), &transformer_config)?;
-        crate::resources::load_weights(&transformer_weights_resource, &mut var_store)?;
+        let (transformer, var_store) = match transformer_weights_resource {
+            ModelResource::Torch(ref weights_resource) => {
+                let mut var_store = nn::VarStore::new(device);
+                let transformer = SentenceEmbeddingsOption::new(
+                    transformer_type,
+                    var_store.root(),
+                    &transformer_config,
+                )?;
+                crate::resources::load_weights(weights_resource, &mut var_store)?;
+                (transformer, var_store)
+            }
+            #[cfg(feature = "onnx")]
+            ModelResource::ONNX(_) => (
+                SentenceEmbeddingsOption::new_onnx(&transformer_weights_resource, device)?,
+                // ONNX encoders outputs are placed on the CPU
+                nn::VarStore::new(Device::Cpu),
+            ),
+        };
+        let device = var_store.device();
 
         // Setup pooling layer
         let pooling_config = PoolingConfig::from_file(pooling_config_resource.get_local_path()?);
@@ -264,6 +393,9 @@ impl SentenceEmbeddingsModel {
             dense_layer,
             normalize_embeddings,
             embeddings_dim,
+            truncate_dim: None,
+            long_text_windows: None,
+            prompts,
         })
     }
 
@@ -277,14 +409,94 @@ impl SentenceEmbeddingsModel {
         &mut self.tokenizer
     }
 
+    /// Casts the transformer weights to half precision (`Kind::Half`).
+    /// The pooling, dense and normalization layers are computed in single precision.
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.get_var_store_mut(), Kind::Half)
+    }
+
+    /// Casts the transformer weights to bfloat16 precision (`Kind::BFloat16`).
+    /// The pooling, dense and normalization layers are computed in single precision.
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.get_var_store_mut(), Kind::BFloat16)
+    }
+
+    /// Casts the transformer weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.get_var_store_mut(), Kind::Float)
+    }
+
+    /// Moves the model weights to the target device, inputs are placed on this device for subsequent encodings.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        set_var_store_device(self.get_var_store_mut(), device)?;
+        if let Some(dense_layer) = &mut self.dense_layer {
+            dense_layer.set_device(device);
+        }
+        Ok(())
+    }
+
+    /// Returns the var store of the transformer, `None` for ONNX encoders
+    fn get_var_store_mut(&mut self) -> Option<&mut nn::VarStore> {
+        #[cfg(feature = "onnx")]
+        let is_onnx = matches!(self.transformer, SentenceEmbeddingsOption::ONNX(_));
+        #[cfg(not(feature = "onnx"))]
+        let is_onnx = false;
+        if is_onnx {
+            None
+        } else {
+            Some(&mut self.var_store)
+        }
+    }
+
     /// Sets the tokenizer's truncation strategy
     pub fn set_tokenizer_truncation(&mut self, truncation_strategy: TruncationStrategy) {
         self.tokenizer_truncation_strategy = truncation_strategy;
     }
 
-    /// Return the embedding output dimension
+    /// Return the embedding output dimension (the truncated dimension if a truncation is set)
     pub fn get_embedding_dim(&self) -> Result<i64, RustBertError> {
-        Ok(self.embeddings_dim)
+        Ok(self.truncate_dim.unwrap_or(self.embeddings_dim))
+    }
+
+    /// Truncates the output embeddings to their first `truncate_dim` dimensions, for models trained with
+    /// a Matryoshka representation learning objective. Truncated embeddings are re-normalized to unit length.
+    /// Setting `None` restores the full embeddings.
+    pub fn set_truncate_dim(&mut self, truncate_dim: Option<usize>) -> Result<(), RustBertError> {
+        if let Some(truncate_dim) = truncate_dim {
+            if truncate_dim == 0 || truncate_dim as i64 > self.embeddings_dim {
+                return Err(RustBertError::ValueError(format!(
+                    "Truncation dimension {truncate_dim} must be between 1 and the embeddings dimension ({})",
+                    self.embeddings_dim
+                )));
+            }
+        }
+        self.truncate_dim = truncate_dim.map(|truncate_dim| truncate_dim as i64);
+        Ok(())
+    }
+
+    /// Get a reference to the query and passage prompts
+    pub fn get_prompts(&self) -> &SentenceEmbeddingsPrompts {
+        &self.prompts
+    }
+
+    /// Sets the prompts prepended to queries and passages by `encode_queries` and `encode_passages`
+    pub fn set_prompts(&mut self, prompts: SentenceEmbeddingsPrompts) {
+        self.prompts = prompts;
+    }
+
+    /// Get the sliding windows configuration used for long texts, if set
+    pub fn get_long_text_windows(&self) -> Option<&SentenceEmbeddingsWindowConfig> {
+        self.long_text_windows.as_ref()
+    }
+
+    /// Enables the embedding of texts longer than the maximum sequence length of the model: texts are
+    /// split into overlapping windows of tokens, encoded separately and the window embeddings aggregated.
+    /// Setting `None` restores the default behaviour (truncation to the maximum sequence length).
+    pub fn set_long_text_windows(
+        &mut self,
+        windows_config: Option<SentenceEmbeddingsWindowConfig>,
+    ) {
+        self.long_text_windows = windows_config;
     }
 
     /// Tokenizes the inputs
@@ -299,17 +511,92 @@ impl SentenceEmbeddingsModel {
             0,
         );
 
-        let max_len = tokenized_input
-            .iter()
-            .map(|input| input.token_ids.len())
-            .max()
-            .unwrap_or(0);
+        self.pad_token_ids(
+            tokenized_input
+                .into_iter()
+                .map(|input| input.token_ids)
+                .collect(),
+        )
+    }
+
+    /// Tokenizes the inputs into overlapping windows of at most `max_seq_length` tokens.
+    /// Returns the tokenized windows, and for each window the index of its input and its number of tokens.
+    /// Returns an error if the windows overlap is not lower than the window length.
+    pub fn tokenize_windows<S>(
+        &self,
+        inputs: &[S],
+        windows_config: &SentenceEmbeddingsWindowConfig,
+    ) -> Result<(SentenceEmbeddingsTokenizerOutput, Vec<(usize, usize)>), RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let empty_input = || TokenIdsWithOffsets {
+            ids: vec![],
+            offsets: vec![],
+            reference_offsets: vec![],
+            masks: vec![],
+        };
+        let added_tokens = self
+            .tokenizer
+            .build_input_with_special_tokens(empty_input(), None)
+            .token_ids
+            .len();
+        let window_length = self
+            .sentence_bert_config
+            .max_seq_length
+            .saturating_sub(added_tokens)
+            .max(1);
+        if windows_config.overlap >= window_length {
+            return Err(RustBertError::InvalidConfigurationError(format!(
+                "The windows overlap ({}) must be lower than the window length ({window_length} tokens)",
+                windows_config.overlap
+            )));
+        }
+        let step = window_length - windows_config.overlap;
+
+        let mut windows = Vec::new();
+        let mut window_inputs = Vec::new();
+        for (input_index, input) in inputs.iter().enumerate() {
+            let tokenized_input = self.tokenizer.tokenize_with_offsets(input.as_ref());
+            let encoded_input = TokenIdsWithOffsets {
+                ids: self
+                    .tokenizer
+                    .convert_tokens_to_ids(&tokenized_input.tokens),
+                offsets: tokenized_input.offsets,
+                reference_offsets: tokenized_input.reference_offsets,
+                masks: tokenized_input.masks,
+            };
+            let mut start = 0;
+            loop {
+                let end = min(start + window_length, encoded_input.ids.len());
+                let window = TokenIdsWithOffsets {
+                    ids: encoded_input.ids[start..end].to_vec(),
+                    offsets: encoded_input.offsets[start..end].to_vec(),
+                    reference_offsets: encoded_input.reference_offsets[start..end].to_vec(),
+                    masks: encoded_input.masks[start..end].to_vec(),
+                };
+                windows.push(
+                    self.tokenizer
+                        .build_input_with_special_tokens(window, None)
+                        .token_ids,
+                );
+                window_inputs.push((input_index, end - start));
+                if end == encoded_input.ids.len() {
+                    break;
+                }
+                start += step;
+            }
+        }
+        Ok((self.pad_token_ids(windows), window_inputs))
+    }
+
+    fn pad_token_ids(&self, tokens_ids: Vec<Vec<i64>>) -> SentenceEmbeddingsTokenizerOutput {
+        let max_len = tokens_ids.iter().map(Vec::len).max().unwrap_or(0);
 
         let pad_token_id = self.tokenizer.get_pad_id().unwrap_or(0);
-        let tokens_ids = tokenized_input
+        let tokens_ids = tokens_ids
             .into_iter()
-            .map(|input| {
-                let mut token_ids = input.token_ids;
+            .map(|mut token_ids| {
                 token_ids.extend(vec![pad_token_id; max_len - token_ids.len()]);
                 token_ids
             })
@@ -339,6 +626,9 @@ impl SentenceEmbeddingsModel {
     }
 
     /// Computes sentence embeddings, outputs `Tensor`.
+    ///
+    /// If long text windows are set (see `set_long_text_windows`), the inputs are split into overlapping
+    /// windows and the window embeddings are aggregated. Attentions are not returned in this case.
     pub fn encode_as_tensor<S>(
         &self,
         inputs: &[S],
@@ -346,15 +636,142 @@ impl SentenceEmbeddingsModel {
     where
         S: AsRef<str> + Sync,
     {
-        let SentenceEmbeddingsTokenizerOutput {
-            tokens_ids,
+        let (embeddings, all_attentions) = match &self.long_text_windows {
+            None => self.forward_tokens(self.tokenize(inputs))?,
+            Some(windows_config) => {
+                let (tokenized_windows, window_inputs) =
+                    self.tokenize_windows(inputs, windows_config)?;
+                let batch_size = windows_config.batch_size.max(1);
+                let mut tokens_ids = tokenized_windows.tokens_ids.into_iter();
+                let mut tokens_masks = tokenized_windows.tokens_masks.into_iter();
+                let mut batch_embeddings = Vec::new();
+                loop {
+                    let batch_tokens_ids = tokens_ids.by_ref().take(batch_size).collect::<Vec<_>>();
+                    if batch_tokens_ids.is_empty() {
+                        break;
+                    }
+                    let (embeddings, _) =
+                        self.forward_tokens(SentenceEmbeddingsTokenizerOutput {
+                            tokens_ids: batch_tokens_ids,
+                            tokens_masks: tokens_masks.by_ref().take(batch_size).collect(),
+                        })?;
+                    batch_embeddings.push(embeddings);
+                }
+                let window_embeddings = Tensor::cat(batch_embeddings.as_slice(), 0);
+                let mut aggregation_weights = vec![vec![0f32; window_inputs.len()]; inputs.len()];
+                for (window_index, (input_index, num_tokens)) in
+                    window_inputs.into_iter().enumerate()
+                {
+                    aggregation_weights[input_index][window_index] =
+                        match windows_config.aggregation {
+                            SentenceEmbeddingsWindowAggregation::Mean => 1.0,
+                            SentenceEmbeddingsWindowAggregation::TokenWeightedMean => {
+                                num_tokens.max(1) as f32
+                            }
+                        };
+                }
+                let aggregation_weights = Tensor::from_slice2(&aggregation_weights)
+                    .to_kind(window_embeddings.kind())
+                    .to(window_embeddings.device());
+                let aggregation_weights = &aggregation_weights
+                    / aggregation_weights.sum_dim_intlist(
+                        [1].as_slice(),
+                        true,
+                        aggregation_weights.kind(),
+                    );
+                (aggregation_weights.matmul(&window_embeddings), None)
+            }
+        };
+
+        Ok(SentenceEmbeddingsModelOutput {
+            embeddings: self.truncate_and_normalize(embeddings),
+            all_attentions,
+        })
+    }
+
+    /// Computes token-level embeddings for late-interaction (ColBERT-style) retrieval, outputs `Tensor`.
+    ///
+    /// The token embeddings of the transformer are projected by the dense layer of the model if it contains one,
+    /// and are truncated and normalized following the sentence embeddings options. Padding positions are
+    /// set to zero and indicated by the returned attention mask.
+    pub fn encode_tokens_as_tensor<S>(
+        &self,
+        inputs: &[S],
+    ) -> Result<SentenceEmbeddingsTokensOutput, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let (tokens_embeddings, tokens_masks, _) =
+            self.forward_transformer(self.tokenize(inputs))?;
+        let maybe_linear = if let Some(dense_layer) = &self.dense_layer {
+            tch::no_grad(|| dense_layer.forward(&tokens_embeddings))
+        } else {
+            tokens_embeddings
+        };
+        let tokens_embeddings = self.truncate_and_normalize(maybe_linear);
+        let tokens_embeddings =
+            &tokens_embeddings * tokens_masks.unsqueeze(-1).to_kind(tokens_embeddings.kind());
+        Ok(SentenceEmbeddingsTokensOutput {
+            tokens_embeddings,
             tokens_masks,
-        } = self.tokenize(inputs);
-        let tokens_ids = Tensor::stack(&tokens_ids, 0).to(self.var_store.device());
-        let tokens_masks = Tensor::stack(&tokens_masks, 0).to(self.var_store.device());
+        })
+    }
 
-        let (tokens_embeddings, all_attentions) =
-            tch::no_grad(|| self.transformer.forward(&tokens_ids, &tokens_masks))?;
+    /// Computes token-level embeddings for late-interaction (ColBERT-style) retrieval.
+    /// Returns for each input the embeddings of its tokens (padding excluded), that can be scored with `max_sim`.
+    pub fn encode_tokens<S>(&self, inputs: &[S]) -> Result<Vec<Vec<Embedding>>, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let SentenceEmbeddingsTokensOutput {
+            tokens_embeddings,
+            tokens_masks,
+        } = self.encode_tokens_as_tensor(inputs)?;
+        let sequence_lengths = Vec::<i64>::try_from(tokens_masks.sum_dim_intlist(
+            [1].as_slice(),
+            false,
+            tch::Kind::Int64,
+        ))?;
+        sequence_lengths
+            .into_iter()
+            .enumerate()
+            .map(|(input_index, sequence_length)| {
+                Ok(Vec::<Embedding>::try_from(
+                    tokens_embeddings
+                        .select(0, input_index as i64)
+                        .slice(0, 0, sequence_length, 1),
+                )?)
+            })
+            .collect()
+    }
+
+    /// Truncates the embeddings (last dimension) if a truncation dimension is set, and normalizes them
+    /// if the model contains a normalization module or the embeddings are truncated
+    fn truncate_and_normalize(&self, embeddings: Tensor) -> Tensor {
+        let maybe_truncated = match self.truncate_dim {
+            Some(truncate_dim) if truncate_dim < self.embeddings_dim => {
+                embeddings.slice(-1, 0, truncate_dim, 1)
+            }
+            _ => embeddings,
+        };
+        if self.normalize_embeddings || self.truncate_dim.is_some() {
+            let norm = &maybe_truncated
+                .norm_scalaropt_dim(2, [-1], true)
+                .clamp_min(1e-12)
+                .expand_as(&maybe_truncated);
+            maybe_truncated / norm
+        } else {
+            maybe_truncated
+        }
+    }
+
+    /// Computes the pooled (and projected if the model contains a dense layer) embeddings of tokenized inputs
+    fn forward_tokens(
+        &self,
+        tokenized_input: SentenceEmbeddingsTokenizerOutput,
+    ) -> Result<(Tensor, Option<Vec<Tensor>>), RustBertError> {
+        let (tokens_embeddings, tokens_masks, all_attentions) =
+            self.forward_transformer(tokenized_input)?;
 
         let mean_pool =
             tch::no_grad(|| self.pooling_layer.forward(tokens_embeddings, &tokens_masks));
@@ -363,20 +780,32 @@ impl SentenceEmbeddingsModel {
         } else {
             mean_pool
         };
-        let maybe_normalized = if self.normalize_embeddings {
-            let norm = &maybe_linear
-                .norm_scalaropt_dim(2, [1], true)
-                .clamp_min(1e-12)
-                .expand_as(&maybe_linear);
-            maybe_linear / norm
-        } else {
-            maybe_linear
-        };
+        Ok((maybe_linear, all_attentions))
+    }
 
-        Ok(SentenceEmbeddingsModelOutput {
-            embeddings: maybe_normalized,
-            all_attentions,
-        })
+    /// Computes the token embeddings of the transformer, returns the token embeddings, attention masks and attentions
+    fn forward_transformer(
+        &self,
+        tokenized_input: SentenceEmbeddingsTokenizerOutput,
+    ) -> Result<(Tensor, Tensor, Option<Vec<Tensor>>), RustBertError> {
+        let SentenceEmbeddingsTokenizerOutput {
+            tokens_ids,
+            tokens_masks,
+        } = tokenized_input;
+        let tokens_ids = Tensor::stack(&tokens_ids, 0).to(self.var_store.device());
+        let tokens_masks = Tensor::stack(&tokens_masks, 0).to(self.var_store.device());
+
+        let (tokens_embeddings, all_attentions) =
+            tch::no_grad(|| self.transformer.forward(&tokens_ids, &tokens_masks))?;
+        // Half and bfloat16 precision transformer outputs are pooled and normalized in single precision
+        let tokens_embeddings = tokens_embeddings.to_kind(Kind::Float);
+        let all_attentions = all_attentions.map(|attentions| {
+            attentions
+                .iter()
+                .map(|attention| attention.to_kind(Kind::Float))
+                .collect()
+        });
+        Ok((tokens_embeddings, tokens_masks, all_attentions))
     }
 
     /// Computes sentence embeddings.
@@ -388,36 +817,115 @@ impl SentenceEmbeddingsModel {
         Ok(Vec::try_from(embeddings)?)
     }
 
-    fn nb_layers(&self) -> usize {
+    /// Computes sentence embeddings quantized to int8, see `Int8Embedding`.
+    pub fn encode_int8<S>(&self, inputs: &[S]) -> Result<Vec<Int8Embedding>, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        Ok(self
+            .encode(inputs)?
+            .iter()
+            .map(|embedding| Int8Embedding::quantize(embedding))
+            .collect())
+    }
+
+    /// Computes sentence embeddings quantized to packed sign bits, see `BinaryEmbedding`.
+    pub fn encode_binary<S>(&self, inputs: &[S]) -> Result<Vec<BinaryEmbedding>, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        Ok(self
+            .encode(inputs)?
+            .iter()
+            .map(|embedding| quantize_binary(embedding))
+            .collect())
+    }
+
+    /// Computes embeddings for search queries, prepending the query prompt if one is defined.
+    pub fn encode_queries<S>(&self, inputs: &[S]) -> Result<Vec<Embedding>, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        self.encode_with_prompt(inputs, self.prompts.query.as_deref())
+    }
+
+    /// Computes embeddings for passages, prepending the passage prompt if one is defined.
+    pub fn encode_passages<S>(&self, inputs: &[S]) -> Result<Vec<Embedding>, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        self.encode_with_prompt(inputs, self.prompts.passage.as_deref())
+    }
+
+    fn encode_with_prompt<S>(
+        &self,
+        inputs: &[S],
+        prompt: Option<&str>,
+    ) -> Result<Vec<Embedding>, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        match prompt {
+            Some(prompt) => {
+                let prompted_inputs = inputs
+                    .iter()
+                    .map(|input| format!("{prompt}{}", input.as_ref()))
+                    .collect::<Vec<_>>();
+                self.encode(&prompted_inputs)
+            }
+            None => self.encode(inputs),
+        }
+    }
+
+    fn config_mismatch_error(&self) -> RustBertError {
+        RustBertError::InvalidConfigurationError(
+            "The transformer configuration does not match the sentence embeddings model type"
+                .to_string(),
+        )
+    }
+
+    fn nb_layers(&self) -> Result<usize, RustBertError> {
         use SentenceEmbeddingsOption::*;
-        match (&self.transformer, &self.transformer_config) {
+        Ok(match (&self.transformer, &self.transformer_config) {
             (Bert(_), ConfigOption::Bert(conf)) => conf.num_hidden_layers as usize,
-            (Bert(_), _) => unreachable!(),
             (DistilBert(_), ConfigOption::DistilBert(conf)) => conf.n_layers as usize,
-            (DistilBert(_), _) => unreachable!(),
-            (Roberta(_), ConfigOption::Bert(conf)) => conf.num_hidden_layers as usize,
-            (Roberta(_), _) => unreachable!(),
+            (Roberta(_) | XLMRoberta(_), ConfigOption::Roberta(conf)) => {
+                conf.num_hidden_layers as usize
+            }
             (Albert(_), ConfigOption::Albert(conf)) => conf.num_hidden_layers as usize,
-            (Albert(_), _) => unreachable!(),
             (T5(_), ConfigOption::T5(conf)) => conf.num_layers as usize,
-            (T5(_), _) => unreachable!(),
-        }
+            (DebertaV2(_), ConfigOption::DebertaV2(conf)) => conf.num_hidden_layers as usize,
+            (MPNet(_), ConfigOption::MPNet(conf)) => conf.num_hidden_layers as usize,
+            #[cfg(feature = "onnx")]
+            (ONNX(_), _) => {
+                return Err(RustBertError::InvalidConfigurationError(
+                    "ONNX sentence embeddings models do not output attentions".to_string(),
+                ));
+            }
+            _ => return Err(self.config_mismatch_error()),
+        })
     }
 
-    fn nb_heads(&self) -> usize {
+    fn nb_heads(&self) -> Result<usize, RustBertError> {
         use SentenceEmbeddingsOption::*;
-        match (&self.transformer, &self.transformer_config) {
+        Ok(match (&self.transformer, &self.transformer_config) {
             (Bert(_), ConfigOption::Bert(conf)) => conf.num_attention_heads as usize,
-            (Bert(_), _) => unreachable!(),
             (DistilBert(_), ConfigOption::DistilBert(conf)) => conf.n_heads as usize,
-            (DistilBert(_), _) => unreachable!(),
-            (Roberta(_), ConfigOption::Roberta(conf)) => conf.num_attention_heads as usize,
-            (Roberta(_), _) => unreachable!(),
+            (Roberta(_) | XLMRoberta(_), ConfigOption::Roberta(conf)) => {
+                conf.num_attention_heads as usize
+            }
             (Albert(_), ConfigOption::Albert(conf)) => conf.num_attention_heads as usize,
-            (Albert(_), _) => unreachable!(),
             (T5(_), ConfigOption::T5(conf)) => conf.num_heads as usize,
-            (T5(_), _) => unreachable!(),
-        }
+            (DebertaV2(_), ConfigOption::DebertaV2(conf)) => conf.num_attention_heads as usize,
+            (MPNet(_), ConfigOption::MPNet(conf)) => conf.num_attention_heads as usize,
+            #[cfg(feature = "onnx")]
+            (ONNX(_), _) => {
+                return Err(RustBertError::InvalidConfigurationError(
+                    "ONNX sentence embeddings models do not output attentions".to_string(),
+                ));
+            }
+            _ => return Err(self.config_mismatch_error()),
+        })
     }
 
     /// Computes sentence embeddings, also outputs `AttentionOutput`s.
@@ -438,12 +946,14 @@ impl SentenceEmbeddingsModel {
             RustBertError::InvalidConfigurationError("No attention outputted".into())
         })?;
 
+        let nb_layers = self.nb_layers()?;
+        let nb_heads = self.nb_heads()?;
         let attention_outputs = (0..inputs.len() as i64)
             .map(|i| {
-                let mut attention_output = AttentionOutput::with_capacity(self.nb_layers());
+                let mut attention_output = AttentionOutput::with_capacity(nb_layers);
                 for layer in all_attentions.iter() {
-                    let mut attention_layer = AttentionLayer::with_capacity(self.nb_heads());
-                    for head in 0..self.nb_heads() {
+                    let mut attention_layer = AttentionLayer::with_capacity(nb_heads);
+                    for head in 0..nb_heads {
                         let attention_slice = layer
                             .slice(0, i, i + 1, 1)
                             .slice(1, head as i64, head as i64 + 1, 1)
@@ -467,6 +977,14 @@ pub struct SentenceEmbeddingsTokenizerOutput {
     pub tokens_masks: Vec<Tensor>,
 }
 
+/// Container for the token-level (late-interaction) embeddings output.
+pub struct SentenceEmbeddingsTokensOutput {
+    /// Token embeddings of shape (batch size, sequence length, embeddings dimension), zero for padding positions
+    pub tokens_embeddings: Tensor,
+    /// Attention mask of shape (batch size, sequence length), 0 for padding positions
+    pub tokens_masks: Tensor,
+}
+
 /// Container for the SentenceEmbeddings model output.
 pub struct SentenceEmbeddingsModelOutput {
     pub embeddings: Tensor,
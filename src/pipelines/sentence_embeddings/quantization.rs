@@ -0,0 +1,132 @@
+//! # Embeddings quantization
+//!
+//! Compact representations of sentence embeddings reducing the storage cost of embedding indices:
+//! - `Int8Embedding`: symmetric scalar quantization of each dimension to an `i8` with a per-embedding scale (~4x smaller)
+//! - `BinaryEmbedding`: sign of each dimension packed into bits (32x smaller), compared with the Hamming distance
+
+use crate::pipelines::sentence_embeddings::Embedding;
+
+/// # Int8 quantized embedding
+/// Each dimension is stored as `round(value / scale)` with `scale = max(|value|) / 127`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Int8Embedding {
+    /// Quantized values
+    pub values: Vec<i8>,
+    /// Scale to apply to the quantized values to recover the original embedding
+    pub scale: f32,
+}
+
+impl Int8Embedding {
+    /// Quantizes an embedding to int8
+    pub fn quantize(embedding: &[f32]) -> Self {
+        let max_value = embedding
+            .iter()
+            .fold(0f32, |max_value, value| max_value.max(value.abs()));
+        let scale = if max_value > 0.0 {
+            max_value / i8::MAX as f32
+        } else {
+            1.0
+        };
+        let values = embedding
+            .iter()
+            .map(|value| (value / scale).round().clamp(-127.0, 127.0) as i8)
+            .collect();
+        Self { values, scale }
+    }
+
+    /// Recovers an approximation of the original embedding
+    pub fn dequantize(&self) -> Embedding {
+        self.values
+            .iter()
+            .map(|value| *value as f32 * self.scale)
+            .collect()
+    }
+}
+
+/// Binary quantized embedding: the sign bits of the dimensions (1 for positive values) packed into bytes,
+/// most significant bit first. The last byte is padded with zeros.
+pub type BinaryEmbedding = Vec<u8>;
+
+/// Quantizes an embedding to a `BinaryEmbedding`
+pub fn quantize_binary(embedding: &[f32]) -> BinaryEmbedding {
+    embedding
+        .chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, value)| **value > 0.0)
+                .fold(0u8, |byte, (position, _)| byte | (0x80 >> position))
+        })
+        .collect()
+}
+
+/// Dot product between two int8 quantized embeddings, approximating the dot product of the original embeddings
+pub fn int8_dot_product(a: &Int8Embedding, b: &Int8Embedding) -> f32 {
+    let dot_product = a
+        .values
+        .iter()
+        .zip(b.values.iter())
+        .map(|(a, b)| *a as i32 * *b as i32)
+        .sum::<i32>();
+    dot_product as f32 * a.scale * b.scale
+}
+
+/// Cosine similarity between two int8 quantized embeddings
+pub fn int8_cosine_similarity(a: &Int8Embedding, b: &Int8Embedding) -> f32 {
+    let squared_norm = |embedding: &Int8Embedding| {
+        embedding
+            .values
+            .iter()
+            .map(|value| *value as i32 * *value as i32)
+            .sum::<i32>()
+    };
+    let norms = ((squared_norm(a) as f64) * (squared_norm(b) as f64)).sqrt();
+    if norms == 0.0 {
+        return 0.0;
+    }
+    let dot_product = a
+        .values
+        .iter()
+        .zip(b.values.iter())
+        .map(|(a, b)| *a as i32 * *b as i32)
+        .sum::<i32>();
+    (dot_product as f64 / norms) as f32
+}
+
+/// Hamming distance (number of differing bits) between two binary embeddings.
+/// Lower distances indicate more similar embeddings.
+pub fn hamming_distance(a: &[u8], b: &[u8]) -> u32 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(a, b)| (a ^ b).count_ones())
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_int8_quantization() {
+        let embedding = [0.5f32, -0.25, 0.0, 0.127];
+        let quantized = Int8Embedding::quantize(&embedding);
+        assert_eq!(quantized.values, vec![127, -64, 0, 32]);
+        for (value, original) in quantized.dequantize().iter().zip(embedding) {
+            assert!((value - original).abs() < 2e-3);
+        }
+        assert!((int8_cosine_similarity(&quantized, &quantized) - 1.0).abs() < 1e-6);
+        let expected_dot_product = embedding.iter().map(|value| value * value).sum::<f32>();
+        assert!((int8_dot_product(&quantized, &quantized) - expected_dot_product).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_binary_quantization() {
+        let a = quantize_binary(&[0.1, -0.2, 0.3, 0.4, -0.5, -0.6, 0.7, -0.8, 0.9]);
+        let b = quantize_binary(&[0.1, 0.2, 0.3, 0.4, -0.5, -0.6, 0.7, -0.8, -0.9]);
+        assert_eq!(a, vec![0b1011_0010, 0b1000_0000]);
+        assert_eq!(b, vec![0b1111_0010, 0b0000_0000]);
+        assert_eq!(hamming_distance(&a, &b), 2);
+        assert_eq!(hamming_distance(&a, &a), 0);
+    }
+}
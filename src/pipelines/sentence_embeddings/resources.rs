@@ -24,6 +24,8 @@ pub enum SentenceEmbeddingsModelType {
     AllDistilrobertaV1,
     ParaphraseAlbertSmallV2,
     SentenceT5Base,
+    AllMpnetBaseV2,
+    ParaphraseMultilingualMpnetBaseV2,
 }
 
 impl SentenceEmbeddingsModulesConfigResources {
@@ -62,6 +64,16 @@ impl SentenceEmbeddingsModulesConfigResources {
         "sentence-t5-base/sbert-config",
         "https://huggingface.co/sentence-transformers/sentence-t5-base/resolve/main/modules.json",
     );
+    /// Shared under Apache 2.0 license at <https://huggingface.co/sentence-transformers/all-mpnet-base-v2>. Modified with conversion to C-array format.
+    pub const ALL_MPNET_BASE_V2: (&'static str, &'static str) = (
+        "all-mpnet-base-v2/sbert-config",
+        "https://huggingface.co/sentence-transformers/all-mpnet-base-v2/resolve/main/modules.json",
+    );
+    /// Shared under Apache 2.0 license at <https://huggingface.co/sentence-transformers/paraphrase-multilingual-mpnet-base-v2>. Modified with conversion to C-array format.
+    pub const PARAPHRASE_MULTILINGUAL_MPNET_BASE_V2: (&'static str, &'static str) = (
+        "paraphrase-multilingual-mpnet-base-v2/sbert-config",
+        "https://huggingface.co/sentence-transformers/paraphrase-multilingual-mpnet-base-v2/resolve/main/modules.json",
+    );
 }
 
 impl SentenceEmbeddingsDenseResources {
@@ -126,6 +138,16 @@ impl SentenceEmbeddingsPoolingConfigResources {
         "sentence-t5-base/sbert-pooling-config",
         "https://huggingface.co/sentence-transformers/sentence-t5-base/resolve/main/1_Pooling/config.json",
     );
+    /// Shared under Apache 2.0 license at <https://huggingface.co/sentence-transformers/all-mpnet-base-v2>. Modified with conversion to C-array format.
+    pub const ALL_MPNET_BASE_V2: (&'static str, &'static str) = (
+        "all-mpnet-base-v2/sbert-pooling-config",
+        "https://huggingface.co/sentence-transformers/all-mpnet-base-v2/resolve/main/1_Pooling/config.json",
+    );
+    /// Shared under Apache 2.0 license at <https://huggingface.co/sentence-transformers/paraphrase-multilingual-mpnet-base-v2>. Modified with conversion to C-array format.
+    pub const PARAPHRASE_MULTILINGUAL_MPNET_BASE_V2: (&'static str, &'static str) = (
+        "paraphrase-multilingual-mpnet-base-v2/sbert-pooling-config",
+        "https://huggingface.co/sentence-transformers/paraphrase-multilingual-mpnet-base-v2/resolve/main/1_Pooling/config.json",
+    );
 }
 
 impl SentenceEmbeddingsConfigResources {
@@ -164,6 +186,16 @@ impl SentenceEmbeddingsConfigResources {
         "sentence-t5-base/sbert-config",
         "https://huggingface.co/sentence-transformers/sentence-t5-base/resolve/main/sentence_bert_config.json",
     );
+    /// Shared under Apache 2.0 license at <https://huggingface.co/sentence-transformers/all-mpnet-base-v2>. Modified with conversion to C-array format.
+    pub const ALL_MPNET_BASE_V2: (&'static str, &'static str) = (
+        "all-mpnet-base-v2/sbert-config",
+        "https://huggingface.co/sentence-transformers/all-mpnet-base-v2/resolve/main/sentence_bert_config.json",
+    );
+    /// Shared under Apache 2.0 license at <https://huggingface.co/sentence-transformers/paraphrase-multilingual-mpnet-base-v2>. Modified with conversion to C-array format.
+    pub const PARAPHRASE_MULTILINGUAL_MPNET_BASE_V2: (&'static str, &'static str) = (
+        "paraphrase-multilingual-mpnet-base-v2/sbert-config",
+        "https://huggingface.co/sentence-transformers/paraphrase-multilingual-mpnet-base-v2/resolve/main/sentence_bert_config.json",
+    );
 }
 
 impl SentenceEmbeddingsTokenizerConfigResources {
@@ -202,4 +234,14 @@ impl SentenceEmbeddingsTokenizerConfigResources {
         "sentence-t5-base/tokenizer-config",
         "https://huggingface.co/sentence-transformers/sentence-t5-base/resolve/main/tokenizer_config.json",
     );
+    /// Shared under Apache 2.0 license at <https://huggingface.co/sentence-transformers/all-mpnet-base-v2>. Modified with conversion to C-array format.
+    pub const ALL_MPNET_BASE_V2: (&'static str, &'static str) = (
+        "all-mpnet-base-v2/tokenizer-config",
+        "https://huggingface.co/sentence-transformers/all-mpnet-base-v2/resolve/main/tokenizer_config.json",
+    );
+    /// Shared under Apache 2.0 license at <https://huggingface.co/sentence-transformers/paraphrase-multilingual-mpnet-base-v2>. Modified with conversion to C-array format.
+    pub const PARAPHRASE_MULTILINGUAL_MPNET_BASE_V2: (&'static str, &'static str) = (
+        "paraphrase-multilingual-mpnet-base-v2/tokenizer-config",
+        "https://huggingface.co/sentence-transformers/paraphrase-multilingual-mpnet-base-v2/resolve/main/tokenizer_config.json",
+    );
 }
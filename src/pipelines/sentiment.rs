@@ -60,6 +60,7 @@ use crate::pipelines::sequence_classification::{
     SequenceClassificationConfig, SequenceClassificationModel,
 };
 use serde::{Deserialize, Serialize};
+use tch::Device;
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 /// Enum with the possible sentiment polarities. Note that the pre-trained SST2 model does not include neutral sentiment.
@@ -154,6 +155,26 @@ impl SentimentModel {
         self.sequence_classification_model.get_tokenizer_mut()
     }
 
+    /// Casts the model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        self.sequence_classification_model.half()
+    }
+
+    /// Casts the model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        self.sequence_classification_model.bfloat16()
+    }
+
+    /// Casts the model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        self.sequence_classification_model.float()
+    }
+
+    /// Moves the model weights to the target device.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        self.sequence_classification_model.set_device(device)
+    }
+
     /// Extract sentiment form an array of text inputs
     ///
     /// # Arguments
@@ -58,6 +58,22 @@
 //! ]
 //! # ;
 //! ```
+//!
+//! Text pairs (e.g. natural language inference, paraphrase detection or semantic textual similarity) are classified with
+//! `predict_pair`, encoding both texts jointly with token type ids. Models with a single output label (e.g. STS-B regression
+//! models) return the raw regression score.
+//! ```no_run
+//! # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+//! # fn main() -> anyhow::Result<()> {
+//! # let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+//! let input = [(
+//!     "A soccer game with multiple males playing.",
+//!     "Some men are playing a sport.",
+//! )];
+//! let output = sequence_classification_model.predict_pair(&input, 32);
+//! # Ok(())
+//! # }
+//! ```
 use crate::albert::AlbertForSequenceClassification;
 use crate::bart::BartForSequenceClassification;
 use crate::bert::BertForSequenceClassification;
@@ -68,7 +84,8 @@ use crate::fnet::FNetForSequenceClassification;
 use crate::longformer::LongformerForSequenceClassification;
 use crate::mobilebert::MobileBertForSequenceClassification;
 use crate::pipelines::common::{
-    get_device, ConfigOption, ModelResource, ModelType, TokenizerOption,
+    cast_var_store, get_device, set_var_store_device, ConfigOption, ModelResource, ModelType,
+    TokenizerOption,
 };
 use crate::reformer::ReformerForSequenceClassification;
 use crate::resources::ResourceProvider;
@@ -225,16 +242,25 @@ impl SequenceClassificationOption {
     /// * `SequenceClassificationConfig` - Sequence classification pipeline configuration. The type of model created will be inferred from the
     ///     `ModelResources` (Torch or ONNX) and `ModelType` (Architecture for Torch models) variants provided and
     pub fn new(config: &SequenceClassificationConfig) -> Result<Self, RustBertError> {
+        Ok(Self::new_with_var_store(config)?.0)
+    }
+
+    /// Instantiate a new sequence classification model, also returning the `VarStore` holding the weights of Torch models
+    pub(crate) fn new_with_var_store(
+        config: &SequenceClassificationConfig,
+    ) -> Result<(Self, Option<VarStore>), RustBertError> {
         match config.model_resource {
-            ModelResource::Torch(_) => Self::new_torch(config),
+            ModelResource::Torch(_) => {
+                let (model, var_store) = Self::new_torch(config)?;
+                Ok((model, Some(var_store)))
+            }
             #[cfg(feature = "onnx")]
-            ModelResource::ONNX(_) => Self::new_onnx(config),
+            ModelResource::ONNX(_) => Ok((Self::new_onnx(config)?, None)),
         }
     }
 
-    fn new_torch(config: &SequenceClassificationConfig) -> Result<Self, RustBertError> {
+    fn new_torch(config: &SequenceClassificationConfig) -> Result<(Self, VarStore), RustBertError> {
         let device = config.device;
-        let weights_path = config.model_resource.get_torch_local_path()?;
         let mut var_store = VarStore::new(device);
         let model_config =
             &ConfigOption::from_file(config.model_type, config.config_resource.get_local_path()?);
@@ -391,8 +417,8 @@ impl SequenceClassificationOption {
                 "Sequence Classification not implemented for {model_type:?}!",
             ))),
         }?;
-        var_store.load(weights_path)?;
-        Ok(model)
+        crate::resources::load_weights(&config.model_resource, &mut var_store)?;
+        Ok((model, var_store))
     }
 
     #[cfg(feature = "onnx")]
@@ -597,6 +623,7 @@ pub struct SequenceClassificationModel {
     tokenizer: TokenizerOption,
     sequence_classifier: SequenceClassificationOption,
     label_mapping: HashMap<i64, String>,
+    var_store: Option<VarStore>,
     device: Device,
     max_length: usize,
 }
@@ -669,7 +696,8 @@ impl SequenceClassificationModel {
         tokenizer: TokenizerOption,
     ) -> Result<SequenceClassificationModel, RustBertError> {
         let config_path = config.config_resource.get_local_path()?;
-        let sequence_classifier = SequenceClassificationOption::new(&config)?;
+        let (sequence_classifier, var_store) =
+            SequenceClassificationOption::new_with_var_store(&config)?;
 
         let model_config = ConfigOption::from_file(config.model_type, config_path);
         let max_length = model_config
@@ -682,6 +710,7 @@ impl SequenceClassificationModel {
             tokenizer,
             sequence_classifier,
             label_mapping,
+            var_store,
             device,
             max_length,
         })
@@ -696,6 +725,28 @@ impl SequenceClassificationModel {
     pub fn get_tokenizer_mut(&mut self) -> &mut TokenizerOption {
         &mut self.tokenizer
     }
+
+    /// Casts the model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::Half)
+    }
+
+    /// Casts the model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::BFloat16)
+    }
+
+    /// Casts the model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::Float)
+    }
+
+    /// Moves the model weights to the target device, inputs are placed on this device for subsequent predictions.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        set_var_store_device(self.var_store.as_mut(), device)?;
+        self.device = device;
+        Ok(())
+    }
     /// Classify texts
     ///
     /// # Arguments
@@ -809,7 +860,11 @@ impl SequenceClassificationModel {
                 None,
                 false,
             );
-            output.sigmoid().detach().to(Device::Cpu)
+            output
+                .to_kind(Kind::Float)
+                .sigmoid()
+                .detach()
+                .to(Device::Cpu)
         });
         let label_indices = output.as_ref().ge(threshold).nonzero();
 
@@ -842,6 +897,180 @@ impl SequenceClassificationModel {
         }
         Ok(labels)
     }
+
+    fn forward_pairs(&self, input: &[(&str, &str)], batch_size: usize) -> Tensor {
+        let pad_id = self
+            .tokenizer
+            .get_pad_id()
+            .expect("The Tokenizer used for sequence classification should contain a PAD id");
+        let batch_logits = input
+            .chunks(batch_size.max(1))
+            .map(|batch| {
+                let (input_ids, token_type_ids) =
+                    self.tokenizer
+                        .tokenize_pair_and_pad(batch, self.max_length, self.device);
+                let mask = input_ids.ne(pad_id).to_kind(Kind::Int64);
+                no_grad(|| {
+                    self.sequence_classifier
+                        .forward_t(
+                            Some(&input_ids),
+                            Some(&mask),
+                            Some(&token_type_ids),
+                            None,
+                            None,
+                            false,
+                        )
+                        .to_kind(Kind::Float)
+                        .detach()
+                        .to(Device::Cpu)
+                })
+            })
+            .collect::<Vec<Tensor>>();
+        Tensor::cat(batch_logits.as_slice(), 0)
+    }
+
+    /// Classify text pairs (e.g. natural language inference, paraphrase detection or semantic textual similarity)
+    ///
+    /// Both texts of a pair are encoded jointly, with token type ids identifying the first and second text.
+    /// For regression models with a single output label (e.g. STS-B), the score returned is the raw output of the
+    /// regression head instead of a probability.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[(&str, &str)]` Array of text pairs to classify.
+    /// * `batch_size` - `usize` maximum number of text pairs classified in a single forward pass.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Label>` containing labels for input text pairs
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let input = [
+    ///     ("A man is playing a guitar.", "A person plays an instrument."),
+    ///     ("A man is playing a guitar.", "A woman is slicing an onion."),
+    /// ];
+    /// let output = sequence_classification_model.predict_pair(&input, 32);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_pair<'a, S>(&self, input: S, batch_size: usize) -> Vec<Label>
+    where
+        S: AsRef<[(&'a str, &'a str)]>,
+    {
+        let input = input.as_ref();
+        if input.is_empty() {
+            return vec![];
+        }
+        let logits = self.forward_pairs(input, batch_size);
+
+        if logits.size().last() == Some(&1) {
+            let label_string = self.label_mapping.get(&0).cloned().unwrap_or_default();
+            return logits
+                .squeeze_dim(-1)
+                .iter::<f64>()
+                .unwrap()
+                .enumerate()
+                .map(|(sentence, score)| Label {
+                    text: label_string.clone(),
+                    score,
+                    id: 0,
+                    sentence,
+                })
+                .collect();
+        }
+
+        let output = logits.softmax(-1, Kind::Float);
+        let label_indices = output.argmax(-1, true).squeeze_dim(1);
+        let scores = output
+            .gather(1, &label_indices.unsqueeze(-1), false)
+            .squeeze_dim(1);
+        label_indices
+            .iter::<i64>()
+            .unwrap()
+            .zip(scores.iter::<f64>().unwrap())
+            .enumerate()
+            .map(|(sentence, (id, score))| Label {
+                text: self.label_mapping.get(&id).unwrap().clone(),
+                score,
+                id,
+                sentence,
+            })
+            .collect()
+    }
+
+    /// Multi-label classification of text pairs
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[(&str, &str)]` Array of text pairs to classify.
+    /// * `threshold` - `f64` threshold above which a label will be considered true by the classifier
+    /// * `batch_size` - `usize` maximum number of text pairs classified in a single forward pass.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Vec<Label>>` containing a vector of true labels for each input text pair
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let input = [
+    ///     ("How do I learn Rust?", "What is the best way to learn Rust?"),
+    ///     ("How do I learn Rust?", "Where can I buy a guitar?"),
+    /// ];
+    /// let output = sequence_classification_model.predict_pair_multilabel(&input, 0.5, 32)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_pair_multilabel(
+        &self,
+        input: &[(&str, &str)],
+        threshold: f64,
+        batch_size: usize,
+    ) -> Result<Vec<Vec<Label>>, RustBertError> {
+        if input.is_empty() {
+            return Ok(vec![]);
+        }
+        let output = self.forward_pairs(input, batch_size).sigmoid();
+
+        let mut labels: Vec<Vec<Label>> = Vec::with_capacity(input.len());
+        for (sentence, sentence_scores) in output.split(1, 0).iter().enumerate() {
+            let sentence_scores = sentence_scores.view(-1).iter::<f64>()?;
+            labels.push(
+                sentence_scores
+                    .enumerate()
+                    .filter(|(_, score)| *score >= threshold)
+                    .map(|(id, score)| {
+                        let id = id as i64;
+                        Ok(Label {
+                            text: self
+                                .label_mapping
+                                .get(&id)
+                                .ok_or_else(|| {
+                                    RustBertError::InvalidConfigurationError(format!(
+                                        "No label mapping provided for label id {id}"
+                                    ))
+                                })?
+                                .clone(),
+                            score,
+                            id,
+                            sentence,
+                        })
+                    })
+                    .collect::<Result<Vec<Label>, RustBertError>>()?,
+            );
+        }
+        Ok(labels)
+    }
 }
 
 #[cfg(test)]
@@ -333,6 +333,66 @@ impl SummarizationOption {
         }
     }
 
+    /// Interface method to cast the model weights to half precision (`Kind::Half`)
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        match self {
+            Self::Bart(model_ref) => model_ref.half(),
+            Self::T5(model_ref) => model_ref.half(),
+            Self::LongT5(model_ref) => model_ref.half(),
+            Self::ProphetNet(model_ref) => model_ref.half(),
+            Self::Pegasus(model_ref) => model_ref.half(),
+            #[cfg(feature = "onnx")]
+            Self::ONNX(_) => Err(RustBertError::OrtError(
+                "Type casting not supported for ONNX models.".to_string(),
+            )),
+        }
+    }
+
+    /// Interface method to cast the model weights to bfloat16 precision (`Kind::BFloat16`)
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        match self {
+            Self::Bart(model_ref) => model_ref.bfloat16(),
+            Self::T5(model_ref) => model_ref.bfloat16(),
+            Self::LongT5(model_ref) => model_ref.bfloat16(),
+            Self::ProphetNet(model_ref) => model_ref.bfloat16(),
+            Self::Pegasus(model_ref) => model_ref.bfloat16(),
+            #[cfg(feature = "onnx")]
+            Self::ONNX(_) => Err(RustBertError::OrtError(
+                "Type casting not supported for ONNX models.".to_string(),
+            )),
+        }
+    }
+
+    /// Interface method to cast the model weights to single precision (`Kind::Float`)
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        match self {
+            Self::Bart(model_ref) => model_ref.float(),
+            Self::T5(model_ref) => model_ref.float(),
+            Self::LongT5(model_ref) => model_ref.float(),
+            Self::ProphetNet(model_ref) => model_ref.float(),
+            Self::Pegasus(model_ref) => model_ref.float(),
+            #[cfg(feature = "onnx")]
+            Self::ONNX(_) => Err(RustBertError::OrtError(
+                "Type casting not supported for ONNX models.".to_string(),
+            )),
+        }
+    }
+
+    /// Interface method to move the model weights to a device
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        match self {
+            Self::Bart(model_ref) => model_ref.set_device(device),
+            Self::T5(model_ref) => model_ref.set_device(device),
+            Self::LongT5(model_ref) => model_ref.set_device(device),
+            Self::ProphetNet(model_ref) => model_ref.set_device(device),
+            Self::Pegasus(model_ref) => model_ref.set_device(device),
+            #[cfg(feature = "onnx")]
+            Self::ONNX(_) => Err(RustBertError::OrtError(
+                "Device assignment not supported for ONNX models.".to_string(),
+            )),
+        }
+    }
+
     /// Interface method to generate() of the particular models.
     pub fn generate<S>(&self, prompt_texts: Option<&[S]>) -> Vec<String>
     where
@@ -458,6 +518,26 @@ impl SummarizationModel {
         self.model.get_tokenizer_mut()
     }
 
+    /// Casts the model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        self.model.half()
+    }
+
+    /// Casts the model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        self.model.bfloat16()
+    }
+
+    /// Casts the model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        self.model.float()
+    }
+
+    /// Moves the model weights to the target device.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        self.model.set_device(device)
+    }
+
     /// Summarize texts provided
     ///
     /// # Arguments
@@ -39,7 +39,9 @@ use crate::gpt_j::GptJGenerator;
 use crate::gpt_neo::GptNeoGenerator;
 use crate::openai_gpt::OpenAIGenerator;
 use crate::pipelines::common::{ModelResource, ModelType, TokenizerOption};
-use crate::pipelines::generation_utils::{GenerateConfig, GenerateOptions, LanguageGenerator};
+use crate::pipelines::generation_utils::{
+    GenerateConfig, GenerateOptions, LanguageGenerator, PrefixCache,
+};
 use crate::reformer::ReformerGenerator;
 use crate::resources::ResourceProvider;
 use crate::t5::T5Generator;
@@ -335,11 +337,43 @@ impl TextGenerationOption {
     where
         S: AsRef<str> + Sync,
     {
-        let generate_options = Some(GenerateOptions {
+        let generate_options = GenerateOptions {
             min_length,
             max_length,
             ..Default::default()
-        });
+        };
+        self.generate_indices_with_options(prompt_texts, generate_options)
+    }
+
+    /// Interface method to generate() of the particular models, starting from a cached prefix.
+    pub fn generate_indices_with_prefix_cache<S>(
+        &self,
+        prompt_texts: Option<&[S]>,
+        prefix_cache: &PrefixCache,
+        min_length: Option<i64>,
+        max_length: Option<i64>,
+    ) -> Vec<Vec<i64>>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let generate_options = GenerateOptions {
+            min_length,
+            max_length,
+            prefix_cache: Some(prefix_cache),
+            ..Default::default()
+        };
+        self.generate_indices_with_options(prompt_texts, generate_options)
+    }
+
+    fn generate_indices_with_options<S>(
+        &self,
+        prompt_texts: Option<&[S]>,
+        generate_options: GenerateOptions,
+    ) -> Vec<Vec<i64>>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let generate_options = Some(generate_options);
         match *self {
             Self::GPT(ref model) => model
                 .generate_indices(prompt_texts, generate_options)
@@ -385,6 +419,23 @@ impl TextGenerationOption {
         }
     }
 
+    /// Interface method to prefix_cache() of the particular models.
+    pub fn prefix_cache(&self, prefix: &str) -> Result<PrefixCache, RustBertError> {
+        let tokenizer = self.get_tokenizer();
+        let prefix_ids = tokenizer.convert_tokens_to_ids(&tokenizer.tokenize(prefix));
+        match *self {
+            Self::GPT(ref model) => model.prefix_cache(&prefix_ids),
+            Self::GPT2(ref model) => model.prefix_cache(&prefix_ids),
+            Self::GPTNeo(ref model) => model.prefix_cache(&prefix_ids),
+            Self::GPTJ(ref model) => model.prefix_cache(&prefix_ids),
+            Self::XLNet(ref model) => model.prefix_cache(&prefix_ids),
+            Self::Reformer(ref model) => model.prefix_cache(&prefix_ids),
+            Self::T5(ref model) => model.prefix_cache(&prefix_ids),
+            #[cfg(feature = "onnx")]
+            Self::ONNX(ref model) => model.prefix_cache(&prefix_ids),
+        }
+    }
+
     pub fn half(&mut self) -> Result<(), RustBertError> {
         match self {
             Self::GPT(model_ref) => model_ref.half(),
@@ -401,6 +452,22 @@ impl TextGenerationOption {
         }
     }
 
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        match self {
+            Self::GPT(model_ref) => model_ref.bfloat16(),
+            Self::GPT2(model_ref) => model_ref.bfloat16(),
+            Self::GPTNeo(model_ref) => model_ref.bfloat16(),
+            Self::GPTJ(model_ref) => model_ref.bfloat16(),
+            Self::XLNet(model_ref) => model_ref.bfloat16(),
+            Self::Reformer(model_ref) => model_ref.bfloat16(),
+            Self::T5(model_ref) => model_ref.bfloat16(),
+            #[cfg(feature = "onnx")]
+            Self::ONNX(_) => Err(RustBertError::OrtError(
+                "Type casting not supported for ONNX models.".to_string(),
+            )),
+        }
+    }
+
     pub fn float(&mut self) -> Result<(), RustBertError> {
         match self {
             Self::GPT(model_ref) => model_ref.float(),
@@ -561,6 +628,10 @@ with people, even a bishop, begging for his blessing. <eod> </s> <eos>"
         self.model.half()
     }
 
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        self.model.bfloat16()
+    }
+
     pub fn float(&mut self) -> Result<(), RustBertError> {
         self.model.float()
     }
@@ -634,6 +705,84 @@ with people, even a bishop, begging for his blessing. <eod> </s> <eos>"
         }
         output
     }
+
+    /// Encodes a prompt prefix shared by many generations (e.g. a system prompt or few-shot examples), to be
+    /// passed to `generate_with_prefix_cache`. Supported for GPT2, GPT-Neo and GPT-J models.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - `&str` Prefix text.
+    ///
+    /// # Returns
+    /// * `PrefixCache` Encoded prefix
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::text_generation::TextGenerationModel;
+    ///
+    /// let model = TextGenerationModel::new(Default::default())?;
+    /// let prefix_cache = model.prefix_cache("The following are descriptions of animals.")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn prefix_cache(&self, prefix: &str) -> Result<PrefixCache, RustBertError> {
+        self.model.prefix_cache(prefix)
+    }
+
+    /// Generate texts from provided prompts, appended to a cached prefix. The prefix is not encoded again and is
+    /// excluded from the generated sequences. The prompts are tokenized separately from the prefix and should
+    /// include a leading space if they continue the prefix text.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of prompts following the prefix.
+    /// * `prefix_cache` - `&PrefixCache` Prefix encoded with `prefix_cache`.
+    ///
+    /// # Returns
+    /// * `Vec<String>` Generated texts
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::text_generation::TextGenerationModel;
+    ///
+    /// let model = TextGenerationModel::new(Default::default())?;
+    /// let prefix_cache = model.prefix_cache("The following are descriptions of animals.")?;
+    ///
+    /// let input = [" The dog", " The cat was"];
+    /// let output = model.generate_with_prefix_cache(&input, &prefix_cache);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn generate_with_prefix_cache<S>(
+        &self,
+        texts: &[S],
+        prefix_cache: &PrefixCache,
+    ) -> Vec<String>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let prefix_length = prefix_cache.prefix_ids().len();
+        let generated_indices = self.model.generate_indices_with_prefix_cache(
+            Some(texts),
+            prefix_cache,
+            Some(self.min_length + prefix_length as i64),
+            self.max_length
+                .map(|max_length| max_length + prefix_length as i64),
+        );
+
+        generated_indices
+            .into_iter()
+            .map(|generated_sequence| {
+                self.model
+                    .get_tokenizer()
+                    .decode(&generated_sequence[prefix_length..], true, true)
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
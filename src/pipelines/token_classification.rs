@@ -114,6 +114,7 @@
 
 use crate::albert::AlbertForTokenClassification;
 use crate::bert::BertForTokenClassification;
+use crate::common::crf::{argmax, viterbi_decode, LinearChainCRF};
 use crate::common::error::RustBertError;
 use crate::deberta::DebertaForTokenClassification;
 use crate::distilbert::DistilBertForTokenClassification;
@@ -122,7 +123,8 @@ use crate::fnet::FNetForTokenClassification;
 use crate::longformer::LongformerForTokenClassification;
 use crate::mobilebert::MobileBertForTokenClassification;
 use crate::pipelines::common::{
-    get_device, ConfigOption, ModelResource, ModelType, TokenizerOption,
+    cast_var_store, get_device, set_var_store_device, ConfigOption, ModelResource, ModelType,
+    TokenizerOption,
 };
 use crate::resources::ResourceProvider;
 use crate::roberta::RobertaForTokenClassification;
@@ -222,6 +224,237 @@ pub enum LabelAggregationOption {
     Custom(LabelAggregationFunction),
 }
 
+/// # Enum defining the aggregation method for the scores of tokens making up an entity
+/// Defines how the score of an entity is computed from the scores of its tokens when entities are chunked (e.g. by the NER pipeline).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreAggregationOption {
+    /// Product of the token scores
+    Product,
+    /// Average of the token scores
+    Average,
+    /// Minimum of the token scores
+    Min,
+    /// Score of the first token
+    First,
+}
+
+impl ScoreAggregationOption {
+    /// Aggregates the scores of the tokens making up an entity
+    pub fn aggregate<I>(&self, scores: I) -> f64
+    where
+        I: IntoIterator<Item = f64>,
+    {
+        let mut scores = scores.into_iter();
+        match self {
+            Self::Product => scores.product(),
+            Self::Average => {
+                let (sum, count) = scores.fold((0f64, 0usize), |(sum, count), score| {
+                    (sum + score, count + 1)
+                });
+                if count > 0 {
+                    sum / count as f64
+                } else {
+                    0f64
+                }
+            }
+            Self::Min => scores.fold(f64::INFINITY, f64::min),
+            Self::First => scores.next().unwrap_or(0f64),
+        }
+    }
+}
+
+/// # Enum defining the tagging scheme of the token labels
+/// Labels are expected to be made of a tag prefix and an entity type separated by a dash (e.g. `B-PER`), or `O` for tokens outside of entities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaggingScheme {
+    /// Begin, Inside, Outside (e.g. `B-PER`, `I-PER`, `O`)
+    BIO,
+    /// Begin, Inside, Outside, End, Single (e.g. `B-PER`, `I-PER`, `E-PER`, `S-PER`, `O`)
+    BIOES,
+    /// Begin, Inside, Last, Outside, Unit (e.g. `B-PER`, `I-PER`, `L-PER`, `U-PER`, `O`)
+    BILOU,
+}
+
+impl TaggingScheme {
+    /// Infers the tagging scheme from a set of labels. Returns `None` if some labels do not follow any of the supported schemes.
+    pub fn from_labels<'a, I>(labels: I) -> Option<TaggingScheme>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut scheme = TaggingScheme::BIO;
+        for label in labels {
+            match label.split('-').next()? {
+                "B" | "I" | "O" => {}
+                "E" | "S" => scheme = TaggingScheme::BIOES,
+                "L" | "U" => scheme = TaggingScheme::BILOU,
+                _ => return None,
+            }
+        }
+        Some(scheme)
+    }
+
+    fn is_allowed_start(&self, tag: Tag) -> bool {
+        match self {
+            Self::BIO => matches!(tag, Tag::Outside | Tag::Begin),
+            Self::BIOES | Self::BILOU => matches!(tag, Tag::Outside | Tag::Begin | Tag::Single),
+        }
+    }
+
+    fn is_allowed_end(&self, tag: Tag) -> bool {
+        match self {
+            Self::BIO => matches!(tag, Tag::Outside | Tag::Begin | Tag::Inside),
+            Self::BIOES | Self::BILOU => matches!(tag, Tag::Outside | Tag::End | Tag::Single),
+        }
+    }
+
+    fn is_allowed_transition(&self, from: (Tag, &str), to: (Tag, &str)) -> bool {
+        let same_entity = from.1 == to.1;
+        match self {
+            Self::BIO => match to.0 {
+                Tag::Outside | Tag::Begin => true,
+                Tag::Inside => matches!(from.0, Tag::Begin | Tag::Inside) & same_entity,
+                Tag::End | Tag::Single => false,
+            },
+            Self::BIOES | Self::BILOU => match from.0 {
+                Tag::Begin | Tag::Inside => matches!(to.0, Tag::Inside | Tag::End) & same_entity,
+                Tag::Outside | Tag::End | Tag::Single => {
+                    matches!(to.0, Tag::Outside | Tag::Begin | Tag::Single)
+                }
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Tag {
+    Begin,
+    Inside,
+    Outside,
+    End,
+    Single,
+}
+
+/// Splits a label (e.g. `B-PER`) into its tag and entity type. Returns `None` for labels not following a BIO, BIOES or BILOU scheme.
+pub(crate) fn parse_label(label: &str) -> Option<(Tag, &str)> {
+    let mut split_label = label.splitn(2, '-');
+    let tag = match split_label.next()? {
+        "B" => Tag::Begin,
+        "I" => Tag::Inside,
+        "O" => Tag::Outside,
+        "E" | "L" => Tag::End,
+        "S" | "U" => Tag::Single,
+        _ => return None,
+    };
+    Some((tag, split_label.next().unwrap_or("")))
+}
+
+/// # Label transition scores for a linear-chain decoding of token labels
+/// Scores are in log-space, illegal transitions are set to negative infinity.
+#[derive(Debug, Clone)]
+pub(crate) struct LabelTransitions {
+    /// Score for starting a sequence with each label
+    start: Vec<f64>,
+    /// Score for ending a sequence with each label
+    end: Vec<f64>,
+    /// Transition scores, indexed as `[from_label][to_label]`
+    transitions: Vec<Vec<f64>>,
+}
+
+impl LabelTransitions {
+    /// Builds hard transition constraints forbidding the label sequences that are illegal for the tagging scheme provided
+    pub(crate) fn from_tagging_scheme(
+        tagging_scheme: TaggingScheme,
+        label_mapping: &HashMap<i64, String>,
+    ) -> Result<LabelTransitions, RustBertError> {
+        let num_labels = label_mapping.keys().max().map_or(0, |max_id| *max_id + 1) as usize;
+        let mut tags = vec![None; num_labels];
+        for (label_id, label) in label_mapping {
+            tags[*label_id as usize] = Some(parse_label(label).ok_or_else(|| {
+                RustBertError::InvalidConfigurationError(format!(
+                    "Label {label} does not follow the {tagging_scheme:?} tagging scheme"
+                ))
+            })?);
+        }
+        let allowed_score = |allowed: bool| if allowed { 0f64 } else { f64::NEG_INFINITY };
+        let start = tags
+            .iter()
+            .map(|tag| {
+                allowed_score(
+                    matches!(tag, Some((tag, _)) if tagging_scheme.is_allowed_start(*tag)),
+                )
+            })
+            .collect();
+        let end = tags
+            .iter()
+            .map(|tag| {
+                allowed_score(matches!(tag, Some((tag, _)) if tagging_scheme.is_allowed_end(*tag)))
+            })
+            .collect();
+        let transitions = tags
+            .iter()
+            .map(|from| {
+                tags.iter()
+                    .map(|to| {
+                        allowed_score(match (from, to) {
+                            (Some(from), Some(to)) => {
+                                tagging_scheme.is_allowed_transition(*from, *to)
+                            }
+                            _ => false,
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(LabelTransitions {
+            start,
+            end,
+            transitions,
+        })
+    }
+
+    /// Builds transition scores from the weights of a linear-chain CRF layer
+    pub(crate) fn from_crf(crf: &LinearChainCRF) -> Result<LabelTransitions, RustBertError> {
+        let (start, end, transitions) = crf.get_transition_scores()?;
+        Ok(LabelTransitions {
+            start,
+            end,
+            transitions,
+        })
+    }
+
+    /// Adds the scores of another set of transitions (e.g. hard constraints on top of CRF transition scores)
+    pub(crate) fn combine(self, other: &LabelTransitions) -> LabelTransitions {
+        let add = |scores: Vec<f64>, other_scores: &[f64]| {
+            scores
+                .into_iter()
+                .zip(other_scores)
+                .map(|(score, other_score)| score + other_score)
+                .collect::<Vec<f64>>()
+        };
+        LabelTransitions {
+            start: add(self.start, &other.start),
+            end: add(self.end, &other.end),
+            transitions: self
+                .transitions
+                .into_iter()
+                .zip(&other.transitions)
+                .map(|(scores, other_scores)| add(scores, other_scores))
+                .collect(),
+        }
+    }
+
+    /// Viterbi search for the highest-scoring label sequence given per-position label (log-)scores of shape (*sequence_length*, *num_labels*).
+    /// Falls back to the greedy label sequence if no sequence satisfies the transition constraints.
+    pub(crate) fn decode(&self, emissions: &[Vec<f64>]) -> Vec<i64> {
+        viterbi_decode(emissions, &self.start, &self.end, &self.transitions).unwrap_or_else(|| {
+            emissions
+                .iter()
+                .map(|position_scores| argmax(position_scores).0 as i64)
+                .collect()
+        })
+    }
+}
+
 /// # Configuration for TokenClassificationModel
 /// Contains information regarding the model to load and device to place the model on.
 pub struct TokenClassificationConfig {
@@ -247,6 +480,16 @@ pub struct TokenClassificationConfig {
     pub label_aggregation_function: LabelAggregationOption,
     /// Batch size for predictions
     pub batch_size: usize,
+    /// Tagging scheme of the model labels (default: None, inferred from the model labels)
+    pub tagging_scheme: Option<TaggingScheme>,
+    /// Flag indicating if labels should be decoded with a Viterbi search forbidding illegal transitions for the tagging scheme (e.g. `I-PER` after `B-LOC`).
+    /// If false, the most likely label is selected independently for each token (default: false)
+    pub constrained_decoding: bool,
+    /// Aggregation method for the score of entities made of multiple tokens (default: `ScoreAggregationOption::Product`)
+    pub entity_score_aggregation: ScoreAggregationOption,
+    /// Optional resource with the weights of a linear-chain CRF output layer (`crf.transitions`, `crf.start_transitions`, `crf.end_transitions`),
+    /// for example the model weights of a BERT-CRF checkpoint. If provided, labels are decoded with a Viterbi search over the CRF transition scores (default: None)
+    pub crf_resource: Option<Box<dyn ResourceProvider + Send>>,
 }
 
 impl TokenClassificationConfig {
@@ -287,6 +530,10 @@ impl TokenClassificationConfig {
             device: Device::cuda_if_available(),
             label_aggregation_function,
             batch_size: 64,
+            tagging_scheme: None,
+            constrained_decoding: false,
+            entity_score_aggregation: ScoreAggregationOption::Product,
+            crf_resource: None,
         }
     }
 }
@@ -351,16 +598,25 @@ impl TokenClassificationOption {
     /// * `TokenClassificationConfig` - Token classification pipeline configuration. The type of model created will be inferred from the
     ///     `ModelResources` (Torch or ONNX) and `ModelType` (Architecture for Torch models) variants provided and
     pub fn new(config: &TokenClassificationConfig) -> Result<Self, RustBertError> {
+        Ok(Self::new_with_var_store(config)?.0)
+    }
+
+    /// Instantiate a new token classification model, also returning the `VarStore` holding the weights of Torch models
+    pub(crate) fn new_with_var_store(
+        config: &TokenClassificationConfig,
+    ) -> Result<(Self, Option<VarStore>), RustBertError> {
         match config.model_resource {
-            ModelResource::Torch(_) => Self::new_torch(config),
+            ModelResource::Torch(_) => {
+                let (model, var_store) = Self::new_torch(config)?;
+                Ok((model, Some(var_store)))
+            }
             #[cfg(feature = "onnx")]
-            ModelResource::ONNX(_) => Self::new_onnx(config),
+            ModelResource::ONNX(_) => Ok((Self::new_onnx(config)?, None)),
         }
     }
 
-    fn new_torch(config: &TokenClassificationConfig) -> Result<Self, RustBertError> {
+    fn new_torch(config: &TokenClassificationConfig) -> Result<(Self, VarStore), RustBertError> {
         let device = config.device;
-        let weights_path = config.model_resource.get_torch_local_path()?;
         let mut var_store = VarStore::new(device);
         let model_config =
             &ConfigOption::from_file(config.model_type, config.config_resource.get_local_path()?);
@@ -506,8 +762,8 @@ impl TokenClassificationOption {
                 "Token classification not implemented for {model_type:?}!"
             ))),
         }?;
-        var_store.load(weights_path)?;
-        Ok(model)
+        crate::resources::load_weights(&config.model_resource, &mut var_store)?;
+        Ok((model, var_store))
     }
 
     #[cfg(feature = "onnx")]
@@ -695,8 +951,10 @@ pub struct TokenClassificationModel {
     tokenizer: TokenizerOption,
     token_sequence_classifier: TokenClassificationOption,
     label_mapping: HashMap<i64, String>,
+    var_store: Option<VarStore>,
     device: Device,
     label_aggregation_function: LabelAggregationOption,
+    label_transitions: Option<LabelTransitions>,
     max_length: usize,
     batch_size: usize,
 }
@@ -769,7 +1027,8 @@ impl TokenClassificationModel {
         tokenizer: TokenizerOption,
     ) -> Result<TokenClassificationModel, RustBertError> {
         let config_path = config.config_resource.get_local_path()?;
-        let token_sequence_classifier = TokenClassificationOption::new(&config)?;
+        let (token_sequence_classifier, var_store) =
+            TokenClassificationOption::new_with_var_store(&config)?;
 
         let label_aggregation_function = config.label_aggregation_function;
 
@@ -779,14 +1038,49 @@ impl TokenClassificationModel {
             .map(|v| v as usize)
             .unwrap_or(usize::MAX);
         let label_mapping = model_config.get_label_mapping().clone();
+        let crf_transitions = config
+            .crf_resource
+            .as_ref()
+            .map(|crf_resource| {
+                let num_labels = label_mapping.keys().max().map_or(0, |max_id| *max_id + 1);
+                let mut crf_var_store = VarStore::new(Device::Cpu);
+                let crf = LinearChainCRF::new(crf_var_store.root() / "crf", num_labels);
+                crf_var_store.load(crf_resource.get_local_path()?)?;
+                LabelTransitions::from_crf(&crf)
+            })
+            .transpose()?;
+        let constraint_transitions = if config.constrained_decoding {
+            let tagging_scheme = config
+                .tagging_scheme
+                .or_else(|| TaggingScheme::from_labels(label_mapping.values().map(String::as_str)))
+                .ok_or_else(|| {
+                    RustBertError::InvalidConfigurationError(
+                        "Constrained decoding requires labels following a BIO, BIOES or BILOU tagging scheme".to_string(),
+                    )
+                })?;
+            Some(LabelTransitions::from_tagging_scheme(
+                tagging_scheme,
+                &label_mapping,
+            )?)
+        } else {
+            None
+        };
+        let label_transitions = match (crf_transitions, constraint_transitions) {
+            (Some(crf_transitions), Some(constraint_transitions)) => {
+                Some(crf_transitions.combine(&constraint_transitions))
+            }
+            (crf_transitions, constraint_transitions) => crf_transitions.or(constraint_transitions),
+        };
         let batch_size = config.batch_size;
         let device = get_device(config.model_resource, config.device);
         Ok(TokenClassificationModel {
             tokenizer,
             token_sequence_classifier,
             label_mapping,
+            var_store,
             device,
             label_aggregation_function,
+            label_transitions,
             max_length,
             batch_size,
         })
@@ -802,6 +1096,28 @@ impl TokenClassificationModel {
         &mut self.tokenizer
     }
 
+    /// Casts the model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::Half)
+    }
+
+    /// Casts the model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::BFloat16)
+    }
+
+    /// Casts the model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::Float)
+    }
+
+    /// Moves the model weights to the target device, inputs are placed on this device for subsequent predictions.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        set_var_store_device(self.var_store.as_mut(), device)?;
+        self.device = device;
+        Ok(())
+    }
+
     fn generate_features<S>(&self, input: S, example_index: usize) -> Vec<InputFeature>
     where
         S: AsRef<str>,
@@ -977,22 +1293,33 @@ impl TokenClassificationModel {
                 let batch_features = &mut features[start..end];
                 let (input_ids, attention_masks, token_type_ids) =
                     self.pad_features(batch_features);
-                let output = self.token_sequence_classifier.forward_t(
-                    Some(&input_ids),
-                    Some(&attention_masks),
-                    Some(&token_type_ids),
-                    None,
-                    None,
-                    false,
-                );
+                let output = self
+                    .token_sequence_classifier
+                    .forward_t(
+                        Some(&input_ids),
+                        Some(&attention_masks),
+                        Some(&token_type_ids),
+                        None,
+                        None,
+                        false,
+                    )
+                    .to_kind(Kind::Float);
                 let score = output.exp()
                     / output
                         .exp()
                         .sum_dim_intlist([-1].as_slice(), true, Kind::Float);
                 let label_indices = score.argmax(-1, true);
                 for sentence_idx in 0..label_indices.size()[0] {
-                    let labels = label_indices.get(sentence_idx);
                     let feature = &features[sentence_idx as usize];
+                    let labels = match &self.label_transitions {
+                        Some(label_transitions) => Self::decode_viterbi_labels(
+                            label_transitions,
+                            &score.get(sentence_idx),
+                            &label_indices.get(sentence_idx),
+                            feature,
+                        ),
+                        None => label_indices.get(sentence_idx),
+                    };
                     let sentence_reference_flag = &feature.reference_feature;
                     let original_chars = input[feature.example_index]
                         .as_ref()
@@ -1038,6 +1365,45 @@ impl TokenClassificationModel {
         tokens
     }
 
+    fn decode_viterbi_labels(
+        label_transitions: &LabelTransitions,
+        scores: &Tensor,
+        greedy_labels: &Tensor,
+        feature: &InputFeature,
+    ) -> Tensor {
+        let mut labels = greedy_labels
+            .view(-1)
+            .iter::<i64>()
+            .unwrap()
+            .collect::<Vec<i64>>();
+        // Special tokens are excluded from the label sequence, padding positions are beyond the feature mask
+        let positions = feature
+            .mask
+            .iter()
+            .enumerate()
+            .filter(|(_, mask)| **mask != Mask::Special)
+            .map(|(position, _)| position)
+            .collect::<Vec<usize>>();
+        let log_scores = scores.log().to(Device::Cpu);
+        let emissions = positions
+            .iter()
+            .map(|position| {
+                log_scores
+                    .get(*position as i64)
+                    .iter::<f64>()
+                    .unwrap()
+                    .collect::<Vec<f64>>()
+            })
+            .collect::<Vec<Vec<f64>>>();
+        for (position, label) in positions
+            .into_iter()
+            .zip(label_transitions.decode(&emissions))
+        {
+            labels[position] = label;
+        }
+        Tensor::from_slice(&labels)
+    }
+
     fn pad_features(&self, features: &mut [InputFeature]) -> (Tensor, Tensor, Tensor) {
         let max_len = features
             .iter()
@@ -1251,3 +1617,88 @@ impl TokenClassificationModel {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bio_label_mapping() -> HashMap<i64, String> {
+        ["O", "B-PER", "I-PER", "B-LOC", "I-LOC"]
+            .iter()
+            .enumerate()
+            .map(|(label_id, label)| (label_id as i64, label.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_tagging_scheme_from_labels() {
+        assert_eq!(
+            TaggingScheme::from_labels(["O", "B-PER", "I-PER"]),
+            Some(TaggingScheme::BIO)
+        );
+        assert_eq!(
+            TaggingScheme::from_labels(["O", "B-PER", "I-PER", "E-PER", "S-PER"]),
+            Some(TaggingScheme::BIOES)
+        );
+        assert_eq!(
+            TaggingScheme::from_labels(["O", "B-PER", "I-PER", "L-PER", "U-PER"]),
+            Some(TaggingScheme::BILOU)
+        );
+        assert_eq!(TaggingScheme::from_labels(["NN", "VBZ"]), None);
+    }
+
+    #[test]
+    fn test_constrained_decoding_forbids_illegal_transitions() {
+        // Given
+        let label_transitions =
+            LabelTransitions::from_tagging_scheme(TaggingScheme::BIO, &bio_label_mapping())
+                .unwrap();
+        // Greedy decoding yields B-LOC I-PER I-PER, I-PER cannot follow B-LOC
+        let emissions = [
+            [0.05, 0.30, 0.05, 0.55, 0.05],
+            [0.05, 0.05, 0.50, 0.05, 0.35],
+            [0.05, 0.05, 0.60, 0.05, 0.25],
+        ]
+        .iter()
+        .map(|scores| scores.iter().map(|score: &f64| score.ln()).collect())
+        .collect::<Vec<Vec<f64>>>();
+
+        // When
+        let labels = label_transitions.decode(&emissions);
+
+        // Then
+        assert_eq!(labels, vec![1, 2, 2]);
+    }
+
+    #[test]
+    fn test_constrained_decoding_bilou() {
+        // Given
+        let label_mapping: HashMap<i64, String> = ["O", "B-PER", "I-PER", "L-PER", "U-PER"]
+            .iter()
+            .enumerate()
+            .map(|(label_id, label)| (label_id as i64, label.to_string()))
+            .collect();
+        let label_transitions =
+            LabelTransitions::from_tagging_scheme(TaggingScheme::BILOU, &label_mapping).unwrap();
+        // Greedy decoding yields an unterminated entity B-PER I-PER
+        let emissions = [[0.1, 0.6, 0.1, 0.1, 0.1], [0.1, 0.1, 0.5, 0.3, 0.0]]
+            .iter()
+            .map(|scores| scores.iter().map(|score: &f64| score.ln()).collect())
+            .collect::<Vec<Vec<f64>>>();
+
+        // When
+        let labels = label_transitions.decode(&emissions);
+
+        // Then
+        assert_eq!(labels, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_score_aggregation() {
+        let scores = [0.9, 0.5, 0.8];
+        assert!((ScoreAggregationOption::Product.aggregate(scores) - 0.36).abs() < 1e-9);
+        assert!((ScoreAggregationOption::Average.aggregate(scores) - 0.7333333333).abs() < 1e-9);
+        assert!((ScoreAggregationOption::Min.aggregate(scores) - 0.5).abs() < 1e-9);
+        assert!((ScoreAggregationOption::First.aggregate(scores) - 0.9).abs() < 1e-9);
+    }
+}
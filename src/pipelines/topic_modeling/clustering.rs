@@ -0,0 +1,363 @@
+/// # Clustering algorithm variants used to group documents into topics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClusteringType {
+    /// K-means clustering, assigning every document to one of `num_clusters` topics.
+    /// Centroids are initialized deterministically by farthest point sampling.
+    KMeans {
+        /// Number of clusters (topics) to create
+        num_clusters: usize,
+        /// Maximum number of assignment/update iterations
+        max_iterations: usize,
+    },
+    /// HDBSCAN (Campello et al., 2013) density-based clustering. The number of topics is inferred from the data
+    /// and documents that do not belong to a dense region are marked as outliers.
+    Hdbscan {
+        /// Minimum number of documents in a topic
+        min_cluster_size: usize,
+        /// Number of neighbours used to estimate the density around a document (defaults to `min_cluster_size`).
+        /// Larger values lead to more documents being marked as outliers.
+        min_samples: Option<usize>,
+    },
+}
+
+impl ClusteringType {
+    /// Clusters the points provided, returning the cluster of each point (`None` for outliers).
+    /// Cluster identifiers are contiguous and start at 0.
+    pub(crate) fn cluster(&self, points: &[Vec<f32>]) -> Vec<Option<usize>> {
+        if points.is_empty() {
+            return vec![];
+        }
+        match self {
+            ClusteringType::KMeans {
+                num_clusters,
+                max_iterations,
+            } => k_means(points, *num_clusters, *max_iterations)
+                .into_iter()
+                .map(Some)
+                .collect(),
+            ClusteringType::Hdbscan {
+                min_cluster_size,
+                min_samples,
+            } => hdbscan(
+                points,
+                *min_cluster_size,
+                min_samples.unwrap_or(*min_cluster_size),
+            ),
+        }
+    }
+}
+
+pub(super) fn squared_distance(a: &[f32], b: &[f32]) -> f64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(a, b)| (*a as f64 - *b as f64).powi(2))
+        .sum()
+}
+
+fn k_means(points: &[Vec<f32>], num_clusters: usize, max_iterations: usize) -> Vec<usize> {
+    let num_clusters = num_clusters.clamp(1, points.len());
+    let dimension = points[0].len();
+
+    // The first centroid is the point closest to the mean, the following ones the points farthest from the existing centroids
+    let mean = (0..dimension)
+        .map(|dim| points.iter().map(|point| point[dim] as f64).sum::<f64>() / points.len() as f64)
+        .map(|value| value as f32)
+        .collect::<Vec<f32>>();
+    let first_centroid = (0..points.len())
+        .min_by(|a, b| {
+            squared_distance(&points[*a], &mean).total_cmp(&squared_distance(&points[*b], &mean))
+        })
+        .unwrap();
+    let mut centroids = vec![points[first_centroid].clone()];
+    let mut min_distances = points
+        .iter()
+        .map(|point| squared_distance(point, &centroids[0]))
+        .collect::<Vec<f64>>();
+    while centroids.len() < num_clusters {
+        let next_centroid = (0..points.len())
+            .max_by(|a, b| min_distances[*a].total_cmp(&min_distances[*b]))
+            .unwrap();
+        centroids.push(points[next_centroid].clone());
+        for (point, min_distance) in points.iter().zip(min_distances.iter_mut()) {
+            *min_distance =
+                min_distance.min(squared_distance(point, &centroids[centroids.len() - 1]));
+        }
+    }
+
+    let mut assignments = vec![0usize; points.len()];
+    for iteration in 0..max_iterations.max(1) {
+        let mut changed = false;
+        for (point, assignment) in points.iter().zip(assignments.iter_mut()) {
+            let closest = (0..centroids.len())
+                .min_by(|a, b| {
+                    squared_distance(point, &centroids[*a])
+                        .total_cmp(&squared_distance(point, &centroids[*b]))
+                })
+                .unwrap();
+            if closest != *assignment {
+                *assignment = closest;
+                changed = true;
+            }
+        }
+        if !changed && iteration > 0 {
+            break;
+        }
+        let mut sums = vec![vec![0f64; dimension]; centroids.len()];
+        let mut counts = vec![0usize; centroids.len()];
+        for (point, assignment) in points.iter().zip(assignments.iter()) {
+            counts[*assignment] += 1;
+            for (sum, value) in sums[*assignment].iter_mut().zip(point.iter()) {
+                *sum += *value as f64;
+            }
+        }
+        for ((centroid, sum), count) in centroids.iter_mut().zip(sums).zip(counts) {
+            // Empty clusters keep their previous centroid
+            if count > 0 {
+                *centroid = sum
+                    .into_iter()
+                    .map(|value| (value / count as f64) as f32)
+                    .collect();
+            }
+        }
+    }
+    assignments
+}
+
+struct CondensedCluster {
+    parent: Option<usize>,
+    birth_lambda: f64,
+    children: Vec<usize>,
+    stability: f64,
+}
+
+fn hdbscan(points: &[Vec<f32>], min_cluster_size: usize, min_samples: usize) -> Vec<Option<usize>> {
+    let num_points = points.len();
+    let min_cluster_size = min_cluster_size.max(2);
+    if num_points < min_cluster_size {
+        return vec![None; num_points];
+    }
+    let distance = |a: usize, b: usize| squared_distance(&points[a], &points[b]).sqrt();
+
+    // Core distance: distance to the `min_samples`-th nearest neighbour (including the point itself)
+    let core_distances = (0..num_points)
+        .map(|point| {
+            let mut distances = (0..num_points)
+                .map(|other| distance(point, other))
+                .collect::<Vec<f64>>();
+            let k = min_samples.clamp(1, num_points) - 1;
+            *distances.select_nth_unstable_by(k, |a, b| a.total_cmp(b)).1
+        })
+        .collect::<Vec<f64>>();
+
+    // Minimum spanning tree of the mutual reachability graph (Prim's algorithm on the dense graph)
+    let mut in_tree = vec![false; num_points];
+    let mut best_distances = vec![f64::INFINITY; num_points];
+    let mut best_neighbours = vec![0usize; num_points];
+    let mut edges = Vec::with_capacity(num_points - 1);
+    let mut current = 0;
+    in_tree[0] = true;
+    for _ in 1..num_points {
+        for other in 0..num_points {
+            if in_tree[other] {
+                continue;
+            }
+            let mutual_reachability = distance(current, other)
+                .max(core_distances[current])
+                .max(core_distances[other]);
+            if mutual_reachability < best_distances[other] {
+                best_distances[other] = mutual_reachability;
+                best_neighbours[other] = current;
+            }
+        }
+        let next = (0..num_points)
+            .filter(|point| !in_tree[*point])
+            .min_by(|a, b| best_distances[*a].total_cmp(&best_distances[*b]))
+            .unwrap();
+        edges.push((best_neighbours[next], next, best_distances[next]));
+        in_tree[next] = true;
+        current = next;
+    }
+    edges.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+    // Single linkage hierarchy: node `num_points + i` merges the two components joined by the i-th edge
+    let mut union_parents = (0..2 * num_points - 1).collect::<Vec<usize>>();
+    let find = |union_parents: &mut Vec<usize>, mut node: usize| {
+        while union_parents[node] != node {
+            union_parents[node] = union_parents[union_parents[node]];
+            node = union_parents[node];
+        }
+        node
+    };
+    let mut merges = Vec::with_capacity(num_points - 1);
+    let mut sizes = vec![1usize; 2 * num_points - 1];
+    for (index, (a, b, edge_distance)) in edges.into_iter().enumerate() {
+        let node = num_points + index;
+        let left = find(&mut union_parents, a);
+        let right = find(&mut union_parents, b);
+        union_parents[left] = node;
+        union_parents[right] = node;
+        sizes[node] = sizes[left] + sizes[right];
+        merges.push((left, right, edge_distance));
+    }
+
+    let leaves = |node: usize| {
+        let mut stack = vec![node];
+        let mut leaves = Vec::new();
+        while let Some(node) = stack.pop() {
+            if node < num_points {
+                leaves.push(node);
+            } else {
+                let (left, right, _) = merges[node - num_points];
+                stack.push(left);
+                stack.push(right);
+            }
+        }
+        leaves
+    };
+
+    // Condensed tree: splits creating a component smaller than `min_cluster_size` are considered
+    // as points falling out of the parent cluster rather than as new clusters
+    let mut clusters = vec![CondensedCluster {
+        parent: None,
+        birth_lambda: 0.0,
+        children: vec![],
+        stability: 0.0,
+    }];
+    let mut point_clusters = vec![0usize; num_points];
+    let mut stack = vec![(2 * num_points - 2, 0usize)];
+    while let Some((node, cluster)) = stack.pop() {
+        let (left, right, merge_distance) = merges[node - num_points];
+        let lambda = 1.0 / merge_distance.max(1e-12);
+        let birth_lambda = clusters[cluster].birth_lambda;
+        let is_large = |child: usize| sizes[child] >= min_cluster_size;
+        if is_large(left) && is_large(right) {
+            for child in [left, right] {
+                let child_cluster = clusters.len();
+                clusters.push(CondensedCluster {
+                    parent: Some(cluster),
+                    birth_lambda: lambda,
+                    children: vec![],
+                    stability: 0.0,
+                });
+                clusters[cluster].children.push(child_cluster);
+                clusters[cluster].stability += (lambda - birth_lambda) * sizes[child] as f64;
+                stack.push((child, child_cluster));
+            }
+        } else {
+            for child in [left, right] {
+                if is_large(child) {
+                    stack.push((child, cluster));
+                } else {
+                    for point in leaves(child) {
+                        point_clusters[point] = cluster;
+                        clusters[cluster].stability += lambda - birth_lambda;
+                    }
+                }
+            }
+        }
+    }
+
+    // Excess of mass cluster selection, processing children before their parents. The root cluster is never selected.
+    let mut selected = vec![false; clusters.len()];
+    let mut subtree_stabilities = vec![0f64; clusters.len()];
+    for cluster in (1..clusters.len()).rev() {
+        let children_stability = clusters[cluster]
+            .children
+            .iter()
+            .map(|child| subtree_stabilities[*child])
+            .sum::<f64>();
+        if clusters[cluster].children.is_empty()
+            || clusters[cluster].stability >= children_stability
+        {
+            selected[cluster] = true;
+            subtree_stabilities[cluster] = clusters[cluster].stability;
+        } else {
+            subtree_stabilities[cluster] = children_stability;
+        }
+    }
+
+    // Points belong to their highest selected ancestor cluster, if any
+    let mut labels = vec![None; clusters.len()];
+    let mut num_labels = 0;
+    for cluster in 1..clusters.len() {
+        if selected[cluster] && !has_selected_ancestor(&clusters, &selected, cluster) {
+            labels[cluster] = Some(num_labels);
+            num_labels += 1;
+        }
+    }
+    point_clusters
+        .into_iter()
+        .map(|mut cluster| {
+            let mut label = labels[cluster];
+            while let Some(parent) = clusters[cluster].parent {
+                cluster = parent;
+                label = labels[cluster].or(label);
+            }
+            label
+        })
+        .collect()
+}
+
+fn has_selected_ancestor(clusters: &[CondensedCluster], selected: &[bool], cluster: usize) -> bool {
+    let mut ancestor = clusters[cluster].parent;
+    while let Some(cluster) = ancestor {
+        if selected[cluster] {
+            return true;
+        }
+        ancestor = clusters[cluster].parent;
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn blobs() -> Vec<Vec<f32>> {
+        let mut points = Vec::new();
+        for center in [(0.0f32, 0.0f32), (10.0, 10.0), (-10.0, 10.0)] {
+            for i in 0..8 {
+                let angle = i as f32 * std::f32::consts::PI / 4.0;
+                let radius = 0.5 + 0.1 * i as f32;
+                points.push(vec![
+                    center.0 + radius * angle.cos(),
+                    center.1 + radius * angle.sin(),
+                ]);
+            }
+        }
+        points
+    }
+
+    fn assert_blob_clusters(labels: &[Option<usize>]) {
+        for blob in labels.chunks(8) {
+            assert!(blob[0].is_some());
+            assert!(blob.iter().all(|label| *label == blob[0]));
+        }
+        assert_ne!(labels[0], labels[8]);
+        assert_ne!(labels[0], labels[16]);
+        assert_ne!(labels[8], labels[16]);
+    }
+
+    #[test]
+    fn test_k_means() {
+        let labels = ClusteringType::KMeans {
+            num_clusters: 3,
+            max_iterations: 100,
+        }
+        .cluster(&blobs());
+        assert_blob_clusters(&labels);
+    }
+
+    #[test]
+    fn test_hdbscan() {
+        let mut points = blobs();
+        points.push(vec![100.0, -100.0]);
+        let labels = ClusteringType::Hdbscan {
+            min_cluster_size: 5,
+            min_samples: None,
+        }
+        .cluster(&points);
+        assert_blob_clusters(&labels[..24]);
+        assert_eq!(labels[24], None);
+    }
+}
@@ -0,0 +1,44 @@
+//! # Topic modeling pipeline
+//! Identifies the topics of a corpus by clustering the documents embeddings, following the approach of
+//! [BERTopic](https://github.com/MaartenGr/BERTopic). Documents are embedded with a `SentenceEmbeddingsModel`,
+//! the dimension of the embeddings is optionally reduced (PCA or UMAP) and the documents are clustered (HDBSCAN or k-means).
+//! Each topic is then labelled with keywords extracted by a `KeywordExtractionModel` from the topic documents.
+//!
+//! ```no_run
+//! use rust_bert::pipelines::topic_modeling::{ClusteringType, TopicModelingConfig, TopicModelingModel};
+//! # fn main() -> anyhow::Result<()> {
+//! let config = TopicModelingConfig {
+//!     clustering: ClusteringType::KMeans {
+//!         num_clusters: 2,
+//!         max_iterations: 100,
+//!     },
+//!     ..Default::default()
+//! };
+//! let topic_modeling_model = TopicModelingModel::new(config)?;
+//!
+//! let input = [
+//!     "The team won the championship after a dramatic final.",
+//!     "The striker scored twice in the second half.",
+//!     "The central bank raised interest rates again.",
+//!     "Inflation slowed down for the third consecutive month.",
+//! ];
+//! let output = topic_modeling_model.predict(&input)?;
+//! # Ok(())
+//! # }
+//! ```
+//! Output (topic of each document, keywords of each topic):
+//! ```ignore
+//! [Some(0), Some(0), Some(1), Some(1)]
+//! [
+//!     (0, ["striker", "championship", "team", "final", "scored"]),
+//!     (1, ["inflation", "bank", "rates", "interest", "month"]),
+//! ]
+//! ```
+
+mod clustering;
+mod pipeline;
+mod reduction;
+
+pub use clustering::ClusteringType;
+pub use pipeline::{Topic, TopicModelingConfig, TopicModelingModel, TopicModelingOutput};
+pub use reduction::DimensionalityReductionType;
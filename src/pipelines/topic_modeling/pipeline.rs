@@ -0,0 +1,286 @@
+use crate::pipelines::common::TokenizerOption;
+use crate::pipelines::keywords_extraction::{
+    Keyword, KeywordExtractionConfig, KeywordExtractionModel,
+};
+use crate::pipelines::topic_modeling::clustering::ClusteringType;
+use crate::pipelines::topic_modeling::reduction::DimensionalityReductionType;
+use crate::RustBertError;
+use tch::Tensor;
+
+/// # Topic identified by a `TopicModelingModel`
+#[derive(Debug, Clone)]
+pub struct Topic {
+    /// Topic identifier. Topics are sorted by decreasing number of documents
+    pub id: usize,
+    /// Indices of the documents assigned to the topic
+    pub documents: Vec<usize>,
+    /// Keywords describing the topic. The keywords are extracted from the concatenation of the topic
+    /// documents (separated by a new line) and the offsets refer to this concatenated text.
+    pub keywords: Vec<Keyword>,
+}
+
+/// # Output of a `TopicModelingModel`
+#[derive(Debug, Clone)]
+pub struct TopicModelingOutput {
+    /// Topic assigned to each input document, `None` for outliers
+    pub document_topics: Vec<Option<usize>>,
+    /// Topics identified in the corpus
+    pub topics: Vec<Topic>,
+}
+
+/// # Configuration for topic modeling
+/// Contains the keyword extraction configuration (including the sentence embeddings model used to embed
+/// the documents), the dimensionality reduction and clustering options.
+pub struct TopicModelingConfig<'a> {
+    /// `KeywordExtractionConfig` defining the sentence embeddings model and the keywords labelling each topic
+    pub keyword_extraction_config: KeywordExtractionConfig<'a>,
+    /// `DimensionalityReductionType` applied to the documents embeddings before clustering (default: PCA with 5 components)
+    pub dimensionality_reduction: DimensionalityReductionType,
+    /// `ClusteringType` used to group documents into topics (default: HDBSCAN with a minimum topic size of 10)
+    pub clustering: ClusteringType,
+    /// Batch size used for encoding the documents (default: 64)
+    pub batch_size: usize,
+}
+
+impl<'a> TopicModelingConfig<'a> {
+    /// Instantiate a new topic modeling configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `keyword_extraction_config` - `KeywordExtractionConfig` defining the sentence embeddings and keyword extraction options
+    pub fn new(keyword_extraction_config: KeywordExtractionConfig<'a>) -> TopicModelingConfig<'a> {
+        TopicModelingConfig {
+            keyword_extraction_config,
+            dimensionality_reduction: DimensionalityReductionType::Pca { num_components: 5 },
+            clustering: ClusteringType::Hdbscan {
+                min_cluster_size: 10,
+                min_samples: None,
+            },
+            batch_size: 64,
+        }
+    }
+}
+
+#[cfg(feature = "remote")]
+impl Default for TopicModelingConfig<'_> {
+    /// Provides a default topic modeling configuration based on the default keyword extraction model (`all-MiniLM-L6-v2`)
+    fn default() -> Self {
+        TopicModelingConfig::new(KeywordExtractionConfig::default())
+    }
+}
+
+/// # TopicModelingModel to identify the topics of a corpus
+/// Documents are embedded with the sentence embeddings model of the keyword extraction model, the dimension of the
+/// embeddings is reduced and documents are clustered into topics. Each topic is labelled with keywords extracted
+/// from its documents and ranked against the centroid of the topic documents embeddings.
+/// - `keyword_extraction_model`: Keyword extraction model, also used to embed the documents
+pub struct TopicModelingModel<'a> {
+    pub keyword_extraction_model: KeywordExtractionModel<'a>,
+    dimensionality_reduction: DimensionalityReductionType,
+    clustering: ClusteringType,
+    batch_size: usize,
+}
+
+impl<'a> TopicModelingModel<'a> {
+    /// Build a new `TopicModelingModel`
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - `TopicModelingConfig` object containing the keyword extraction, dimensionality reduction and clustering options
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::topic_modeling::TopicModelingModel;
+    ///
+    /// let topic_modeling_model = TopicModelingModel::new(Default::default())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(config: TopicModelingConfig<'a>) -> Result<TopicModelingModel<'a>, RustBertError> {
+        let keyword_extraction_model =
+            KeywordExtractionModel::new(config.keyword_extraction_config)?;
+        Ok(TopicModelingModel {
+            keyword_extraction_model,
+            dimensionality_reduction: config.dimensionality_reduction,
+            clustering: config.clustering,
+            batch_size: config.batch_size,
+        })
+    }
+
+    /// Get a reference to the sentence embeddings model tokenizer.
+    pub fn get_tokenizer(&self) -> &TokenizerOption {
+        self.keyword_extraction_model.get_tokenizer()
+    }
+
+    /// Get a mutable reference to the sentence embeddings model tokenizer.
+    pub fn get_tokenizer_mut(&mut self) -> &mut TokenizerOption {
+        self.keyword_extraction_model.get_tokenizer_mut()
+    }
+
+    /// Identify the topics of a corpus
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - slice of string-like documents
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TopicModelingOutput, RustBertError>` containing the topic of each document and the topics keywords
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::topic_modeling::TopicModelingModel;
+    ///
+    /// let topic_modeling_model = TopicModelingModel::new(Default::default())?;
+    /// let input = [
+    ///     "The team won the championship after a dramatic final.",
+    ///     "The central bank raised interest rates again.",
+    /// ];
+    /// let output = topic_modeling_model.predict(&input)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict<S>(&self, inputs: &[S]) -> Result<TopicModelingOutput, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        if inputs.is_empty() {
+            return Ok(TopicModelingOutput {
+                document_topics: vec![],
+                topics: vec![],
+            });
+        }
+        let embeddings = inputs
+            .chunks(self.batch_size.max(1))
+            .map(|batch| {
+                Ok(self
+                    .keyword_extraction_model
+                    .sentence_embeddings_model
+                    .encode_as_tensor(batch)?
+                    .embeddings)
+            })
+            .collect::<Result<Vec<Tensor>, RustBertError>>()?;
+        self.predict_from_embeddings(inputs, &Tensor::cat(&embeddings, 0))
+    }
+
+    /// Identify the topics of a corpus from pre-computed documents embeddings
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - slice of string-like documents
+    /// * `embeddings` - Tensor of shape (number of documents, embeddings dimension) containing the documents embeddings,
+    /// computed with the sentence embeddings model of the keyword extraction model
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TopicModelingOutput, RustBertError>` containing the topic of each document and the topics keywords
+    pub fn predict_from_embeddings<S>(
+        &self,
+        inputs: &[S],
+        embeddings: &Tensor,
+    ) -> Result<TopicModelingOutput, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        if embeddings.size()[0] != inputs.len() as i64 {
+            return Err(RustBertError::ValueError(format!(
+                "Number of embeddings ({}) does not match the number of documents ({})",
+                embeddings.size()[0],
+                inputs.len()
+            )));
+        }
+        if inputs.is_empty() {
+            return Ok(TopicModelingOutput {
+                document_topics: vec![],
+                topics: vec![],
+            });
+        }
+
+        let reduced_embeddings = tch::no_grad(|| self.dimensionality_reduction.reduce(embeddings))?;
+        let clusters = self.clustering.cluster(&reduced_embeddings);
+
+        // Topics are numbered by decreasing size
+        let num_clusters = clusters.iter().flatten().max().map_or(0, |max| max + 1);
+        let mut cluster_documents = vec![Vec::new(); num_clusters];
+        for (document_index, cluster) in clusters.iter().enumerate() {
+            if let Some(cluster) = cluster {
+                cluster_documents[*cluster].push(document_index);
+            }
+        }
+        let mut cluster_order = (0..num_clusters).collect::<Vec<usize>>();
+        cluster_order.sort_by_key(|cluster| std::cmp::Reverse(cluster_documents[*cluster].len()));
+        let mut topic_ids = vec![0; num_clusters];
+        for (topic_id, cluster) in cluster_order.iter().enumerate() {
+            topic_ids[*cluster] = topic_id;
+        }
+        let document_topics = clusters
+            .into_iter()
+            .map(|cluster| cluster.map(|cluster| topic_ids[cluster]))
+            .collect::<Vec<Option<usize>>>();
+        let topic_documents = cluster_order
+            .into_iter()
+            .map(|cluster| std::mem::take(&mut cluster_documents[cluster]))
+            .collect::<Vec<Vec<usize>>>();
+
+        let topic_texts = topic_documents
+            .iter()
+            .map(|documents| {
+                documents
+                    .iter()
+                    .map(|document_index| inputs[*document_index].as_ref())
+                    .collect::<Vec<&str>>()
+                    .join("\n")
+            })
+            .collect::<Vec<String>>();
+        let topic_keywords = if topic_texts.is_empty() {
+            vec![]
+        } else {
+            let centroids = topic_documents
+                .iter()
+                .map(|documents| {
+                    let indices = documents
+                        .iter()
+                        .map(|document_index| *document_index as i64)
+                        .collect::<Vec<i64>>();
+                    embeddings
+                        .index_select(0, &Tensor::from_slice(&indices).to(embeddings.device()))
+                        .mean_dim([0].as_slice(), false, embeddings.kind())
+                })
+                .collect::<Vec<Tensor>>();
+            self.keyword_extraction_model
+                .predict_with_embeddings(&topic_texts, &Tensor::stack(&centroids, 0))?
+        };
+
+        let topics = topic_documents
+            .into_iter()
+            .zip(topic_keywords)
+            .enumerate()
+            .map(|(id, (documents, keywords))| Topic {
+                id,
+                documents,
+                keywords,
+            })
+            .collect();
+
+        Ok(TopicModelingOutput {
+            document_topics,
+            topics,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[ignore] // no need to run, compilation is enough to verify it is Send
+    fn test() {
+        let config = TopicModelingConfig::default();
+        let _: Box<dyn Send> = Box::new(TopicModelingModel::new(config));
+    }
+}
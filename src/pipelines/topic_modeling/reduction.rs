@@ -0,0 +1,352 @@
+use crate::pipelines::topic_modeling::clustering::squared_distance;
+use crate::RustBertError;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use tch::{Kind, Tensor};
+
+/// # Dimensionality reduction variants applied to the documents embeddings before clustering
+/// Density-based clustering algorithms perform poorly in high dimensional spaces, reducing the dimension
+/// of the embeddings before clustering usually improves the quality of the topics.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DimensionalityReductionType {
+    /// The documents embeddings are clustered without reduction
+    None,
+    /// Principal component analysis, projecting the centered embeddings on their `num_components` first principal components
+    Pca {
+        /// Number of components to keep
+        num_components: usize,
+    },
+    /// UMAP (McInnes et al., 2018) manifold learning, preserving the local neighbourhood of the documents.
+    /// The fuzzy graph of the embeddings is built from the exact (euclidean) nearest neighbours, and the
+    /// low-dimensional layout is initialized by PCA before being optimized by negative sampling.
+    Umap {
+        /// Number of components to keep
+        num_components: usize,
+        /// Size of the neighbourhood used to build the fuzzy graph. Larger values preserve more of the global structure.
+        num_neighbors: usize,
+        /// Minimum distance between points of the low-dimensional layout. Smaller values create denser clusters.
+        min_distance: f64,
+        /// Number of optimization epochs
+        num_epochs: usize,
+        /// Seed of the negative sampling, the layout is deterministic for a given seed
+        seed: u64,
+    },
+}
+
+impl DimensionalityReductionType {
+    /// Reduces the dimension of embeddings of shape (number of documents, embeddings dimension)
+    pub(crate) fn reduce(&self, embeddings: &Tensor) -> Result<Vec<Vec<f32>>, RustBertError> {
+        match self {
+            DimensionalityReductionType::None => {
+                Ok(Vec::<Vec<f32>>::try_from(embeddings.to_kind(Kind::Float))?)
+            }
+            DimensionalityReductionType::Pca { num_components } => {
+                Ok(Vec::<Vec<f32>>::try_from(pca(embeddings, *num_components))?)
+            }
+            DimensionalityReductionType::Umap {
+                num_components,
+                num_neighbors,
+                min_distance,
+                num_epochs,
+                seed,
+            } => umap(
+                embeddings,
+                *num_components,
+                *num_neighbors,
+                *min_distance,
+                *num_epochs,
+                *seed,
+            ),
+        }
+    }
+}
+
+fn pca(embeddings: &Tensor, num_components: usize) -> Tensor {
+    let embeddings = embeddings.to_kind(Kind::Float);
+    let centered = &embeddings - embeddings.mean_dim([0].as_slice(), true, Kind::Float);
+    let (_, _, components) = centered.svd(true, true);
+    let num_components = (num_components as i64).min(components.size()[1]).max(1);
+    centered.matmul(&components.slice(1, 0, num_components, 1))
+}
+
+/// SplitMix64 generator, used for a reproducible negative sampling
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_index(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+fn umap(
+    embeddings: &Tensor,
+    num_components: usize,
+    num_neighbors: usize,
+    min_distance: f64,
+    num_epochs: usize,
+    seed: u64,
+) -> Result<Vec<Vec<f32>>, RustBertError> {
+    let points = Vec::<Vec<f32>>::try_from(embeddings.to_kind(Kind::Float))?;
+    let num_points = points.len();
+    let num_neighbors = num_neighbors.min(num_points.saturating_sub(1));
+    let initial_layout = pca(embeddings, num_components);
+    if num_neighbors < 2 {
+        return Ok(Vec::<Vec<f32>>::try_from(initial_layout)?);
+    }
+
+    let edges = fuzzy_graph(&points, num_neighbors);
+    let mut layout = Vec::<Vec<f32>>::try_from(rescale(&initial_layout, 10.0))?;
+    let (a, b) = fit_curve_parameters(min_distance);
+    optimize_layout(&mut layout, &edges, a, b, num_epochs, seed);
+    Ok(layout)
+}
+
+/// Builds the symmetric fuzzy simplicial set of the points, returning the (head, tail, weight) edges.
+fn fuzzy_graph(points: &[Vec<f32>], num_neighbors: usize) -> Vec<(usize, usize, f64)> {
+    let target = (num_neighbors as f64).log2();
+    let mut memberships: HashMap<(usize, usize), f64> = HashMap::new();
+    for (index, point) in points.iter().enumerate() {
+        let mut distances = points
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != index)
+            .map(|(other, other_point)| (other, squared_distance(point, other_point).sqrt()))
+            .collect::<Vec<(usize, f64)>>();
+        distances.sort_by(|a, b| a.1.total_cmp(&b.1));
+        distances.truncate(num_neighbors);
+
+        // Distance to the closest neighbour (rho) and bandwidth (sigma) such that the memberships sum to log2(k)
+        let rho = distances
+            .iter()
+            .map(|(_, distance)| *distance)
+            .find(|distance| *distance > 0.0)
+            .unwrap_or(0.0);
+        let membership_sum = |sigma: f64| {
+            distances
+                .iter()
+                .map(|(_, distance)| (-(distance - rho).max(0.0) / sigma).exp())
+                .sum::<f64>()
+        };
+        let (mut low, mut high, mut sigma) = (0.0, f64::INFINITY, 1.0);
+        for _ in 0..64 {
+            let sum = membership_sum(sigma);
+            if (sum - target).abs() < 1e-5 {
+                break;
+            }
+            if sum > target {
+                high = sigma;
+                sigma = (low + high) / 2.0;
+            } else {
+                low = sigma;
+                sigma = if high.is_infinite() {
+                    sigma * 2.0
+                } else {
+                    (low + high) / 2.0
+                };
+            }
+        }
+        let mean_distance =
+            distances.iter().map(|(_, distance)| distance).sum::<f64>() / distances.len() as f64;
+        let sigma = sigma.max(1e-3 * mean_distance).max(f64::MIN_POSITIVE);
+
+        for (neighbor, distance) in distances {
+            let membership = (-(distance - rho).max(0.0) / sigma).exp();
+            let key = (index.min(neighbor), index.max(neighbor));
+            // Fuzzy union of the directed memberships: a + b - a * b
+            let value = memberships.entry(key).or_insert(0.0);
+            *value = *value + membership - *value * membership;
+        }
+    }
+    let mut edges = memberships
+        .into_iter()
+        .map(|((head, tail), weight)| (head, tail, weight))
+        .collect::<Vec<(usize, usize, f64)>>();
+    edges.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+    edges
+}
+
+/// Scales each component of the layout to the range [0, `scale`]
+fn rescale(layout: &Tensor, scale: f64) -> Tensor {
+    let min = layout.amin([0].as_slice(), true);
+    let max = layout.amax([0].as_slice(), true);
+    (layout - &min) / (max - min).clamp_min(1e-12) * scale
+}
+
+/// Fits the parameters of the low-dimensional similarity `1 / (1 + a * d^(2b))` to the offset exponential decay
+/// defined by the minimum distance (with a spread of 1), by least squares over a refined grid.
+fn fit_curve_parameters(min_distance: f64) -> (f64, f64) {
+    let distances = (1..=300)
+        .map(|step| step as f64 * 0.01)
+        .collect::<Vec<f64>>();
+    let targets = distances
+        .iter()
+        .map(|distance| {
+            if *distance < min_distance {
+                1.0
+            } else {
+                (-(distance - min_distance)).exp()
+            }
+        })
+        .collect::<Vec<f64>>();
+    let error = |a: f64, b: f64| {
+        distances
+            .iter()
+            .zip(targets.iter())
+            .map(|(distance, target)| (1.0 / (1.0 + a * distance.powf(2.0 * b)) - target).powi(2))
+            .sum::<f64>()
+    };
+
+    let (mut a, mut b) = (1.0f64, 1.0f64);
+    let (mut log_a_step, mut b_step) = (1.0f64, 0.5f64);
+    for _ in 0..8 {
+        let mut best = (error(a, b), a, b);
+        for a_index in -10..=10 {
+            for b_index in -10..=10 {
+                let candidate_a = a * (log_a_step * a_index as f64 / 10.0).exp();
+                let candidate_b = (b + b_step * b_index as f64 / 10.0).max(0.05);
+                let candidate_error = error(candidate_a, candidate_b);
+                if candidate_error < best.0 {
+                    best = (candidate_error, candidate_a, candidate_b);
+                }
+            }
+        }
+        a = best.1;
+        b = best.2;
+        log_a_step /= 4.0;
+        b_step /= 4.0;
+    }
+    (a, b)
+}
+
+fn clip(value: f64) -> f64 {
+    value.clamp(-4.0, 4.0)
+}
+
+/// Optimizes the layout by stochastic gradient descent on the fuzzy set cross-entropy, sampling each edge
+/// proportionally to its weight and drawing negative samples uniformly.
+fn optimize_layout(
+    layout: &mut [Vec<f32>],
+    edges: &[(usize, usize, f64)],
+    a: f64,
+    b: f64,
+    num_epochs: usize,
+    seed: u64,
+) {
+    const NEGATIVE_SAMPLE_RATE: usize = 5;
+    let num_points = layout.len();
+    let num_epochs = num_epochs.max(1);
+    let max_weight = edges.iter().map(|edge| edge.2).fold(0.0, f64::max);
+    let epochs_per_sample = edges
+        .iter()
+        .map(|(_, _, weight)| max_weight / weight.max(f64::MIN_POSITIVE))
+        .collect::<Vec<f64>>();
+    let mut next_sample_epoch = epochs_per_sample.clone();
+    let mut rng = Rng(seed);
+
+    for epoch in 1..=num_epochs {
+        let learning_rate = 1.0 - (epoch - 1) as f64 / num_epochs as f64;
+        for (edge_index, (head, tail, _)) in edges.iter().enumerate() {
+            if next_sample_epoch[edge_index] > epoch as f64 {
+                continue;
+            }
+            next_sample_epoch[edge_index] += epochs_per_sample[edge_index];
+
+            for (head, tail) in [(*head, *tail), (*tail, *head)] {
+                // Attraction between the neighbours
+                let distance = squared_distance(&layout[head], &layout[tail]);
+                if distance > 0.0 {
+                    let coefficient =
+                        -2.0 * a * b * distance.powf(b - 1.0) / (1.0 + a * distance.powf(b));
+                    for dim in 0..layout[head].len() {
+                        let delta = (layout[head][dim] - layout[tail][dim]) as f64;
+                        let gradient = clip(coefficient * delta) * learning_rate;
+                        layout[head][dim] += gradient as f32;
+                        layout[tail][dim] -= gradient as f32;
+                    }
+                }
+                // Repulsion from randomly sampled points
+                for _ in 0..NEGATIVE_SAMPLE_RATE {
+                    let other = rng.next_index(num_points);
+                    if other == head {
+                        continue;
+                    }
+                    let distance = squared_distance(&layout[head], &layout[other]);
+                    let coefficient = if distance > 0.0 {
+                        2.0 * b / ((0.001 + distance) * (1.0 + a * distance.powf(b)))
+                    } else {
+                        0.0
+                    };
+                    for dim in 0..layout[head].len() {
+                        let delta = (layout[head][dim] - layout[other][dim]) as f64;
+                        let gradient = if coefficient > 0.0 {
+                            clip(coefficient * delta)
+                        } else {
+                            4.0
+                        };
+                        layout[head][dim] += (gradient * learning_rate) as f32;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fit_curve_parameters() {
+        // Reference values of umap-learn for a spread of 1
+        let (a, b) = fit_curve_parameters(0.1);
+        assert!((a - 1.577).abs() < 0.05);
+        assert!((b - 0.895).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_umap() {
+        // Two well separated groups of points in 10 dimensions
+        let mut points = Vec::new();
+        for group in 0..2 {
+            for i in 0..15 {
+                points.extend((0..10).map(|dim| {
+                    (group * 20) as f32 + ((i * 7 + dim * 3) % 13) as f32 / 13.0 + i as f32 * 0.01
+                }));
+            }
+        }
+        let embeddings = Tensor::from_slice(&points).view([30, 10]);
+        let reduction = DimensionalityReductionType::Umap {
+            num_components: 2,
+            num_neighbors: 5,
+            min_distance: 0.1,
+            num_epochs: 100,
+            seed: 42,
+        };
+        let layout = reduction.reduce(&embeddings).unwrap();
+
+        assert_eq!(layout.len(), 30);
+        assert!(layout.iter().all(|point| point.len() == 2));
+        assert_eq!(layout, reduction.reduce(&embeddings).unwrap());
+
+        let mean_distance = |first: &[Vec<f32>], second: &[Vec<f32>]| {
+            let mut total = 0.0;
+            for a in first {
+                for b in second {
+                    total += squared_distance(a, b).sqrt();
+                }
+            }
+            total / (first.len() * second.len()) as f64
+        };
+        let within = mean_distance(&layout[..15], &layout[..15]);
+        let between = mean_distance(&layout[..15], &layout[15..]);
+        assert!(between > 2.0 * within);
+    }
+}
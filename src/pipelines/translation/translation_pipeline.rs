@@ -1210,6 +1210,66 @@ impl TranslationOption {
             Self::ONNX(model_ref) => model_ref.get_tokenizer_mut(),
         }
     }
+
+    /// Interface method to cast the model weights to half precision (`Kind::Half`)
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        match self {
+            Self::Marian(model_ref) => model_ref.half(),
+            Self::T5(model_ref) => model_ref.half(),
+            Self::MBart(model_ref) => model_ref.half(),
+            Self::M2M100(model_ref) => model_ref.half(),
+            Self::NLLB(model_ref) => model_ref.half(),
+            #[cfg(feature = "onnx")]
+            Self::ONNX(_) => Err(RustBertError::OrtError(
+                "Type casting not supported for ONNX models.".to_string(),
+            )),
+        }
+    }
+
+    /// Interface method to cast the model weights to bfloat16 precision (`Kind::BFloat16`)
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        match self {
+            Self::Marian(model_ref) => model_ref.bfloat16(),
+            Self::T5(model_ref) => model_ref.bfloat16(),
+            Self::MBart(model_ref) => model_ref.bfloat16(),
+            Self::M2M100(model_ref) => model_ref.bfloat16(),
+            Self::NLLB(model_ref) => model_ref.bfloat16(),
+            #[cfg(feature = "onnx")]
+            Self::ONNX(_) => Err(RustBertError::OrtError(
+                "Type casting not supported for ONNX models.".to_string(),
+            )),
+        }
+    }
+
+    /// Interface method to cast the model weights to single precision (`Kind::Float`)
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        match self {
+            Self::Marian(model_ref) => model_ref.float(),
+            Self::T5(model_ref) => model_ref.float(),
+            Self::MBart(model_ref) => model_ref.float(),
+            Self::M2M100(model_ref) => model_ref.float(),
+            Self::NLLB(model_ref) => model_ref.float(),
+            #[cfg(feature = "onnx")]
+            Self::ONNX(_) => Err(RustBertError::OrtError(
+                "Type casting not supported for ONNX models.".to_string(),
+            )),
+        }
+    }
+
+    /// Interface method to move the model weights to a device
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        match self {
+            Self::Marian(model_ref) => model_ref.set_device(device),
+            Self::T5(model_ref) => model_ref.set_device(device),
+            Self::MBart(model_ref) => model_ref.set_device(device),
+            Self::M2M100(model_ref) => model_ref.set_device(device),
+            Self::NLLB(model_ref) => model_ref.set_device(device),
+            #[cfg(feature = "onnx")]
+            Self::ONNX(_) => Err(RustBertError::OrtError(
+                "Device assignment not supported for ONNX models.".to_string(),
+            )),
+        }
+    }
     /// Interface method to generate() of the particular models.
     pub fn generate<S>(
         &self,
@@ -1413,6 +1473,26 @@ impl TranslationModel {
         self.model.get_tokenizer_mut()
     }
 
+    /// Casts the model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        self.model.half()
+    }
+
+    /// Casts the model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        self.model.bfloat16()
+    }
+
+    /// Casts the model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        self.model.float()
+    }
+
+    /// Moves the model weights to the target device.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        self.model.set_device(device)
+    }
+
     /// Translates texts provided
     ///
     /// # Arguments
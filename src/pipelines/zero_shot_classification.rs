@@ -105,7 +105,9 @@ use crate::deberta::DebertaForSequenceClassification;
 use crate::distilbert::DistilBertModelClassifier;
 use crate::longformer::LongformerForSequenceClassification;
 use crate::mobilebert::MobileBertForSequenceClassification;
-use crate::pipelines::common::{ConfigOption, ModelResource, ModelType, TokenizerOption};
+use crate::pipelines::common::{
+    cast_var_store, set_var_store_device, ConfigOption, ModelResource, ModelType, TokenizerOption,
+};
 use crate::pipelines::sequence_classification::Label;
 use crate::resources::ResourceProvider;
 use crate::roberta::RobertaForSequenceClassification;
@@ -252,16 +254,25 @@ impl ZeroShotClassificationOption {
     /// * `ZeroShotClassificationConfig` - Zero-shot classification pipeline configuration. The type of model created will be inferred from the
     ///     `ModelResources` (Torch or ONNX) and `ModelType` (Architecture for Torch models) variants provided and
     pub fn new(config: &ZeroShotClassificationConfig) -> Result<Self, RustBertError> {
+        Ok(Self::new_with_var_store(config)?.0)
+    }
+
+    /// Instantiate a new zero-shot classification model, also returning the `VarStore` holding the weights of Torch models
+    pub(crate) fn new_with_var_store(
+        config: &ZeroShotClassificationConfig,
+    ) -> Result<(Self, Option<VarStore>), RustBertError> {
         match config.model_resource {
-            ModelResource::Torch(_) => Self::new_torch(config),
+            ModelResource::Torch(_) => {
+                let (model, var_store) = Self::new_torch(config)?;
+                Ok((model, Some(var_store)))
+            }
             #[cfg(feature = "onnx")]
-            ModelResource::ONNX(_) => Self::new_onnx(config),
+            ModelResource::ONNX(_) => Ok((Self::new_onnx(config)?, None)),
         }
     }
 
-    fn new_torch(config: &ZeroShotClassificationConfig) -> Result<Self, RustBertError> {
+    fn new_torch(config: &ZeroShotClassificationConfig) -> Result<(Self, VarStore), RustBertError> {
         let device = config.device;
-        let weights_path = config.model_resource.get_torch_local_path()?;
         let mut var_store = VarStore::new(device);
         let model_config =
             &ConfigOption::from_file(config.model_type, config.config_resource.get_local_path()?);
@@ -385,8 +396,8 @@ impl ZeroShotClassificationOption {
                 "Zero shot classification not implemented for {model_type:?}!",
             ))),
         }?;
-        var_store.load(weights_path)?;
-        Ok(model)
+        crate::resources::load_weights(&config.model_resource, &mut var_store)?;
+        Ok((model, var_store))
     }
 
     #[cfg(feature = "onnx")]
@@ -578,6 +589,7 @@ pub type ZeroShotTemplate = Box<dyn Fn(&str) -> String>;
 pub struct ZeroShotClassificationModel {
     tokenizer: TokenizerOption,
     zero_shot_classifier: ZeroShotClassificationOption,
+    var_store: Option<VarStore>,
     device: Device,
 }
 
@@ -649,11 +661,13 @@ impl ZeroShotClassificationModel {
         tokenizer: TokenizerOption,
     ) -> Result<ZeroShotClassificationModel, RustBertError> {
         let device = config.device;
-        let zero_shot_classifier = ZeroShotClassificationOption::new(&config)?;
+        let (zero_shot_classifier, var_store) =
+            ZeroShotClassificationOption::new_with_var_store(&config)?;
 
         Ok(ZeroShotClassificationModel {
             tokenizer,
             zero_shot_classifier,
+            var_store,
             device,
         })
     }
@@ -668,6 +682,28 @@ impl ZeroShotClassificationModel {
         &mut self.tokenizer
     }
 
+    /// Casts the model weights to half precision (`Kind::Half`).
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::Half)
+    }
+
+    /// Casts the model weights to bfloat16 precision (`Kind::BFloat16`).
+    pub fn bfloat16(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::BFloat16)
+    }
+
+    /// Casts the model weights to single precision (`Kind::Float`).
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        cast_var_store(self.var_store.as_mut(), Kind::Float)
+    }
+
+    /// Moves the model weights to the target device, inputs are placed on this device for subsequent predictions.
+    pub fn set_device(&mut self, device: Device) -> Result<(), RustBertError> {
+        set_var_store_device(self.var_store.as_mut(), device)?;
+        self.device = device;
+        Ok(())
+    }
+
     fn prepare_for_model<'a, S, T>(
         &self,
         inputs: S,
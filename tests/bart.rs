@@ -2,7 +2,10 @@ use rust_bert::bart::{
     BartConfig, BartConfigResources, BartMergesResources, BartModel, BartModelResources,
     BartVocabResources,
 };
-use rust_bert::pipelines::common::ModelResource;
+use rust_bert::pipelines::common::{ModelResource, ModelType};
+use rust_bert::pipelines::sequence_classification::{
+    SequenceClassificationConfig, SequenceClassificationModel,
+};
 use rust_bert::pipelines::summarization::{SummarizationConfig, SummarizationModel};
 use rust_bert::pipelines::zero_shot_classification::{
     ZeroShotClassificationConfig, ZeroShotClassificationModel,
@@ -333,3 +336,48 @@ fn bart_zero_shot_classification_multilabel_try_error() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+#[cfg_attr(not(feature = "all-tests"), ignore)]
+fn bart_mnli_pair_classification() -> anyhow::Result<()> {
+    //    Set-up model
+    let config = SequenceClassificationConfig {
+        device: Device::Cpu,
+        ..SequenceClassificationConfig::new(
+            ModelType::Bart,
+            ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
+                BartModelResources::BART_MNLI,
+            ))),
+            RemoteResource::from_pretrained(BartConfigResources::BART_MNLI),
+            RemoteResource::from_pretrained(BartVocabResources::BART_MNLI),
+            Some(RemoteResource::from_pretrained(
+                BartMergesResources::BART_MNLI,
+            )),
+            false,
+            None,
+            None,
+        )
+    };
+    let sequence_classification_model = SequenceClassificationModel::new(config)?;
+
+    let input = [
+        (
+            "A soccer game with multiple males playing.",
+            "Some men are playing a sport.",
+        ),
+        (
+            "A man inspects the uniform of a figure in some East Asian country.",
+            "The man is sleeping.",
+        ),
+    ];
+    let output = sequence_classification_model.predict_pair(input, 2);
+
+    assert_eq!(output.len(), 2);
+    assert_eq!(output[0].text, "entailment");
+    assert_eq!(output[0].sentence, 0);
+    assert!(output[0].score > 0.5);
+    assert_eq!(output[1].text, "contradiction");
+    assert_eq!(output[1].sentence, 1);
+    assert!(output[1].score > 0.5);
+    Ok(())
+}
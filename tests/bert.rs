@@ -12,7 +12,10 @@ use rust_bert::pipelines::ner::NERModel;
 use rust_bert::pipelines::question_answering::{
     QaInput, QuestionAnsweringConfig, QuestionAnsweringModel,
 };
-use rust_bert::resources::{RemoteResource, ResourceProvider};
+use rust_bert::pipelines::sequence_classification::{
+    SequenceClassificationConfig, SequenceClassificationModel,
+};
+use rust_bert::resources::{LocalResource, RemoteResource, ResourceProvider};
 use rust_bert::Config;
 use rust_tokenizers::tokenizer::{BertTokenizer, MultiThreadedTokenizer, TruncationStrategy};
 use rust_tokenizers::vocab::Vocab;
@@ -486,3 +489,68 @@ fn bert_question_answering() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn bert_sequence_classification_pair_regression() -> anyhow::Result<()> {
+    //    Resources paths
+    let config_resource = RemoteResource::from_pretrained(BertConfigResources::BERT);
+    let vocab_resource = RemoteResource::from_pretrained(BertVocabResources::BERT);
+    let mut config = BertConfig::from_file(config_resource.get_local_path()?);
+    let mut label_mapping = HashMap::new();
+    label_mapping.insert(0, String::from("similarity"));
+    config.id2label = Some(label_mapping);
+
+    //    Save a randomly initialized regression model (single output label) and its configuration
+    tch::manual_seed(0);
+    let vs = nn::VarStore::new(Device::Cpu);
+    let _ = BertForSequenceClassification::new(vs.root(), &config)?;
+    let model_dir = tempfile::tempdir()?;
+    let weights_path = model_dir.path().join("rust_model.ot");
+    let config_path = model_dir.path().join("config.json");
+    vs.save(&weights_path)?;
+    std::fs::write(&config_path, serde_json::to_string(&config)?)?;
+
+    //    Set-up pipeline
+    let mut sequence_classification_config = SequenceClassificationConfig::new(
+        ModelType::Bert,
+        ModelResource::Torch(Box::new(LocalResource::from(weights_path))),
+        LocalResource::from(config_path),
+        vocab_resource,
+        None,
+        true,
+        None,
+        None,
+    );
+    sequence_classification_config.device = Device::Cpu;
+    let sequence_classification_model =
+        SequenceClassificationModel::new(sequence_classification_config)?;
+
+    //    Define input
+    let input = [
+        (
+            "A man is playing a guitar.",
+            "A person plays an instrument.",
+        ),
+        ("A man is playing a guitar.", "A woman is slicing an onion."),
+        ("A plane is taking off.", "An air plane is taking off."),
+    ];
+
+    //    The raw regression score is returned, independently of the batch size
+    let output = sequence_classification_model.predict_pair(input, 2);
+    let unbatched_output = sequence_classification_model.predict_pair(input, 1);
+    let multilabel_output =
+        sequence_classification_model.predict_pair_multilabel(&input, 0.0, 3)?;
+
+    assert_eq!(output.len(), 3);
+    for (sentence, label) in output.iter().enumerate() {
+        assert_eq!(label.text, "similarity");
+        assert_eq!(label.id, 0);
+        assert_eq!(label.sentence, sentence);
+        assert!((label.score - unbatched_output[sentence].score).abs() < 1e-4);
+        //    Multi-label classification applies a sigmoid to the same regression output
+        let sigmoid_score = 1.0 / (1.0 + (-label.score).exp());
+        assert_eq!(multilabel_output[sentence].len(), 1);
+        assert!((multilabel_output[sentence][0].score - sigmoid_score).abs() < 1e-4);
+    }
+    Ok(())
+}
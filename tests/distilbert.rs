@@ -1,11 +1,12 @@
 use rust_bert::distilbert::{
     DistilBertConfig, DistilBertConfigResources, DistilBertForQuestionAnswering,
-    DistilBertForTokenClassification, DistilBertModelMaskedLM, DistilBertModelResources,
-    DistilBertVocabResources,
+    DistilBertForTokenClassification, DistilBertModelClassifier, DistilBertModelMaskedLM,
+    DistilBertModelResources, DistilBertVocabResources,
 };
+use rust_bert::pipelines::common::ModelResource;
 use rust_bert::pipelines::question_answering::{QaInput, QuestionAnsweringModel};
-use rust_bert::pipelines::sentiment::{SentimentModel, SentimentPolarity};
-use rust_bert::resources::{RemoteResource, ResourceProvider};
+use rust_bert::pipelines::sentiment::{SentimentConfig, SentimentModel, SentimentPolarity};
+use rust_bert::resources::{load_weights, BufferResource, RemoteResource, ResourceProvider};
 use rust_bert::Config;
 use rust_tokenizers::tokenizer::{BertTokenizer, MultiThreadedTokenizer, TruncationStrategy};
 use rust_tokenizers::vocab::Vocab;
@@ -39,6 +40,33 @@ fn distilbert_sentiment_classifier() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn distilbert_sentiment_classifier_bfloat16() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let mut sentiment_classifier = SentimentModel::new(Default::default())?;
+    sentiment_classifier.set_device(Device::Cpu)?;
+    sentiment_classifier.bfloat16()?;
+
+    //    Get sentiments
+    let input = [
+        "Probably my all-time favorite movie, a story of selflessness, sacrifice and dedication to a noble cause, but it's not preachy or boring.",
+        "This film tried to be too many things all at once: stinging political satire, Hollywood blockbuster, sappy romantic comedy, family values promo...",
+        "If you like original gut wrenching laughter you will like this movie. If you are young or old then you will love this movie, hell even my mom liked it.",
+    ];
+
+    let output = sentiment_classifier.predict(input);
+
+    assert_eq!(output.len(), 3usize);
+    assert_eq!(output[0].polarity, SentimentPolarity::Positive);
+    assert!((output[0].score - 0.9981).abs() < 1e-2);
+    assert_eq!(output[1].polarity, SentimentPolarity::Negative);
+    assert!((output[1].score - 0.9927).abs() < 1e-2);
+    assert_eq!(output[2].polarity, SentimentPolarity::Positive);
+    assert!((output[2].score - 0.9997).abs() < 1e-2);
+
+    Ok(())
+}
+
 #[test]
 fn distilbert_masked_lm() -> anyhow::Result<()> {
     //    Resources paths
@@ -276,3 +304,100 @@ fn distilbert_question_answering() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn distilbert_safetensors_weights() -> anyhow::Result<()> {
+    //    Resources paths
+    let config_resource = Box::new(RemoteResource::from_pretrained(
+        DistilBertConfigResources::DISTIL_BERT_SST2,
+    ));
+    let weights_resource = Box::new(RemoteResource::from_pretrained(
+        DistilBertModelResources::DISTIL_BERT_SST2,
+    ));
+    let safetensors_resource = Box::new(RemoteResource::new(
+        "https://huggingface.co/distilbert-base-uncased-finetuned-sst-2-english/resolve/main/model.safetensors",
+        "distilbert-sst2/safetensors",
+    ));
+    let config = DistilBertConfig::from_file(config_resource.get_local_path()?);
+    let input_tensor = Tensor::from_slice(&[101i64, 2023, 3185, 2001, 2307, 999, 102]).unsqueeze(0);
+
+    let get_logits = |vs: &nn::VarStore, model: &DistilBertModelClassifier| {
+        assert_eq!(vs.device(), Device::Cpu);
+        no_grad(|| {
+            model
+                .forward_t(Some(&input_tensor), None, None, false)
+                .unwrap()
+                .logits
+        })
+    };
+
+    //    Reference weights (.ot file)
+    let mut vs = nn::VarStore::new(Device::Cpu);
+    let model = DistilBertModelClassifier::new(vs.root(), &config)?;
+    load_weights(weights_resource.as_ref(), &mut vs)?;
+    let expected_logits = get_logits(&vs, &model);
+
+    //    Memory-mapped safetensors file
+    let mut vs = nn::VarStore::new(Device::Cpu);
+    let model = DistilBertModelClassifier::new(vs.root(), &config)?;
+    load_weights(safetensors_resource.as_ref(), &mut vs)?;
+    let mapped_logits = get_logits(&vs, &model);
+
+    //    Safetensors data held in a buffer
+    let buffer_resource =
+        BufferResource::from(std::fs::read(safetensors_resource.get_local_path()?)?);
+    let mut vs = nn::VarStore::new(Device::Cpu);
+    let model = DistilBertModelClassifier::new(vs.root(), &config)?;
+    load_weights(&buffer_resource, &mut vs)?;
+    let buffer_logits = get_logits(&vs, &model);
+
+    assert!(
+        (mapped_logits - &expected_logits)
+            .abs()
+            .max()
+            .double_value(&[])
+            < 1e-5
+    );
+    assert!(
+        (buffer_logits - &expected_logits)
+            .abs()
+            .max()
+            .double_value(&[])
+            < 1e-5
+    );
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sentiment_classifier_safetensors() -> anyhow::Result<()> {
+    //    Set-up classifier from memory-mapped safetensors weights
+    let safetensors_resource = Box::new(RemoteResource::new(
+        "https://huggingface.co/distilbert-base-uncased-finetuned-sst-2-english/resolve/main/model.safetensors",
+        "distilbert-sst2/safetensors",
+    ));
+    let sentiment_config = SentimentConfig {
+        model_resource: ModelResource::Torch(safetensors_resource),
+        ..Default::default()
+    };
+    let sentiment_classifier = SentimentModel::new(sentiment_config)?;
+
+    //    Get sentiments
+    let input = [
+        "Probably my all-time favorite movie, a story of selflessness, sacrifice and dedication to a noble cause, but it's not preachy or boring.",
+        "This film tried to be too many things all at once: stinging political satire, Hollywood blockbuster, sappy romantic comedy, family values promo...",
+        "If you like original gut wrenching laughter you will like this movie. If you are young or old then you will love this movie, hell even my mom liked it.",
+    ];
+
+    let output = sentiment_classifier.predict(input);
+
+    assert_eq!(output.len(), 3usize);
+    assert_eq!(output[0].polarity, SentimentPolarity::Positive);
+    assert!((output[0].score - 0.9981).abs() < 1e-4);
+    assert_eq!(output[1].polarity, SentimentPolarity::Negative);
+    assert!((output[1].score - 0.9927).abs() < 1e-4);
+    assert_eq!(output[2].polarity, SentimentPolarity::Positive);
+    assert!((output[2].score - 0.9997).abs() < 1e-4);
+
+    Ok(())
+}
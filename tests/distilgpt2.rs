@@ -77,9 +77,10 @@ fn distilgpt2_lm_model() -> anyhow::Result<()> {
         Cache::GPT2Cache(past) => {
             assert!(past.is_some());
             assert_eq!(past.as_ref().unwrap().len(), config.n_layer as usize);
+            assert_eq!(past.as_ref().unwrap()[0].len(), 11);
             assert_eq!(
-                past.as_ref().unwrap()[0].size(),
-                vec!(2, 1, config.n_head, 11, 64)
+                past.as_ref().unwrap()[0].keys().size(),
+                vec!(1, config.n_head, 11, 64)
             );
         }
         _ => panic!("Wrong cache returned for GPT2"),
@@ -10,10 +10,11 @@ use rust_bert::pipelines::generation_utils::{
     Cache, GenerateConfig, GenerateOptions, LanguageGenerator,
 };
 use rust_bert::pipelines::text_generation::{TextGenerationConfig, TextGenerationModel};
-use rust_bert::resources::{RemoteResource, ResourceProvider};
+use rust_bert::quantization::{load_and_quantize_weights, WeightQuantization};
+use rust_bert::resources::{load_weights, LocalResource, RemoteResource, ResourceProvider};
 use rust_bert::Config;
 use rust_tokenizers::tokenizer::{Gpt2Tokenizer, Tokenizer, TruncationStrategy};
-use tch::{nn, Device, Tensor};
+use tch::{nn, no_grad, Device, Tensor};
 
 #[test]
 fn gpt2_lm_model() -> anyhow::Result<()> {
@@ -75,9 +76,10 @@ fn gpt2_lm_model() -> anyhow::Result<()> {
         Cache::GPT2Cache(past) => {
             assert!(past.is_some());
             assert_eq!(past.as_ref().unwrap().len(), config.n_layer as usize);
+            assert_eq!(past.as_ref().unwrap()[0].len(), 4);
             assert_eq!(
-                past.as_ref().unwrap()[0].size(),
-                vec!(2, 1, config.n_head, 4, 64)
+                past.as_ref().unwrap()[0].keys().size(),
+                vec!(1, config.n_head, 4, 64)
             );
         }
         _ => panic!("Wrong cache returned for GPT2"),
@@ -129,6 +131,40 @@ fn gpt2_generation_greedy() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn gpt2_generation_greedy_bfloat16() -> anyhow::Result<()> {
+    //    Resources definition
+    let config_resource = Box::new(RemoteResource::from_pretrained(Gpt2ConfigResources::GPT2));
+    let vocab_resource = Box::new(RemoteResource::from_pretrained(Gpt2VocabResources::GPT2));
+    let merges_resource = Box::new(RemoteResource::from_pretrained(Gpt2MergesResources::GPT2));
+    let model_resource = Box::new(RemoteResource::from_pretrained(Gpt2ModelResources::GPT2));
+
+    let generate_config = TextGenerationConfig {
+        model_type: ModelType::GPT2,
+        model_resource: ModelResource::Torch(model_resource),
+        config_resource,
+        vocab_resource,
+        merges_resource: Some(merges_resource),
+        max_length: Some(12),
+        do_sample: false,
+        num_beams: 1,
+        temperature: 1.1,
+        repetition_penalty: 1.1,
+        ..Default::default()
+    };
+    let mut model = TextGenerationModel::new(generate_config)?;
+    model.set_device(Device::Cpu)?;
+    model.bfloat16()?;
+
+    let input_context = "The cat";
+    let output = model.generate(&[input_context], None);
+
+    assert_eq!(output.len(), 1);
+    assert!(output[0].starts_with("The cat was found in a field"));
+
+    Ok(())
+}
+
 #[test]
 fn gpt2_generation_beam_search() -> anyhow::Result<()> {
     //    Resources definition
@@ -718,6 +754,67 @@ fn gpt2_beam_search_token_scores() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn gpt2_prefix_cache() -> anyhow::Result<()> {
+    //    Resources definition
+    let config_resource = Box::new(RemoteResource::from_pretrained(Gpt2ConfigResources::GPT2));
+    let vocab_resource = Box::new(RemoteResource::from_pretrained(Gpt2VocabResources::GPT2));
+    let merges_resource = Box::new(RemoteResource::from_pretrained(Gpt2MergesResources::GPT2));
+    let model_resource = Box::new(RemoteResource::from_pretrained(Gpt2ModelResources::GPT2));
+
+    let generate_config = GenerateConfig {
+        max_length: Some(48),
+        model_resource: ModelResource::Torch(model_resource),
+        config_resource,
+        vocab_resource,
+        merges_resource: Some(merges_resource),
+        do_sample: false,
+        num_beams: 1,
+        device: Device::Cpu,
+        ..Default::default()
+    };
+    let model = GPT2Generator::new(generate_config)?;
+
+    let tokenizer = model.get_tokenizer();
+    let prefix_ids = tokenizer.convert_tokens_to_ids(
+        &tokenizer.tokenize("The following are short biographies of famous people."),
+    );
+    let prompt = " Hello, my name is";
+    let prompt_ids = tokenizer.convert_tokens_to_ids(&tokenizer.tokenize(prompt));
+    let prefix_cache = model.prefix_cache(&prefix_ids)?;
+    assert_eq!(prefix_cache.prefix_ids(), prefix_ids.as_slice());
+
+    let input_ids =
+        Tensor::from_slice(&[prefix_ids.as_slice(), prompt_ids.as_slice()].concat()).unsqueeze(0);
+
+    for num_beams in [1, 3] {
+        let baseline_generate_options = GenerateOptions {
+            num_beams: Some(num_beams),
+            output_scores: true,
+            ..Default::default()
+        };
+        let test_generate_options = GenerateOptions {
+            num_beams: Some(num_beams),
+            output_scores: true,
+            prefix_cache: Some(&prefix_cache),
+            ..Default::default()
+        };
+
+        let baseline_output = model.generate_from_ids_and_past(
+            input_ids.copy(),
+            None,
+            Some(baseline_generate_options),
+        );
+        let output = model.generate_indices(Some(&[prompt]), Some(test_generate_options));
+
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].indices, baseline_output[0].indices);
+        assert!((output[0].score.unwrap() - baseline_output[0].score.unwrap()).abs() < 1e-4);
+    }
+
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(not(feature = "all-tests"), ignore)]
 fn dialogpt_single_multi_turn_conversation() -> anyhow::Result<()> {
@@ -891,3 +988,58 @@ fn dialogpt_multiple_multi_turn_conversation_with_conversation_deletion() -> any
 
     Ok(())
 }
+
+#[test]
+fn gpt2_weight_quantization() -> anyhow::Result<()> {
+    //    Resources paths
+    let config_resource = RemoteResource::from_pretrained(Gpt2ConfigResources::GPT2);
+    let weights_resource = RemoteResource::from_pretrained(Gpt2ModelResources::GPT2);
+    let config = Gpt2Config::from_file(config_resource.get_local_path()?);
+
+    //    "One two three four"
+    let input_tensor = Tensor::from_slice(&[3198i64, 734, 1115, 1440]).unsqueeze(0);
+    let next_word_logits = |model: &GPT2LMHeadModel| {
+        no_grad(|| {
+            model
+                .forward_t(Some(&input_tensor), None, None, None, None, None, false)
+                .unwrap()
+                .lm_logits
+                .get(0)
+                .get(-1)
+        })
+    };
+
+    let mut vs = nn::VarStore::new(Device::Cpu);
+    let model = GPT2LMHeadModel::new(vs.root(), &config);
+    load_weights(&weights_resource, &mut vs)?;
+    let reference_logits = next_word_logits(&model);
+
+    let quantized_dir = tempfile::tempdir()?;
+    for quantization in [
+        WeightQuantization::Int8,
+        WeightQuantization::Int4 { group_size: 64 },
+    ] {
+        let mut quantized_config = config.clone();
+        quantized_config.quantization = Some(quantization);
+
+        //    Quantization of full precision weights
+        let mut vs = nn::VarStore::new(Device::Cpu);
+        let model = GPT2LMHeadModel::new(vs.root(), &quantized_config);
+        load_and_quantize_weights(&weights_resource, &mut vs)?;
+        let logits = next_word_logits(&model);
+        assert_eq!(logits.argmax(-1, false).int64_value(&[]), 1936i64);
+        let relative_error = (&logits - &reference_logits).norm().double_value(&[])
+            / reference_logits.norm().double_value(&[]);
+        assert!(relative_error < 0.05);
+
+        //    Loading of the pre-quantized weights
+        let quantized_weights_path = quantized_dir.path().join("rust_model.ot");
+        vs.save(&quantized_weights_path)?;
+        let mut vs = nn::VarStore::new(Device::Cpu);
+        let model = GPT2LMHeadModel::new(vs.root(), &quantized_config);
+        load_weights(&LocalResource::from(quantized_weights_path), &mut vs)?;
+        assert_eq!(next_word_logits(&model), logits);
+    }
+
+    Ok(())
+}
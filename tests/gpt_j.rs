@@ -3,11 +3,22 @@ use rust_bert::gpt_j::{
     GptJVocabResources,
 };
 use rust_bert::pipelines::generation_utils::Cache;
-use rust_bert::resources::{RemoteResource, ResourceProvider};
+use rust_bert::quantization::{load_and_quantize_weights, WeightQuantization};
+use rust_bert::resources::{LocalResource, RemoteResource, ResourceProvider};
+use rust_bert::tensor_parallel::{
+    load_sharded_weights, LocalProcessGroup, ProcessGroup, TcpProcessGroup,
+};
 use rust_bert::Config;
 use rust_tokenizers::tokenizer::{Gpt2Tokenizer, Tokenizer};
 use rust_tokenizers::vocab::Vocab;
 use std::convert::TryFrom;
+use std::env;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::thread;
 use tch::{nn, Device, Tensor};
 
 /// Equivalent Python code:
@@ -171,3 +182,229 @@ fn gpt_j_correctness() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+fn gpt_j_logits(
+    config: &GptJConfig,
+    weights_path: PathBuf,
+    input_tensor: &Tensor,
+    process_group: Option<Arc<dyn ProcessGroup>>,
+) -> anyhow::Result<Tensor> {
+    let mut vs = nn::VarStore::new(Device::Cpu);
+    let model = match process_group {
+        Some(process_group) => {
+            let model = GptJLMHeadModel::new_sharded(vs.root(), config, process_group.clone())?;
+            load_sharded_weights(
+                &LocalResource::from(weights_path),
+                &mut vs,
+                process_group.rank(),
+                process_group.world_size(),
+            )?;
+            model
+        }
+        None => {
+            let model = GptJLMHeadModel::new(vs.root(), config);
+            vs.load(weights_path)?;
+            model
+        }
+    };
+    let logits = tch::no_grad(|| {
+        model.forward_t(
+            Some(input_tensor),
+            Cache::None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+    })?
+    .lm_logits;
+    Ok(logits)
+}
+
+fn tensor_parallel_setup() -> anyhow::Result<(GptJConfig, PathBuf, Tensor)> {
+    // Resources paths
+    let config_resource = RemoteResource::from_pretrained(GptJConfigResources::GPT_J_TINY_RANDOM);
+    let model_resource = RemoteResource::from_pretrained(GptJModelResources::GPT_J_TINY_RANDOM);
+    let mut config = GptJConfig::from_file(config_resource.get_local_path()?);
+    config.use_float16 = false;
+    let weights_path = model_resource.get_local_path()?;
+
+    let input_tensor =
+        Tensor::from_slice(&[1026_i64, 373, 257, 845, 3621, 290, 27737]).unsqueeze(0);
+    Ok((config, weights_path, input_tensor))
+}
+
+fn assert_logits_match(logits: &Tensor, expected_logits: &Tensor) {
+    assert_eq!(logits.size(), expected_logits.size());
+    let max_difference = (logits - expected_logits).abs().max().double_value(&[]);
+    assert!(max_difference < 1e-5);
+}
+
+#[test]
+fn gpt_j_tensor_parallel_threads() -> anyhow::Result<()> {
+    let (config, weights_path, input_tensor) = tensor_parallel_setup()?;
+    let expected_logits = gpt_j_logits(&config, weights_path.clone(), &input_tensor, None)?;
+
+    // Workers running in threads, summing partial results in shared memory
+    let workers = LocalProcessGroup::new_group(2)
+        .into_iter()
+        .map(|process_group| {
+            let (config, weights_path) = (config.clone(), weights_path.clone());
+            let input_tensor = input_tensor.copy();
+            thread::spawn(move || {
+                let process_group = Arc::new(process_group);
+                gpt_j_logits(&config, weights_path, &input_tensor, Some(process_group))
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for worker in workers {
+        assert_logits_match(&worker.join().unwrap()?, &expected_logits);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn gpt_j_sharded_weight_quantization() -> anyhow::Result<()> {
+    let (config, weights_path, input_tensor) = tensor_parallel_setup()?;
+
+    let quantized_dir = tempfile::tempdir()?;
+    for (index, quantization) in [
+        WeightQuantization::Int8,
+        WeightQuantization::Int4 { group_size: 8 },
+    ]
+    .iter()
+    .enumerate()
+    {
+        let mut quantized_config = config.clone();
+        quantized_config.quantization = Some(*quantization);
+
+        //    Quantization of the full precision weights, saved as pre-quantized weights
+        let mut vs = nn::VarStore::new(Device::Cpu);
+        let _model = GptJLMHeadModel::new(vs.root(), &quantized_config);
+        load_and_quantize_weights(&LocalResource::from(weights_path.clone()), &mut vs)?;
+        let quantized_weights_path = quantized_dir
+            .path()
+            .join(format!("rust_model_{}.ot", index));
+        vs.save(&quantized_weights_path)?;
+        let expected_logits = gpt_j_logits(
+            &quantized_config,
+            quantized_weights_path.clone(),
+            &input_tensor,
+            None,
+        )?;
+
+        //    Shards of the quantized weights, scales and zero points loaded by each worker
+        let workers = LocalProcessGroup::new_group(2)
+            .into_iter()
+            .map(|process_group| {
+                let (config, weights_path) =
+                    (quantized_config.clone(), quantized_weights_path.clone());
+                let input_tensor = input_tensor.copy();
+                thread::spawn(move || {
+                    let process_group = Arc::new(process_group);
+                    gpt_j_logits(&config, weights_path, &input_tensor, Some(process_group))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for worker in workers {
+            assert_logits_match(&worker.join().unwrap()?, &expected_logits);
+        }
+    }
+
+    Ok(())
+}
+
+const WORKER_RANK: &str = "GPT_J_WORKER_RANK";
+const WORKER_ADDRESS: &str = "GPT_J_WORKER_ADDRESS";
+const WORKER_OUTPUT: &str = "GPT_J_WORKER_OUTPUT";
+const LISTENING_PREFIX: &str = "Worker of rank 0 listening on ";
+
+/// Tensor-parallel worker process, spawned by `gpt_j_tensor_parallel_processes` (no-op when run directly).
+/// The worker of rank 0 listens on a port chosen by the system and prints its address for the other worker.
+#[test]
+#[ignore]
+fn gpt_j_tensor_parallel_worker() -> anyhow::Result<()> {
+    let rank: usize = match env::var(WORKER_RANK) {
+        Ok(rank) => rank.parse()?,
+        Err(_) => return Ok(()),
+    };
+    let (config, weights_path, input_tensor) = tensor_parallel_setup()?;
+    let process_group = if rank == 0 {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        println!("{}{}", LISTENING_PREFIX, listener.local_addr()?);
+        io::stdout().flush()?;
+        TcpProcessGroup::from_listener(listener, 2)?
+    } else {
+        TcpProcessGroup::new(rank, 2, env::var(WORKER_ADDRESS)?)?
+    };
+    let logits = gpt_j_logits(
+        &config,
+        weights_path,
+        &input_tensor,
+        Some(Arc::new(process_group)),
+    )?;
+    logits.save(env::var(WORKER_OUTPUT)?)?;
+    Ok(())
+}
+
+#[test]
+fn gpt_j_tensor_parallel_processes() -> anyhow::Result<()> {
+    let (config, weights_path, input_tensor) = tensor_parallel_setup()?;
+    let expected_logits = gpt_j_logits(&config, weights_path, &input_tensor, None)?;
+
+    // Workers running in separate processes (this test binary), communicating over TCP sockets
+    let output_dir = tempfile::tempdir()?;
+    let output_path = |rank: usize| output_dir.path().join(format!("logits_{}.ot", rank));
+    let spawn_worker = |rank: usize, address: Option<&str>| -> io::Result<Child> {
+        let mut command = Command::new(env::current_exe()?);
+        command
+            .args(&[
+                "gpt_j_tensor_parallel_worker",
+                "--exact",
+                "--ignored",
+                "--nocapture",
+            ])
+            .env(WORKER_RANK, rank.to_string())
+            .env(WORKER_OUTPUT, output_path(rank))
+            .stdout(if rank == 0 {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            });
+        if let Some(address) = address {
+            command.env(WORKER_ADDRESS, address);
+        }
+        command.spawn()
+    };
+
+    let mut rank_0 = spawn_worker(0, None)?;
+    let mut rank_0_output = BufReader::new(rank_0.stdout.take().unwrap()).lines();
+    let address = loop {
+        match rank_0_output.next() {
+            Some(line) => {
+                if let Some(address) = line?.strip_prefix(LISTENING_PREFIX) {
+                    break address.to_string();
+                }
+            }
+            None => anyhow::bail!("The worker of rank 0 exited before listening"),
+        }
+    };
+    let mut rank_1 = spawn_worker(1, Some(&address))?;
+    for line in rank_0_output {
+        line?;
+    }
+    assert!(rank_0.wait()?.success());
+    assert!(rank_1.wait()?.success());
+
+    for rank in 0..2 {
+        assert_logits_match(&Tensor::load(output_path(rank))?, &expected_logits);
+    }
+
+    Ok(())
+}
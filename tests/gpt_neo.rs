@@ -4,10 +4,11 @@ use rust_bert::gpt_neo::{
 };
 use rust_bert::pipelines::common::{ModelResource, ModelType};
 use rust_bert::pipelines::text_generation::{TextGenerationConfig, TextGenerationModel};
-use rust_bert::resources::{RemoteResource, ResourceProvider};
+use rust_bert::quantization::{load_and_quantize_weights, WeightQuantization};
+use rust_bert::resources::{load_weights, LocalResource, RemoteResource, ResourceProvider};
 use rust_bert::Config;
 use rust_tokenizers::tokenizer::{Gpt2Tokenizer, Tokenizer, TruncationStrategy};
-use tch::{nn, Device, Tensor};
+use tch::{nn, no_grad, Device, Tensor};
 
 #[test]
 fn gpt_neo_lm() -> anyhow::Result<()> {
@@ -151,3 +152,58 @@ fn test_generation_gpt_neo() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn gpt_neo_weight_quantization() -> anyhow::Result<()> {
+    //    Resources paths
+    let config_resource = RemoteResource::from_pretrained(GptNeoConfigResources::GPT_NEO_125M);
+    let weights_resource = RemoteResource::from_pretrained(GptNeoModelResources::GPT_NEO_125M);
+    let config = GptNeoConfig::from_file(config_resource.get_local_path()?);
+
+    //    "It was a sunny"
+    let input_tensor = Tensor::from_slice(&[1026i64, 373, 257, 27737]).unsqueeze(0);
+    let next_word_logits = |model: &GptNeoForCausalLM| {
+        no_grad(|| {
+            model
+                .forward_t(Some(&input_tensor), None, None, None, None, None, false)
+                .unwrap()
+                .lm_logits
+                .get(0)
+                .get(-1)
+        })
+    };
+
+    let mut vs = nn::VarStore::new(Device::Cpu);
+    let model = GptNeoForCausalLM::new(vs.root(), &config)?;
+    load_weights(&weights_resource, &mut vs)?;
+    let reference_logits = next_word_logits(&model);
+
+    let quantized_dir = tempfile::tempdir()?;
+    for quantization in [
+        WeightQuantization::Int8,
+        WeightQuantization::Int4 { group_size: 64 },
+    ] {
+        let mut quantized_config = config.clone();
+        quantized_config.quantization = Some(quantization);
+
+        //    Quantization of full precision weights
+        let mut vs = nn::VarStore::new(Device::Cpu);
+        let model = GptNeoForCausalLM::new(vs.root(), &quantized_config)?;
+        load_and_quantize_weights(&weights_resource, &mut vs)?;
+        let logits = next_word_logits(&model);
+        assert_eq!(logits.argmax(-1, false).int64_value(&[]), 1110i64);
+        let relative_error = (&logits - &reference_logits).norm().double_value(&[])
+            / reference_logits.norm().double_value(&[]);
+        assert!(relative_error < 0.05);
+
+        //    Loading of the pre-quantized weights
+        let quantized_weights_path = quantized_dir.path().join("rust_model.ot");
+        vs.save(&quantized_weights_path)?;
+        let mut vs = nn::VarStore::new(Device::Cpu);
+        let model = GptNeoForCausalLM::new(vs.root(), &quantized_config)?;
+        load_weights(&LocalResource::from(quantized_weights_path), &mut vs)?;
+        assert_eq!(next_word_logits(&model), logits);
+    }
+
+    Ok(())
+}
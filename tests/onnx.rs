@@ -2,13 +2,28 @@
 mod tests {
     extern crate anyhow;
 
+    use rust_bert::bert::{
+        BertConfig, BertConfigResources, BertEmbeddings, BertForTokenClassification, BertModel,
+    };
+    use rust_bert::distilbert::{
+        DistilBertConfig, DistilBertConfigResources, DistilBertModelClassifier,
+        DistilBertModelResources, DistilBertVocabResources,
+    };
     use rust_bert::m2m_100::{M2M100SourceLanguages, M2M100TargetLanguages};
-    use rust_bert::pipelines::common::{ModelResource, ModelType, ONNXModelResources};
+    use rust_bert::pipelines::common::{
+        ConfigOption, ModelResource, ModelType, ONNXModelResources,
+    };
     use rust_bert::pipelines::masked_language::{MaskedLanguageConfig, MaskedLanguageModel};
     use rust_bert::pipelines::ner::NERModel;
+    use rust_bert::pipelines::onnx::config::ONNXEnvironmentConfig;
+    use rust_bert::pipelines::onnx::export::{export_to_onnx, ONNXExportTask};
+    use rust_bert::pipelines::onnx::ONNXEncoder;
     use rust_bert::pipelines::question_answering::{
         QaInput, QuestionAnsweringConfig, QuestionAnsweringModel,
     };
+    use rust_bert::pipelines::sentence_embeddings::{
+        SentenceEmbeddingsBuilder, SentenceEmbeddingsModelType,
+    };
     use rust_bert::pipelines::sentiment::{SentimentModel, SentimentPolarity};
     use rust_bert::pipelines::sequence_classification::SequenceClassificationConfig;
     use rust_bert::pipelines::text_generation::{TextGenerationConfig, TextGenerationModel};
@@ -16,8 +31,14 @@ mod tests {
         LabelAggregationOption, TokenClassificationConfig,
     };
     use rust_bert::pipelines::translation::{Language, TranslationConfig, TranslationModel};
-    use rust_bert::resources::RemoteResource;
-    use tch::Device;
+    use rust_bert::resources::{LocalResource, RemoteResource, ResourceProvider};
+    use rust_bert::roberta::{
+        RobertaConfigResources, RobertaEmbeddings, RobertaForTokenClassification,
+    };
+    use rust_bert::Config;
+    use std::collections::HashMap;
+    use std::path::Path;
+    use tch::{nn, no_grad, Device, Kind, Tensor};
 
     #[test]
     fn onnx_masked_lm() -> anyhow::Result<()> {
@@ -292,4 +313,263 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn onnx_sentence_embeddings() -> anyhow::Result<()> {
+        let model = SentenceEmbeddingsBuilder::remote(SentenceEmbeddingsModelType::AllMiniLmL6V2)
+            .with_device(Device::Cpu)
+            .create_model()?;
+        let onnx_model =
+            SentenceEmbeddingsBuilder::remote(SentenceEmbeddingsModelType::AllMiniLmL6V2)
+                .transformer_onnx_weights(RemoteResource::new(
+                    "https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2/resolve/main/onnx/model.onnx",
+                    "onnx-all-mini-lm-l6-v2",
+                ))
+                .with_device(Device::Cpu)
+                .create_model()?;
+
+        let sentences = ["this is an example sentence", "each sentence is converted"];
+        let embeddings = model.encode(&sentences)?;
+        let onnx_embeddings = onnx_model.encode(&sentences)?;
+        assert_eq!(onnx_embeddings.len(), 2);
+        assert_eq!(onnx_embeddings[0].len(), 384);
+        for (embedding, onnx_embedding) in embeddings.iter().zip(onnx_embeddings.iter()) {
+            for (value, onnx_value) in embedding.iter().zip(onnx_embedding.iter()) {
+                assert!((value - onnx_value).abs() < 1e-4);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn onnx_export_sequence_classification() -> anyhow::Result<()> {
+        let config_resource =
+            RemoteResource::from_pretrained(DistilBertConfigResources::DISTIL_BERT_SST2);
+        let weights_resource =
+            RemoteResource::from_pretrained(DistilBertModelResources::DISTIL_BERT_SST2);
+        let config = DistilBertConfig::from_file(config_resource.get_local_path()?);
+        let mut var_store = nn::VarStore::new(Device::Cpu);
+        let _model = DistilBertModelClassifier::new(var_store.root(), &config)?;
+        var_store.load(weights_resource.get_local_path()?)?;
+
+        let export_dir = tempfile::tempdir()?;
+        let onnx_path = export_dir.path().join("model.onnx");
+        export_to_onnx(
+            &var_store,
+            &ConfigOption::DistilBert(config),
+            ONNXExportTask::SequenceClassification,
+            &onnx_path,
+        )?;
+
+        let classification_model = SentimentModel::new(SequenceClassificationConfig::new(
+            ModelType::DistilBert,
+            ModelResource::ONNX(ONNXModelResources {
+                encoder_resource: Some(Box::new(LocalResource::from(onnx_path))),
+                ..Default::default()
+            }),
+            config_resource,
+            RemoteResource::from_pretrained(DistilBertVocabResources::DISTIL_BERT_SST2),
+            None,
+            true,
+            None,
+            None,
+        ))?;
+        let input = [
+            "Probably my all-time favorite movie, a story of selflessness, sacrifice and dedication to a noble cause, but it's not preachy or boring.",
+            "This film tried to be too many things all at once: stinging political satire, Hollywood blockbuster, sappy romantic comedy, family values promo...",
+            "If you like original gut wrenching laughter you will like this movie. If you are young or old then you will love this movie, hell even my mom liked it.",
+        ];
+        let output = classification_model.predict(input);
+        assert_eq!(output.len(), 3);
+        assert_eq!(output[0].polarity, SentimentPolarity::Positive);
+        assert!((output[0].score - 0.9981).abs() < 1e-4);
+        assert_eq!(output[1].polarity, SentimentPolarity::Negative);
+        assert!((output[1].score - 0.9927).abs() < 1e-4);
+        assert_eq!(output[2].polarity, SentimentPolarity::Positive);
+        assert!((output[2].score - 0.9997).abs() < 1e-4);
+        Ok(())
+    }
+
+    fn export_and_load(
+        var_store: &nn::VarStore,
+        config: ConfigOption,
+        task: ONNXExportTask,
+        export_dir: &Path,
+    ) -> anyhow::Result<ONNXEncoder> {
+        let onnx_path = export_dir.join(format!("{task:?}.onnx"));
+        export_to_onnx(var_store, &config, task, &onnx_path)?;
+        let onnx_config = ONNXEnvironmentConfig::default();
+        let environment = onnx_config.get_environment()?;
+        Ok(ONNXEncoder::new(onnx_path, &environment, &onnx_config)?)
+    }
+
+    /// Maximum absolute difference between the outputs at the positions of non-padding tokens
+    fn max_masked_difference(torch_output: &Tensor, onnx_output: &Tensor, mask: &Tensor) -> f64 {
+        ((torch_output - onnx_output).abs() * mask.unsqueeze(-1))
+            .max()
+            .double_value(&[])
+    }
+
+    fn encoder_config(config_resource: RemoteResource) -> anyhow::Result<BertConfig> {
+        let mut config = BertConfig::from_file(config_resource.get_local_path()?);
+        config.num_hidden_layers = 2;
+        let mut label_mapping = HashMap::new();
+        label_mapping.insert(0, String::from("O"));
+        label_mapping.insert(1, String::from("B-PER"));
+        label_mapping.insert(2, String::from("I-PER"));
+        config.id2label = Some(label_mapping);
+        Ok(config)
+    }
+
+    #[test]
+    fn onnx_export_bert_compare() -> anyhow::Result<()> {
+        let config = encoder_config(RemoteResource::from_pretrained(BertConfigResources::BERT))?;
+        let export_dir = tempfile::tempdir()?;
+        tch::manual_seed(0);
+
+        //    Padded batch with two segments (token type ids)
+        let input_ids = Tensor::from_slice2(&[
+            [101i64, 7592, 2088, 102, 2129, 2024, 2017, 102],
+            [101, 2009, 2003, 102, 2307, 102, 0, 0],
+        ]);
+        let mask = input_ids.ne(0).to_kind(Kind::Int64);
+        let token_type_ids =
+            Tensor::from_slice2(&[[0i64, 0, 0, 0, 1, 1, 1, 1], [0, 0, 0, 0, 1, 1, 1, 1]]);
+        let float_mask = mask.to_kind(Kind::Float);
+
+        //    Bare encoder
+        let var_store = nn::VarStore::new(Device::Cpu);
+        let model = BertModel::<BertEmbeddings>::new(var_store.root(), &config);
+        let torch_output = no_grad(|| {
+            model.forward_t(
+                Some(&input_ids),
+                Some(&mask),
+                Some(&token_type_ids),
+                None,
+                None,
+                None,
+                None,
+                false,
+            )
+        })?
+        .hidden_state;
+        let onnx_model = export_and_load(
+            &var_store,
+            ConfigOption::Bert(config.clone()),
+            ONNXExportTask::Encoder,
+            export_dir.path(),
+        )?;
+        let onnx_output = onnx_model
+            .forward(
+                Some(&input_ids),
+                Some(&mask),
+                Some(&token_type_ids),
+                None,
+                None,
+            )?
+            .last_hidden_state
+            .unwrap();
+        assert_eq!(onnx_output.size(), torch_output.size());
+        assert!(max_masked_difference(&torch_output, &onnx_output, &float_mask) < 1e-4);
+
+        //    Token classification
+        let var_store = nn::VarStore::new(Device::Cpu);
+        let model = BertForTokenClassification::new(var_store.root(), &config)?;
+        let torch_output = no_grad(|| {
+            model.forward_t(
+                Some(&input_ids),
+                Some(&mask),
+                Some(&token_type_ids),
+                None,
+                None,
+                false,
+            )
+        })
+        .logits;
+        let onnx_model = export_and_load(
+            &var_store,
+            ConfigOption::Bert(config),
+            ONNXExportTask::TokenClassification,
+            export_dir.path(),
+        )?;
+        let onnx_output = onnx_model
+            .forward(
+                Some(&input_ids),
+                Some(&mask),
+                Some(&token_type_ids),
+                None,
+                None,
+            )?
+            .logits
+            .unwrap();
+        assert_eq!(onnx_output.size(), vec![2, 8, 3]);
+        assert!(max_masked_difference(&torch_output, &onnx_output, &float_mask) < 1e-4);
+        Ok(())
+    }
+
+    #[test]
+    fn onnx_export_roberta_compare() -> anyhow::Result<()> {
+        let config = encoder_config(RemoteResource::from_pretrained(
+            RobertaConfigResources::ROBERTA,
+        ))?;
+        let export_dir = tempfile::tempdir()?;
+        tch::manual_seed(0);
+
+        //    Padded batch: positions are offset by the padding index and skip padding tokens
+        let input_ids = Tensor::from_slice2(&[
+            [0i64, 31414, 232, 328, 20, 2199, 16, 2],
+            [0, 713, 16, 2, 1, 1, 1, 1],
+        ]);
+        let mask = input_ids.ne(1).to_kind(Kind::Int64);
+        let float_mask = mask.to_kind(Kind::Float);
+
+        //    Bare encoder
+        let var_store = nn::VarStore::new(Device::Cpu);
+        let model = BertModel::<RobertaEmbeddings>::new(var_store.root(), &config);
+        let torch_output = no_grad(|| {
+            model.forward_t(
+                Some(&input_ids),
+                Some(&mask),
+                None,
+                None,
+                None,
+                None,
+                None,
+                false,
+            )
+        })?
+        .hidden_state;
+        let onnx_model = export_and_load(
+            &var_store,
+            ConfigOption::Roberta(config.clone()),
+            ONNXExportTask::Encoder,
+            export_dir.path(),
+        )?;
+        let onnx_output = onnx_model
+            .forward(Some(&input_ids), Some(&mask), None, None, None)?
+            .last_hidden_state
+            .unwrap();
+        assert_eq!(onnx_output.size(), torch_output.size());
+        assert!(max_masked_difference(&torch_output, &onnx_output, &float_mask) < 1e-4);
+
+        //    Token classification
+        let var_store = nn::VarStore::new(Device::Cpu);
+        let model = RobertaForTokenClassification::new(var_store.root(), &config)?;
+        let torch_output =
+            no_grad(|| model.forward_t(Some(&input_ids), Some(&mask), None, None, None, false))
+                .logits;
+        let onnx_model = export_and_load(
+            &var_store,
+            ConfigOption::Roberta(config),
+            ONNXExportTask::TokenClassification,
+            export_dir.path(),
+        )?;
+        let onnx_output = onnx_model
+            .forward(Some(&input_ids), Some(&mask), None, None, None)?
+            .logits
+            .unwrap();
+        assert_eq!(onnx_output.size(), vec![2, 8, 3]);
+        assert!(max_masked_difference(&torch_output, &onnx_output, &float_mask) < 1e-4);
+        Ok(())
+    }
 }
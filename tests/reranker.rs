@@ -0,0 +1,167 @@
+use rust_bert::deberta::{
+    DebertaConfigResources, DebertaMergesResources, DebertaModelResources, DebertaVocabResources,
+};
+use rust_bert::distilbert::{
+    DistilBertConfigResources, DistilBertModelResources, DistilBertVocabResources,
+};
+use rust_bert::pipelines::common::{ModelResource, ModelType};
+use rust_bert::pipelines::reranker::{RerankerConfig, RerankerModel};
+use rust_bert::pipelines::sequence_classification::{
+    SequenceClassificationConfig, SequenceClassificationModel,
+};
+use rust_bert::resources::RemoteResource;
+
+extern crate anyhow;
+
+fn sigmoid(score: f64) -> f64 {
+    1.0 / (1.0 + (-score).exp())
+}
+
+#[test]
+fn reranker_ms_marco_cross_encoder() -> anyhow::Result<()> {
+    //    Set-up model (cross-encoder with a single relevance logit)
+    let config = RerankerConfig::new(
+        ModelType::Bert,
+        ModelResource::Torch(Box::new(RemoteResource::new(
+            "https://huggingface.co/cross-encoder/ms-marco-MiniLM-L-6-v2/resolve/main/model.safetensors",
+            "cross-encoder-ms-marco-minilm-l6",
+        ))),
+        RemoteResource::new(
+            "https://huggingface.co/cross-encoder/ms-marco-MiniLM-L-6-v2/resolve/main/config.json",
+            "cross-encoder-ms-marco-minilm-l6",
+        ),
+        RemoteResource::new(
+            "https://huggingface.co/cross-encoder/ms-marco-MiniLM-L-6-v2/resolve/main/vocab.txt",
+            "cross-encoder-ms-marco-minilm-l6",
+        ),
+        None,
+        true,
+        None,
+        None,
+    );
+    let reranker_model = RerankerModel::new(config)?;
+
+    //    Define input
+    let query = "How many people live in Berlin?";
+    let passages = [
+        "New York City is famous for the Metropolitan Museum of Art.",
+        "Berlin has a population of 3,520,031 registered inhabitants in an area of 891.82 square kilometers.",
+        "The weather in Paris is mild in spring.",
+    ];
+
+    //    Run model
+    let output = reranker_model.rerank(query, &passages, 32)?;
+
+    assert_eq!(output.len(), 3);
+    assert_eq!(output[0].index, 1);
+    assert!(output[0].score > 0.0);
+    for (ranked, next) in output.iter().zip(output.iter().skip(1)) {
+        assert!(ranked.score >= next.score);
+    }
+    for ranked in output.iter() {
+        assert_eq!(ranked.text, passages[ranked.index]);
+        assert!((ranked.probability - sigmoid(ranked.score)).abs() < 1e-9);
+    }
+
+    //    Scores do not depend on the batching of the pairs
+    let pairs = passages
+        .iter()
+        .map(|passage| (query, *passage))
+        .collect::<Vec<(&str, &str)>>();
+    let batched_scores = reranker_model.score_pairs(&pairs, 32)?;
+    let unbatched_scores = reranker_model.score_pairs(&pairs, 2)?;
+    assert_eq!(batched_scores.len(), 3);
+    for (batched, unbatched) in batched_scores.iter().zip(unbatched_scores.iter()) {
+        assert!((batched - unbatched).abs() < 1e-4);
+    }
+    for ranked in output.iter() {
+        assert!((ranked.score - batched_scores[ranked.index]).abs() < 1e-4);
+    }
+    let no_passages: [&str; 0] = [];
+    assert!(reranker_model.rerank(query, no_passages, 32)?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn reranker_two_labels_logit_difference() -> anyhow::Result<()> {
+    //    Set-up models: the probability of the second label is used as relevance for 2-labels models
+    let config = || {
+        SequenceClassificationConfig::new(
+            ModelType::DistilBert,
+            ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
+                DistilBertModelResources::DISTIL_BERT_SST2,
+            ))),
+            RemoteResource::from_pretrained(DistilBertConfigResources::DISTIL_BERT_SST2),
+            RemoteResource::from_pretrained(DistilBertVocabResources::DISTIL_BERT_SST2),
+            None,
+            true,
+            None,
+            None,
+        )
+    };
+    let reranker_model = RerankerModel::new(config())?;
+    let sequence_classification_model = SequenceClassificationModel::new(config())?;
+
+    //    Define input
+    let query = "Review of the movie:";
+    let passages = [
+        "This film was a complete waste of time.",
+        "Probably my all-time favorite movie.",
+        "The plot was dull but the actors were great.",
+    ];
+    let pairs = passages
+        .iter()
+        .map(|passage| (query, *passage))
+        .collect::<Vec<(&str, &str)>>();
+
+    //    Run models
+    let output = reranker_model.rerank(query, &passages, 2)?;
+    let labels = sequence_classification_model.predict_pair(&pairs, 32);
+
+    assert_eq!(output.len(), 3);
+    assert_eq!(output[0].index, 1);
+    assert_eq!(output[2].index, 0);
+    for ranked in output.iter() {
+        let label = &labels[ranked.index];
+        let positive_probability = if label.id == 1 {
+            label.score
+        } else {
+            1.0 - label.score
+        };
+        assert!((ranked.probability - positive_probability).abs() < 1e-4);
+        assert!((ranked.probability - sigmoid(ranked.score)).abs() < 1e-9);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn reranker_invalid_label_count() -> anyhow::Result<()> {
+    //    Set-up model with 3 output labels (natural language inference)
+    let config = RerankerConfig::new(
+        ModelType::Deberta,
+        ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
+            DebertaModelResources::DEBERTA_BASE_MNLI,
+        ))),
+        RemoteResource::from_pretrained(DebertaConfigResources::DEBERTA_BASE_MNLI),
+        RemoteResource::from_pretrained(DebertaVocabResources::DEBERTA_BASE_MNLI),
+        Some(RemoteResource::from_pretrained(
+            DebertaMergesResources::DEBERTA_BASE_MNLI,
+        )),
+        false,
+        None,
+        None,
+    );
+    let reranker_model = RerankerModel::new(config)?;
+
+    let output = reranker_model.rerank(
+        "How many people live in Berlin?",
+        &["Berlin has a population of 3,520,031 registered inhabitants."],
+        32,
+    );
+
+    assert!(output.is_err());
+
+    Ok(())
+}
@@ -1,9 +1,22 @@
+use rust_bert::pipelines::deduplication::{
+    SemanticDeduplicationConfig, SemanticDeduplicationModel,
+};
 use rust_bert::pipelines::keywords_extraction::{
-    KeywordExtractionConfig, KeywordExtractionModel, KeywordScorerType,
+    get_stopwords, KeywordCandidatesType, KeywordExtractionConfig, KeywordExtractionModel,
+    KeywordScorerType, NounPhrasesConfig, StatisticalKeywordExtractionConfig,
+    StatisticalKeywordExtractionModel, StatisticalScorerType,
 };
 use rust_bert::pipelines::sentence_embeddings::{
-    SentenceEmbeddingsBuilder, SentenceEmbeddingsConfig, SentenceEmbeddingsModelType,
+    hamming_distance, int8_cosine_similarity, max_sim, max_sim_scores, SentenceEmbeddingsBuilder,
+    SentenceEmbeddingsConfig, SentenceEmbeddingsModelType, SentenceEmbeddingsPrompts,
+    SentenceEmbeddingsWindowConfig,
+};
+use rust_bert::pipelines::topic_modeling::{
+    ClusteringType, DimensionalityReductionType, TopicModelingConfig, TopicModelingModel,
 };
+use rust_bert::pipelines::translation::Language;
+use std::convert::TryFrom;
+use tch::{Device, Kind};
 
 #[test]
 fn sbert_distilbert() -> anyhow::Result<()> {
@@ -32,6 +45,34 @@ fn sbert_distilbert() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn sbert_distilbert_bfloat16() -> anyhow::Result<()> {
+    let model = SentenceEmbeddingsBuilder::remote(
+        SentenceEmbeddingsModelType::DistiluseBaseMultilingualCased,
+    )
+    .create_model()?;
+    let mut bf16_model = SentenceEmbeddingsBuilder::remote(
+        SentenceEmbeddingsModelType::DistiluseBaseMultilingualCased,
+    )
+    .create_model()?;
+    bf16_model.set_device(Device::Cpu)?;
+    bf16_model.bfloat16()?;
+
+    let sentences = ["This is an example sentence", "Each sentence is converted"];
+    let embeddings = model.encode_as_tensor(&sentences)?.embeddings;
+    let bf16_embeddings = bf16_model.encode_as_tensor(&sentences)?.embeddings;
+
+    //    The mean pooling and dense projection run in single precision, the output stays in `Float`
+    assert_eq!(bf16_embeddings.kind(), Kind::Float);
+    let similarity =
+        embeddings.cosine_similarity(&bf16_embeddings.to(embeddings.device()), 1, 1e-8);
+    for value in Vec::<f32>::try_from(similarity)? {
+        assert!(value > 0.99);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn sbert_bert() -> anyhow::Result<()> {
     let model =
@@ -159,6 +200,58 @@ fn sbert_t5() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn sbert_mpnet() -> anyhow::Result<()> {
+    let model = SentenceEmbeddingsBuilder::remote(SentenceEmbeddingsModelType::AllMpnetBaseV2)
+        .create_model()?;
+
+    let sentences = [
+        "This is an example sentence",
+        "This sentence is an example",
+        "The weather is sunny today",
+    ];
+    let embeddings = model.encode(&sentences)?;
+
+    assert_eq!(embeddings.len(), 3);
+    assert_eq!(embeddings[0].len(), 768);
+    // all-mpnet-base-v2 includes a normalization module
+    let norm = embeddings[0].iter().map(|v| v * v).sum::<f32>().sqrt();
+    assert!((norm as f64 - 1.0).abs() < 1e-4);
+
+    let similarity = |a: &[f32], b: &[f32]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+    assert!(
+        similarity(&embeddings[0], &embeddings[1]) > similarity(&embeddings[0], &embeddings[2])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn sbert_prompts() -> anyhow::Result<()> {
+    let model = SentenceEmbeddingsBuilder::remote(SentenceEmbeddingsModelType::AllMiniLmL6V2)
+        .with_prompts(SentenceEmbeddingsPrompts::new(
+            "query: ".to_string(),
+            "passage: ".to_string(),
+        ))
+        .create_model()?;
+
+    let queries = model.encode_queries(&["how much protein should a female eat"])?;
+    let passages = model.encode_passages(&["how much protein should a female eat"])?;
+    let prefixed = model.encode(&[
+        "query: how much protein should a female eat",
+        "passage: how much protein should a female eat",
+    ])?;
+
+    for (value, expected) in queries[0].iter().zip(prefixed[0].iter()) {
+        assert!((value - expected).abs() < 1e-6);
+    }
+    for (value, expected) in passages[0].iter().zip(prefixed[1].iter()) {
+        assert!((value - expected).abs() < 1e-6);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn keyword_extraction_cosine_similarity() -> anyhow::Result<()> {
     let keyword_extraction_config = KeywordExtractionConfig {
@@ -356,3 +449,355 @@ fn keyword_extraction_cosine_similarity_n_grams() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn keyword_extraction_stopwords_language() -> anyhow::Result<()> {
+    let keyword_extraction_config = KeywordExtractionConfig {
+        sentence_embeddings_config: SentenceEmbeddingsConfig::from(
+            SentenceEmbeddingsModelType::ParaphraseMultilingualMpnetBaseV2,
+        ),
+        tokenizer_language: Some(Language::German),
+        scorer_type: KeywordScorerType::CosineSimilarity,
+        ngram_range: (1, 2),
+        num_keywords: 10,
+        ..Default::default()
+    };
+
+    let keyword_extraction_model = KeywordExtractionModel::new(keyword_extraction_config)?;
+
+    let input = ["Rust ist eine Multiparadigmen-Systemprogrammiersprache, die von der Open-Source-Community \
+ entwickelt wurde. Die Sprache soll sicher, nebenläufig und praxisnah sein. Sicherheit bezieht sich dabei \
+ insbesondere auf die Vermeidung von Programmfehlern, die zu Speicherzugriffsfehlern oder Pufferüberläufen führen."];
+    // Credits: Wikimedia foundation https://de.wikipedia.org/wiki/Rust_(Programmiersprache)
+
+    let keywords = keyword_extraction_model.predict(&input)?;
+    let stopwords = get_stopwords(Language::German)?;
+
+    assert_eq!(keywords.len(), 1);
+    assert_eq!(keywords[0].len(), 10);
+    for keyword in &keywords[0] {
+        assert!(keyword
+            .text
+            .split_whitespace()
+            .all(|word| !stopwords.contains(word.to_lowercase().as_str())));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn keyword_extraction_noun_phrases() -> anyhow::Result<()> {
+    let keyword_extraction_config = KeywordExtractionConfig {
+        sentence_embeddings_config: SentenceEmbeddingsConfig::from(
+            SentenceEmbeddingsModelType::AllMiniLmL6V2,
+        ),
+        candidates_type: KeywordCandidatesType::NounPhrases(NounPhrasesConfig::default()),
+        scorer_type: KeywordScorerType::CosineSimilarity,
+        num_keywords: 5,
+        ..Default::default()
+    };
+
+    let keyword_extraction_model = KeywordExtractionModel::new(keyword_extraction_config)?;
+
+    let input = [
+        "Rust is a multi-paradigm, general-purpose programming language. \
+ Rust emphasizes performance, type safety, and concurrency. Rust enforces memory safety without \
+ requiring the use of a garbage collector.",
+    ];
+    // Credits: Wikimedia foundation https://en.wikipedia.org/wiki/Rust_(programming_language)
+
+    let keywords = keyword_extraction_model.predict(&input)?;
+
+    assert_eq!(keywords.len(), 1);
+    assert_eq!(keywords[0].len(), 5);
+    assert!(keywords[0]
+        .iter()
+        .any(|keyword| keyword.text.split_whitespace().count() > 1));
+    for keyword in &keywords[0] {
+        let offset = keyword.offsets[0];
+        assert_eq!(
+            input[0][offset.begin as usize..offset.end as usize].to_lowercase(),
+            keyword.text
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn keyword_extraction_statistical() -> anyhow::Result<()> {
+    let input = ["Rust is a multi-paradigm, general-purpose programming language. \
+       Rust emphasizes performance, type safety, and concurrency. Rust enforces memory safety—that is, \
+       that all references point to valid memory—without requiring the use of a garbage collector or \
+       reference counting present in other memory-safe languages. To simultaneously enforce \
+       memory safety and prevent concurrent data races, Rust's borrow checker tracks the object lifetime \
+       and variable scope of all references in a program during compilation."];
+
+    for scorer_type in [StatisticalScorerType::Yake, StatisticalScorerType::TextRank] {
+        let keyword_extraction_model =
+            StatisticalKeywordExtractionModel::new(StatisticalKeywordExtractionConfig {
+                scorer_type,
+                ngram_range: (1, 2),
+                num_keywords: 5,
+                ..Default::default()
+            })?;
+        let keywords = keyword_extraction_model.predict(&input);
+
+        assert_eq!(keywords.len(), 1);
+        assert_eq!(keywords[0].len(), 5);
+        assert!(keywords[0]
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score));
+        for keyword in &keywords[0] {
+            for offset in &keyword.offsets {
+                assert_eq!(
+                    input[0][offset.begin as usize..offset.end as usize].to_lowercase(),
+                    keyword.text
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn keyword_extraction_hybrid() -> anyhow::Result<()> {
+    let keyword_extraction_config = KeywordExtractionConfig {
+        sentence_embeddings_config: SentenceEmbeddingsConfig::from(
+            SentenceEmbeddingsModelType::AllMiniLmL6V2,
+        ),
+        scorer_type: KeywordScorerType::Hybrid {
+            statistical_scorer: StatisticalScorerType::Yake,
+            embeddings_weight: 0.5,
+        },
+        ngram_range: (1, 1),
+        num_keywords: 5,
+        ..Default::default()
+    };
+
+    let keyword_extraction_model = KeywordExtractionModel::new(keyword_extraction_config)?;
+
+    let input = "Rust is a multi-paradigm, general-purpose programming language. \
+       Rust emphasizes performance, type safety, and concurrency. Rust enforces memory safety—that is, \
+       that all references point to valid memory—without requiring the use of a garbage collector.";
+
+    let keywords = keyword_extraction_model.predict(&[input])?;
+
+    assert_eq!(keywords.len(), 1);
+    assert_eq!(keywords[0].len(), 5);
+    assert_eq!(keywords[0][0].text, "rust");
+    assert!(keywords[0].iter().all(|keyword| keyword.score <= 1.0));
+
+    Ok(())
+}
+
+#[test]
+fn keyword_extraction_hybrid_invalid_weight() {
+    for embeddings_weight in [-0.1, 1.5, f64::NAN] {
+        let keyword_extraction_config = KeywordExtractionConfig {
+            scorer_type: KeywordScorerType::Hybrid {
+                statistical_scorer: StatisticalScorerType::TextRank,
+                embeddings_weight,
+            },
+            ..Default::default()
+        };
+        assert!(KeywordExtractionModel::new(keyword_extraction_config).is_err());
+    }
+}
+
+#[test]
+fn topic_modeling_k_means() -> anyhow::Result<()> {
+    let topic_modeling_config = TopicModelingConfig {
+        keyword_extraction_config: KeywordExtractionConfig {
+            sentence_embeddings_config: SentenceEmbeddingsConfig::from(
+                SentenceEmbeddingsModelType::AllMiniLmL6V2,
+            ),
+            num_keywords: 3,
+            ..Default::default()
+        },
+        dimensionality_reduction: DimensionalityReductionType::Pca { num_components: 2 },
+        clustering: ClusteringType::KMeans {
+            num_clusters: 2,
+            max_iterations: 100,
+        },
+        batch_size: 2,
+    };
+    let topic_modeling_model = TopicModelingModel::new(topic_modeling_config)?;
+
+    let input = [
+        "The team won the football championship after a dramatic final.",
+        "The striker scored twice and the football club won the league.",
+        "The goalkeeper saved a penalty in the football match.",
+        "The central bank raised interest rates to fight inflation.",
+        "Inflation slowed down as the central bank kept rates high.",
+        "Interest rates and inflation weigh on the economy.",
+    ];
+    let output = topic_modeling_model.predict(&input)?;
+
+    assert_eq!(output.document_topics.len(), 6);
+    assert_eq!(output.topics.len(), 2);
+    assert!(output.document_topics[..3]
+        .iter()
+        .all(|topic| *topic == output.document_topics[0]));
+    assert!(output.document_topics[3..]
+        .iter()
+        .all(|topic| *topic == output.document_topics[3]));
+    assert_ne!(output.document_topics[0], output.document_topics[3]);
+    for topic in &output.topics {
+        assert_eq!(topic.documents.len(), 3);
+        assert_eq!(topic.keywords.len(), 3);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn sbert_truncated_quantized_embeddings() -> anyhow::Result<()> {
+    let model = SentenceEmbeddingsBuilder::remote(SentenceEmbeddingsModelType::AllMiniLmL6V2)
+        .with_truncate_dim(128)
+        .create_model()?;
+
+    let sentences = [
+        "The cat sits on the mat",
+        "A cat is sitting on a mat",
+        "The stock market crashed today",
+    ];
+    let embeddings = model.encode(&sentences)?;
+    assert_eq!(model.get_embedding_dim()?, 128);
+    assert_eq!(embeddings[0].len(), 128);
+    let norm = embeddings[0].iter().map(|value| value * value).sum::<f32>();
+    assert!((norm - 1.0).abs() < 1e-4);
+
+    let int8_embeddings = model.encode_int8(&sentences)?;
+    assert_eq!(int8_embeddings[0].values.len(), 128);
+    let cosine_similarity = |a: &[f32], b: &[f32]| a.iter().zip(b).map(|(a, b)| a * b).sum::<f32>();
+    assert!(
+        (int8_cosine_similarity(&int8_embeddings[0], &int8_embeddings[1])
+            - cosine_similarity(&embeddings[0], &embeddings[1]))
+        .abs()
+            < 1e-2
+    );
+
+    let binary_embeddings = model.encode_binary(&sentences)?;
+    assert_eq!(binary_embeddings[0].len(), 16);
+    assert!(
+        hamming_distance(&binary_embeddings[0], &binary_embeddings[1])
+            < hamming_distance(&binary_embeddings[0], &binary_embeddings[2])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn sbert_long_text_windows() -> anyhow::Result<()> {
+    let mut model = SentenceEmbeddingsBuilder::remote(SentenceEmbeddingsModelType::AllMiniLmL6V2)
+        .create_model()?;
+
+    let short_text = "This is an example sentence";
+    let long_text = "Rust is a multi-paradigm, general-purpose programming language. ".repeat(100);
+    let truncated_embeddings = model.encode(&[short_text, long_text.as_str()])?;
+
+    let windows_config = SentenceEmbeddingsWindowConfig {
+        batch_size: 2,
+        ..Default::default()
+    };
+    model.set_long_text_windows(Some(windows_config));
+    let (tokenized_windows, window_inputs) =
+        model.tokenize_windows(&[short_text, long_text.as_str()], &windows_config)?;
+    assert!(tokenized_windows.tokens_ids.len() > 2);
+    assert_eq!(window_inputs[0].0, 0);
+    assert!(window_inputs[1..].iter().all(|(input, _)| *input == 1));
+
+    let window_embeddings = model.encode(&[short_text, long_text.as_str()])?;
+    assert_eq!(window_embeddings.len(), 2);
+    assert_eq!(window_embeddings[1].len(), 384);
+    // Short texts fit in a single window and are not affected
+    for (windowed, truncated) in window_embeddings[0].iter().zip(&truncated_embeddings[0]) {
+        assert!((windowed - truncated).abs() < 1e-4);
+    }
+    // The embeddings do not depend on the number of windows encoded in a single forward pass
+    model.set_long_text_windows(Some(SentenceEmbeddingsWindowConfig {
+        batch_size: 64,
+        ..windows_config
+    }));
+    let unbatched_embeddings = model.encode(&[short_text, long_text.as_str()])?;
+    for (batched, unbatched) in window_embeddings[1].iter().zip(&unbatched_embeddings[1]) {
+        assert!((batched - unbatched).abs() < 1e-4);
+    }
+
+    // The windows overlap must be lower than the window length
+    let invalid_windows_config = SentenceEmbeddingsWindowConfig {
+        overlap: 512,
+        ..windows_config
+    };
+    assert!(model
+        .tokenize_windows(&[long_text.as_str()], &invalid_windows_config)
+        .is_err());
+    model.set_long_text_windows(Some(invalid_windows_config));
+    assert!(model.encode(&[long_text.as_str()]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn sbert_token_embeddings_max_sim() -> anyhow::Result<()> {
+    let model = SentenceEmbeddingsBuilder::remote(SentenceEmbeddingsModelType::AllMiniLmL6V2)
+        .create_model()?;
+
+    let query = ["How do I configure the connection pool size?"];
+    let documents = [
+        "The connection pool size is configured with the max_connections option.",
+        "Bake the cake for forty minutes.",
+    ];
+    let query_tokens = model.encode_tokens(&query)?;
+    let documents_tokens = model.encode_tokens(&documents)?;
+
+    assert_eq!(query_tokens.len(), 1);
+    assert_eq!(documents_tokens.len(), 2);
+    assert_eq!(query_tokens[0][0].len(), 384);
+    assert!(documents_tokens[0].len() > documents_tokens[1].len());
+    let scores = documents_tokens
+        .iter()
+        .map(|document| max_sim(&query_tokens[0], document))
+        .collect::<Vec<f32>>();
+    assert!(scores[0] > scores[1]);
+
+    let batch_scores = Vec::<Vec<f32>>::try_from(max_sim_scores(
+        &model.encode_tokens_as_tensor(&query)?,
+        &model.encode_tokens_as_tensor(&documents)?,
+    ))?;
+    assert!((batch_scores[0][0] - scores[0]).abs() < 1e-3);
+    assert!((batch_scores[0][1] - scores[1]).abs() < 1e-3);
+
+    Ok(())
+}
+
+#[test]
+fn semantic_deduplication() -> anyhow::Result<()> {
+    let mut config = SemanticDeduplicationConfig::new(SentenceEmbeddingsConfig::from(
+        SentenceEmbeddingsModelType::AllMiniLmL6V2,
+    ));
+    config.index_config.threshold = 0.85;
+    config.batch_size = 2;
+    let mut deduplication_model = SemanticDeduplicationModel::new(config)?;
+
+    let duplicate_of = deduplication_model.add(&[
+        "How do I reset my password?",
+        "The delivery arrived two days late.",
+        "How can I reset my password?",
+    ])?;
+    assert_eq!(duplicate_of, vec![None, None, Some(0)]);
+
+    let duplicate_of = deduplication_model.add(&[
+        "My package was delivered two days late.",
+        "What is the capital of France?",
+    ])?;
+    assert_eq!(duplicate_of[1], None);
+
+    let clusters = deduplication_model.clusters();
+    assert_eq!(clusters[0].representative, 0);
+    assert_eq!(clusters[0].members, vec![0, 2]);
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use rust_bert::pipelines::common::{ModelResource, ModelType};
+use rust_bert::pipelines::multiple_choice::{
+    MultipleChoiceConfig, MultipleChoiceInput, MultipleChoiceModel,
+};
 use rust_bert::pipelines::text_generation::{TextGenerationConfig, TextGenerationModel};
-use rust_bert::resources::{RemoteResource, ResourceProvider};
+use rust_bert::resources::{LocalResource, RemoteResource, ResourceProvider};
 use rust_bert::xlnet::{
     XLNetConfig, XLNetConfigResources, XLNetForMultipleChoice, XLNetForQuestionAnswering,
     XLNetForSequenceClassification, XLNetForTokenClassification, XLNetLMHeadModel, XLNetModel,
@@ -497,3 +500,61 @@ fn xlnet_for_question_answering() -> anyhow::Result<()> {
     assert_eq!(model_output.end_logits.size(), &[1, 21]);
     Ok(())
 }
+
+#[test]
+fn xlnet_multiple_choice_pipeline_padding() -> anyhow::Result<()> {
+    //    Resources paths
+    let config_resource = RemoteResource::from_pretrained(XLNetConfigResources::XLNET_BASE_CASED);
+    let vocab_resource = RemoteResource::from_pretrained(XLNetVocabResources::XLNET_BASE_CASED);
+    let config = XLNetConfig::from_file(config_resource.get_local_path()?);
+
+    //    Save randomly initialized multiple choice weights
+    tch::manual_seed(0);
+    let vs = nn::VarStore::new(Device::Cpu);
+    let _ = XLNetForMultipleChoice::new(vs.root(), &config)?;
+    let weights_dir = tempfile::tempdir()?;
+    let weights_path = weights_dir.path().join("rust_model.ot");
+    vs.save(&weights_path)?;
+
+    //    Set-up pipeline
+    let mut multiple_choice_config = MultipleChoiceConfig::new(
+        ModelType::XLNet,
+        ModelResource::Torch(Box::new(LocalResource::from(weights_path))),
+        config_resource,
+        vocab_resource,
+        None,
+        false,
+        None,
+        None,
+    );
+    multiple_choice_config.device = Device::Cpu;
+    let multiple_choice_model = MultipleChoiceModel::new(multiple_choice_config)?;
+
+    //    Define input
+    let short_input = MultipleChoiceInput {
+        context: "The dog is running around trying to avoid a bath. She".to_string(),
+        choices: vec![
+            "uses a hose to keep it from getting soapy.".to_string(),
+            "gets the dog wet, then it runs away again.".to_string(),
+        ],
+    };
+    let long_input = MultipleChoiceInput {
+        context: "In Italy, pizza served in formal settings, such as at a restaurant, is presented unsliced.".to_string(),
+        choices: vec![
+            "It is eaten with the use of a knife and fork, while in casual settings it is cut into wedges to be eaten while held in the hand.".to_string(),
+            "It is eaten while held in the hand.".to_string(),
+        ],
+    };
+
+    //    The choices are summarized from their last token: padding (on the left) must not change the scores
+    let unpadded_output = multiple_choice_model.predict_batch(&[short_input.clone()], 1)?;
+    let padded_output = multiple_choice_model.predict_batch(&[short_input, long_input], 2)?;
+
+    assert_eq!(padded_output.len(), 2);
+    for (unpadded_choice, padded_choice) in unpadded_output[0].iter().zip(padded_output[0].iter()) {
+        assert_eq!(unpadded_choice.index, padded_choice.index);
+        assert!((unpadded_choice.score - padded_choice.score).abs() < 1e-4);
+    }
+
+    Ok(())
+}
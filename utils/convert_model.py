@@ -30,6 +30,11 @@ if __name__ == "__main__":
         "--dtype",
         help="Convert weights to a specific numpy DataType (float32, float16, ...)",
     )
+    parser.add_argument(
+        "--safetensors",
+        action="store_true",
+        help="Save the weights in the safetensors format (memory-mapped when loaded) instead of a .ot file",
+    )
     parser.add_argument(
         "--download_libtorch",
         action="store_true",
@@ -72,6 +77,12 @@ if __name__ == "__main__":
                 print(f"converted {k} - {str(sys.getsizeof(nps[k]))} bytes")
             else:
                 print(f"skipped non-tensor object: {k}")
+    if args.safetensors:
+        from safetensors.numpy import save_file
+
+        save_file(nps, str(target_folder / "rust_model.safetensors"))
+        sys.exit(0)
+
     np.savez(target_folder / "model.npz", **nps)
 
     source = str(target_folder / "model.npz")